@@ -122,8 +122,8 @@ int main() {
     );
 
     if (result != Success) {
-        printf("Error: Decryption failed (code: %d)\n", result);
-        if (result == MemoryError) {
+        printf("Error: Decryption failed: %s (code: %d)\n", timelock_result_to_string(result), result);
+        if (result == BufferTooSmall) {
             printf("Required buffer size: %zu bytes\n", plaintext_len);
         }
     } else {
@@ -0,0 +1,32 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Decode untrusted text as an armored message. Whatever decodes must encode
+//! to a message that decodes to the same headers and data.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelock::tlock::Armored;
+
+fuzz_target!(|data: &[u8]| {
+	let Ok(text) = std::str::from_utf8(data) else {
+		return;
+	};
+	if let Ok(armored) = Armored::decode(text) {
+		assert_eq!(Armored::decode(&armored.encode()), Ok(armored));
+	}
+});
@@ -0,0 +1,91 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Call the C entry points with untrusted ciphertexts, signatures and public
+//! keys. The first byte picks the entry point, the rest is the input.
+
+#![no_main]
+
+use ark_ec::PrimeGroup;
+use ark_serialize::CanonicalSerialize;
+use libfuzzer_sys::fuzz_target;
+use std::ffi::CString;
+use timelock::engines::{drand::TinyBLS381, EngineBLS};
+use timelock_ffi::{timelock_decrypt, timelock_encrypt_into, TimelockCiphertext};
+
+/// The hex encoding of a valid (compressed) point
+fn hex_point<G: CanonicalSerialize>(point: G) -> CString {
+	let mut bytes = Vec::new();
+	point.serialize_compressed(&mut bytes).unwrap();
+	CString::new(hex::encode(bytes)).unwrap()
+}
+
+/// Decrypt a ciphertext with a signature
+fn decrypt(ciphertext: &[u8], signature_hex: &CString) {
+	let ciphertext =
+		TimelockCiphertext { data: ciphertext.as_ptr() as *mut u8, len: ciphertext.len() };
+	let mut plaintext = [0u8; 4096];
+	let mut plaintext_len = plaintext.len();
+	unsafe {
+		timelock_decrypt(
+			&ciphertext,
+			signature_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		)
+	};
+}
+
+/// Encrypt a message to a public key
+fn encrypt(public_key_hex: &CString) {
+	let message = b"fuzz";
+	let identity = [1u8; 32];
+	let secret_key = [2u8; 32];
+	let mut ciphertext = [0u8; 512];
+	let mut ciphertext_len = ciphertext.len();
+	unsafe {
+		timelock_encrypt_into(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			public_key_hex.as_ptr(),
+			secret_key.as_ptr(),
+			ciphertext.as_mut_ptr(),
+			&mut ciphertext_len,
+		)
+	};
+}
+
+fuzz_target!(|data: &[u8]| {
+	let Some((mode, input)) = data.split_first() else {
+		return;
+	};
+	match mode % 3 {
+		// an untrusted ciphertext
+		0 => decrypt(input, &hex_point(<TinyBLS381 as EngineBLS>::SignatureGroup::generator())),
+		// an untrusted signature, as hex
+		1 =>
+			if let Ok(signature_hex) = CString::new(input) {
+				decrypt(&[0u8; 300], &signature_hex);
+			},
+		// an untrusted public key, as hex
+		_ =>
+			if let Ok(public_key_hex) = CString::new(input) {
+				encrypt(&public_key_hex);
+			},
+	}
+});
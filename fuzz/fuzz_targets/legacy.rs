@@ -0,0 +1,44 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Decode untrusted bytes as a ciphertext in either the current or the
+//! legacy etf-sdk layout, and decrypt legacy ones with arbitrary secrets to
+//! reach the parsing of their IBE capsules.
+
+#![no_main]
+
+use ark_ec::PrimeGroup;
+use libfuzzer_sys::fuzz_target;
+use timelock::{
+	engines::{drand::TinyBLS381, EngineBLS},
+	tlock::compat::{decode, tld_legacy, AnyCiphertext},
+};
+
+/// The most capsules decrypted per input, to keep runs fast
+const MAX_CAPSULES: usize = 8;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(AnyCiphertext::Legacy(ciphertext)) = decode::<TinyBLS381>(data) {
+		if ciphertext.etf_ct.len() <= MAX_CAPSULES {
+			let secrets = vec![
+				<TinyBLS381 as EngineBLS>::SignatureGroup::generator();
+				ciphertext.etf_ct.len()
+			];
+			let ibe_pp = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator();
+			let _ = tld_legacy(&ciphertext, ibe_pp, &secrets);
+		}
+	}
+});
@@ -0,0 +1,47 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Deserialize untrusted bytes as a ciphertext, leniently, strictly and as a
+//! borrowed view. Whatever parses must serialize back to the bytes it
+//! consumed. The lenient path reads a prefix and ignores trailing bytes; the
+//! strict path and the view consume the whole input.
+
+#![no_main]
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use libfuzzer_sys::fuzz_target;
+use timelock::{
+	engines::drand::TinyBLS381,
+	tlock::{strict, TLECiphertext, TLECiphertextRef},
+};
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(ciphertext) = TLECiphertext::<TinyBLS381>::deserialize_compressed(data) {
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+		assert_eq!(bytes, data[..bytes.len()]);
+	}
+	if let Ok(ciphertext) = strict::ciphertext::<TinyBLS381>(data) {
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+		assert_eq!(bytes, data);
+	}
+	if let Ok(view) = TLECiphertextRef::<TinyBLS381>::parse(data) {
+		let mut bytes = Vec::new();
+		view.to_ciphertext().serialize_compressed(&mut bytes).unwrap();
+		assert_eq!(bytes, data);
+	}
+});
@@ -0,0 +1,180 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The chain a beacon serves: its drand style chain info and its rounds.
+//!
+//! The chain hash is computed as drand computes it, so clients pinning the
+//! hash (see [`timelock_client::fetch_chain_info`]) accept the chain. The
+//! group hash, which drand takes from the group file of its nodes, is the
+//! SHA-256 hash of the public key.
+
+use crate::{
+	signer::{hex, Engine, Signer},
+	Error,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	sync::{Arc, RwLock},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use timelock::{rounds::RoundCalculator, tlock::Randomness};
+use timelock_client::{chain_info::ChainMetadata, ChainInfo};
+
+/// The scheme of served beacons
+pub const SCHEME: &str = "bls-unchained-g1-rfc9380";
+
+/// A round, in the format served at `/public/<round>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicBeacon {
+	/// The round
+	pub round: u64,
+	/// The hex encoded randomness of the round, the hash of the signature
+	pub randomness: String,
+	/// The hex encoded compressed signature of the round
+	pub signature: String,
+}
+
+/// A beacon chain: a signer publishing a round every period
+pub struct Chain {
+	signer: Signer,
+	info: ChainInfo,
+	latest: RwLock<Option<PublicBeacon>>,
+}
+
+impl Chain {
+	/// A chain signed by `signer`
+	///
+	/// * `signer`: The key to sign rounds with
+	/// * `genesis`: The unix time of round 1
+	/// * `period`: The number of seconds between rounds (non-zero)
+	/// * `beacon_id`: The id of the beacon (e.g. `default`)
+	pub fn new(signer: Signer, genesis: u64, period: u64, beacon_id: &str) -> Self {
+		let public_key =
+			array_bytes::hex2bytes(hex(&signer.public_key())).expect("The hex encoding is valid.");
+		let mut info = ChainInfo {
+			group_hash: Sha256::digest(&public_key).to_vec(),
+			public_key,
+			period,
+			genesis_time: genesis,
+			hash: Vec::new(),
+			scheme_id: SCHEME.into(),
+			metadata: ChainMetadata { beacon_id: beacon_id.into() },
+		};
+		info.hash = info.compute_hash().to_vec();
+		Self { signer, info, latest: RwLock::new(None) }
+	}
+
+	/// The chain info of the chain
+	pub fn info(&self) -> &ChainInfo {
+		&self.info
+	}
+
+	/// The hex encoded chain hash
+	pub fn hash(&self) -> String {
+		array_bytes::bytes2hex("", &self.info.hash)
+	}
+
+	/// A calculator for the rounds of the chain
+	pub fn rounds(&self) -> RoundCalculator {
+		self.info.round_calculator()
+	}
+
+	/// A round, or `None` if it is not published at `now`
+	///
+	/// * `round`: The round
+	/// * `now`: The current unix time
+	pub fn beacon(&self, round: u64, now: u64) -> Result<Option<PublicBeacon>, Error> {
+		if round == 0 || round > self.rounds().round_at(now) {
+			return Ok(None);
+		}
+		if let Some(latest) = self.latest.read().expect("The lock is not poisoned.").as_ref() {
+			if latest.round == round {
+				return Ok(Some(latest.clone()));
+			}
+		}
+		let signature = self.signer.sign(round)?;
+		Ok(Some(PublicBeacon {
+			round,
+			randomness: array_bytes::bytes2hex(
+				"",
+				Randomness::from_signature::<Engine>(&signature).as_bytes(),
+			),
+			signature: hex(&signature),
+		}))
+	}
+
+	/// The latest round published at `now`, or `None` before genesis
+	///
+	/// * `now`: The current unix time
+	pub fn latest(&self, now: u64) -> Result<Option<PublicBeacon>, Error> {
+		self.beacon(self.rounds().round_at(now), now)
+	}
+
+	/// Wait until a round is published
+	///
+	/// * `round`: The round
+	pub async fn wait_for(&self, round: u64) {
+		let wait = self.rounds().time_of(round).saturating_sub(now());
+		tokio::time::sleep(Duration::from_secs(wait)).await;
+	}
+
+	/// Sign every round when it is due, so the latest round is served without
+	/// signing it on request
+	pub async fn tick(self: Arc<Self>) {
+		loop {
+			let next = self.rounds().round_at(now()) + 1;
+			self.wait_for(next).await;
+			match self.beacon(next, now()) {
+				Ok(Some(beacon)) => {
+					eprintln!("round {}: {}", beacon.round, beacon.randomness);
+					*self.latest.write().expect("The lock is not poisoned.") = Some(beacon);
+				},
+				Ok(None) => {},
+				Err(e) => eprintln!("round {} could not be signed: {}", next, e),
+			}
+		}
+	}
+}
+
+/// The current unix time
+pub fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.expect("The clock is after the unix epoch.")
+		.as_secs()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+	use timelock::beacon::BeaconSigner;
+
+	#[test]
+	fn rounds_are_served_once_published() {
+		let signer = BeaconSigner::<Engine>::generate(StdRng::seed_from_u64(2369));
+		let expected = signer.sign_bytes(3);
+		let chain = Chain::new(Signer::Local(Box::new(signer)), 1000, 3, "test");
+		assert!(chain.info().validate().is_ok());
+
+		assert_eq!(chain.latest(999).unwrap(), None);
+		assert_eq!(chain.beacon(3, 1005).unwrap(), None);
+		let beacon = chain.beacon(3, 1006).unwrap().unwrap();
+		assert_eq!(beacon.signature, array_bytes::bytes2hex("", &expected));
+		assert_eq!(chain.latest(1008).unwrap(), Some(beacon));
+	}
+}
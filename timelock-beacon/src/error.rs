@@ -0,0 +1,60 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The errors reported by the beacon server.
+
+use std::fmt;
+
+/// Errors that may occur while running a beacon
+#[derive(Debug)]
+pub enum Error {
+	/// A file could not be read or written, or the server could not listen
+	Io(std::io::Error),
+	/// The key file is not a valid test beacon key
+	InvalidKey,
+	/// The key share files do not make up a threshold of one committee
+	InvalidShares(String),
+	/// Key generation or threshold signing failed
+	Dkg(timelock::dkg::Error),
+	/// The gRPC server failed
+	Grpc(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Io(e) => write!(f, "{}", e),
+			Error::InvalidKey => write!(f, "the key is not a valid test beacon key"),
+			Error::InvalidShares(reason) => write!(f, "invalid key shares: {}", reason),
+			Error::Dkg(e) => write!(f, "threshold signing failed: {}", e),
+			Error::Grpc(e) => write!(f, "the gRPC server failed: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+	fn from(e: std::io::Error) -> Self {
+		Error::Io(e)
+	}
+}
+
+impl From<timelock::dkg::Error> for Error {
+	fn from(e: timelock::dkg::Error) -> Self {
+		Error::Dkg(e)
+	}
+}
@@ -0,0 +1,148 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The gRPC API of the beacon (see `timelock-client/proto/beacon.proto`).
+//!
+//! It serves the same chain as the HTTP API, for server-to-server pipelines:
+//! `StreamRounds` pushes every round as it is published, starting with the
+//! already published rounds from the requested one.
+
+use crate::chain::{now, Chain, PublicBeacon};
+use futures_util::Stream;
+use std::{pin::Pin, sync::Arc};
+use timelock_client::grpc::{
+	proto::beacon_server::{Beacon, BeaconServer},
+	ChainInfoRequest, ChainInfoResponse, RoundRequest, RoundResponse, StreamRoundsRequest,
+};
+use tonic::{Request, Response, Status};
+
+/// The rounds streamed to a client
+type RoundStream = Pin<Box<dyn Stream<Item = Result<RoundResponse, Status>> + Send>>;
+
+/// The gRPC service of a chain
+pub struct BeaconService {
+	chain: Arc<Chain>,
+}
+
+/// The gRPC service of a chain, ready to be served
+///
+/// * `chain`: The chain to serve
+pub fn service(chain: Arc<Chain>) -> BeaconServer<BeaconService> {
+	BeaconServer::new(BeaconService { chain })
+}
+
+#[tonic::async_trait]
+impl Beacon for BeaconService {
+	async fn get_chain_info(
+		&self,
+		_request: Request<ChainInfoRequest>,
+	) -> Result<Response<ChainInfoResponse>, Status> {
+		Ok(Response::new(self.chain.info().into()))
+	}
+
+	async fn get_round(
+		&self,
+		request: Request<RoundRequest>,
+	) -> Result<Response<RoundResponse>, Status> {
+		let round = match request.into_inner().round {
+			0 => self.chain.rounds().round_at(now()),
+			round => round,
+		};
+		round_response(&self.chain, round).map(Response::new)
+	}
+
+	type StreamRoundsStream = RoundStream;
+
+	async fn stream_rounds(
+		&self,
+		request: Request<StreamRoundsRequest>,
+	) -> Result<Response<RoundStream>, Status> {
+		let chain = self.chain.clone();
+		let from = match request.into_inner().from {
+			0 => chain.rounds().round_at(now()) + 1,
+			from => from,
+		};
+		let rounds = futures_util::stream::unfold(from, move |round| {
+			let chain = chain.clone();
+			async move {
+				chain.wait_for(round).await;
+				Some((round_response(&chain, round), round + 1))
+			}
+		});
+		Ok(Response::new(Box::pin(rounds)))
+	}
+}
+
+/// A published round, or `NOT_FOUND`
+// the service returns its errors as `Status`, however large
+#[allow(clippy::result_large_err)]
+fn round_response(chain: &Chain, round: u64) -> Result<RoundResponse, Status> {
+	match chain.beacon(round, now()) {
+		Ok(Some(PublicBeacon { round, randomness, signature })) => Ok(RoundResponse {
+			round,
+			signature: array_bytes::hex2bytes(signature).expect("The signature is hex encoded."),
+			randomness: array_bytes::hex2bytes(randomness).expect("The randomness is hex encoded."),
+		}),
+		Ok(None) => Err(Status::not_found(format!("round {} is not published", round))),
+		Err(e) => Err(Status::internal(e.to_string())),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::signer::{Engine, Signer};
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+	use futures_util::StreamExt;
+	use timelock::beacon::BeaconSigner;
+	use timelock_client::{Error, GrpcClient};
+	use tonic::transport::{server::TcpIncoming, Server};
+
+	#[tokio::test]
+	async fn clients_get_and_stream_verified_rounds() {
+		let signer = BeaconSigner::<Engine>::generate(StdRng::seed_from_u64(2370));
+		let chain = Arc::new(Chain::new(Signer::Local(Box::new(signer)), now() - 9, 1, "test"));
+		let hash = chain.info().hash.clone();
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let endpoint = format!("http://{}", listener.local_addr().unwrap());
+		let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+		tokio::spawn(Server::builder().add_service(service(chain)).serve_with_incoming(incoming));
+
+		assert!(matches!(
+			GrpcClient::connect(&endpoint, &[0; 32]).await,
+			Err(Error::ChainHashMismatch)
+		));
+		let client = GrpcClient::connect(&endpoint, &hash).await.unwrap();
+		assert_eq!(client.chain_info().hash, hash);
+
+		let (round, _) = client.signature::<Engine>(5).await.unwrap();
+		assert_eq!(round, 5);
+		let (latest, _) = client.signature::<Engine>(0).await.unwrap();
+		assert!(latest >= 10);
+		assert!(matches!(client.signature::<Engine>(latest + 100).await, Err(Error::Transport(_))));
+
+		// published rounds are streamed at once, then the next as it is published
+		let rounds: Vec<u64> = client
+			.stream::<Engine>(latest - 1)
+			.await
+			.unwrap()
+			.take(3)
+			.map(|round| round.unwrap().0)
+			.collect()
+			.await;
+		assert_eq!(rounds, [latest - 1, latest, latest + 1]);
+	}
+}
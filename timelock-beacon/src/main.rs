@@ -0,0 +1,181 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! `timelock-beacon`: a self-hosted randomness beacon.
+//!
+//! The beacon publishes a round every period, signed with the master secret
+//! of a test beacon or with a threshold of a committee's key shares, and
+//! serves rounds over the drand HTTP API, so integration tests and private
+//! deployments can timelock without the drand network.
+//!
+//! ```text
+//! timelock keygen -o beacon.key
+//! timelock-beacon run --key beacon.key --period 3 --listen 127.0.0.1:8080
+//! timelock-beacon run --key beacon.key --grpc-listen 127.0.0.1:50051
+//! timelock-beacon dkg --threshold 2 --participants 3 -o shares/
+//! timelock-beacon run --share shares/share-1.json --share shares/share-3.json
+//! ```
+
+mod chain;
+mod error;
+mod grpc;
+mod server;
+mod signer;
+
+use ark_std::rand::rngs::OsRng;
+use chain::{now, Chain};
+use clap::{Args, Parser, Subcommand};
+use error::Error;
+use signer::{generate_shares, ShareFile, Signer};
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
+use timelock::dkg::Parameters;
+use tonic::transport::{server::TcpIncoming, Server};
+
+/// A self-hosted randomness beacon
+#[derive(Debug, Parser)]
+#[command(name = "timelock-beacon", version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Publish and serve rounds
+	Run(RunArgs),
+	/// Generate the key shares of a committee locally
+	Dkg(DkgArgs),
+}
+
+/// Publish and serve rounds
+#[derive(Debug, Args)]
+struct RunArgs {
+	/// The key file of the beacon, as written by `timelock keygen`
+	#[arg(long, required_unless_present = "share", conflicts_with = "share")]
+	key: Option<PathBuf>,
+	/// A key share file of the committee (at least the threshold of them)
+	#[arg(long)]
+	share: Vec<PathBuf>,
+	/// The unix time of round 1 (now by default)
+	#[arg(long)]
+	genesis: Option<u64>,
+	/// The number of seconds between rounds
+	#[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..=u32::MAX as u64))]
+	period: u64,
+	/// The id of the beacon, part of its chain hash
+	#[arg(long, default_value = "timelock")]
+	beacon_id: String,
+	/// The address to serve the HTTP API on
+	#[arg(long, default_value = "127.0.0.1:8080")]
+	listen: String,
+	/// The address to also serve the gRPC API on
+	#[arg(long)]
+	grpc_listen: Option<String>,
+}
+
+/// Generate the key shares of a committee locally
+#[derive(Debug, Args)]
+struct DkgArgs {
+	/// The number of members needed to sign a round
+	#[arg(long)]
+	threshold: u32,
+	/// The number of members
+	#[arg(long)]
+	participants: u32,
+	/// The directory to write the key share files to
+	#[arg(short, long)]
+	output: PathBuf,
+}
+
+async fn run(args: RunArgs) -> Result<(), Error> {
+	let signer = match args.key {
+		Some(key) => Signer::from_key(&std::fs::read(key)?)?,
+		None => Signer::from_shares(
+			&args.share.iter().map(std::fs::read).collect::<Result<Vec<_>, _>>()?,
+		)?,
+	};
+	let chain = Arc::new(Chain::new(
+		signer,
+		args.genesis.unwrap_or_else(now),
+		args.period,
+		&args.beacon_id,
+	));
+	let listener = tokio::net::TcpListener::bind(&args.listen).await?;
+	eprintln!("chain {} on http://{}", chain.hash(), listener.local_addr()?);
+	tokio::spawn(chain.clone().tick());
+	if let Some(address) = args.grpc_listen {
+		let listener = tokio::net::TcpListener::bind(&address).await?;
+		eprintln!("gRPC on {}", listener.local_addr()?);
+		let incoming = TcpIncoming::from_listener(listener, true, None)
+			.map_err(|e| Error::Grpc(e.to_string()))?;
+		let server = Server::builder().add_service(grpc::service(chain.clone()));
+		tokio::spawn(async move {
+			if let Err(e) = server.serve_with_incoming(incoming).await {
+				eprintln!("error: {}", Error::Grpc(e.to_string()));
+			}
+		});
+	}
+	axum::serve(listener, server::router(chain)).await?;
+	Ok(())
+}
+
+fn dkg(args: DkgArgs) -> Result<(), Error> {
+	let params = Parameters::new(args.threshold, args.participants)?;
+	let shares = generate_shares(params, OsRng)?;
+	std::fs::create_dir_all(&args.output)?;
+	for share in &shares {
+		let file = ShareFile::new(share);
+		let json = serde_json::to_vec_pretty(&file).expect("The file must be serializable.");
+		std::fs::write(args.output.join(format!("share-{}.json", share.index)), json)?;
+	}
+	eprintln!("public key: {}", signer::hex(&shares[0].group.public_key()));
+	Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+	let result = match Cli::parse().command {
+		Command::Run(args) => run(args).await,
+		Command::Dkg(args) => dkg(args),
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {}", e);
+			ExitCode::FAILURE
+		},
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn exactly_one_kind_of_key_is_required() {
+		let parse = |args: &[&str]| {
+			Cli::try_parse_from([&["timelock-beacon", "run"], args].concat()).map(|cli| cli.command)
+		};
+		assert!(matches!(
+			parse(&["--key", "beacon.key"]),
+			Ok(Command::Run(args)) if args.period == 3 && args.share.is_empty()
+		));
+		assert!(parse(&["--share", "a.json", "--share", "b.json"]).is_ok());
+		assert!(parse(&[]).is_err());
+		assert!(parse(&["--key", "beacon.key", "--share", "a.json"]).is_err());
+		assert!(parse(&["--key", "beacon.key", "--period", "0"]).is_err());
+	}
+}
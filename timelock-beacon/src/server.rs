@@ -0,0 +1,141 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The HTTP API of the beacon, in the shape of the drand HTTP API.
+//!
+//! The chain is served both at the root and under its hash, as relays serve
+//! the chains of a drand network:
+//!
+//! ```text
+//! GET /chains                   the hashes of the served chains
+//! GET /info                     the chain info
+//! GET /public/latest            the latest round
+//! GET /public/<round>           a published round
+//! GET /<hash>/info              as above, for the chain with the hash
+//! GET /<hash>/public/<round>
+//! ```
+//!
+//! Rounds not yet published and unknown chains are `404 Not Found`.
+
+use crate::chain::{now, Chain, PublicBeacon};
+use axum::{
+	extract::{Path, State},
+	http::StatusCode,
+	routing::get,
+	Json, Router,
+};
+use std::sync::Arc;
+use timelock_client::ChainInfo;
+
+/// The router of the API
+///
+/// * `chain`: The chain to serve
+pub fn router(chain: Arc<Chain>) -> Router {
+	Router::new()
+		.route("/chains", get(chains))
+		.route("/info", get(info))
+		.route("/public/{round}", get(public))
+		.route("/{hash}/info", get(info_of))
+		.route("/{hash}/public/{round}", get(public_of))
+		.with_state(chain)
+}
+
+async fn chains(State(chain): State<Arc<Chain>>) -> Json<Vec<String>> {
+	Json(vec![chain.hash()])
+}
+
+async fn info(State(chain): State<Arc<Chain>>) -> Json<ChainInfo> {
+	Json(chain.info().clone())
+}
+
+async fn public(
+	State(chain): State<Arc<Chain>>,
+	Path(round): Path<String>,
+) -> Result<Json<PublicBeacon>, StatusCode> {
+	let beacon = match round.as_str() {
+		"latest" => chain.latest(now()),
+		round => chain.beacon(round.parse().map_err(|_| StatusCode::BAD_REQUEST)?, now()),
+	};
+	match beacon {
+		Ok(Some(beacon)) => Ok(Json(beacon)),
+		Ok(None) => Err(StatusCode::NOT_FOUND),
+		Err(e) => {
+			eprintln!("round {} could not be signed: {}", round, e);
+			Err(StatusCode::INTERNAL_SERVER_ERROR)
+		},
+	}
+}
+
+async fn info_of(
+	State(chain): State<Arc<Chain>>,
+	Path(hash): Path<String>,
+) -> Result<Json<ChainInfo>, StatusCode> {
+	check_hash(&chain, &hash)?;
+	Ok(info(State(chain)).await)
+}
+
+async fn public_of(
+	State(chain): State<Arc<Chain>>,
+	Path((hash, round)): Path<(String, String)>,
+) -> Result<Json<PublicBeacon>, StatusCode> {
+	check_hash(&chain, &hash)?;
+	public(State(chain), Path(round)).await
+}
+
+/// Check that a requested chain hash is that of the served chain
+fn check_hash(chain: &Chain, hash: &str) -> Result<(), StatusCode> {
+	match hash.eq_ignore_ascii_case(&chain.hash()) {
+		true => Ok(()),
+		false => Err(StatusCode::NOT_FOUND),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::signer::{Engine, Signer};
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+	use timelock::beacon::BeaconSigner;
+	use timelock_client::{fetch_chain_info, fetch_signature, Error, HttpTransport};
+
+	#[tokio::test]
+	async fn clients_fetch_verified_rounds() {
+		let signer = BeaconSigner::<Engine>::generate(StdRng::seed_from_u64(2369));
+		let chain = Arc::new(Chain::new(Signer::Local(Box::new(signer)), now() - 30, 3, "test"));
+		let hash = chain.info().hash.clone();
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let relay = format!("http://{}", listener.local_addr().unwrap());
+		tokio::spawn(async move { axum::serve(listener, router(chain)).await });
+
+		let transport = HttpTransport::default();
+		let info = fetch_chain_info(&transport, &relay, &hash).await.unwrap();
+		assert!(fetch_signature::<Engine, _>(&transport, &relay, &info, 5).await.is_ok());
+		let future = info.round_calculator().round_at(now()) + 100;
+		assert!(matches!(
+			fetch_signature::<Engine, _>(&transport, &relay, &info, future).await,
+			Err(Error::Transport(_))
+		));
+		assert!(fetch_chain_info(&transport, &relay, &[0; 32]).await.is_err());
+
+		let latest: PublicBeacon = serde_json::from_slice(
+			&timelock_client::Transport::get(&transport, &format!("{}/public/latest", relay))
+				.await
+				.unwrap(),
+		)
+		.unwrap();
+		assert!(latest.round >= 10);
+	}
+}
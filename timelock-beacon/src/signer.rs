@@ -0,0 +1,235 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The keys a beacon signs rounds with.
+//!
+//! A beacon signs with the master secret of a test beacon, in the key file
+//! format of `timelock keygen`, or with a threshold of the key shares of a
+//! committee (see [`timelock::dkg`]), whose partial signatures it combines as
+//! the committee would. `timelock-beacon dkg` runs key generation for a
+//! committee locally and writes one key share file per participant.
+
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use timelock::{
+	beacon::BeaconSigner,
+	dkg::{deal, Dealing, KeyShare, Parameters, Participant},
+	engines::{drand::TinyBLS381, EngineBLS},
+};
+
+/// The engine of served beacons: signatures in G1, like drand's quicknet
+pub type Engine = TinyBLS381;
+
+/// The key file of a test beacon, as written by `timelock keygen`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyFile {
+	/// The hex encoded master secret
+	pub secret_key: String,
+	/// The hex encoded compressed public key
+	pub public_key: String,
+}
+
+/// The key share file of a committee member
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShareFile {
+	/// The index of the member
+	pub index: u32,
+	/// The hex encoded compressed public key of the committee
+	pub public_key: String,
+	/// The hex encoded compressed key share
+	pub key_share: String,
+}
+
+impl ShareFile {
+	/// The file of a key share
+	///
+	/// * `share`: The key share
+	pub fn new(share: &KeyShare<Engine>) -> Self {
+		Self {
+			index: share.index,
+			public_key: hex(&share.group.public_key()),
+			key_share: hex(share),
+		}
+	}
+}
+
+/// The key a beacon signs rounds with
+pub enum Signer {
+	/// The master secret of the beacon
+	Local(Box<BeaconSigner<Engine>>),
+	/// A threshold of the key shares of a committee
+	Threshold(Vec<KeyShare<Engine>>),
+}
+
+impl Signer {
+	/// A signer with the master secret of a key file
+	///
+	/// * `json`: The contents of the key file
+	pub fn from_key(json: &[u8]) -> Result<Self, Error> {
+		let key: KeyFile = serde_json::from_slice(json).map_err(|_| Error::InvalidKey)?;
+		let signer = BeaconSigner::new(decode(&key.secret_key).ok_or(Error::InvalidKey)?);
+		if hex(&signer.public_key()) != key.public_key.to_lowercase() {
+			return Err(Error::InvalidKey);
+		}
+		Ok(Signer::Local(Box::new(signer)))
+	}
+
+	/// A signer with the key shares of key share files
+	///
+	/// The shares must be of distinct members of one committee, and at least
+	/// its threshold.
+	///
+	/// * `jsons`: The contents of the key share files
+	pub fn from_shares(jsons: &[Vec<u8>]) -> Result<Self, Error> {
+		let mut shares: Vec<KeyShare<Engine>> = Vec::new();
+		for json in jsons {
+			let file: ShareFile = serde_json::from_slice(json)
+				.map_err(|_| Error::InvalidShares("malformed key share file".into()))?;
+			let share: KeyShare<Engine> = decode(&file.key_share)
+				.ok_or(Error::InvalidShares("malformed key share".into()))?;
+			if share.index != file.index {
+				return Err(Error::InvalidShares("the index does not match the key share".into()));
+			}
+			if shares.iter().any(|other| other.group != share.group) {
+				return Err(Error::InvalidShares("the shares are of different committees".into()));
+			}
+			if shares.iter().any(|other| other.index == share.index) {
+				return Err(Error::InvalidShares(format!(
+					"duplicate share of member {}",
+					share.index
+				)));
+			}
+			shares.push(share);
+		}
+		match shares.first() {
+			Some(share) if shares.len() >= share.group.threshold as usize =>
+				Ok(Signer::Threshold(shares)),
+			_ => Err(Error::InvalidShares("fewer shares than the threshold".into())),
+		}
+	}
+
+	/// The public key of the beacon
+	pub fn public_key(&self) -> <Engine as EngineBLS>::PublicKeyGroup {
+		match self {
+			Signer::Local(signer) => signer.public_key(),
+			Signer::Threshold(shares) => shares[0].group.public_key(),
+		}
+	}
+
+	/// The signature of a round
+	///
+	/// * `round`: The round to sign
+	pub fn sign(&self, round: u64) -> Result<<Engine as EngineBLS>::SignatureGroup, Error> {
+		match self {
+			Signer::Local(signer) => Ok(signer.sign(round)),
+			Signer::Threshold(shares) => {
+				let partials: Vec<_> = shares.iter().map(|share| share.sign(round)).collect();
+				Ok(shares[0].group.combine(round, &partials)?)
+			},
+		}
+	}
+}
+
+/// Run key generation for a committee whose members are all honest and local
+///
+/// * `params`: The parameters of the committee
+/// * `rng`: A CSPRNG
+pub fn generate_shares<R: Rng + CryptoRng>(
+	params: Parameters,
+	mut rng: R,
+) -> Result<Vec<KeyShare<Engine>>, Error> {
+	let dealings = (1..=params.participants)
+		.map(|dealer| deal(&params, dealer, &mut rng))
+		.collect::<Result<Vec<Dealing<Engine>>, _>>()?;
+	let qualified: Vec<u32> = (1..=params.participants).collect();
+	let mut shares = Vec::new();
+	for index in 1..=params.participants {
+		let mut participant = Participant::new(params, index)?;
+		for dealing in &dealings {
+			let share = dealing.share_for(index).expect("Every participant is dealt a share.");
+			participant.receive(dealing.dealer, dealing.commitments.clone(), share)?;
+		}
+		shares.push(participant.finish(&qualified)?);
+	}
+	Ok(shares)
+}
+
+/// The hex encoded compressed serialization of a value
+pub fn hex<T: CanonicalSerialize>(value: &T) -> String {
+	let mut bytes = Vec::new();
+	value.serialize_compressed(&mut bytes).expect("The value must be serializable.");
+	array_bytes::bytes2hex("", bytes)
+}
+
+/// Decode the hex encoded compressed serialization of a value
+fn decode<T: CanonicalDeserialize>(hex: &str) -> Option<T> {
+	let bytes = array_bytes::hex2bytes(hex).ok()?;
+	T::deserialize_compressed(&bytes[..]).ok()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+	use timelock::tlock::verify_signature;
+
+	fn share_files(shares: &[KeyShare<Engine>]) -> Vec<Vec<u8>> {
+		shares
+			.iter()
+			.map(|share| serde_json::to_vec(&ShareFile::new(share)).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn threshold_signers_sign_as_the_committee() {
+		let params = Parameters::new(2, 3).unwrap();
+		let shares = generate_shares(params, StdRng::seed_from_u64(2369)).unwrap();
+		let files = share_files(&shares);
+
+		let first = Signer::from_shares(&files[..2]).unwrap();
+		let second = Signer::from_shares(&files[1..]).unwrap();
+		assert_eq!(first.public_key(), second.public_key());
+		let signature = first.sign(7).unwrap();
+		assert_eq!(second.sign(7).unwrap(), signature);
+		assert_eq!(verify_signature::<Engine>(first.public_key(), 7, signature), Ok(()));
+
+		assert!(matches!(Signer::from_shares(&files[..1]), Err(Error::InvalidShares(_))));
+		let duplicate = [files[0].clone(), files[0].clone()];
+		assert!(matches!(Signer::from_shares(&duplicate), Err(Error::InvalidShares(_))));
+		let others = generate_shares(params, StdRng::seed_from_u64(1)).unwrap();
+		let mixed = [files[0].clone(), share_files(&others)[1].clone()];
+		assert!(matches!(Signer::from_shares(&mixed), Err(Error::InvalidShares(_))));
+	}
+
+	#[test]
+	fn key_files_must_hold_their_public_key() {
+		let secret = <Engine as EngineBLS>::Scalar::from(2369u64);
+		let public_key = hex(&BeaconSigner::<Engine>::new(secret).public_key());
+		let key = KeyFile { secret_key: hex(&secret), public_key };
+		let signer = Signer::from_key(&serde_json::to_vec(&key).unwrap()).unwrap();
+		assert_eq!(hex(&signer.public_key()), key.public_key);
+		assert_eq!(signer.sign(7).unwrap(), BeaconSigner::<Engine>::new(secret).sign(7));
+
+		let key = KeyFile { secret_key: hex(&(secret + secret)), ..key };
+		assert!(matches!(
+			Signer::from_key(&serde_json::to_vec(&key).unwrap()),
+			Err(Error::InvalidKey)
+		));
+		assert!(matches!(Signer::from_key(b"{}"), Err(Error::InvalidKey)));
+	}
+}
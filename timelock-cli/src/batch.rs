@@ -0,0 +1,340 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Encrypting and decrypting whole directories.
+//!
+//! Every file below the input directory is processed on a pool of threads
+//! and written to the same relative path below the output directory, with a
+//! `.tle` suffix added on encryption and removed on decryption. Decryption
+//! fetches the signature of each distinct round once. A [`Manifest`] of the
+//! outcome for every file is written to [`MANIFEST`] in the output
+//! directory, and a failed file does not stop the others.
+
+use crate::{
+	decrypt::{signature, write_plaintext},
+	encrypt::encrypt_stream,
+	format::TimelockFile,
+	io::{Input, Output},
+	progress::Progress,
+	Error,
+};
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	sync::atomic::{AtomicUsize, Ordering},
+	thread,
+};
+use timelock::{
+	beacons::BeaconPreset, block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381,
+	tlock::tld,
+};
+use timelock_client::Transport;
+
+/// The file name of the manifest
+pub const MANIFEST: &str = "manifest.json";
+
+/// The suffix of encrypted files
+pub const SUFFIX: &str = "tle";
+
+/// The outcome for every file of a batch
+#[derive(Debug, Default, Serialize)]
+pub struct Manifest {
+	/// The files, in path order
+	pub files: Vec<Entry>,
+}
+
+/// The outcome for one file of a batch
+#[derive(Debug, Serialize)]
+pub struct Entry {
+	/// The path of the input, relative to the input directory
+	pub input: PathBuf,
+	/// The path of the output, relative to the output directory
+	pub output: PathBuf,
+	/// The round the file is locked to, if known
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub round: Option<u64>,
+	/// The hex encoded hash of the chain of the round, if known
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub chain_hash: Option<String>,
+	/// Why the file could not be processed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl Manifest {
+	/// The number of files that could not be processed
+	pub fn failures(&self) -> usize {
+		self.files.iter().filter(|entry| entry.error.is_some()).count()
+	}
+
+	/// Write the manifest to [`MANIFEST`] in a directory
+	///
+	/// * `dir`: The output directory of the batch
+	pub fn write(&self, dir: &Path) -> Result<PathBuf, Error> {
+		let path = dir.join(MANIFEST);
+		let json = serde_json::to_vec_pretty(self).expect("The manifest must be serializable.");
+		fs::write(&path, json)?;
+		Ok(path)
+	}
+}
+
+/// Write the manifest of a batch and summarize it on stderr
+///
+/// * `manifest`: The outcome of the batch
+/// * `output`: The output directory of the batch
+pub fn report(manifest: &Manifest, output: &Path) -> Result<(), Error> {
+	let path = manifest.write(output)?;
+	let failures = manifest.failures();
+	eprintln!(
+		"processed {} of {} files; manifest written to {}",
+		manifest.files.len() - failures,
+		manifest.files.len(),
+		path.display()
+	);
+	match failures {
+		0 => Ok(()),
+		failures => Err(Error::Batch(failures)),
+	}
+}
+
+/// Encrypt every file below a directory to a round
+///
+/// Encrypted files and manifests already below `input` are skipped.
+///
+/// * `preset`: The beacon to encrypt to
+/// * `round`: The round to encrypt to
+/// * `input`: The directory to encrypt
+/// * `output`: The directory to write ciphertexts to
+pub fn encrypt_dir(
+	preset: &BeaconPreset,
+	round: u64,
+	input: &Path,
+	output: &Path,
+) -> Result<Manifest, Error> {
+	let files: Vec<PathBuf> = walk(input)?
+		.into_iter()
+		.filter(|path| !has_suffix(path) && path != Path::new(MANIFEST))
+		.collect();
+	let chain_hash = preset.chain_hash.to_string();
+	let entries = parallel_map(&files, |path| {
+		let out = with_suffix(path);
+		let result = (|| {
+			let mut reader = Input::open(Some(&input.join(path)))?.reader;
+			let mut writer = create(&output.join(&out))?;
+			encrypt_stream(preset, round, &mut reader, &mut writer, &mut Progress::hidden())?;
+			writer.finish()
+		})();
+		Entry {
+			input: path.clone(),
+			output: out,
+			round: Some(round),
+			chain_hash: Some(chain_hash.clone()),
+			error: result.err().map(|e| e.to_string()),
+		}
+	});
+	Ok(Manifest { files: entries })
+}
+
+/// Decrypt every `.tle` file below a directory
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `now`: The current unix time
+/// * `input`: The directory to decrypt
+/// * `output`: The directory to write plaintexts to
+pub async fn decrypt_dir<T: Transport>(
+	transport: &T,
+	relay: &str,
+	now: u64,
+	input: &Path,
+	output: &Path,
+) -> Result<Manifest, Error> {
+	let files: Vec<PathBuf> = walk(input)?.into_iter().filter(|path| has_suffix(path)).collect();
+	let rounds: Vec<Option<(Vec<u8>, u64)>> = files
+		.iter()
+		.map(|path| {
+			let mut reader = Input::open(Some(&input.join(path))).ok()?.reader;
+			let (file, _) = TimelockFile::read(&mut reader).ok()?;
+			Some((file.chain_hash, file.round))
+		})
+		.collect();
+
+	let mut distinct: Vec<&(Vec<u8>, u64)> = rounds.iter().flatten().collect();
+	distinct.sort();
+	distinct.dedup();
+	let fetched = join_all(
+		distinct
+			.iter()
+			.map(|(chain_hash, round)| signature(transport, relay, now, chain_hash, *round)),
+	)
+	.await;
+	let signatures: HashMap<_, _> = distinct
+		.into_iter()
+		.zip(fetched)
+		.map(|(key, result)| (key.clone(), result.map_err(|e| e.to_string())))
+		.collect();
+
+	let jobs: Vec<_> = files.iter().zip(&rounds).collect();
+	let entries = parallel_map(&jobs, |(path, round)| {
+		let out = path.with_extension("");
+		let result = match round.as_ref().map(|key| &signatures[key]) {
+			None => Err(Error::InvalidCiphertext.to_string()),
+			Some(Err(e)) => Err(e.clone()),
+			Some(Ok(signature)) => (|| {
+				let mut reader = Input::open(Some(&input.join(path)))?.reader;
+				let (file, encoding) = TimelockFile::read(&mut reader)?;
+				let plaintext =
+					tld::<TinyBLS381, AESGCMBlockCipherProvider>(file.ciphertext, *signature)?;
+				let mut writer = create(&output.join(&out))?;
+				let mut progress = Progress::hidden();
+				write_plaintext(&plaintext, encoding, &mut reader, &mut writer, &mut progress)?;
+				writer.finish()
+			})()
+			.map_err(|e| e.to_string()),
+		};
+		Entry {
+			input: (*path).clone(),
+			output: out,
+			round: round.as_ref().map(|(_, round)| *round),
+			chain_hash: round.as_ref().map(|(hash, _)| array_bytes::bytes2hex("", hash)),
+			error: result.err(),
+		}
+	});
+	Ok(Manifest { files: entries })
+}
+
+/// Whether a path has the suffix of encrypted files
+fn has_suffix(path: &Path) -> bool {
+	path.extension().is_some_and(|extension| extension == SUFFIX)
+}
+
+/// A path with the suffix of encrypted files appended
+fn with_suffix(path: &Path) -> PathBuf {
+	let mut path = path.as_os_str().to_owned();
+	path.push(".");
+	path.push(SUFFIX);
+	path.into()
+}
+
+/// Create an output file and its parent directories
+fn create(path: &Path) -> Result<Output, Error> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	Output::create(Some(path))
+}
+
+/// The paths of the regular files below a directory, relative to it and in
+/// order
+///
+/// * `dir`: The directory
+fn walk(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+	let mut files = Vec::new();
+	let mut pending = vec![PathBuf::new()];
+	while let Some(relative) = pending.pop() {
+		for entry in fs::read_dir(dir.join(&relative))? {
+			let entry = entry?;
+			let path = relative.join(entry.file_name());
+			let file_type = entry.file_type()?;
+			if file_type.is_dir() {
+				pending.push(path);
+			} else if file_type.is_file() {
+				files.push(path);
+			}
+		}
+	}
+	files.sort();
+	Ok(files)
+}
+
+/// Apply `f` to every item on a pool of threads, keeping the order of items
+fn parallel_map<I: Sync, O: Send>(items: &[I], f: impl Fn(&I) -> O + Sync) -> Vec<O> {
+	let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(items.len());
+	let next = AtomicUsize::new(0);
+	let mut results: Vec<(usize, O)> = thread::scope(|scope| {
+		let handles: Vec<_> = (0..workers)
+			.map(|_| {
+				scope.spawn(|| {
+					let mut done = Vec::new();
+					loop {
+						let i = next.fetch_add(1, Ordering::Relaxed);
+						let Some(item) = items.get(i) else { break done };
+						done.push((i, f(item)));
+					}
+				})
+			})
+			.collect();
+		handles
+			.into_iter()
+			.flat_map(|handle| handle.join().expect("A batch worker panicked."))
+			.collect()
+	});
+	results.sort_by_key(|(i, _)| *i);
+	results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::decrypt::test::{MockRelay, RELAY};
+	use timelock::beacons::QUICKNET;
+
+	fn scratch(name: &str) -> PathBuf {
+		let dir =
+			std::env::temp_dir().join(format!("timelock-batch-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	#[tokio::test]
+	async fn directories_round_trip_with_manifests() {
+		let plain = scratch("plain");
+		fs::create_dir_all(plain.join("nested")).unwrap();
+		fs::write(plain.join("a.txt"), b"first").unwrap();
+		fs::write(plain.join("nested/b.bin"), vec![7u8; 100_000]).unwrap();
+
+		let sealed = scratch("sealed");
+		let manifest = encrypt_dir(&QUICKNET, 1000, &plain, &sealed).unwrap();
+		assert_eq!(manifest.failures(), 0);
+		let outputs: Vec<_> = manifest.files.iter().map(|entry| entry.output.clone()).collect();
+		assert_eq!(outputs, [PathBuf::from("a.txt.tle"), PathBuf::from("nested/b.bin.tle")]);
+		manifest.write(&sealed).unwrap();
+		fs::write(sealed.join("stray.tle"), b"not a ciphertext").unwrap();
+
+		let opened = scratch("opened");
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+		let manifest = decrypt_dir(&MockRelay::default(), RELAY, unlock_time, &sealed, &opened)
+			.await
+			.unwrap();
+		assert_eq!(manifest.files.len(), 3);
+		assert_eq!(manifest.failures(), 1);
+		assert!(manifest.files[2].error.is_some());
+		assert_eq!(fs::read(opened.join("a.txt")).unwrap(), b"first");
+		assert_eq!(fs::read(opened.join("nested/b.bin")).unwrap(), vec![7u8; 100_000]);
+
+		let json = serde_json::to_value(&manifest).unwrap();
+		assert_eq!(json["files"][0]["round"], 1000);
+		assert_eq!(json["files"][0]["chain_hash"], QUICKNET.chain_hash);
+
+		for dir in [plain, sealed, opened] {
+			fs::remove_dir_all(dir).unwrap();
+		}
+	}
+}
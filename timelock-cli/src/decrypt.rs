@@ -0,0 +1,414 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `decrypt` command.
+
+use crate::{
+	batch,
+	format::{Encoding, Format, TimelockFile},
+	io::{Input, Output},
+	mock,
+	progress::Progress,
+	sig, stream, time, Error, DEFAULT_RELAY,
+};
+use clap::Args;
+use std::{
+	io::{BufRead, Read, Write},
+	path::PathBuf,
+};
+use timelock::{
+	beacons::{BeaconPreset, QUICKNET},
+	block_ciphers::AESGCMBlockCipherProvider,
+	engines::{drand::TinyBLS381, EngineBLS},
+	tlock::{interop::drand, tld},
+};
+use timelock_client::{
+	fetch_chain_info, fetch_signature, verify_signature, HttpTransport, Transport,
+};
+
+/// Decrypt a file, fetching the signature of its round from a relay
+#[derive(Debug, Args)]
+pub struct DecryptArgs {
+	/// The base URL of the relay to fetch the signature from
+	#[arg(long, default_value = DEFAULT_RELAY)]
+	pub relay: String,
+	/// The signature of the round, as printed by `timelock sig`, to decrypt
+	/// without reaching a relay
+	#[arg(long)]
+	pub signature: Option<String>,
+	/// The public key of the test beacon that made the signature, as
+	/// printed by `timelock keygen`
+	#[arg(long, requires = "signature")]
+	pub public_key: Option<String>,
+	/// The format of the ciphertext
+	#[arg(long, value_enum, default_value_t)]
+	pub format: Format,
+	/// Decrypt every `.tle` file below the input directory, in parallel
+	#[arg(
+		short,
+		long,
+		requires = "input",
+		conflicts_with = "signature",
+		conflicts_with = "format"
+	)]
+	pub recursive: bool,
+	/// The file to write the plaintext to (stdout by default), or the
+	/// directory to write plaintexts to (the input directory by default)
+	#[arg(short, long)]
+	pub output: Option<PathBuf>,
+	/// The file to decrypt, binary or armored (stdin by default), or the
+	/// directory to decrypt
+	pub input: Option<PathBuf>,
+}
+
+/// Run the command
+pub async fn run(args: DecryptArgs) -> Result<(), Error> {
+	if let (true, Some(input)) = (args.recursive, &args.input) {
+		let output = args.output.as_deref().unwrap_or(input);
+		let transport = HttpTransport::default();
+		let manifest =
+			batch::decrypt_dir(&transport, &args.relay, time::now(), input, output).await?;
+		return batch::report(&manifest, output);
+	}
+
+	let mut input = Input::open(args.input.as_deref())?;
+	if args.format == Format::TlockAge {
+		let mut ciphertext = Vec::new();
+		input.reader.read_to_end(&mut ciphertext)?;
+		let plaintext = match args.signature {
+			Some(signature) => {
+				let header = drand::decrypt_header(&ciphertext)?;
+				let signature = sig::parse(&signature)?;
+				let beacon = offline_beacon(&header.chain_hash, args.public_key.as_deref())?;
+				verify_offline(&beacon, header.round, signature)?;
+				drand::decrypt(&ciphertext, signature)?
+			},
+			None => {
+				let transport = HttpTransport::default();
+				decrypt_tlock_age(&transport, &args.relay, time::now(), &ciphertext).await?
+			},
+		};
+		let mut output = Output::create(args.output.as_deref())?;
+		output.write_all(&plaintext)?;
+		return output.finish();
+	}
+
+	let (file, encoding) = TimelockFile::read(&mut input.reader)?;
+	let payload_len = input.len.map(|len| len.saturating_sub(file.header_len()));
+	let plaintext = match args.signature {
+		Some(signature) => {
+			let beacon = offline_beacon(&file.chain_hash, args.public_key.as_deref())?;
+			decrypt_with_signature(&beacon, file, sig::parse(&signature)?)?
+		},
+		None => decrypt(&HttpTransport::default(), &args.relay, time::now(), file).await?,
+	};
+
+	let mut output = Output::create(args.output.as_deref())?;
+	let mut progress = Progress::new(payload_len);
+	write_plaintext(&plaintext, encoding, &mut input.reader, &mut output, &mut progress)?;
+	output.finish()
+}
+
+/// Write the message of a file given its decrypted ciphertext
+///
+/// * `plaintext`: The decrypted ciphertext of the file
+/// * `encoding`: The encoding of the file
+/// * `reader`: The rest of the file
+/// * `writer`: Where to write the message
+/// * `progress`: Advanced by the payload bytes read
+pub fn write_plaintext<R: BufRead, W: Write>(
+	plaintext: &[u8],
+	encoding: Encoding,
+	reader: &mut R,
+	writer: &mut W,
+	progress: &mut Progress,
+) -> Result<(), Error> {
+	if encoding.is_streamed() {
+		decrypt_payload(plaintext, reader, writer, progress)
+	} else {
+		Ok(writer.write_all(plaintext)?)
+	}
+}
+
+/// Decrypt the chunked payload of a streamed file
+///
+/// * `payload_key`: The decrypted ciphertext of the file
+/// * `reader`: The rest of the file
+/// * `writer`: Where to write the message
+/// * `progress`: Advanced by the payload bytes read
+pub fn decrypt_payload<R: BufRead, W: Write>(
+	payload_key: &[u8],
+	reader: &mut R,
+	writer: &mut W,
+	progress: &mut Progress,
+) -> Result<(), Error> {
+	let payload_key = payload_key.try_into().map_err(|_| Error::InvalidCiphertext)?;
+	stream::decrypt(payload_key, reader, writer, progress)
+}
+
+/// Decrypt a file once its round is published
+///
+/// The chain info is fetched pinned to the chain hash of the file, so the
+/// relay does not need to be trusted.
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `now`: The current unix time
+/// * `file`: The file to decrypt
+pub async fn decrypt<T: Transport>(
+	transport: &T,
+	relay: &str,
+	now: u64,
+	file: TimelockFile,
+) -> Result<Vec<u8>, Error> {
+	let signature = signature(transport, relay, now, &file.chain_hash, file.round).await?;
+	Ok(tld::<TinyBLS381, AESGCMBlockCipherProvider>(file.ciphertext, signature)?)
+}
+
+/// Decrypt a ciphertext of drand's `tlock` once its round is published
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `now`: The current unix time
+/// * `ciphertext`: A binary or armored ciphertext
+pub async fn decrypt_tlock_age<T: Transport>(
+	transport: &T,
+	relay: &str,
+	now: u64,
+	ciphertext: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let header = drand::decrypt_header(ciphertext)?;
+	let signature = signature(transport, relay, now, &header.chain_hash, header.round).await?;
+	Ok(drand::decrypt(ciphertext, signature)?)
+}
+
+/// Fetch and verify the signature of a round once it is published
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `now`: The current unix time
+/// * `chain_hash`: The hash of the chain of the round
+/// * `round`: The round
+pub async fn signature<T: Transport>(
+	transport: &T,
+	relay: &str,
+	now: u64,
+	chain_hash: &[u8],
+	round: u64,
+) -> Result<<TinyBLS381 as EngineBLS>::SignatureGroup, Error> {
+	let info = fetch_chain_info(transport, relay, chain_hash).await?;
+	let unlock_time = info.round_calculator().time_of(round);
+	if now < unlock_time {
+		return Err(Error::TooEarly { round, unlock_time });
+	}
+	Ok(fetch_signature::<TinyBLS381, T>(transport, relay, &info, round).await?)
+}
+
+/// The beacon to verify a signature obtained out of band against
+///
+/// * `chain_hash`: The hash of the chain of the round
+/// * `public_key`: The hex encoded public key of a test beacon, if any
+pub fn offline_beacon(chain_hash: &[u8], public_key: Option<&str>) -> Result<BeaconPreset, Error> {
+	let chain_hash = array_bytes::bytes2hex("", chain_hash);
+	let preset = match public_key {
+		// the schedule of the test beacon does not matter for verification
+		Some(public_key) => Some(mock::preset(public_key, &QUICKNET)?),
+		None => BeaconPreset::by_chain_hash(&chain_hash),
+	};
+	preset
+		.filter(|preset| preset.chain_hash.eq_ignore_ascii_case(&chain_hash))
+		.ok_or(Error::UnknownChain(chain_hash))
+}
+
+/// Decrypt a file with a signature obtained out of band
+///
+/// * `beacon`: The beacon of the file's chain, see [`offline_beacon`]
+/// * `file`: The file to decrypt
+/// * `signature`: The signature of the file's round
+pub fn decrypt_with_signature(
+	beacon: &BeaconPreset,
+	file: TimelockFile,
+	signature: <TinyBLS381 as EngineBLS>::SignatureGroup,
+) -> Result<Vec<u8>, Error> {
+	verify_offline(beacon, file.round, signature)?;
+	Ok(tld::<TinyBLS381, AESGCMBlockCipherProvider>(file.ciphertext, signature)?)
+}
+
+/// Verify a signature obtained out of band
+///
+/// * `beacon`: The beacon of the round
+/// * `round`: The round
+/// * `signature`: The signature of the round
+pub fn verify_offline(
+	beacon: &BeaconPreset,
+	round: u64,
+	signature: <TinyBLS381 as EngineBLS>::SignatureGroup,
+) -> Result<(), Error> {
+	let config = beacon
+		.config::<TinyBLS381>()
+		.map_err(|_| Error::UnsupportedChain(beacon.name.into()))?;
+	verify_signature::<TinyBLS381>(config.p_pub, round, signature)
+		.map_err(|_| Error::InvalidSignature)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+	use super::*;
+	use crate::encrypt::{encrypt, encrypt_stream, encrypt_tlock_age};
+	use std::{collections::HashMap, future::Future};
+	use timelock::tlock::Armored;
+
+	pub const RELAY: &str = "https://relay.example";
+	pub const QUICKNET_SIG_1000: &str = "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39";
+
+	/// A relay serving quicknet's chain info and round 1000
+	pub struct MockRelay(HashMap<String, String>);
+
+	impl Default for MockRelay {
+		fn default() -> Self {
+			let prefix = format!("{}/{}", RELAY, QUICKNET.chain_hash);
+			let info = format!(
+				r#"{{"public_key":"{}","period":{},"genesis_time":{},"hash":"{}","groupHash":"{}","schemeID":"{}","metadata":{{"beaconID":"{}"}}}}"#,
+				QUICKNET.public_key,
+				QUICKNET.period,
+				QUICKNET.genesis,
+				QUICKNET.chain_hash,
+				QUICKNET.group_hash,
+				QUICKNET.scheme,
+				QUICKNET.beacon_id
+			);
+			let beacon = format!(r#"{{"round":1000,"signature":"{}"}}"#, QUICKNET_SIG_1000);
+			Self(HashMap::from([
+				(format!("{}/info", prefix), info),
+				(format!("{}/public/1000", prefix), beacon),
+			]))
+		}
+	}
+
+	impl Transport for MockRelay {
+		fn get(
+			&self,
+			url: &str,
+		) -> impl Future<Output = Result<Vec<u8>, timelock_client::Error>> + Send {
+			let response = self.0.get(url).map(|body| body.clone().into_bytes());
+			async move { response.ok_or(timelock_client::Error::Transport("not found".into())) }
+		}
+	}
+
+	#[tokio::test]
+	async fn encrypted_files_decrypt_once_the_round_is_published() {
+		let file = encrypt(&QUICKNET, 1000, b"hello from the cli").unwrap();
+		let file = TimelockFile::decode(&file.encode()).unwrap();
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+
+		let result = decrypt(&MockRelay::default(), RELAY, unlock_time - 1, file).await;
+		assert!(matches!(result, Err(Error::TooEarly { round: 1000, .. })));
+
+		let file = encrypt(&QUICKNET, 1000, b"hello from the cli").unwrap();
+		let plaintext = decrypt(&MockRelay::default(), RELAY, unlock_time, file).await.unwrap();
+		assert_eq!(plaintext, b"hello from the cli".to_vec());
+	}
+
+	#[test]
+	fn invalid_files_are_rejected() {
+		let mut bytes = encrypt(&QUICKNET, 1000, b"").unwrap().encode();
+		assert!(TimelockFile::decode(&bytes[..bytes.len() - 1]).is_err());
+		bytes[3] = 2;
+		assert!(matches!(TimelockFile::decode(&bytes), Err(Error::InvalidCiphertext)));
+		assert!(matches!(
+			encrypt(&timelock::beacons::MAINNET, 1000, b""),
+			Err(Error::UnsupportedChain(_))
+		));
+	}
+
+	#[test]
+	fn files_decrypt_offline_with_a_signature() {
+		let file = encrypt(&QUICKNET, 1000, b"air gapped").unwrap();
+		let signature = sig::parse(QUICKNET_SIG_1000).unwrap();
+		let beacon = offline_beacon(&file.chain_hash, None).unwrap();
+		assert_eq!(beacon, QUICKNET);
+		assert_eq!(
+			decrypt_with_signature(&beacon, file, signature).unwrap(),
+			b"air gapped".to_vec()
+		);
+
+		let file = encrypt(&QUICKNET, 1001, b"air gapped").unwrap();
+		assert!(matches!(
+			decrypt_with_signature(&QUICKNET, file, signature),
+			Err(Error::InvalidSignature)
+		));
+		assert!(matches!(offline_beacon(&[0; 32], None), Err(Error::UnknownChain(_))));
+	}
+
+	#[tokio::test]
+	async fn tlock_age_files_round_trip() {
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+		for armor in [false, true] {
+			let ciphertext = encrypt_tlock_age(&QUICKNET, 1000, b"from go", armor).unwrap();
+			assert_eq!(armor, ciphertext.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"));
+			let plaintext =
+				decrypt_tlock_age(&MockRelay::default(), RELAY, unlock_time, &ciphertext)
+					.await
+					.unwrap();
+			assert_eq!(plaintext, b"from go".to_vec());
+		}
+
+		let ciphertext = encrypt_tlock_age(&QUICKNET, 1000, b"", false).unwrap();
+		let result =
+			decrypt_tlock_age(&MockRelay::default(), RELAY, unlock_time - 1, &ciphertext).await;
+		assert!(matches!(result, Err(Error::TooEarly { round: 1000, .. })));
+	}
+
+	#[tokio::test]
+	async fn streamed_files_decrypt_chunk_by_chunk() {
+		let message = vec![42u8; 3 * timelock::tlock::stream::CHUNK_SIZE + 5];
+		let mut bytes = Vec::new();
+		encrypt_stream(&QUICKNET, 1000, &mut &message[..], &mut bytes, &mut Progress::hidden())
+			.unwrap();
+
+		let mut reader = &bytes[..];
+		let (file, encoding) = TimelockFile::read(&mut reader).unwrap();
+		assert_eq!(encoding, Encoding::Streamed);
+		assert_eq!(file.header_len() as usize, bytes.len() - reader.len());
+		assert!(matches!(TimelockFile::decode(&bytes), Err(Error::InvalidCiphertext)));
+
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+		let payload_key = decrypt(&MockRelay::default(), RELAY, unlock_time, file).await.unwrap();
+		let mut plaintext = Vec::new();
+		decrypt_payload(&payload_key, &mut reader, &mut plaintext, &mut Progress::hidden())
+			.unwrap();
+		assert_eq!(plaintext, message);
+	}
+
+	#[tokio::test]
+	async fn armored_files_decrypt_like_binary_ones() {
+		let armored = encrypt(&QUICKNET, 1000, b"pasted into a ticket").unwrap().armor();
+		assert!(armored.contains(&format!("Chain-Hash: {}", QUICKNET.chain_hash)));
+
+		let pasted = format!("\n  {}\n", armored.replace('\n', "\r\n"));
+		let file = TimelockFile::decode(pasted.as_bytes()).unwrap();
+		assert_eq!(file.round, 1000);
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+		let plaintext = decrypt(&MockRelay::default(), RELAY, unlock_time, file).await.unwrap();
+		assert_eq!(plaintext, b"pasted into a ticket".to_vec());
+
+		let headerless = Armored::new(Armored::decode(&armored).unwrap().data).encode();
+		assert!(matches!(
+			TimelockFile::decode(headerless.as_bytes()),
+			Err(Error::InvalidCiphertext)
+		));
+	}
+}
@@ -0,0 +1,232 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `encrypt` command.
+
+use crate::{
+	batch,
+	format::{Format, TimelockFile},
+	io::{Input, Output},
+	mock,
+	progress::Progress,
+	stream, time, Error,
+};
+use ark_std::rand::{rngs::OsRng, RngCore};
+use clap::Args;
+use std::{
+	io::{Read, Write},
+	path::PathBuf,
+	time::SystemTime,
+};
+use timelock::{
+	beacons::BeaconPreset,
+	block_ciphers::AESGCMBlockCipherProvider,
+	engines::drand::TinyBLS381,
+	tlock::{interop::drand, tle, BeaconConfig},
+};
+
+/// When a ciphertext unlocks: exactly one of a round or a time
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+pub struct Unlock {
+	/// The round to encrypt to
+	#[arg(long)]
+	pub round: Option<u64>,
+	/// The UTC time to unlock at (e.g. 2026-01-01T00:00:00Z)
+	#[arg(long)]
+	pub unlock_at: Option<humantime::Timestamp>,
+	/// The time from now to unlock after (e.g. 48h or "1day 12h")
+	#[arg(long = "in")]
+	pub unlock_in: Option<humantime::Duration>,
+}
+
+/// The beacon and round to encrypt to
+#[derive(Debug, Args)]
+pub struct Target {
+	#[command(flatten)]
+	pub unlock: Unlock,
+	/// The chain to encrypt to, by name or chain hash
+	#[arg(long, default_value = "quicknet")]
+	pub chain: String,
+	/// Encrypt to the test beacon with this public key, as printed by
+	/// `timelock keygen`, on the round schedule of the chain
+	#[arg(long)]
+	pub public_key: Option<String>,
+}
+
+impl Target {
+	/// The beacon and round, reported on stderr
+	pub fn resolve(&self) -> Result<(BeaconPreset, u64), Error> {
+		let preset = match &self.public_key {
+			Some(public_key) => mock::preset(public_key, &chain(&self.chain)?)?,
+			None => chain(&self.chain)?,
+		};
+		let round = match (self.unlock.round, self.unlock.unlock_at, self.unlock.unlock_in) {
+			(Some(round), ..) => round,
+			(_, Some(unlock_at), _) => round_at(&preset, SystemTime::from(unlock_at)),
+			(.., Some(unlock_in)) => round_at(&preset, SystemTime::now() + *unlock_in),
+			_ => unreachable!("clap requires one of the targets"),
+		};
+		eprintln!(
+			"locking to round {} of {}, which unlocks at {}",
+			round,
+			preset.name,
+			time::format(preset.rounds().time_of(round))
+		);
+		Ok((preset, round))
+	}
+}
+
+/// Encrypt a file to a round of a beacon
+#[derive(Debug, Args)]
+pub struct EncryptArgs {
+	#[command(flatten)]
+	pub target: Target,
+	/// Write the ciphertext as ASCII armor (buffers the whole input)
+	#[arg(short, long)]
+	pub armor: bool,
+	/// The format of the ciphertext (tlock-age buffers the whole input)
+	#[arg(long, value_enum, default_value_t)]
+	pub format: Format,
+	/// Encrypt every file below the input directory, in parallel
+	#[arg(short, long, requires = "input", conflicts_with = "armor", conflicts_with = "format")]
+	pub recursive: bool,
+	/// The file to write the ciphertext to (stdout by default), or the
+	/// directory to write ciphertexts to (the input directory by default)
+	#[arg(short, long)]
+	pub output: Option<PathBuf>,
+	/// The file to encrypt (stdin by default), or the directory to encrypt
+	pub input: Option<PathBuf>,
+}
+
+/// Run the command
+pub fn run(args: EncryptArgs) -> Result<(), Error> {
+	let (preset, round) = args.target.resolve()?;
+	if let (true, Some(input)) = (args.recursive, &args.input) {
+		let output = args.output.as_deref().unwrap_or(input);
+		let manifest = batch::encrypt_dir(&preset, round, input, output)?;
+		return batch::report(&manifest, output);
+	}
+
+	let mut input = Input::open(args.input.as_deref())?;
+	let mut output = Output::create(args.output.as_deref())?;
+	if args.format == Format::TlockAge {
+		let mut message = Vec::new();
+		input.reader.read_to_end(&mut message)?;
+		output.write_all(&encrypt_tlock_age(&preset, round, &message, args.armor)?)?;
+	} else if args.armor {
+		let mut message = Vec::new();
+		input.reader.read_to_end(&mut message)?;
+		output.write_all(encrypt(&preset, round, &message)?.armor().as_bytes())?;
+	} else {
+		let mut progress = Progress::new(input.len);
+		encrypt_stream(&preset, round, &mut input.reader, &mut output, &mut progress)?;
+	}
+	output.finish()
+}
+
+/// The first round of a beacon published at or after a time
+///
+/// * `preset`: The beacon
+/// * `unlock_at`: The time to unlock at
+pub fn round_at(preset: &BeaconPreset, unlock_at: SystemTime) -> u64 {
+	preset.rounds().round_after(time::unix(unlock_at))
+}
+
+/// The preset of a chain given by name or chain hash
+///
+/// * `chain`: The name or hex encoded hash of the chain
+pub fn chain(chain: &str) -> Result<BeaconPreset, Error> {
+	BeaconPreset::by_name(chain)
+		.or_else(|| BeaconPreset::by_chain_hash(chain))
+		.ok_or_else(|| Error::UnknownChain(chain.into()))
+}
+
+/// Encrypt a message to a round of a beacon
+///
+/// * `preset`: The beacon to encrypt to
+/// * `round`: The round to encrypt to
+/// * `message`: The message to encrypt
+pub fn encrypt(preset: &BeaconPreset, round: u64, message: &[u8]) -> Result<TimelockFile, Error> {
+	let config = preset
+		.config::<TinyBLS381>()
+		.map_err(|_| Error::UnsupportedChain(preset.name.into()))?;
+	let mut secret_key = [0u8; 32];
+	OsRng.fill_bytes(&mut secret_key);
+	let ciphertext = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+		config.p_pub,
+		secret_key,
+		message,
+		BeaconConfig::<TinyBLS381>::identity(round),
+		OsRng,
+	)?;
+	Ok(TimelockFile { round, chain_hash: preset.chain_hash_bytes(), ciphertext })
+}
+
+/// Encrypt a message to a round of a beacon in the format of drand's `tlock`
+///
+/// * `preset`: The beacon to encrypt to
+/// * `round`: The round to encrypt to
+/// * `message`: The message to encrypt
+/// * `armor`: Whether to armor the ciphertext
+pub fn encrypt_tlock_age(
+	preset: &BeaconPreset,
+	round: u64,
+	message: &[u8],
+	armor: bool,
+) -> Result<Vec<u8>, Error> {
+	let config = preset
+		.config::<TinyBLS381>()
+		.map_err(|_| Error::UnsupportedChain(preset.name.into()))?;
+	let ciphertext =
+		drand::encrypt(config.p_pub, &preset.chain_hash_bytes(), round, message, OsRng)?;
+	Ok(if armor { drand::armor(&ciphertext).into_bytes() } else { ciphertext })
+}
+
+/// Encrypt a stream to a round of a beacon as a chunked payload
+///
+/// * `preset`: The beacon to encrypt to
+/// * `round`: The round to encrypt to
+/// * `reader`: The message to encrypt
+/// * `writer`: Where to write the file
+/// * `progress`: Advanced by the message bytes read
+pub fn encrypt_stream<R: Read, W: Write>(
+	preset: &BeaconPreset,
+	round: u64,
+	reader: &mut R,
+	writer: &mut W,
+	progress: &mut Progress,
+) -> Result<(), Error> {
+	let mut payload_key = [0u8; 32];
+	OsRng.fill_bytes(&mut payload_key);
+	writer.write_all(&encrypt(preset, round, &payload_key)?.encode_header())?;
+	stream::encrypt(&payload_key, reader, writer, progress)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use timelock::beacons::QUICKNET;
+
+	#[test]
+	fn unlock_times_resolve_to_the_next_round() {
+		let unlock_at: humantime::Timestamp = "2026-01-01T00:00:00Z".parse().unwrap();
+		let round = round_at(&QUICKNET, unlock_at.into());
+		let published = QUICKNET.rounds().time_of(round);
+		assert_eq!(time::format(published), "2026-01-01T00:00:00Z");
+		assert_eq!(published, 1767225600);
+	}
+}
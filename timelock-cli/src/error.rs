@@ -0,0 +1,107 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The errors reported by the command line tool.
+
+use std::fmt;
+
+/// Errors that may occur while running a command
+#[derive(Debug)]
+pub enum Error {
+	/// A file or stream could not be read or written
+	Io(std::io::Error),
+	/// Talking to the beacon failed
+	Client(timelock_client::Error),
+	/// Encryption or decryption failed
+	Timelock(timelock::tlock::Error),
+	/// The chain is not a known preset
+	UnknownChain(String),
+	/// The chain is known but cannot be timelocked to
+	UnsupportedChain(String),
+	/// The input is not a timelock ciphertext
+	InvalidCiphertext,
+	/// A vault already has an entry with the label
+	DuplicateLabel(String),
+	/// A vault has no entry with the label
+	UnknownLabel(String),
+	/// The key of a test beacon is malformed
+	InvalidKey,
+	/// The signature is malformed or not valid for the round
+	InvalidSignature,
+	/// Some files of a batch could not be processed
+	Batch(usize),
+	/// The round of the ciphertext has not been reached yet
+	TooEarly {
+		/// The round the ciphertext is locked to
+		round: u64,
+		/// The unix time at which the round is published
+		unlock_time: u64,
+	},
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Io(e) => write!(f, "{}", e),
+			Error::Client(e) => write!(f, "beacon request failed: {:?}", e),
+			Error::Timelock(e) => write!(f, "timelock operation failed: {}", e),
+			Error::UnknownChain(chain) => write!(f, "unknown chain `{}`", chain),
+			Error::UnsupportedChain(chain) =>
+				write!(f, "chain `{}` does not support timelock encryption", chain),
+			Error::InvalidCiphertext => write!(f, "the input is not a timelock ciphertext"),
+			Error::Batch(failures) =>
+				write!(f, "{} files could not be processed; see the manifest", failures),
+			Error::DuplicateLabel(label) => write!(f, "the vault already has an entry `{}`", label),
+			Error::UnknownLabel(label) => write!(f, "the vault has no entry `{}`", label),
+			Error::InvalidKey => write!(f, "the key is not a valid test beacon key"),
+			Error::InvalidSignature => write!(f, "the signature is not valid for the round"),
+			Error::TooEarly { round, unlock_time } => write!(
+				f,
+				"round {} is not published yet; it unlocks at {}",
+				round,
+				crate::time::format(*unlock_time)
+			),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Io(e) => Some(e),
+			Error::Timelock(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(e: std::io::Error) -> Self {
+		Error::Io(e)
+	}
+}
+
+impl From<timelock_client::Error> for Error {
+	fn from(e: timelock_client::Error) -> Self {
+		Error::Client(e)
+	}
+}
+
+impl From<timelock::tlock::Error> for Error {
+	fn from(e: timelock::tlock::Error) -> Self {
+		Error::Timelock(e)
+	}
+}
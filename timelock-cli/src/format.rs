@@ -0,0 +1,182 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The file format of the command line tool.
+//!
+//! A file starts with the magic bytes `TLE` and a version byte, followed by
+//! the compressed serialization of a [`TimelockFile`]: the round and chain the
+//! ciphertext is locked to, and the ciphertext itself. Carrying the chain and
+//! round lets `decrypt` fetch the signature without further arguments.
+//!
+//! In a [`VERSION`] file the ciphertext holds the whole message. In a
+//! [`STREAMED_VERSION`] file it holds a payload key instead, and the message
+//! follows as chunks encrypted under that key (see [`crate::stream`]), so
+//! inputs of any size can be processed without buffering them.
+//!
+//! Files may also be ASCII armored, carrying the ciphertext in a
+//! `TIMELOCK MESSAGE` block with `Round` and `Chain-Hash` headers. Armored
+//! files always hold the whole message. [`TimelockFile::read`] accepts every
+//! form.
+//!
+//! Ciphertexts of drand's `tlock` tools are read and written in their own
+//! format instead, with [`Format::TlockAge`].
+
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::io::BufRead;
+use timelock::{
+	engines::drand::TinyBLS381,
+	tlock::{armor::BEGIN_LINE, Armored, TLECiphertext},
+};
+
+/// The magic bytes of a file
+pub const MAGIC: &[u8; 3] = b"TLE";
+
+/// The version of files whose ciphertext holds the whole message
+pub const VERSION: u8 = 1;
+
+/// The version of files whose ciphertext holds the key of a chunked payload
+pub const STREAMED_VERSION: u8 = 2;
+
+/// The ciphertext formats the command line tool reads and writes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+	/// The format of this tool, described above
+	#[default]
+	Tle,
+	/// The age-based format of drand's `tlock` and `tlock-js`
+	TlockAge,
+}
+
+/// How a file is encoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+	/// A binary [`VERSION`] file
+	Sealed,
+	/// A binary [`STREAMED_VERSION`] file
+	Streamed,
+	/// An ASCII armored file
+	Armored,
+}
+
+impl Encoding {
+	/// Whether a chunked payload follows the file
+	pub fn is_streamed(self) -> bool {
+		self == Encoding::Streamed
+	}
+
+	/// The version of the format
+	pub fn version(self) -> u8 {
+		match self {
+			Encoding::Streamed => STREAMED_VERSION,
+			Encoding::Sealed | Encoding::Armored => VERSION,
+		}
+	}
+}
+
+/// A ciphertext with the round and chain it is locked to
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct TimelockFile {
+	/// The round the ciphertext is locked to
+	pub round: u64,
+	/// The hash of the chain of the round
+	pub chain_hash: Vec<u8>,
+	/// The ciphertext
+	pub ciphertext: TLECiphertext<TinyBLS381>,
+}
+
+impl TimelockFile {
+	/// Encode the file
+	#[cfg(test)]
+	pub fn encode(&self) -> Vec<u8> {
+		self.encode_as(VERSION)
+	}
+
+	/// Encode the file as the header of a chunked payload
+	pub fn encode_header(&self) -> Vec<u8> {
+		self.encode_as(STREAMED_VERSION)
+	}
+
+	fn encode_as(&self, version: u8) -> Vec<u8> {
+		let mut bytes = MAGIC.to_vec();
+		bytes.push(version);
+		self.serialize_compressed(&mut bytes).expect("The file must be serializable.");
+		bytes
+	}
+
+	/// The length of the file encoded by [`TimelockFile::encode_header`]
+	pub fn header_len(&self) -> u64 {
+		(MAGIC.len() + 1 + self.compressed_size()) as u64
+	}
+
+	/// Encode the file as ASCII armor
+	pub fn armor(&self) -> String {
+		Armored::from_value(&self.ciphertext)
+			.with_round(self.round, &self.chain_hash)
+			.encode()
+	}
+
+	/// Decode a binary or armored file holding the whole message
+	///
+	/// * `bytes`: The contents of the file
+	#[cfg(test)]
+	pub fn decode(mut bytes: &[u8]) -> Result<Self, Error> {
+		match Self::read(&mut bytes)? {
+			(_, Encoding::Streamed) => Err(Error::InvalidCiphertext),
+			(file, _) => Ok(file),
+		}
+	}
+
+	/// Read a binary or armored file up to its payload
+	///
+	/// Returns the file and its encoding, which tells whether a chunked
+	/// payload follows in `reader`.
+	///
+	/// * `reader`: The contents of the file
+	pub fn read<R: BufRead>(reader: &mut R) -> Result<(Self, Encoding), Error> {
+		if reader.fill_buf()?.trim_ascii_start().starts_with(BEGIN_LINE.as_bytes()) {
+			let mut text = String::new();
+			reader.read_to_string(&mut text).map_err(|_| Error::InvalidCiphertext)?;
+			return Ok((Self::dearmor(&text)?, Encoding::Armored));
+		}
+
+		let mut prefix = [0u8; 4];
+		reader.read_exact(&mut prefix).map_err(|_| Error::InvalidCiphertext)?;
+		let encoding = match prefix.split_last() {
+			Some((&VERSION, magic)) if magic == MAGIC => Encoding::Sealed,
+			Some((&STREAMED_VERSION, magic)) if magic == MAGIC => Encoding::Streamed,
+			_ => return Err(Error::InvalidCiphertext),
+		};
+		let file =
+			Self::deserialize_compressed(&mut *reader).map_err(|_| Error::InvalidCiphertext)?;
+		if !encoding.is_streamed() && reader.read(&mut [0u8])? != 0 {
+			return Err(Error::InvalidCiphertext);
+		}
+		Ok((file, encoding))
+	}
+
+	/// Decode an armored file
+	///
+	/// * `text`: A string output by [`TimelockFile::armor`]
+	pub fn dearmor(text: &str) -> Result<Self, Error> {
+		let armored = Armored::decode(text).map_err(|_| Error::InvalidCiphertext)?;
+		Ok(Self {
+			round: armored.round().ok_or(Error::InvalidCiphertext)?,
+			chain_hash: armored.chain_hash().ok_or(Error::InvalidCiphertext)?,
+			ciphertext: armored.to_value().map_err(|_| Error::InvalidCiphertext)?,
+		})
+	}
+}
@@ -0,0 +1,155 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `inspect` command.
+
+use crate::{
+	format::{Encoding, TimelockFile},
+	io::Input,
+	stream, time, Error,
+};
+use ark_serialize::CanonicalDeserialize;
+use clap::Args;
+use std::{
+	fmt,
+	io::{self, BufRead},
+	path::PathBuf,
+};
+use timelock::{beacons::BeaconPreset, block_ciphers::AESOutput};
+
+/// The size of an AES-GCM authentication tag
+const TAG_SIZE: u64 = 16;
+
+/// Describe a file without decrypting it
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+	/// The file to inspect, binary or armored (stdin by default)
+	pub input: Option<PathBuf>,
+}
+
+/// What can be learned about a file without decrypting it
+#[derive(Debug, PartialEq)]
+pub struct Report {
+	/// How the file is encoded
+	pub encoding: Encoding,
+	/// The cipher suite of the ciphertext
+	pub cipher_suite: String,
+	/// The hex encoded hash of the chain
+	pub chain_hash: String,
+	/// The name of the chain, if it is a known preset
+	pub chain: Option<&'static str>,
+	/// The round the file is locked to
+	pub round: u64,
+	/// The unix time the round is published at, if the chain is known
+	pub unlock_time: Option<u64>,
+	/// The size of the message, if it is well-formed
+	pub message_len: Option<u64>,
+}
+
+/// Run the command
+pub fn run(args: InspectArgs) -> Result<(), Error> {
+	let mut input = Input::open(args.input.as_deref())?;
+	print!("{}", inspect(&mut input.reader)?);
+	Ok(())
+}
+
+/// Describe a file without decrypting it
+///
+/// * `reader`: The contents of the file
+pub fn inspect<R: BufRead>(reader: &mut R) -> Result<Report, Error> {
+	let (file, encoding) = TimelockFile::read(reader)?;
+	let message_len = if encoding.is_streamed() {
+		stream::plaintext_len(io::copy(reader, &mut io::sink())?)
+	} else {
+		AESOutput::deserialize_compressed(&file.ciphertext.body[..])
+			.ok()
+			.and_then(|output| (output.ciphertext.len() as u64).checked_sub(TAG_SIZE))
+	};
+	let chain_hash = array_bytes::bytes2hex("", &file.chain_hash);
+	let preset = BeaconPreset::by_chain_hash(&chain_hash);
+	Ok(Report {
+		encoding,
+		cipher_suite: String::from_utf8_lossy(&file.ciphertext.cipher_suite).into(),
+		chain: preset.as_ref().map(|preset| preset.name),
+		unlock_time: preset.map(|preset| preset.rounds().time_of(file.round)),
+		chain_hash,
+		round: file.round,
+		message_len,
+	})
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let encoding = match self.encoding {
+			Encoding::Sealed => "binary",
+			Encoding::Streamed => "binary, chunked payload",
+			Encoding::Armored => "armored",
+		};
+		writeln!(f, "version:      {} ({})", self.encoding.version(), encoding)?;
+		writeln!(f, "cipher suite: {}", self.cipher_suite)?;
+		writeln!(f, "chain:        {} ({})", self.chain.unwrap_or("unknown"), self.chain_hash)?;
+		writeln!(f, "round:        {}", self.round)?;
+		match self.unlock_time {
+			Some(unlock_time) => writeln!(f, "unlocks at:   {}", time::format(unlock_time))?,
+			None => writeln!(f, "unlocks at:   unknown")?,
+		}
+		match self.message_len {
+			Some(len) => writeln!(f, "payload size: {} bytes", len),
+			None => writeln!(f, "payload size: malformed"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		encrypt::{encrypt, encrypt_stream},
+		progress::Progress,
+	};
+	use timelock::beacons::QUICKNET;
+
+	#[test]
+	fn files_are_described_without_decryption() {
+		let sealed = encrypt(&QUICKNET, 1000, b"twelve bytes").unwrap();
+		let report = inspect(&mut &sealed.encode()[..]).unwrap();
+		assert_eq!(
+			report,
+			Report {
+				encoding: Encoding::Sealed,
+				cipher_suite: "AES_GCM_".into(),
+				chain_hash: QUICKNET.chain_hash.into(),
+				chain: Some("quicknet"),
+				round: 1000,
+				unlock_time: Some(1692806364),
+				message_len: Some(12),
+			}
+		);
+		assert!(report.to_string().contains("unlocks at:   2023-08-23T15:59:24Z"));
+		let armored = inspect(&mut sealed.armor().as_bytes()).unwrap();
+		assert_eq!(armored.encoding, Encoding::Armored);
+		assert_eq!(armored.message_len, Some(12));
+
+		let message = vec![0u8; timelock::tlock::stream::CHUNK_SIZE + 1];
+		let mut streamed = Vec::new();
+		encrypt_stream(&QUICKNET, 7, &mut &message[..], &mut streamed, &mut Progress::hidden())
+			.unwrap();
+		let report = inspect(&mut &streamed[..]).unwrap();
+		assert_eq!(report.encoding, Encoding::Streamed);
+		assert_eq!(report.round, 7);
+		assert_eq!(report.message_len, Some(message.len() as u64));
+	}
+}
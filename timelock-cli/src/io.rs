@@ -0,0 +1,111 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Reading inputs and writing outputs, with `-` or no path meaning the
+//! standard streams.
+
+use crate::Error;
+use std::{
+	fs::{self, File},
+	io::{BufRead, BufReader, BufWriter, Write},
+	path::{Path, PathBuf},
+};
+
+/// A file, or `None` for the standard stream
+fn file(path: Option<&Path>) -> Option<&Path> {
+	path.filter(|path| *path != Path::new("-"))
+}
+
+/// A buffered input stream
+pub struct Input {
+	/// The stream
+	pub reader: Box<dyn BufRead>,
+	/// The length of the stream, if it is a file
+	pub len: Option<u64>,
+}
+
+impl Input {
+	/// Open a file, or stdin
+	///
+	/// * `path`: The file, or `None` or `-` for stdin
+	pub fn open(path: Option<&Path>) -> Result<Self, Error> {
+		match file(path) {
+			Some(path) => {
+				let file = File::open(path)?;
+				let len = file.metadata()?.len();
+				Ok(Self { reader: Box::new(BufReader::new(file)), len: Some(len) })
+			},
+			None => Ok(Self { reader: Box::new(std::io::stdin().lock()), len: None }),
+		}
+	}
+}
+
+/// A buffered output stream
+///
+/// Files are written next to their destination and only moved into place
+/// by [`Output::finish`], so a failed command never leaves a partial file.
+pub struct Output {
+	/// The stream
+	writer: BufWriter<Box<dyn Write>>,
+	/// The partial and final paths of a file output
+	paths: Option<(PathBuf, PathBuf)>,
+}
+
+impl Output {
+	/// Create a file, or write to stdout
+	///
+	/// * `path`: The file, or `None` or `-` for stdout
+	pub fn create(path: Option<&Path>) -> Result<Self, Error> {
+		match file(path) {
+			Some(path) => {
+				let mut partial = path.as_os_str().to_owned();
+				partial.push(".partial");
+				let partial = PathBuf::from(partial);
+				let writer: Box<dyn Write> = Box::new(File::create(&partial)?);
+				Ok(Self { writer: BufWriter::new(writer), paths: Some((partial, path.into())) })
+			},
+			None =>
+				Ok(Self { writer: BufWriter::new(Box::new(std::io::stdout().lock())), paths: None }),
+		}
+	}
+
+	/// Flush the output and move a file into place
+	pub fn finish(mut self) -> Result<(), Error> {
+		self.writer.flush()?;
+		if let Some((partial, path)) = self.paths.take() {
+			fs::rename(partial, path)?;
+		}
+		Ok(())
+	}
+}
+
+impl Write for Output {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		self.writer.write(buf)
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		self.writer.flush()
+	}
+}
+
+impl Drop for Output {
+	fn drop(&mut self) {
+		if let Some((partial, _)) = self.paths.take() {
+			let _ = fs::remove_file(partial);
+		}
+	}
+}
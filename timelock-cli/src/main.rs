@@ -0,0 +1,122 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! `timelock`: timelock encrypt and decrypt files from the command line.
+//!
+//! ```text
+//! timelock encrypt --round 1000 --chain quicknet secret.txt -o secret.tle
+//! timelock encrypt --unlock-at 2026-01-01T00:00:00Z secret.txt -o secret.tle
+//! timelock encrypt --in 48h --armor secret.txt -o secret.tle.asc
+//! timelock encrypt --round 1000 --recursive secrets/ -o sealed/
+//! timelock encrypt --round 1000 --format tlock-age --armor secret.txt -o secret.age
+//! timelock inspect secret.tle
+//! timelock decrypt secret.tle
+//! timelock decrypt --recursive sealed/ -o secrets/
+//! timelock decrypt --format tlock-age secret.age
+//! timelock sig --chain quicknet --round 1000
+//! timelock decrypt --signature b44679b9... secret.tle
+//! timelock vault add reveals.tlv --label answer --in 30d answer.txt
+//! timelock vault extract reveals.tlv answer
+//! timelock keygen -o test.key
+//! timelock mock-sign --key test.key --round 1000
+//! ```
+
+mod batch;
+mod decrypt;
+mod encrypt;
+mod error;
+mod format;
+mod inspect;
+mod io;
+mod mock;
+mod progress;
+mod sig;
+mod stream;
+mod time;
+mod vault;
+
+use clap::{Parser, Subcommand};
+use error::Error;
+use std::process::ExitCode;
+
+/// The relay used unless another is given
+pub const DEFAULT_RELAY: &str = "https://api.drand.sh";
+
+/// Timelock encryption to randomness beacons
+#[derive(Debug, Parser)]
+#[command(name = "timelock", version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Encrypt a file to a round of a beacon
+	Encrypt(encrypt::EncryptArgs),
+	/// Decrypt a file once its round is published
+	Decrypt(decrypt::DecryptArgs),
+	/// Describe a file without decrypting it
+	Inspect(inspect::InspectArgs),
+	/// Fetch and verify the signature of a round
+	Sig(sig::SigArgs),
+	/// Manage a file of many timelocked entries
+	Vault(vault::VaultArgs),
+	/// Create the master secret of a test beacon
+	Keygen(mock::KeygenArgs),
+	/// Sign a round with the master secret of a test beacon
+	MockSign(mock::MockSignArgs),
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+	let result = match Cli::parse().command {
+		Command::Encrypt(args) => encrypt::run(args),
+		Command::Decrypt(args) => decrypt::run(args).await,
+		Command::Inspect(args) => inspect::run(args),
+		Command::Sig(args) => sig::run(args).await,
+		Command::Vault(args) => vault::run(args).await,
+		Command::Keygen(args) => mock::keygen(args),
+		Command::MockSign(args) => mock::mock_sign(args),
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {}", e);
+			ExitCode::FAILURE
+		},
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn exactly_one_encryption_target_is_required() {
+		let parse = |args: &[&str]| {
+			Cli::try_parse_from([&["timelock", "encrypt"], args].concat()).map(|cli| cli.command)
+		};
+		assert!(matches!(
+			parse(&["--round", "1000", "--chain", "quicknet", "-o", "out.tle", "in.txt"]),
+			Ok(Command::Encrypt(args)) if args.target.unlock.round == Some(1000)
+		));
+		assert!(parse(&["--in", "48h", "in.txt"]).is_ok());
+		assert!(parse(&["--unlock-at", "2026-01-01T00:00:00Z", "in.txt"]).is_ok());
+		assert!(parse(&["in.txt"]).is_err());
+		assert!(parse(&["--round", "1000", "--in", "48h", "in.txt"]).is_err());
+	}
+}
@@ -0,0 +1,179 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `keygen` and `mock-sign` commands: a local beacon for testing.
+//!
+//! `keygen` creates the master secret of a test beacon, and `mock-sign`
+//! produces the signature the beacon would publish for a round. Files are
+//! encrypted to the test beacon with `encrypt --public-key` and decrypted
+//! with `decrypt --public-key --signature`, so whole flows run without
+//! waiting on a real beacon. A test beacon follows the round schedule of the
+//! chain given with `--chain`, and its chain hash is the SHA-256 hash of its
+//! public key.
+//!
+//! ```text
+//! timelock keygen -o test.key
+//! timelock encrypt --public-key <public key> --round 5 secret.txt -o secret.tle
+//! timelock mock-sign --key test.key --round 5
+//! timelock decrypt --public-key <public key> --signature <signature> secret.tle
+//! ```
+
+use crate::{
+	io::{Input, Output},
+	Error,
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::rngs::OsRng, UniformRand};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	io::{Read, Write},
+	path::PathBuf,
+};
+use timelock::{
+	beacons::BeaconPreset,
+	engines::{drand::TinyBLS381, EngineBLS},
+	tlock::BeaconConfig,
+};
+
+/// The scheme of test beacons
+const SCHEME: &str = "bls-unchained-g1-rfc9380";
+
+/// Create the master secret of a test beacon
+#[derive(Debug, Args)]
+pub struct KeygenArgs {
+	/// The file to write the key to (stdout by default)
+	#[arg(short, long)]
+	pub output: Option<PathBuf>,
+}
+
+/// Sign a round with the master secret of a test beacon
+#[derive(Debug, Args)]
+pub struct MockSignArgs {
+	/// The key file written by `timelock keygen`
+	#[arg(long)]
+	pub key: PathBuf,
+	/// The round to sign
+	#[arg(long)]
+	pub round: u64,
+}
+
+/// The key file of a test beacon
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestKey {
+	/// The hex encoded master secret
+	pub secret_key: String,
+	/// The hex encoded compressed public key
+	pub public_key: String,
+}
+
+impl TestKey {
+	/// Generate a fresh test beacon
+	pub fn generate() -> Self {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let public = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		Self { secret_key: hex(&secret), public_key: hex(&public) }
+	}
+
+	/// Sign a round as the beacon would
+	///
+	/// * `round`: The round to sign
+	pub fn sign(&self, round: u64) -> Result<<TinyBLS381 as EngineBLS>::SignatureGroup, Error> {
+		let bytes = array_bytes::hex2bytes(&self.secret_key).map_err(|_| Error::InvalidKey)?;
+		let secret = CanonicalDeserialize::deserialize_compressed(&bytes[..])
+			.map_err(|_| Error::InvalidKey)?;
+		Ok(BeaconConfig::<TinyBLS381>::identity(round).extract::<TinyBLS381>(secret).0)
+	}
+}
+
+/// The preset of the test beacon with a public key
+///
+/// The strings of the preset are leaked, which is fine for the one preset a
+/// command needs.
+///
+/// * `public_key`: The hex encoded public key of the beacon
+/// * `schedule`: The chain whose round schedule the beacon follows
+pub fn preset(public_key: &str, schedule: &BeaconPreset) -> Result<BeaconPreset, Error> {
+	let bytes = array_bytes::hex2bytes(public_key).map_err(|_| Error::InvalidKey)?;
+	let _: <TinyBLS381 as EngineBLS>::PublicKeyGroup =
+		CanonicalDeserialize::deserialize_compressed(&bytes[..]).map_err(|_| Error::InvalidKey)?;
+	Ok(BeaconPreset {
+		name: "test beacon",
+		public_key: array_bytes::bytes2hex("", &bytes).leak(),
+		chain_hash: array_bytes::bytes2hex("", Sha256::digest(&bytes)).leak(),
+		group_hash: "",
+		genesis: schedule.genesis,
+		period: schedule.period,
+		scheme: SCHEME,
+		beacon_id: "test",
+	})
+}
+
+/// Run `keygen`
+pub fn keygen(args: KeygenArgs) -> Result<(), Error> {
+	let key = TestKey::generate();
+	eprintln!("public key: {}", key.public_key);
+	let mut output = Output::create(args.output.as_deref())?;
+	serde_json::to_writer_pretty(&mut output, &key).expect("The key must be serializable.");
+	output.write_all(b"\n")?;
+	output.finish()
+}
+
+/// Run `mock-sign`
+pub fn mock_sign(args: MockSignArgs) -> Result<(), Error> {
+	let mut json = Vec::new();
+	Input::open(Some(&args.key))?.reader.read_to_end(&mut json)?;
+	let key: TestKey = serde_json::from_slice(&json).map_err(|_| Error::InvalidKey)?;
+	println!("{}", hex(&key.sign(args.round)?));
+	Ok(())
+}
+
+/// The hex encoded compressed serialization of a value
+fn hex<T: CanonicalSerialize>(value: &T) -> String {
+	let mut bytes = Vec::new();
+	value.serialize_compressed(&mut bytes).expect("The value must be serializable.");
+	array_bytes::bytes2hex("", bytes)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{decrypt::decrypt_with_signature, encrypt::encrypt, sig};
+	use timelock::beacons::QUICKNET;
+
+	#[test]
+	fn test_beacons_run_full_flows_locally() {
+		let key = TestKey::generate();
+		let json = serde_json::to_string(&key).unwrap();
+		assert_eq!(serde_json::from_str::<TestKey>(&json).unwrap(), key);
+
+		let beacon = preset(&key.public_key, &QUICKNET).unwrap();
+		assert_eq!(beacon.rounds(), QUICKNET.rounds());
+		let file = encrypt(&beacon, 5, b"no beacon needed").unwrap();
+		let signature = sig::parse(&hex(&key.sign(5).unwrap())).unwrap();
+		let plaintext = decrypt_with_signature(&beacon, file, signature).unwrap();
+		assert_eq!(plaintext, b"no beacon needed".to_vec());
+
+		let file = encrypt(&beacon, 6, b"no beacon needed").unwrap();
+		assert!(matches!(
+			decrypt_with_signature(&beacon, file, signature),
+			Err(Error::InvalidSignature)
+		));
+		assert!(matches!(preset("00", &QUICKNET), Err(Error::InvalidKey)));
+	}
+}
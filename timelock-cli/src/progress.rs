@@ -0,0 +1,100 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A progress bar on stderr for long running streams.
+
+use std::io::{IsTerminal, Write};
+
+/// The width of the bar in characters
+const WIDTH: usize = 40;
+
+/// Bytes between redraws when the total is unknown
+const REDRAW_EVERY: u64 = 16 * 1024 * 1024;
+
+/// Progress through a stream of bytes
+pub struct Progress {
+	/// The total number of bytes, if known
+	total: Option<u64>,
+	/// The number of bytes processed
+	done: u64,
+	/// What was last drawn: a percentage, or a multiple of [`REDRAW_EVERY`]
+	drawn: Option<u64>,
+	/// Whether to draw at all
+	visible: bool,
+}
+
+impl Progress {
+	/// A progress bar shown when stderr is a terminal
+	///
+	/// * `total`: The total number of bytes, if known
+	pub fn new(total: Option<u64>) -> Self {
+		Self { total, done: 0, drawn: None, visible: std::io::stderr().is_terminal() }
+	}
+
+	/// A progress bar that is never shown
+	pub fn hidden() -> Self {
+		Self { total: None, done: 0, drawn: None, visible: false }
+	}
+
+	/// Record that `bytes` more bytes were processed
+	pub fn advance(&mut self, bytes: u64) {
+		self.done += bytes;
+		if !self.visible {
+			return;
+		}
+		let step = match self.total {
+			Some(total) if total > 0 => self.done.min(total) * 100 / total,
+			_ => self.done / REDRAW_EVERY,
+		};
+		if self.drawn != Some(step) {
+			self.drawn = Some(step);
+			self.draw();
+		}
+	}
+
+	/// End the line of the bar
+	pub fn finish(&mut self) {
+		if self.visible && self.drawn.is_some() {
+			eprintln!();
+			self.drawn = None;
+		}
+	}
+
+	fn draw(&self) {
+		let line = match self.total {
+			Some(total) if total > 0 => {
+				let percent = self.done.min(total) * 100 / total;
+				let filled = percent as usize * WIDTH / 100;
+				format!(
+					"[{}{}] {:>3}% of {}",
+					"#".repeat(filled),
+					" ".repeat(WIDTH - filled),
+					percent,
+					megabytes(total)
+				)
+			},
+			_ => megabytes(self.done),
+		};
+		let mut stderr = std::io::stderr().lock();
+		let _ = write!(stderr, "\r{}", line);
+		let _ = stderr.flush();
+	}
+}
+
+/// A byte count in MiB
+fn megabytes(bytes: u64) -> String {
+	format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `sig` command.
+//!
+//! Fetching a signature on a connected machine and passing it to
+//! `decrypt --signature` lets files be decrypted on a machine that never
+//! talks to a relay.
+
+use crate::{encrypt::chain, Error, DEFAULT_RELAY};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Args;
+use timelock::{
+	beacons::BeaconPreset,
+	engines::{drand::TinyBLS381, EngineBLS},
+};
+use timelock_client::{fetch_chain_info, fetch_signature, HttpTransport, Transport};
+
+/// Fetch and verify the signature of a round
+#[derive(Debug, Args)]
+pub struct SigArgs {
+	/// The chain of the round, by name or chain hash
+	#[arg(long, default_value = "quicknet")]
+	pub chain: String,
+	/// The round to fetch the signature of
+	#[arg(long)]
+	pub round: u64,
+	/// The base URL of the relay to fetch the signature from
+	#[arg(long, default_value = DEFAULT_RELAY)]
+	pub relay: String,
+	/// Print the signature as base64 rather than hex
+	#[arg(long)]
+	pub base64: bool,
+}
+
+/// Run the command
+pub async fn run(args: SigArgs) -> Result<(), Error> {
+	let preset = chain(&args.chain)?;
+	let signature = fetch(&HttpTransport::default(), &args.relay, &preset, args.round).await?;
+	let mut bytes = Vec::new();
+	signature
+		.serialize_compressed(&mut bytes)
+		.expect("The signature must be serializable.");
+	if args.base64 {
+		println!("{}", STANDARD.encode(bytes));
+	} else {
+		println!("{}", array_bytes::bytes2hex("", bytes));
+	}
+	Ok(())
+}
+
+/// Fetch and verify the signature of a round
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `preset`: The chain of the round
+/// * `round`: The round to fetch the signature of
+pub async fn fetch<T: Transport>(
+	transport: &T,
+	relay: &str,
+	preset: &BeaconPreset,
+	round: u64,
+) -> Result<<TinyBLS381 as EngineBLS>::SignatureGroup, Error> {
+	if preset.config::<TinyBLS381>().is_err() {
+		return Err(Error::UnsupportedChain(preset.name.into()));
+	}
+	let info = fetch_chain_info(transport, relay, &preset.chain_hash_bytes()).await?;
+	Ok(fetch_signature::<TinyBLS381, T>(transport, relay, &info, round).await?)
+}
+
+/// Parse a signature printed by the command, as hex or base64
+///
+/// * `text`: The signature
+pub fn parse(text: &str) -> Result<<TinyBLS381 as EngineBLS>::SignatureGroup, Error> {
+	let text = text.trim();
+	let bytes = array_bytes::hex2bytes(text)
+		.or_else(|_| STANDARD.decode(text))
+		.map_err(|_| Error::InvalidSignature)?;
+	CanonicalDeserialize::deserialize_compressed(&bytes[..]).map_err(|_| Error::InvalidSignature)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::decrypt::test::{MockRelay, QUICKNET_SIG_1000, RELAY};
+	use timelock::beacons::{MAINNET, QUICKNET};
+
+	#[tokio::test]
+	async fn signatures_are_fetched_verified_and_parsed() {
+		let signature = fetch(&MockRelay::default(), RELAY, &QUICKNET, 1000).await.unwrap();
+		assert_eq!(parse(QUICKNET_SIG_1000).unwrap(), signature);
+
+		let mut bytes = Vec::new();
+		signature.serialize_compressed(&mut bytes).unwrap();
+		assert_eq!(parse(&STANDARD.encode(&bytes)).unwrap(), signature);
+		assert_eq!(parse(&format!("0x{}\n", QUICKNET_SIG_1000)).unwrap(), signature);
+		assert!(matches!(parse("not a signature"), Err(Error::InvalidSignature)));
+
+		assert!(fetch(&MockRelay::default(), RELAY, &QUICKNET, 1001).await.is_err());
+		assert!(matches!(
+			fetch(&MockRelay::default(), RELAY, &MAINNET, 1000).await,
+			Err(Error::UnsupportedChain(_))
+		));
+	}
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The chunked payload of streamed files.
+//!
+//! The payload is the chunked mode of [`timelock::tlock::stream`]; this
+//! module drives its adapters between files and reports progress.
+
+use crate::{progress::Progress, Error};
+use std::io::{self, Read, Write};
+use timelock::tlock::{TldReader, TleWriter};
+
+pub use timelock::tlock::stream::plaintext_len;
+
+/// Advances a progress bar by the bytes read through it
+struct Counted<'a, R> {
+	inner: R,
+	progress: &'a mut Progress,
+}
+
+impl<R: Read> Read for Counted<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let n = self.inner.read(buf)?;
+		self.progress.advance(n as u64);
+		Ok(n)
+	}
+}
+
+/// Authentication failures of the payload mean it is not a valid ciphertext
+fn payload_error(e: io::Error) -> Error {
+	match e.kind() {
+		io::ErrorKind::InvalidData => Error::InvalidCiphertext,
+		_ => Error::Io(e),
+	}
+}
+
+/// Encrypt everything read from `reader` to `writer`
+///
+/// * `key`: The payload key
+/// * `reader`: The plaintext
+/// * `writer`: Where to write the encrypted chunks
+/// * `progress`: Advanced by the plaintext bytes read
+pub fn encrypt<R: Read, W: Write>(
+	key: &[u8; 32],
+	reader: &mut R,
+	writer: &mut W,
+	progress: &mut Progress,
+) -> Result<(), Error> {
+	let mut sealer = TleWriter::with_key(key, writer);
+	io::copy(&mut Counted { inner: reader, progress: &mut *progress }, &mut sealer)?;
+	sealer.finish()?;
+	progress.finish();
+	Ok(())
+}
+
+/// Decrypt everything read from `reader` to `writer`
+///
+/// Chunks are written as soon as they are authenticated, so a failure part
+/// way through leaves a prefix of the plaintext in `writer`.
+///
+/// * `key`: The payload key
+/// * `reader`: The encrypted chunks
+/// * `writer`: Where to write the plaintext
+/// * `progress`: Advanced by the ciphertext bytes read
+pub fn decrypt<R: Read, W: Write>(
+	key: &[u8; 32],
+	reader: &mut R,
+	writer: &mut W,
+	progress: &mut Progress,
+) -> Result<(), Error> {
+	let mut opener = TldReader::with_key(key, Counted { inner: reader, progress: &mut *progress });
+	io::copy(&mut opener, writer).map_err(payload_error)?;
+	progress.finish();
+	Ok(writer.flush()?)
+}
@@ -0,0 +1,36 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Conversions between unix times and human readable times.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The current unix time
+pub fn now() -> u64 {
+	unix(SystemTime::now())
+}
+
+/// The unix time of a system time (0 before the epoch)
+pub fn unix(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH)
+		.map(|elapsed| elapsed.as_secs())
+		.unwrap_or_default()
+}
+
+/// Format a unix time as an RFC 3339 UTC timestamp
+pub fn format(unix: u64) -> String {
+	humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(unix)).to_string()
+}
@@ -0,0 +1,253 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `vault` command: many labelled entries, each locked to its own round,
+//! in a single file.
+//!
+//! Vaults are [`timelock::vault::Vault`]s: each entry is a label and a
+//! ciphertext holding the whole message, so vaults suit the many small
+//! secrets of a reveal schedule rather than large files. Adding an entry
+//! appends one record to the file rather than rewriting it.
+//!
+//! ```text
+//! timelock vault add reveals.tlv --label answer --in 30d answer.txt
+//! timelock vault list reveals.tlv
+//! timelock vault extract reveals.tlv answer
+//! ```
+
+use crate::{
+	decrypt::{decrypt, decrypt_with_signature, offline_beacon},
+	encrypt::{encrypt, Target},
+	format::TimelockFile,
+	io::{Input, Output},
+	sig, time, Error, DEFAULT_RELAY,
+};
+use clap::{Args, Subcommand};
+use std::{
+	fs::{self, OpenOptions},
+	io::{Read, Write},
+	path::{Path, PathBuf},
+};
+use timelock::{
+	beacons::BeaconPreset,
+	engines::drand::TinyBLS381,
+	vault::{self, VaultEntry},
+};
+use timelock_client::HttpTransport;
+
+/// A vault of ciphertexts for drand's beacons
+pub type Vault = vault::Vault<TinyBLS381>;
+
+/// Manage a file of many timelocked entries
+#[derive(Debug, Args)]
+pub struct VaultArgs {
+	#[command(subcommand)]
+	pub command: VaultCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VaultCommand {
+	/// Encrypt a file into a new entry, creating the vault if needed
+	Add(AddArgs),
+	/// List the entries and when they unlock
+	List(ListArgs),
+	/// Decrypt an entry once its round is published
+	Extract(ExtractArgs),
+}
+
+/// Encrypt a file into a new entry
+#[derive(Debug, Args)]
+pub struct AddArgs {
+	/// The vault
+	pub vault: PathBuf,
+	/// The unique label of the entry
+	#[arg(long)]
+	pub label: String,
+	#[command(flatten)]
+	pub target: Target,
+	/// The file to encrypt (stdin by default)
+	pub input: Option<PathBuf>,
+}
+
+/// List the entries of a vault
+#[derive(Debug, Args)]
+pub struct ListArgs {
+	/// The vault
+	pub vault: PathBuf,
+}
+
+/// Decrypt an entry of a vault
+#[derive(Debug, Args)]
+pub struct ExtractArgs {
+	/// The vault
+	pub vault: PathBuf,
+	/// The label of the entry
+	pub label: String,
+	/// The base URL of the relay to fetch the signature from
+	#[arg(long, default_value = DEFAULT_RELAY)]
+	pub relay: String,
+	/// The signature of the entry's round, as printed by `timelock sig`
+	#[arg(long)]
+	pub signature: Option<String>,
+	/// The public key of the test beacon that made the signature
+	#[arg(long, requires = "signature")]
+	pub public_key: Option<String>,
+	/// The file to write the plaintext to (stdout by default)
+	#[arg(short, long)]
+	pub output: Option<PathBuf>,
+}
+
+/// Read a vault from a file
+///
+/// * `path`: The file
+pub fn load(path: &Path) -> Result<Vault, Error> {
+	Vault::from_bytes(fs::read(path)?).map_err(|_| Error::InvalidCiphertext)
+}
+
+/// The entry of a vault with a label, as a [`TimelockFile`]
+///
+/// * `vault`: The vault
+/// * `label`: The label of the entry
+pub fn file(vault: &Vault, label: &str) -> Result<TimelockFile, Error> {
+	let entry = vault.entry(label).map_err(|e| match e {
+		vault::Error::UnknownLabel => Error::UnknownLabel(label.into()),
+		_ => Error::InvalidCiphertext,
+	})?;
+	Ok(TimelockFile {
+		round: entry.round,
+		chain_hash: entry.chain_hash,
+		ciphertext: entry.ciphertext,
+	})
+}
+
+/// Add an entry to a vault, returning the bytes to append to its file
+///
+/// * `vault`: The vault
+/// * `label`: The label of the entry, which must be unique
+/// * `file`: The ciphertext of the entry
+pub fn append(vault: &mut Vault, label: String, file: TimelockFile) -> Result<&[u8], Error> {
+	let TimelockFile { round, chain_hash, ciphertext } = file;
+	let entry = VaultEntry { label, round, chain_hash, ciphertext };
+	vault.append(&entry).map_err(|e| match e {
+		vault::Error::DuplicateLabel => Error::DuplicateLabel(entry.label.clone()),
+		_ => Error::InvalidCiphertext,
+	})
+}
+
+/// Run the command
+pub async fn run(args: VaultArgs) -> Result<(), Error> {
+	match args.command {
+		VaultCommand::Add(args) => add(args),
+		VaultCommand::List(args) => {
+			print!("{}", list(&load(&args.vault)?, time::now()));
+			Ok(())
+		},
+		VaultCommand::Extract(args) => extract(args).await,
+	}
+}
+
+fn add(args: AddArgs) -> Result<(), Error> {
+	let exists = args.vault.exists();
+	let mut vault = match exists {
+		true => load(&args.vault)?,
+		false => Vault::new(),
+	};
+	let (preset, round) = args.target.resolve()?;
+	let mut message = Vec::new();
+	Input::open(args.input.as_deref())?.reader.read_to_end(&mut message)?;
+	let record = append(&mut vault, args.label, encrypt(&preset, round, &message)?)?;
+	if exists {
+		OpenOptions::new().append(true).open(&args.vault)?.write_all(record)?;
+		return Ok(());
+	}
+	let mut output = Output::create(Some(&args.vault))?;
+	output.write_all(vault.as_bytes())?;
+	output.finish()
+}
+
+/// A table of the entries of a vault and when they unlock
+///
+/// * `vault`: The vault
+/// * `now`: The current unix time
+pub fn list(vault: &Vault, now: u64) -> String {
+	let width = vault.iter().map(|entry| entry.label.len()).max().unwrap_or(0).max(5);
+	let mut table = format!("{:width$}  {:>10}  {:20}  status\n", "label", "round", "unlocks at");
+	for entry in vault.iter() {
+		let chain_hash = array_bytes::bytes2hex("", &entry.chain_hash);
+		let unlock_time = BeaconPreset::by_chain_hash(&chain_hash)
+			.map(|preset| preset.rounds().time_of(entry.round));
+		let (unlocks_at, status) = match unlock_time {
+			Some(time) if time <= now => (time::format(time), "unlocked"),
+			Some(time) => (time::format(time), "locked"),
+			None => ("unknown".into(), "unknown chain"),
+		};
+		table.push_str(&format!(
+			"{:width$}  {:>10}  {:20}  {}\n",
+			entry.label, entry.round, unlocks_at, status
+		));
+	}
+	table
+}
+
+async fn extract(args: ExtractArgs) -> Result<(), Error> {
+	let file = file(&load(&args.vault)?, &args.label)?;
+	let plaintext = match args.signature {
+		Some(signature) => {
+			let beacon = offline_beacon(&file.chain_hash, args.public_key.as_deref())?;
+			decrypt_with_signature(&beacon, file, sig::parse(&signature)?)?
+		},
+		None => decrypt(&HttpTransport::default(), &args.relay, time::now(), file).await?,
+	};
+	let mut output = Output::create(args.output.as_deref())?;
+	output.write_all(&plaintext)?;
+	output.finish()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::decrypt::test::{MockRelay, RELAY};
+	use timelock::beacons::QUICKNET;
+
+	#[tokio::test]
+	async fn vaults_hold_many_entries_locked_to_different_rounds() {
+		let mut vault = Vault::new();
+		let mut stored = vault.as_bytes().to_vec();
+		let early = encrypt(&QUICKNET, 1000, b"first reveal").unwrap();
+		stored.extend_from_slice(append(&mut vault, "early".into(), early).unwrap());
+		let later = encrypt(&QUICKNET, 2000, b"second reveal").unwrap();
+		stored.extend_from_slice(append(&mut vault, "late".into(), later).unwrap());
+		let duplicate = encrypt(&QUICKNET, 3000, b"").unwrap();
+		assert!(matches!(
+			append(&mut vault, "late".into(), duplicate),
+			Err(Error::DuplicateLabel(_))
+		));
+
+		let vault = Vault::from_bytes(stored).unwrap();
+		let table = list(&vault, QUICKNET.rounds().time_of(1500));
+		let lines: Vec<&str> = table.lines().collect();
+		assert_eq!(lines.len(), 3);
+		assert!(lines[1].starts_with("early") && lines[1].ends_with("unlocked"));
+		assert!(lines[2].starts_with("late") && lines[2].ends_with("  locked"));
+		assert!(lines[2].contains("2023-08-23T16:49:24Z"));
+
+		let unlock_time = QUICKNET.rounds().time_of(1000);
+		let early = file(&vault, "early").unwrap();
+		let plaintext = decrypt(&MockRelay::default(), RELAY, unlock_time, early).await;
+		assert_eq!(plaintext.unwrap(), b"first reveal".to_vec());
+		assert!(matches!(file(&vault, "missing"), Err(Error::UnknownLabel(_))));
+	}
+}
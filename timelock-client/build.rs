@@ -0,0 +1,54 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generates the gRPC client and server of the `grpc` feature.
+//!
+//! The service is declared here rather than compiled from
+//! `proto/beacon.proto`, so building does not need protoc.
+
+fn main() {
+	#[cfg(feature = "grpc")]
+	grpc();
+}
+
+#[cfg(feature = "grpc")]
+fn grpc() {
+	use tonic_build::manual::{Builder, Method, Service};
+
+	let method = |name: &str, route: &str, input: &str, output: &str| {
+		Method::builder()
+			.name(name)
+			.route_name(route)
+			.input_type(format!("crate::grpc::{}", input))
+			.output_type(format!("crate::grpc::{}", output))
+			.codec_path("tonic::codec::ProstCodec")
+	};
+	let service = Service::builder()
+		.name("Beacon")
+		.package("timelock.beacon.v1")
+		.method(
+			method("get_chain_info", "GetChainInfo", "ChainInfoRequest", "ChainInfoResponse")
+				.build(),
+		)
+		.method(method("get_round", "GetRound", "RoundRequest", "RoundResponse").build())
+		.method(
+			method("stream_rounds", "StreamRounds", "StreamRoundsRequest", "RoundResponse")
+				.server_streaming()
+				.build(),
+		)
+		.build();
+	Builder::new().compile(&[service]);
+}
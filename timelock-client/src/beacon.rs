@@ -0,0 +1,119 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Verified beacon signatures.
+//!
+//! The signature of an unchained beacon for round `r` is a BLS signature on
+//! sha256(r as u64 BE), which is exactly the IBE secret of the identity
+//! timelock ciphertexts for that round are encrypted to. Signatures fetched
+//! from a relay are checked against the pinned public key before use.
+
+use crate::{ChainInfo, Error, Transport};
+use ark_ec::PrimeGroup;
+use ark_serialize::CanonicalDeserialize;
+use serde::{Deserialize, Serialize};
+use timelock::{engines::EngineBLS, tlock::BeaconConfig};
+
+/// A beacon, in the format served at `/<hash>/public/<round>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Beacon {
+	/// The round of the beacon
+	pub round: u64,
+	/// The compressed signature on the round
+	#[serde(with = "crate::chain_info::hex")]
+	pub signature: Vec<u8>,
+}
+
+/// Check a signature of the beacon for a round
+///
+/// * `p_pub`: The public key of the beacon
+/// * `round`: The round of the signature
+/// * `signature`: The signature
+pub fn verify_signature<E: EngineBLS>(
+	p_pub: E::PublicKeyGroup,
+	round: u64,
+	signature: E::SignatureGroup,
+) -> Result<(), Error> {
+	let message = BeaconConfig::<E>::identity(round).public::<E>();
+	if E::pairing(p_pub, message) != E::pairing(E::PublicKeyGroup::generator(), signature) {
+		return Err(Error::InvalidSignature);
+	}
+	Ok(())
+}
+
+/// Fetch and verify the signature of a chain for a round
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay
+/// * `info`: The (validated) chain info of the chain
+/// * `round`: The round to fetch
+pub async fn fetch_signature<E: EngineBLS, T: Transport>(
+	transport: &T,
+	relay: &str,
+	info: &ChainInfo,
+	round: u64,
+) -> Result<E::SignatureGroup, Error> {
+	let url = format!(
+		"{}/{}/public/{}",
+		relay.trim_end_matches('/'),
+		array_bytes::bytes2hex("", &info.hash),
+		round
+	);
+	let beacon: Beacon =
+		serde_json::from_slice(&transport.get(&url).await?).map_err(|_| Error::InvalidResponse)?;
+	if beacon.round != round {
+		return Err(Error::InvalidResponse);
+	}
+	let signature = E::SignatureGroup::deserialize_compressed(&beacon.signature[..])
+		.map_err(|_| Error::InvalidSignature)?;
+	verify_signature::<E>(info.public_key::<E>()?, round, signature)?;
+	Ok(signature)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+	use super::*;
+	use crate::chain_info::test::{MockTransport, QUICKNET_HASH, QUICKNET_INFO};
+	use timelock::engines::drand::TinyBLS381;
+
+	pub const QUICKNET_BEACON_1000: &str = r#"{
+		"round": 1000,
+		"signature": "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39"
+	}"#;
+
+	#[tokio::test]
+	async fn fetch_signature_verifies_the_signature() {
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		let url = format!("https://relay.example/{}/public/1000", QUICKNET_HASH);
+		let mut transport = MockTransport::default();
+		transport.0.insert(url.clone(), QUICKNET_BEACON_1000.as_bytes().to_vec());
+
+		let result =
+			fetch_signature::<TinyBLS381, _>(&transport, "https://relay.example", &info, 1000)
+				.await;
+		assert!(result.is_ok());
+
+		// the signature of round 1000 served for round 1001 is rejected
+		let url = format!("https://relay.example/{}/public/1001", QUICKNET_HASH);
+		transport
+			.0
+			.insert(url, QUICKNET_BEACON_1000.replace("1000", "1001").into_bytes());
+		let result =
+			fetch_signature::<TinyBLS381, _>(&transport, "https://relay.example", &info, 1001)
+				.await;
+		assert!(matches!(result, Err(Error::InvalidSignature)));
+	}
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Chain info of drand beacons, pinned to a chain hash.
+//!
+//! A drand chain is identified by
+//!
+//! ```text
+//! hash = sha256(period as u32 BE || genesis_time as i64 BE || public_key || group_hash || beacon_id)
+//! ```
+//!
+//! where the beacon id is omitted for the `default` beacon. Checking a fetched
+//! chain info against this hash, and the hash against one the user pinned,
+//! means the public key used to encrypt is the right one even if the relay is
+//! not trusted.
+
+use crate::{Error, Transport};
+use ark_serialize::CanonicalDeserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use timelock::{engines::EngineBLS, rounds::RoundCalculator};
+
+/// The beacon id that is not included in the chain hash
+const DEFAULT_BEACON_ID: &str = "default";
+
+/// The metadata of a chain
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainMetadata {
+	/// The id of the beacon within its drand network (e.g. `quicknet`)
+	#[serde(rename = "beaconID", default)]
+	pub beacon_id: String,
+}
+
+/// The parameters of a beacon chain, in the format served at `/<hash>/info`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainInfo {
+	/// The compressed public key of the beacon
+	#[serde(with = "hex")]
+	pub public_key: Vec<u8>,
+	/// The number of seconds between rounds
+	pub period: u64,
+	/// The unix time of the first round
+	pub genesis_time: u64,
+	/// The hash identifying the chain
+	#[serde(with = "hex")]
+	pub hash: Vec<u8>,
+	/// The hash of the group that runs the beacon
+	#[serde(rename = "groupHash", with = "hex")]
+	pub group_hash: Vec<u8>,
+	/// The signature scheme (e.g. `bls-unchained-g1-rfc9380`)
+	#[serde(rename = "schemeID")]
+	pub scheme_id: String,
+	/// The metadata of the chain
+	#[serde(default)]
+	pub metadata: ChainMetadata,
+}
+
+impl ChainInfo {
+	/// Parse the JSON served by a relay, checking the chain hash
+	///
+	/// * `json`: The body of a `/<hash>/info` response
+	pub fn from_json(json: &[u8]) -> Result<Self, Error> {
+		let info: Self = serde_json::from_slice(json).map_err(|_| Error::InvalidResponse)?;
+		info.validate()?;
+		Ok(info)
+	}
+
+	/// Compute the hash identifying the chain from its parameters
+	pub fn compute_hash(&self) -> [u8; 32] {
+		let mut hasher = Sha256::new();
+		hasher.update((self.period as u32).to_be_bytes());
+		hasher.update((self.genesis_time as i64).to_be_bytes());
+		hasher.update(&self.public_key);
+		hasher.update(&self.group_hash);
+		let beacon_id = &self.metadata.beacon_id;
+		if !beacon_id.is_empty() && beacon_id != DEFAULT_BEACON_ID {
+			hasher.update(beacon_id.as_bytes());
+		}
+		hasher.finalize().into()
+	}
+
+	/// Check that the chain hash matches the parameters, and that the period is
+	/// valid
+	pub fn validate(&self) -> Result<(), Error> {
+		if self.period == 0 ||
+			self.period > u32::MAX as u64 ||
+			self.compute_hash()[..] != self.hash[..]
+		{
+			return Err(Error::ChainHashMismatch);
+		}
+		Ok(())
+	}
+
+	/// A calculator for the rounds of the chain
+	pub fn round_calculator(&self) -> RoundCalculator {
+		RoundCalculator::new(self.genesis_time, self.period)
+	}
+
+	/// The public key of the beacon as a group element of the engine `E`
+	pub fn public_key<E: EngineBLS>(&self) -> Result<E::PublicKeyGroup, Error> {
+		E::PublicKeyGroup::deserialize_compressed(&self.public_key[..])
+			.map_err(|_| Error::InvalidPublicKey)
+	}
+}
+
+/// Fetch and validate the chain info of a chain from a relay
+///
+/// Fails with [`Error::ChainHashMismatch`] unless the relay serves info whose
+/// parameters hash to `chain_hash`.
+///
+/// * `transport`: The transport to reach the relay with
+/// * `relay`: The base URL of the relay (e.g. `https://api.drand.sh`)
+/// * `chain_hash`: The pinned hash of the chain
+pub async fn fetch_chain_info<T: Transport>(
+	transport: &T,
+	relay: &str,
+	chain_hash: &[u8],
+) -> Result<ChainInfo, Error> {
+	let url =
+		format!("{}/{}/info", relay.trim_end_matches('/'), array_bytes::bytes2hex("", chain_hash));
+	let info = ChainInfo::from_json(&transport.get(&url).await?)?;
+	if info.hash != chain_hash {
+		return Err(Error::ChainHashMismatch);
+	}
+	Ok(info)
+}
+
+/// (De)serialization of bytes as lowercase hex
+pub(crate) mod hex {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&array_bytes::bytes2hex("", bytes))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		array_bytes::hex2bytes(&s).map_err(|_| serde::de::Error::custom("invalid hex"))
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+	use super::*;
+	use std::{collections::HashMap, future::Future};
+	use timelock::engines::drand::TinyBLS381;
+
+	pub const QUICKNET_INFO: &str = r#"{
+		"public_key": "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a",
+		"period": 3,
+		"genesis_time": 1692803367,
+		"hash": "52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971",
+		"groupHash": "f477d5c89f21a17c863a7f937c6a6d15859414d2be09cd448d4279af331c5d3e",
+		"schemeID": "bls-unchained-g1-rfc9380",
+		"metadata": { "beaconID": "quicknet" }
+	}"#;
+	pub const QUICKNET_HASH: &str =
+		"52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971";
+
+	const MAINNET_INFO: &str = r#"{
+		"public_key": "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31",
+		"period": 30,
+		"genesis_time": 1595431050,
+		"hash": "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce",
+		"groupHash": "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a",
+		"schemeID": "pedersen-bls-chained",
+		"metadata": { "beaconID": "default" }
+	}"#;
+
+	/// A transport serving fixed responses
+	#[derive(Default)]
+	pub struct MockTransport(pub HashMap<String, Vec<u8>>);
+
+	impl Transport for MockTransport {
+		fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+			let response = self.0.get(url).cloned().ok_or(Error::Transport(url.into()));
+			async move { response }
+		}
+	}
+
+	#[test]
+	fn chain_info_hash_is_validated() {
+		for json in [QUICKNET_INFO, MAINNET_INFO] {
+			let info = ChainInfo::from_json(json.as_bytes()).unwrap();
+			assert_eq!(info.compute_hash()[..], info.hash[..]);
+		}
+
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		assert_eq!(info.period, 3);
+		assert_eq!(info.genesis_time, 1692803367);
+		assert_eq!(info.scheme_id, "bls-unchained-g1-rfc9380");
+		assert!(info.public_key::<TinyBLS381>().is_ok());
+		assert_eq!(info.round_calculator().time_of(1000), 1692806364);
+
+		// a swapped public key no longer hashes to the chain hash
+		let swapped = QUICKNET_INFO.replace("83cf0f28", "a0b862a7");
+		assert_eq!(ChainInfo::from_json(swapped.as_bytes()), Err(Error::ChainHashMismatch));
+	}
+
+	#[tokio::test]
+	async fn fetch_chain_info_pins_the_hash() {
+		let pinned = array_bytes::hex2bytes(QUICKNET_HASH).unwrap();
+		let url = format!("https://relay.example/{}/info", QUICKNET_HASH);
+		let mut transport = MockTransport::default();
+		transport.0.insert(url.clone(), QUICKNET_INFO.as_bytes().to_vec());
+
+		let info = fetch_chain_info(&transport, "https://relay.example/", &pinned).await.unwrap();
+		assert_eq!(info.hash, pinned);
+
+		// a relay serving another (valid) chain for the pinned hash is rejected
+		transport.0.insert(url, MAINNET_INFO.as_bytes().to_vec());
+		let result = fetch_chain_info(&transport, "https://relay.example", &pinned).await;
+		assert_eq!(result, Err(Error::ChainHashMismatch));
+	}
+}
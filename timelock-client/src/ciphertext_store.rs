@@ -0,0 +1,361 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Storage for timelocked ciphertexts.
+//!
+//! Services holding many timelocked blobs store them in a
+//! [`CiphertextStore`] under caller chosen ids, together with the round each
+//! is locked to. Listing the ids locked to a round lets a service decrypt
+//! everything that unlocks as soon as the round's signature is published.
+//! Ciphertexts are opaque bytes to a store, so any encoding can be kept.
+//!
+//! [`MemoryCiphertextStore`] is always available; the `sled` and `sqlite`
+//! features add stores backed by sled and SQLite databases.
+
+use crate::Error;
+use std::{
+	collections::BTreeMap,
+	sync::{Mutex, PoisonError},
+};
+
+/// A ciphertext and the round it is locked to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredCiphertext {
+	/// The round the ciphertext is locked to
+	pub round: u64,
+	/// The ciphertext
+	pub ciphertext: Vec<u8>,
+}
+
+/// A store of ciphertexts by id, indexed by round
+pub trait CiphertextStore {
+	/// Store a ciphertext, replacing any ciphertext with the same id
+	///
+	/// * `id`: The id of the ciphertext
+	/// * `round`: The round the ciphertext is locked to
+	/// * `ciphertext`: The ciphertext
+	fn put(&self, id: &[u8], round: u64, ciphertext: &[u8]) -> Result<(), Error>;
+
+	/// The ciphertext with an id, if any
+	///
+	/// * `id`: The id of the ciphertext
+	fn get(&self, id: &[u8]) -> Result<Option<StoredCiphertext>, Error>;
+
+	/// The ids of the ciphertexts locked to a round, in order
+	///
+	/// * `round`: The round
+	fn list_by_round(&self, round: u64) -> Result<Vec<Vec<u8>>, Error>;
+
+	/// Delete the ciphertext with an id, returning whether there was one
+	///
+	/// * `id`: The id of the ciphertext
+	fn delete(&self, id: &[u8]) -> Result<bool, Error>;
+}
+
+/// A ciphertext store held in memory
+#[derive(Debug, Default)]
+pub struct MemoryCiphertextStore {
+	ciphertexts: Mutex<BTreeMap<Vec<u8>, StoredCiphertext>>,
+}
+
+impl CiphertextStore for MemoryCiphertextStore {
+	fn put(&self, id: &[u8], round: u64, ciphertext: &[u8]) -> Result<(), Error> {
+		let mut ciphertexts = self.ciphertexts.lock().unwrap_or_else(PoisonError::into_inner);
+		ciphertexts
+			.insert(id.to_vec(), StoredCiphertext { round, ciphertext: ciphertext.to_vec() });
+		Ok(())
+	}
+
+	fn get(&self, id: &[u8]) -> Result<Option<StoredCiphertext>, Error> {
+		let ciphertexts = self.ciphertexts.lock().unwrap_or_else(PoisonError::into_inner);
+		Ok(ciphertexts.get(id).cloned())
+	}
+
+	fn list_by_round(&self, round: u64) -> Result<Vec<Vec<u8>>, Error> {
+		let ciphertexts = self.ciphertexts.lock().unwrap_or_else(PoisonError::into_inner);
+		Ok(ciphertexts
+			.iter()
+			.filter(|(_, stored)| stored.round == round)
+			.map(|(id, _)| id.clone())
+			.collect())
+	}
+
+	fn delete(&self, id: &[u8]) -> Result<bool, Error> {
+		let mut ciphertexts = self.ciphertexts.lock().unwrap_or_else(PoisonError::into_inner);
+		Ok(ciphertexts.remove(id).is_some())
+	}
+}
+
+#[cfg(feature = "sled")]
+pub use self::sled::SledCiphertextStore;
+
+#[cfg(feature = "sled")]
+mod sled {
+	use super::{CiphertextStore, StoredCiphertext};
+	use crate::Error;
+	use ::sled::{
+		transaction::{ConflictableTransactionError, TransactionError},
+		Db, Transactional, Tree,
+	};
+	use std::path::Path;
+
+	/// A ciphertext store in a sled database
+	///
+	/// Ciphertexts are kept in the `ciphertexts` tree as the big-endian round
+	/// followed by the ciphertext, and indexed in the `rounds` tree by the
+	/// big-endian round followed by the id.
+	#[derive(Debug, Clone)]
+	pub struct SledCiphertextStore {
+		ciphertexts: Tree,
+		rounds: Tree,
+	}
+
+	impl SledCiphertextStore {
+		/// A store in the database at a path, which is created if needed
+		pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+			Self::new(&::sled::open(path).map_err(store_error)?)
+		}
+
+		/// A store in an open database
+		pub fn new(db: &Db) -> Result<Self, Error> {
+			Ok(Self {
+				ciphertexts: db.open_tree("ciphertexts").map_err(store_error)?,
+				rounds: db.open_tree("rounds").map_err(store_error)?,
+			})
+		}
+	}
+
+	impl CiphertextStore for SledCiphertextStore {
+		fn put(&self, id: &[u8], round: u64, ciphertext: &[u8]) -> Result<(), Error> {
+			let value = [&round.to_be_bytes()[..], ciphertext].concat();
+			(&self.ciphertexts, &self.rounds)
+				.transaction(|(ciphertexts, rounds)| {
+					if let Some(previous) = ciphertexts.insert(id, &value[..])? {
+						rounds.remove(index_key(&previous[..8], id))?;
+					}
+					rounds.insert(index_key(&round.to_be_bytes(), id), &[])?;
+					Ok::<_, ConflictableTransactionError>(())
+				})
+				.map_err(transaction_error)
+		}
+
+		fn get(&self, id: &[u8]) -> Result<Option<StoredCiphertext>, Error> {
+			let Some(value) = self.ciphertexts.get(id).map_err(store_error)? else {
+				return Ok(None);
+			};
+			let (round, ciphertext) = value
+				.split_first_chunk::<8>()
+				.ok_or_else(|| Error::Store("a stored ciphertext is truncated".into()))?;
+			Ok(Some(StoredCiphertext {
+				round: u64::from_be_bytes(*round),
+				ciphertext: ciphertext.to_vec(),
+			}))
+		}
+
+		fn list_by_round(&self, round: u64) -> Result<Vec<Vec<u8>>, Error> {
+			self.rounds
+				.scan_prefix(round.to_be_bytes())
+				.keys()
+				.map(|key| key.map(|key| key[8..].to_vec()).map_err(store_error))
+				.collect()
+		}
+
+		fn delete(&self, id: &[u8]) -> Result<bool, Error> {
+			(&self.ciphertexts, &self.rounds)
+				.transaction(|(ciphertexts, rounds)| {
+					let previous = ciphertexts.remove(id)?;
+					if let Some(previous) = &previous {
+						rounds.remove(index_key(&previous[..8], id))?;
+					}
+					Ok::<_, ConflictableTransactionError>(previous.is_some())
+				})
+				.map_err(transaction_error)
+		}
+	}
+
+	/// The key of an id in the `rounds` tree
+	fn index_key(round: &[u8], id: &[u8]) -> Vec<u8> {
+		[round, id].concat()
+	}
+
+	fn store_error(e: ::sled::Error) -> Error {
+		Error::Store(e.to_string())
+	}
+
+	fn transaction_error(e: TransactionError) -> Error {
+		Error::Store(e.to_string())
+	}
+}
+
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteCiphertextStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+	use super::{CiphertextStore, StoredCiphertext};
+	use crate::Error;
+	use rusqlite::{params, Connection, OptionalExtension};
+	use std::{
+		path::Path,
+		sync::{Mutex, PoisonError},
+	};
+
+	/// A ciphertext store in the `ciphertexts` table of a SQLite database
+	#[derive(Debug)]
+	pub struct SqliteCiphertextStore {
+		connection: Mutex<Connection>,
+	}
+
+	impl SqliteCiphertextStore {
+		/// A store in the database at a path, which is created if needed
+		pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+			Self::new(Connection::open(path).map_err(store_error)?)
+		}
+
+		/// A store in a database held in memory
+		pub fn in_memory() -> Result<Self, Error> {
+			Self::new(Connection::open_in_memory().map_err(store_error)?)
+		}
+
+		/// A store in an open database, creating its table if needed
+		pub fn new(connection: Connection) -> Result<Self, Error> {
+			connection
+				.execute_batch(
+					"CREATE TABLE IF NOT EXISTS ciphertexts (
+						id BLOB PRIMARY KEY,
+						round INTEGER NOT NULL,
+						ciphertext BLOB NOT NULL
+					);
+					CREATE INDEX IF NOT EXISTS ciphertexts_by_round ON ciphertexts (round, id);",
+				)
+				.map_err(store_error)?;
+			Ok(Self { connection: Mutex::new(connection) })
+		}
+
+		fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+			self.connection.lock().unwrap_or_else(PoisonError::into_inner)
+		}
+	}
+
+	impl CiphertextStore for SqliteCiphertextStore {
+		fn put(&self, id: &[u8], round: u64, ciphertext: &[u8]) -> Result<(), Error> {
+			self.connection()
+				.execute(
+					"INSERT OR REPLACE INTO ciphertexts (id, round, ciphertext) VALUES (?1, ?2, ?3)",
+					params![id, to_sql(round)?, ciphertext],
+				)
+				.map_err(store_error)?;
+			Ok(())
+		}
+
+		fn get(&self, id: &[u8]) -> Result<Option<StoredCiphertext>, Error> {
+			let row = self
+				.connection()
+				.query_row(
+					"SELECT round, ciphertext FROM ciphertexts WHERE id = ?1",
+					params![id],
+					|row| Ok((row.get::<_, i64>(0)?, row.get(1)?)),
+				)
+				.optional()
+				.map_err(store_error)?;
+			row.map(|(round, ciphertext)| {
+				Ok(StoredCiphertext { round: from_sql(round)?, ciphertext })
+			})
+			.transpose()
+		}
+
+		fn list_by_round(&self, round: u64) -> Result<Vec<Vec<u8>>, Error> {
+			let connection = self.connection();
+			let mut statement = connection
+				.prepare("SELECT id FROM ciphertexts WHERE round = ?1 ORDER BY id")
+				.map_err(store_error)?;
+			let ids = statement
+				.query_map(params![to_sql(round)?], |row| row.get(0))
+				.map_err(store_error)?;
+			ids.collect::<Result<_, _>>().map_err(store_error)
+		}
+
+		fn delete(&self, id: &[u8]) -> Result<bool, Error> {
+			let deleted = self
+				.connection()
+				.execute("DELETE FROM ciphertexts WHERE id = ?1", params![id])
+				.map_err(store_error)?;
+			Ok(deleted > 0)
+		}
+	}
+
+	/// SQLite integers are signed, so rounds past `i64::MAX` cannot be stored
+	fn to_sql(round: u64) -> Result<i64, Error> {
+		i64::try_from(round).map_err(|_| Error::Store("the round is too large to store".into()))
+	}
+
+	fn from_sql(round: i64) -> Result<u64, Error> {
+		u64::try_from(round).map_err(|_| Error::Store("a stored round is negative".into()))
+	}
+
+	fn store_error(e: rusqlite::Error) -> Error {
+		Error::Store(e.to_string())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn exercise<S: CiphertextStore>(store: &S) {
+		store.put(b"b", 1000, b"second").unwrap();
+		store.put(b"a", 1000, b"first").unwrap();
+		store.put(b"c", 2000, b"third").unwrap();
+		assert_eq!(
+			store.get(b"a").unwrap(),
+			Some(StoredCiphertext { round: 1000, ciphertext: b"first".to_vec() })
+		);
+		assert_eq!(store.get(b"d").unwrap(), None);
+		assert_eq!(store.list_by_round(1000).unwrap(), [b"a".to_vec(), b"b".to_vec()]);
+		assert!(store.list_by_round(3000).unwrap().is_empty());
+
+		// replacing a ciphertext moves it to its new round
+		store.put(b"b", 2000, b"second again").unwrap();
+		assert_eq!(store.list_by_round(1000).unwrap(), [b"a".to_vec()]);
+		assert_eq!(store.list_by_round(2000).unwrap(), [b"b".to_vec(), b"c".to_vec()]);
+		assert_eq!(store.get(b"b").unwrap().unwrap().ciphertext, b"second again");
+
+		assert!(store.delete(b"c").unwrap());
+		assert!(!store.delete(b"c").unwrap());
+		assert_eq!(store.get(b"c").unwrap(), None);
+		assert_eq!(store.list_by_round(2000).unwrap(), [b"b".to_vec()]);
+	}
+
+	#[test]
+	fn memory_store_indexes_ciphertexts_by_round() {
+		exercise(&MemoryCiphertextStore::default());
+	}
+
+	#[cfg(feature = "sled")]
+	#[test]
+	fn sled_store_indexes_ciphertexts_by_round() {
+		let db = ::sled::Config::new().temporary(true).open().unwrap();
+		exercise(&SledCiphertextStore::new(&db).unwrap());
+	}
+
+	#[cfg(feature = "sqlite")]
+	#[test]
+	fn sqlite_store_indexes_ciphertexts_by_round() {
+		let store = SqliteCiphertextStore::in_memory().unwrap();
+		exercise(&store);
+		assert!(matches!(store.put(b"e", u64::MAX, b""), Err(Error::Store(_))));
+	}
+}
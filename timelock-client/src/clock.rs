@@ -0,0 +1,47 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The clocks used to wait for rounds.
+
+use std::{future::Future, time::Duration};
+
+/// A source of the current time that can wait
+pub trait Clock {
+	/// The current unix time in seconds
+	fn now(&self) -> u64;
+
+	/// Wait for `duration`
+	fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send;
+}
+
+/// The system clock, sleeping on the tokio timer
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+#[cfg(feature = "tokio")]
+impl Clock for TokioClock {
+	fn now(&self) -> u64 {
+		std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|elapsed| elapsed.as_secs())
+			.unwrap_or_default()
+	}
+
+	fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+		tokio::time::sleep(duration)
+	}
+}
@@ -0,0 +1,190 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Decryption of ciphertexts once their round is published.
+
+use crate::{beacon::fetch_signature, ChainInfo, Clock, Error, Transport};
+use std::time::Duration;
+use timelock::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	tlock::{tld, Armored, TLECiphertext},
+};
+
+/// The number of times a published round is fetched before giving up
+pub const MAX_FETCH_ATTEMPTS: u32 = 5;
+
+/// Wait until the round of a ciphertext is published, then decrypt it
+///
+/// The round is read from the `Round` header of the armored ciphertext. If
+/// the `Chain-Hash` header is present it must match the chain. Relays can lag
+/// behind the schedule, so fetching is retried every period up to
+/// [`MAX_FETCH_ATTEMPTS`] times.
+///
+/// * `transport`: The transport to reach the relay with
+/// * `clock`: The clock to wait with
+/// * `relay`: The base URL of the relay
+/// * `info`: The (validated) chain info of the chain
+/// * `ciphertext`: An armored [`TLECiphertext`] with a round header
+pub async fn wait_and_decrypt<E, S, T, C>(
+	transport: &T,
+	clock: &C,
+	relay: &str,
+	info: &ChainInfo,
+	ciphertext: &Armored,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	T: Transport,
+	C: Clock,
+{
+	let round = ciphertext.round().ok_or(Error::InvalidCiphertext)?;
+	if ciphertext.chain_hash().is_some_and(|hash| hash != info.hash) {
+		return Err(Error::ChainHashMismatch);
+	}
+	let ciphertext: TLECiphertext<E> =
+		ciphertext.to_value().map_err(|_| Error::InvalidCiphertext)?;
+
+	let published_at = info.round_calculator().time_of(round);
+	let now = clock.now();
+	if now < published_at {
+		clock.sleep(Duration::from_secs(published_at - now)).await;
+	}
+
+	let mut attempts = 1;
+	let signature = loop {
+		match fetch_signature::<E, T>(transport, relay, info, round).await {
+			Err(Error::Transport(_)) if attempts < MAX_FETCH_ATTEMPTS => {
+				attempts += 1;
+				clock.sleep(Duration::from_secs(info.period)).await;
+			},
+			result => break result?,
+		}
+	};
+	tld::<E, S>(ciphertext, signature).map_err(|_| Error::InvalidCiphertext)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacon::test::QUICKNET_BEACON_1000,
+		chain_info::test::{MockTransport, QUICKNET_HASH, QUICKNET_INFO},
+	};
+	use std::{
+		future::Future,
+		sync::{Arc, Mutex},
+	};
+	use timelock::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{encrypt_at_time, BeaconConfig},
+	};
+
+	const RELAY: &str = "https://relay.example";
+
+	/// A clock that advances when slept on, publishing a beacon at the given
+	/// time
+	struct MockClock {
+		now: Mutex<u64>,
+		transport: Arc<Mutex<MockTransport>>,
+		publish: (u64, String, Vec<u8>),
+	}
+
+	impl Clock for MockClock {
+		fn now(&self) -> u64 {
+			*self.now.lock().unwrap()
+		}
+
+		fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+			let mut now = self.now.lock().unwrap();
+			*now += duration.as_secs();
+			if *now >= self.publish.0 {
+				let (_, url, body) = &self.publish;
+				self.transport.lock().unwrap().0.insert(url.clone(), body.clone());
+			}
+			async {}
+		}
+	}
+
+	struct SharedTransport(Arc<Mutex<MockTransport>>);
+
+	impl Transport for SharedTransport {
+		fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+			let response = self.0.lock().unwrap().0.get(url).cloned();
+			async move { response.ok_or(Error::Transport("not found".into())) }
+		}
+	}
+
+	fn setup(publish_at: u64) -> (SharedTransport, MockClock, ChainInfo, Armored) {
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		let config = BeaconConfig::<TinyBLS381> {
+			p_pub: info.public_key::<TinyBLS381>().unwrap(),
+			rounds: info.round_calculator(),
+		};
+		let (ciphertext, round) = encrypt_at_time::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			&config,
+			config.rounds.time_of(1000),
+			b"dead simple tlock",
+			ark_std::rand::rngs::OsRng,
+		)
+		.unwrap();
+		let armored = Armored::from_value(&ciphertext).with_round(round, &info.hash);
+
+		let transport = Arc::new(Mutex::new(MockTransport::default()));
+		let clock = MockClock {
+			now: Mutex::new(config.rounds.time_of(990)),
+			transport: transport.clone(),
+			publish: (
+				publish_at,
+				format!("{}/{}/public/1000", RELAY, QUICKNET_HASH),
+				QUICKNET_BEACON_1000.as_bytes().to_vec(),
+			),
+		};
+		(SharedTransport(transport), clock, info, armored)
+	}
+
+	#[tokio::test]
+	async fn wait_and_decrypt_waits_for_the_round() {
+		// the relay lags two periods behind the schedule
+		let (transport, clock, info, armored) = setup(1692806364 + 6);
+		let plaintext = wait_and_decrypt::<TinyBLS381, AESGCMBlockCipherProvider, _, _>(
+			&transport, &clock, RELAY, &info, &armored,
+		)
+		.await;
+		assert_eq!(plaintext.unwrap(), b"dead simple tlock".to_vec());
+		assert_eq!(clock.now(), 1692806364 + 6);
+	}
+
+	#[tokio::test]
+	async fn wait_and_decrypt_gives_up_on_an_unavailable_relay() {
+		let (transport, clock, info, armored) = setup(u64::MAX);
+		let result = wait_and_decrypt::<TinyBLS381, AESGCMBlockCipherProvider, _, _>(
+			&transport, &clock, RELAY, &info, &armored,
+		)
+		.await;
+		assert!(matches!(result, Err(Error::Transport(_))));
+
+		// a ciphertext for another chain is rejected before waiting
+		let armored = Armored::new(armored.data).with_round(1000, &[0; 32]);
+		let result = wait_and_decrypt::<TinyBLS381, AESGCMBlockCipherProvider, _, _>(
+			&transport, &clock, RELAY, &info, &armored,
+		)
+		.await;
+		assert!(matches!(result, Err(Error::ChainHashMismatch)));
+	}
+}
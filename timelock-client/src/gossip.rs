@@ -0,0 +1,232 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Beacons published over libp2p gossipsub.
+//!
+//! drand relays publish every round on the topic
+//! `/drand/pubsub/v0.0.0/<chain hash>` as a protobuf `PublicRandResponse`, of
+//! which only the round (field 1) and the signature (field 2) are needed.
+//! Listening there delivers rounds as soon as they are produced, without
+//! polling a relay over HTTP. Every message is verified against the chain's
+//! public key, so the peers need not be trusted.
+//!
+//! Decoding and verification are always available; the `gossipsub` feature
+//! adds [`subscribe`], which runs a libp2p node on tokio.
+
+use crate::{beacon::verify_signature, Beacon, ChainInfo, Error};
+use ark_serialize::CanonicalDeserialize;
+use timelock::engines::EngineBLS;
+
+#[cfg(feature = "gossipsub")]
+pub use libp2p::Multiaddr;
+
+/// The prefix of the gossipsub topics of drand chains
+pub const TOPIC_PREFIX: &str = "/drand/pubsub/v0.0.0/";
+
+/// The gossipsub topic a chain is published on
+///
+/// * `chain_hash`: The hash of the chain
+pub fn topic(chain_hash: &[u8]) -> String {
+	format!("{}{}", TOPIC_PREFIX, array_bytes::bytes2hex("", chain_hash))
+}
+
+/// Decode the round and signature of a gossiped `PublicRandResponse`
+///
+/// * `message`: The protobuf encoded message
+pub fn decode_beacon(message: &[u8]) -> Result<Beacon, Error> {
+	let mut round = 0;
+	let mut signature = Vec::new();
+	let mut input = message;
+	while !input.is_empty() {
+		let key = read_varint(&mut input)?;
+		match (key >> 3, key & 7) {
+			(1, 0) => round = read_varint(&mut input)?,
+			(2, 2) => signature = read_bytes(&mut input)?.to_vec(),
+			// other fields (previous signature, randomness, metadata) are skipped
+			(_, 0) => {
+				read_varint(&mut input)?;
+			},
+			(_, 1) => input = input.get(8..).ok_or(Error::InvalidResponse)?,
+			(_, 2) => {
+				read_bytes(&mut input)?;
+			},
+			(_, 5) => input = input.get(4..).ok_or(Error::InvalidResponse)?,
+			_ => return Err(Error::InvalidResponse),
+		}
+	}
+	Ok(Beacon { round, signature })
+}
+
+/// Decode and verify a gossiped beacon of a chain
+///
+/// * `info`: The (validated) chain info of the chain
+/// * `message`: The protobuf encoded message
+pub fn verify_message<E: EngineBLS>(
+	info: &ChainInfo,
+	message: &[u8],
+) -> Result<(u64, E::SignatureGroup), Error> {
+	let beacon = decode_beacon(message)?;
+	let signature = E::SignatureGroup::deserialize_compressed(&beacon.signature[..])
+		.map_err(|_| Error::InvalidSignature)?;
+	verify_signature::<E>(info.public_key::<E>()?, beacon.round, signature)?;
+	Ok((beacon.round, signature))
+}
+
+/// Listen for the rounds of a chain over gossipsub
+///
+/// Connects to `peers`, subscribes to the chain's topic, and calls
+/// `on_beacon` with the round and verified signature of each new round until
+/// it returns [`ControlFlow::Break`](core::ops::ControlFlow::Break). Messages
+/// that do not verify and rounds that were already seen are dropped.
+///
+/// * `info`: The (validated) chain info of the chain
+/// * `peers`: The addresses of gossipsub peers of the chain (e.g. relays)
+/// * `on_beacon`: The callback to invoke with each new round
+#[cfg(feature = "gossipsub")]
+pub async fn subscribe<E, F>(
+	info: &ChainInfo,
+	peers: &[Multiaddr],
+	mut on_beacon: F,
+) -> Result<(), Error>
+where
+	E: EngineBLS,
+	F: FnMut(u64, E::SignatureGroup) -> core::ops::ControlFlow<()>,
+{
+	use futures_util::StreamExt;
+	use libp2p::{gossipsub, noise, swarm::SwarmEvent, tcp, yamux};
+
+	let transport_error = |e: &dyn std::fmt::Display| Error::Transport(e.to_string());
+	let mut swarm = libp2p::SwarmBuilder::with_new_identity()
+		.with_tokio()
+		.with_tcp(tcp::Config::default(), noise::Config::new, yamux::Config::default)
+		.map_err(|e| transport_error(&e))?
+		.with_dns()
+		.map_err(|e| transport_error(&e))?
+		.with_behaviour(|key| {
+			gossipsub::Behaviour::<gossipsub::IdentityTransform>::new(
+				gossipsub::MessageAuthenticity::Signed(key.clone()),
+				gossipsub::Config::default(),
+			)
+			.map_err(Box::<dyn std::error::Error + Send + Sync>::from)
+		})
+		.map_err(|e| transport_error(&e))?
+		.with_swarm_config(|config| {
+			config.with_idle_connection_timeout(std::time::Duration::from_secs(60))
+		})
+		.build();
+
+	swarm
+		.behaviour_mut()
+		.subscribe(&gossipsub::IdentTopic::new(topic(&info.hash)))
+		.map_err(|e| transport_error(&e))?;
+	for peer in peers {
+		swarm.dial(peer.clone()).map_err(|e| transport_error(&e))?;
+	}
+
+	let mut latest = 0;
+	loop {
+		if let SwarmEvent::Behaviour(gossipsub::Event::Message { message, .. }) =
+			swarm.select_next_some().await
+		{
+			let Ok((round, signature)) = verify_message::<E>(info, &message.data) else {
+				continue;
+			};
+			if round <= latest {
+				continue;
+			}
+			latest = round;
+			if on_beacon(round, signature).is_break() {
+				return Ok(());
+			}
+		}
+	}
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64, Error> {
+	let mut value = 0u64;
+	for shift in (0..64).step_by(7) {
+		let (byte, rest) = input.split_first().ok_or(Error::InvalidResponse)?;
+		*input = rest;
+		value |= u64::from(byte & 0x7f) << shift;
+		if byte & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err(Error::InvalidResponse)
+}
+
+fn read_bytes<'a>(input: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+	let len = usize::try_from(read_varint(input)?).map_err(|_| Error::InvalidResponse)?;
+	if input.len() < len {
+		return Err(Error::InvalidResponse);
+	}
+	let (bytes, rest) = input.split_at(len);
+	*input = rest;
+	Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{beacon::test::QUICKNET_BEACON_1000, chain_info::test::QUICKNET_INFO};
+	use timelock::engines::drand::TinyBLS381;
+
+	fn varint(mut value: u64) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		while value >= 0x80 {
+			bytes.push(value as u8 | 0x80);
+			value >>= 7;
+		}
+		bytes.push(value as u8);
+		bytes
+	}
+
+	fn message(round: u64) -> Vec<u8> {
+		let beacon: Beacon = serde_json::from_str(QUICKNET_BEACON_1000).unwrap();
+		let mut message = vec![0x08];
+		message.extend(varint(round));
+		message.push(0x12);
+		message.extend(varint(beacon.signature.len() as u64));
+		message.extend_from_slice(&beacon.signature);
+		// a randomness field, which is skipped
+		message.extend_from_slice(&[0x22, 0x02, 0xaa, 0xbb]);
+		message
+	}
+
+	#[test]
+	fn topic_is_derived_from_the_chain_hash() {
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		assert_eq!(
+			topic(&info.hash),
+			"/drand/pubsub/v0.0.0/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971"
+		);
+	}
+
+	#[test]
+	fn gossiped_beacons_are_verified() {
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		let (round, _) = verify_message::<TinyBLS381>(&info, &message(1000)).unwrap();
+		assert_eq!(round, 1000);
+
+		// the signature of round 1000 gossiped as round 1001
+		let result = verify_message::<TinyBLS381>(&info, &message(1001));
+		assert!(matches!(result, Err(Error::InvalidSignature)));
+
+		// truncated messages are rejected
+		let truncated = &message(1000)[..20];
+		assert!(matches!(decode_beacon(truncated), Err(Error::InvalidResponse)));
+	}
+}
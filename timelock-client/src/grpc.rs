@@ -0,0 +1,224 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A gRPC client for `timelock-beacon` servers.
+//!
+//! Server-to-server pipelines get rounds with less overhead over gRPC than
+//! over the drand HTTP API, and can stream rounds as they are published
+//! rather than poll for them. The service is described by
+//! `proto/beacon.proto`; the messages below are its Rust declarations, and
+//! [`proto`] holds the generated client and server.
+//!
+//! As over HTTP, the chain info is checked against a pinned chain hash and
+//! every signature against the chain's public key, so the server does not
+//! need to be trusted.
+
+use crate::{beacon::verify_signature, chain_info::ChainMetadata, ChainInfo, Error};
+use ark_serialize::CanonicalDeserialize;
+use futures_util::{Stream, StreamExt};
+use timelock::engines::EngineBLS;
+use tonic::transport::{Channel, Endpoint};
+
+/// The generated gRPC client and server of the `timelock.beacon.v1.Beacon`
+/// service
+pub mod proto {
+	include!(concat!(env!("OUT_DIR"), "/timelock.beacon.v1.Beacon.rs"));
+}
+
+use proto::beacon_client::BeaconClient;
+
+/// A request for the chain info
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ChainInfoRequest {}
+
+/// The chain info of the served chain
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChainInfoResponse {
+	/// The compressed public key of the beacon
+	#[prost(bytes = "vec", tag = "1")]
+	pub public_key: Vec<u8>,
+	/// The number of seconds between rounds
+	#[prost(uint64, tag = "2")]
+	pub period: u64,
+	/// The unix time of the first round
+	#[prost(uint64, tag = "3")]
+	pub genesis_time: u64,
+	/// The hash identifying the chain
+	#[prost(bytes = "vec", tag = "4")]
+	pub hash: Vec<u8>,
+	/// The hash of the group that runs the beacon
+	#[prost(bytes = "vec", tag = "5")]
+	pub group_hash: Vec<u8>,
+	/// The signature scheme (e.g. `bls-unchained-g1-rfc9380`)
+	#[prost(string, tag = "6")]
+	pub scheme_id: String,
+	/// The id of the beacon
+	#[prost(string, tag = "7")]
+	pub beacon_id: String,
+}
+
+/// A request for a published round
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RoundRequest {
+	/// The round, or 0 for the latest round
+	#[prost(uint64, tag = "1")]
+	pub round: u64,
+}
+
+/// A published round
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RoundResponse {
+	/// The round
+	#[prost(uint64, tag = "1")]
+	pub round: u64,
+	/// The compressed signature of the round
+	#[prost(bytes = "vec", tag = "2")]
+	pub signature: Vec<u8>,
+	/// The randomness of the round, the hash of the signature
+	#[prost(bytes = "vec", tag = "3")]
+	pub randomness: Vec<u8>,
+}
+
+/// A request to stream rounds as they are published
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StreamRoundsRequest {
+	/// The first round to stream, or 0 for the next round to be published
+	#[prost(uint64, tag = "1")]
+	pub from: u64,
+}
+
+impl From<&ChainInfo> for ChainInfoResponse {
+	fn from(info: &ChainInfo) -> Self {
+		Self {
+			public_key: info.public_key.clone(),
+			period: info.period,
+			genesis_time: info.genesis_time,
+			hash: info.hash.clone(),
+			group_hash: info.group_hash.clone(),
+			scheme_id: info.scheme_id.clone(),
+			beacon_id: info.metadata.beacon_id.clone(),
+		}
+	}
+}
+
+impl From<ChainInfoResponse> for ChainInfo {
+	fn from(response: ChainInfoResponse) -> Self {
+		Self {
+			public_key: response.public_key,
+			period: response.period,
+			genesis_time: response.genesis_time,
+			hash: response.hash,
+			group_hash: response.group_hash,
+			scheme_id: response.scheme_id,
+			metadata: ChainMetadata { beacon_id: response.beacon_id },
+		}
+	}
+}
+
+/// A client of one chain of a `timelock-beacon` server
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+	client: BeaconClient<Channel>,
+	info: ChainInfo,
+}
+
+impl GrpcClient {
+	/// Connect to a server and fetch its chain info
+	///
+	/// Fails with [`Error::ChainHashMismatch`] unless the server serves the
+	/// chain with the pinned hash.
+	///
+	/// * `endpoint`: The URL of the server (e.g. `http://127.0.0.1:50051`)
+	/// * `chain_hash`: The pinned hash of the chain
+	pub async fn connect(endpoint: &str, chain_hash: &[u8]) -> Result<Self, Error> {
+		let channel = Endpoint::from_shared(endpoint.to_string())
+			.map_err(|e| Error::Transport(e.to_string()))?
+			.connect()
+			.await
+			.map_err(|e| Error::Transport(e.to_string()))?;
+		let mut client = BeaconClient::new(channel);
+		let response =
+			client.get_chain_info(ChainInfoRequest {}).await.map_err(status)?.into_inner();
+		let info = ChainInfo::from(response);
+		info.validate()?;
+		if info.hash != chain_hash {
+			return Err(Error::ChainHashMismatch);
+		}
+		Ok(Self { client, info })
+	}
+
+	/// The (validated) chain info of the chain
+	pub fn chain_info(&self) -> &ChainInfo {
+		&self.info
+	}
+
+	/// Fetch and verify the signature of a round
+	///
+	/// * `round`: The round to fetch, or 0 for the latest round
+	pub async fn signature<E: EngineBLS>(
+		&self,
+		round: u64,
+	) -> Result<(u64, E::SignatureGroup), Error> {
+		let response =
+			self.client.clone().get_round(RoundRequest { round }).await.map_err(status)?;
+		let response = response.into_inner();
+		if round != 0 && response.round != round {
+			return Err(Error::InvalidResponse);
+		}
+		Ok((response.round, self.verify::<E>(&response)?))
+	}
+
+	/// Stream the verified signatures of rounds as they are published
+	///
+	/// The stream ends with the first error, e.g. an invalid signature.
+	///
+	/// * `from`: The first round to stream, or 0 for the next round to be
+	///   published
+	pub async fn stream<E: EngineBLS>(
+		&self,
+		from: u64,
+	) -> Result<impl Stream<Item = Result<(u64, E::SignatureGroup), Error>> + '_, Error> {
+		let rounds = self
+			.client
+			.clone()
+			.stream_rounds(StreamRoundsRequest { from })
+			.await
+			.map_err(status)?
+			.into_inner();
+		let mut expected = from;
+		Ok(rounds.map(move |response| {
+			let response = response.map_err(status)?;
+			if expected != 0 && response.round != expected {
+				return Err(Error::InvalidResponse);
+			}
+			expected = response.round + 1;
+			Ok((response.round, self.verify::<E>(&response)?))
+		}))
+	}
+
+	/// Check the signature of a round against the public key of the chain
+	fn verify<E: EngineBLS>(&self, response: &RoundResponse) -> Result<E::SignatureGroup, Error> {
+		let signature = E::SignatureGroup::deserialize_compressed(&response.signature[..])
+			.map_err(|_| Error::InvalidSignature)?;
+		verify_signature::<E>(self.info.public_key::<E>()?, response.round, signature)?;
+		Ok(signature)
+	}
+}
+
+/// The error of a failed call
+fn status(status: tonic::Status) -> Error {
+	Error::Transport(status.to_string())
+}
@@ -0,0 +1,227 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A client for the pulses of the Ideal Network beacon.
+//!
+//! Every block the Ideal Network's validators each publish a signature on the
+//! block's identity, sha256(block number as u64 BE), under their share of the
+//! beacon key. The signatures aggregate (by addition) to a signature under the
+//! beacon's public key, which is the IBE secret of ciphertexts encrypted to
+//! that block with [`TinyBLS377`].
+//!
+//! Pulses are read from a [`PulseSource`], so any RPC or WebSocket
+//! subscription that yields them can drive the client; pulses are decoded
+//! from JSON with [`Pulse::from_json`].
+
+use crate::{beacon::verify_signature, Error};
+use ark_serialize::CanonicalDeserialize;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use timelock::{
+	block_ciphers::BlockCipherProvider,
+	engines::{drand::TinyBLS377, EngineBLS},
+	tlock::{tld, TLECiphertext},
+};
+
+pub(crate) type PublicKey = <TinyBLS377 as EngineBLS>::PublicKeyGroup;
+type Signature = <TinyBLS377 as EngineBLS>::SignatureGroup;
+
+/// The signatures published for a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pulse {
+	/// The number of the block
+	#[serde(rename = "blockNumber")]
+	pub block_number: u64,
+	/// The compressed signatures of the validators
+	#[serde(with = "hex_list")]
+	pub signatures: Vec<Vec<u8>>,
+}
+
+impl Pulse {
+	/// Parse a pulse from its JSON form, e.g.
+	/// `{"blockNumber": 7, "signatures": ["<hex>", ...]}`
+	pub fn from_json(json: &[u8]) -> Result<Self, Error> {
+		serde_json::from_slice(json).map_err(|_| Error::InvalidResponse)
+	}
+
+	/// The aggregate of the signatures of the pulse
+	pub fn aggregate(&self) -> Result<Signature, Error> {
+		self.signatures.iter().try_fold(Signature::default(), |aggregate, bytes| {
+			Signature::deserialize_compressed(&bytes[..])
+				.map(|signature| aggregate + signature)
+				.map_err(|_| Error::InvalidSignature)
+		})
+	}
+}
+
+/// A subscription to the pulses of the beacon
+pub trait PulseSource {
+	/// The next pulse, or `None` once the subscription has ended
+	fn next_pulse(&mut self) -> impl Future<Output = Result<Option<Pulse>, Error>> + Send;
+}
+
+/// A client of the Ideal Network beacon
+pub struct IdnClient<P: PulseSource> {
+	source: P,
+	p_pub: PublicKey,
+}
+
+impl<P: PulseSource> IdnClient<P> {
+	/// A client reading pulses from `source`
+	///
+	/// * `source`: The subscription to the pulses of the beacon
+	/// * `p_pub`: The public key of the beacon
+	pub fn new(source: P, p_pub: PublicKey) -> Self {
+		Self { source, p_pub }
+	}
+
+	/// Wait for the pulse of a block and return its verified aggregate
+	/// signature
+	///
+	/// Pulses of earlier blocks are skipped. Fails if the subscription ends or
+	/// moves past the block, or if the aggregate signature is invalid.
+	///
+	/// * `block_number`: The block to wait for
+	pub async fn wait_for(&mut self, block_number: u64) -> Result<Signature, Error> {
+		loop {
+			let pulse = self
+				.source
+				.next_pulse()
+				.await?
+				.ok_or(Error::Transport("The pulse subscription ended.".into()))?;
+			if pulse.block_number < block_number {
+				continue;
+			}
+			if pulse.block_number > block_number {
+				return Err(Error::InvalidResponse);
+			}
+			let signature = pulse.aggregate()?;
+			verify_signature::<TinyBLS377>(self.p_pub, block_number, signature)?;
+			return Ok(signature);
+		}
+	}
+
+	/// Wait for the pulse of a block and decrypt a ciphertext encrypted to it
+	///
+	/// * `ciphertext`: A ciphertext encrypted to the block
+	/// * `block_number`: The block the ciphertext is encrypted to
+	pub async fn decrypt<S: BlockCipherProvider<32>>(
+		&mut self,
+		ciphertext: TLECiphertext<TinyBLS377>,
+		block_number: u64,
+	) -> Result<Vec<u8>, Error> {
+		let signature = self.wait_for(block_number).await?;
+		tld::<TinyBLS377, S>(ciphertext, signature).map_err(|_| Error::InvalidCiphertext)
+	}
+}
+
+/// (De)serialization of a list of byte strings as lowercase hex
+mod hex_list {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	pub fn serialize<S: Serializer>(list: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+		let list: Vec<String> =
+			list.iter().map(|bytes| array_bytes::bytes2hex("", bytes)).collect();
+		list.serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Vec<Vec<u8>>, D::Error> {
+		Vec::<String>::deserialize(deserializer)?
+			.iter()
+			.map(|s| array_bytes::hex2bytes(s).map_err(|_| serde::de::Error::custom("invalid hex")))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+	use super::*;
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{rand::rngs::OsRng, UniformRand};
+	use std::collections::VecDeque;
+	use timelock::{block_ciphers::AESGCMBlockCipherProvider, tlock::BeaconConfig};
+
+	pub(crate) type Scalar = <TinyBLS377 as EngineBLS>::Scalar;
+
+	struct MockSource(VecDeque<Pulse>);
+
+	impl PulseSource for MockSource {
+		fn next_pulse(&mut self) -> impl Future<Output = Result<Option<Pulse>, Error>> + Send {
+			let pulse = self.0.pop_front();
+			async move { Ok(pulse) }
+		}
+	}
+
+	pub(crate) fn pulse(validators: &[Scalar], block_number: u64) -> Pulse {
+		let id = BeaconConfig::<TinyBLS377>::identity(block_number);
+		let signatures = validators
+			.iter()
+			.map(|sk| {
+				let mut bytes = Vec::new();
+				id.extract::<TinyBLS377>(*sk).0.serialize_compressed(&mut bytes).unwrap();
+				bytes
+			})
+			.collect();
+		Pulse { block_number, signatures }
+	}
+
+	pub(crate) fn setup() -> (Vec<Scalar>, PublicKey) {
+		let validators: Vec<Scalar> = (0..3).map(|_| Scalar::rand(&mut OsRng)).collect();
+		let p_pub = validators.iter().map(|sk| PublicKey::generator() * sk).sum();
+		(validators, p_pub)
+	}
+
+	#[tokio::test]
+	async fn idn_client_decrypts_with_aggregated_pulse() {
+		let (validators, p_pub) = setup();
+		let ciphertext = timelock::tlock::tle::<TinyBLS377, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[7; 32],
+			b"ideal network",
+			BeaconConfig::<TinyBLS377>::identity(7),
+			OsRng,
+		)
+		.unwrap();
+
+		// pulses arrive as JSON from the subscription
+		let pulses = (5..=7)
+			.map(|block| {
+				let json = serde_json::to_vec(&pulse(&validators, block)).unwrap();
+				Pulse::from_json(&json).unwrap()
+			})
+			.collect();
+		let mut client = IdnClient::new(MockSource(pulses), p_pub);
+		let plaintext = client.decrypt::<AESGCMBlockCipherProvider>(ciphertext, 7).await.unwrap();
+		assert_eq!(plaintext, b"ideal network".to_vec());
+	}
+
+	#[tokio::test]
+	async fn idn_client_rejects_incomplete_pulses() {
+		let (validators, p_pub) = setup();
+		// a pulse missing a validator's signature does not verify
+		let mut incomplete = pulse(&validators, 7);
+		incomplete.signatures.pop();
+		let mut client = IdnClient::new(MockSource(VecDeque::from([incomplete])), p_pub);
+		assert!(matches!(client.wait_for(7).await, Err(Error::InvalidSignature)));
+
+		// the subscription moved past the block
+		let mut client = IdnClient::new(MockSource(VecDeque::from([pulse(&validators, 8)])), p_pub);
+		assert!(matches!(client.wait_for(7).await, Err(Error::InvalidResponse)));
+	}
+}
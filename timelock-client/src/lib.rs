@@ -0,0 +1,81 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Clients for the beacons whose signatures decrypt timelock ciphertexts.
+//!
+//! Requests go through a [`Transport`], so the crate can be used with any
+//! HTTP stack; the `http` feature (on by default) provides one backed by
+//! reqwest. Waiting for rounds goes through a [`Clock`], with the `tokio`
+//! feature (on by default) providing one backed by the tokio timer. The
+//! `grpc` feature adds a [`GrpcClient`] for `timelock-beacon` servers.
+
+pub mod beacon;
+pub mod chain_info;
+pub mod ciphertext_store;
+pub mod clock;
+pub mod decrypt;
+pub mod gossip;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod idn;
+pub mod relay;
+pub mod store;
+pub mod substrate;
+pub mod transport;
+
+pub use beacon::{fetch_signature, verify_signature, Beacon};
+pub use chain_info::{fetch_chain_info, ChainInfo};
+#[cfg(feature = "sled")]
+pub use ciphertext_store::SledCiphertextStore;
+#[cfg(feature = "sqlite")]
+pub use ciphertext_store::SqliteCiphertextStore;
+pub use ciphertext_store::{CiphertextStore, MemoryCiphertextStore, StoredCiphertext};
+pub use clock::Clock;
+#[cfg(feature = "tokio")]
+pub use clock::TokioClock;
+pub use decrypt::wait_and_decrypt;
+#[cfg(feature = "grpc")]
+pub use grpc::GrpcClient;
+pub use idn::{IdnClient, Pulse, PulseSource};
+pub use relay::RelaySet;
+pub use store::{fetch_signature_cached, FileStore, MemoryStore, SignatureStore};
+pub use substrate::{header_number, ChainClient, FinalizedChain};
+#[cfg(feature = "http")]
+pub use transport::HttpTransport;
+pub use transport::Transport;
+
+/// Errors that may occur while talking to a beacon
+#[derive(Debug, PartialEq)]
+pub enum Error {
+	/// The request failed (e.g. the relay is unreachable)
+	Transport(String),
+	/// The response could not be parsed
+	InvalidResponse,
+	/// The chain hash does not match the chain info, or the pinned hash
+	ChainHashMismatch,
+	/// The public key of the chain is not a valid group element
+	InvalidPublicKey,
+	/// The signature is not a valid signature of the chain for the round
+	InvalidSignature,
+	/// The ciphertext is malformed, lacks a round, or could not be decrypted
+	InvalidCiphertext,
+	/// A relay did not respond in time
+	Timeout,
+	/// Not enough relays agreed on a valid response
+	NoQuorum,
+	/// A signature or ciphertext store could not be read or written
+	Store(String),
+}
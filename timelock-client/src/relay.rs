@@ -0,0 +1,247 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Fetching from several relays at once.
+//!
+//! A [`RelaySet`] queries all of its relays concurrently, each with its own
+//! timeout, verifies every response, and only returns a value that a quorum of
+//! relays agree on. A single relay that is down, slow, or serving bad data
+//! then neither blocks nor misleads the client.
+
+use crate::{beacon, chain_info, ChainInfo, Clock, Error, Transport};
+use futures_util::{
+	future::{join_all, select, Either},
+	pin_mut,
+};
+use std::{future::Future, time::Duration};
+use timelock::engines::EngineBLS;
+
+/// The timeout of a relay unless configured otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A relay of a [`RelaySet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relay {
+	/// The base URL of the relay
+	pub url: String,
+	/// How long to wait for a response
+	pub timeout: Duration,
+}
+
+/// A set of relays queried together
+pub struct RelaySet<T: Transport, C: Clock> {
+	transport: T,
+	clock: C,
+	relays: Vec<Relay>,
+	quorum: usize,
+}
+
+impl<T: Transport, C: Clock> RelaySet<T, C> {
+	/// An empty relay set
+	///
+	/// Queries fail with [`Error::NoQuorum`] unless the quorum is between one
+	/// and the number of relays.
+	///
+	/// * `transport`: The transport to reach the relays with
+	/// * `clock`: The clock to time the requests with
+	/// * `quorum`: The number of relays that must agree on a response
+	pub fn new(transport: T, clock: C, quorum: usize) -> Self {
+		Self { transport, clock, relays: Vec::new(), quorum }
+	}
+
+	/// Add a relay with the [`DEFAULT_TIMEOUT`]
+	///
+	/// * `url`: The base URL of the relay
+	pub fn with_relay(self, url: &str) -> Self {
+		self.with_relay_timeout(url, DEFAULT_TIMEOUT)
+	}
+
+	/// Add a relay
+	///
+	/// * `url`: The base URL of the relay
+	/// * `timeout`: How long to wait for a response from the relay
+	pub fn with_relay_timeout(mut self, url: &str, timeout: Duration) -> Self {
+		self.relays.push(Relay { url: url.into(), timeout });
+		self
+	}
+
+	/// The relays of the set
+	pub fn relays(&self) -> &[Relay] {
+		&self.relays
+	}
+
+	/// Fetch the chain info of a pinned chain
+	///
+	/// * `chain_hash`: The pinned hash of the chain
+	pub async fn fetch_chain_info(&self, chain_hash: &[u8]) -> Result<ChainInfo, Error> {
+		self.query(|relay| chain_info::fetch_chain_info(&self.transport, relay, chain_hash))
+			.await
+	}
+
+	/// Fetch and verify the signature of a chain for a round
+	///
+	/// * `info`: The (validated) chain info of the chain
+	/// * `round`: The round to fetch
+	pub async fn fetch_signature<E: EngineBLS>(
+		&self,
+		info: &ChainInfo,
+		round: u64,
+	) -> Result<E::SignatureGroup, Error> {
+		self.query(|relay| beacon::fetch_signature::<E, T>(&self.transport, relay, info, round))
+			.await
+	}
+
+	/// Query every relay concurrently and return the response a quorum agrees
+	/// on
+	async fn query<'a, V, F, Fut>(&'a self, request: F) -> Result<V, Error>
+	where
+		V: PartialEq,
+		F: Fn(&'a str) -> Fut,
+		Fut: Future<Output = Result<V, Error>> + 'a,
+	{
+		if self.quorum == 0 || self.quorum > self.relays.len() {
+			return Err(Error::NoQuorum);
+		}
+
+		let responses = join_all(self.relays.iter().map(|relay| {
+			let response = request(&relay.url);
+			let timeout = self.clock.sleep(relay.timeout);
+			async move {
+				pin_mut!(response, timeout);
+				match select(response, timeout).await {
+					Either::Left((response, _)) => response,
+					Either::Right(_) => Err(Error::Timeout),
+				}
+			}
+		}))
+		.await;
+
+		let mut tally: Vec<(V, usize)> = Vec::new();
+		for value in responses.into_iter().flatten() {
+			match tally.iter_mut().find(|(v, _)| *v == value) {
+				Some((_, count)) => *count += 1,
+				None => tally.push((value, 1)),
+			}
+		}
+		tally
+			.into_iter()
+			.find(|(_, count)| *count >= self.quorum)
+			.map(|(value, _)| value)
+			.ok_or(Error::NoQuorum)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacon::test::QUICKNET_BEACON_1000,
+		chain_info::test::{MockTransport, QUICKNET_HASH, QUICKNET_INFO},
+	};
+	use timelock::engines::drand::TinyBLS381;
+
+	/// A clock whose timeouts never fire
+	struct NeverClock;
+
+	impl Clock for NeverClock {
+		fn now(&self) -> u64 {
+			0
+		}
+
+		fn sleep(&self, _duration: Duration) -> impl Future<Output = ()> + Send {
+			futures_util::future::pending()
+		}
+	}
+
+	/// A clock whose timeouts fire immediately
+	struct ExpiredClock;
+
+	impl Clock for ExpiredClock {
+		fn now(&self) -> u64 {
+			0
+		}
+
+		fn sleep(&self, _duration: Duration) -> impl Future<Output = ()> + Send {
+			futures_util::future::ready(())
+		}
+	}
+
+	fn transport(beacons: &[(&str, String)]) -> MockTransport {
+		let mut transport = MockTransport::default();
+		for (relay, beacon) in beacons {
+			let info_url = format!("{}/{}/info", relay, QUICKNET_HASH);
+			transport.0.insert(info_url, QUICKNET_INFO.as_bytes().to_vec());
+			let url = format!("{}/{}/public/1000", relay, QUICKNET_HASH);
+			transport.0.insert(url, beacon.clone().into_bytes());
+		}
+		transport
+	}
+
+	#[tokio::test]
+	async fn relay_set_requires_a_quorum() {
+		let good = QUICKNET_BEACON_1000.to_string();
+		// a relay serving a corrupted signature
+		let bad = QUICKNET_BEACON_1000.replace("b44679b9", "a44679b9");
+		let transport =
+			transport(&[("https://a", good.clone()), ("https://b", bad), ("https://c", good)]);
+		let relays = RelaySet::new(transport, NeverClock, 2)
+			.with_relay("https://a")
+			.with_relay("https://b")
+			.with_relay("https://c")
+			// a relay that is unreachable
+			.with_relay("https://d");
+
+		let pinned = array_bytes::hex2bytes(QUICKNET_HASH).unwrap();
+		let info = relays.fetch_chain_info(&pinned).await.unwrap();
+		assert!(relays.fetch_signature::<TinyBLS381>(&info, 1000).await.is_ok());
+
+		// only two relays serve the signature, so three cannot agree
+		let relays = RelaySet { quorum: 3, ..relays };
+		let result = relays.fetch_signature::<TinyBLS381>(&info, 1000).await;
+		assert!(matches!(result, Err(Error::NoQuorum)));
+	}
+
+	/// A transport that never answers for the relay `https://slow`
+	struct SlowTransport(MockTransport);
+
+	impl Transport for SlowTransport {
+		fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+			let slow = url.starts_with("https://slow/");
+			let response = self.0.get(url);
+			async move {
+				if slow {
+					futures_util::future::pending::<()>().await;
+				}
+				response.await
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn relay_set_times_out_slow_relays() {
+		let good = QUICKNET_BEACON_1000.to_string();
+		let transport =
+			SlowTransport(transport(&[("https://a", good.clone()), ("https://slow", good)]));
+		let relays = RelaySet::new(transport, ExpiredClock, 1)
+			.with_relay("https://a")
+			.with_relay_timeout("https://slow", Duration::from_secs(1));
+		let pinned = array_bytes::hex2bytes(QUICKNET_HASH).unwrap();
+		assert!(relays.fetch_chain_info(&pinned).await.is_ok());
+
+		let relays = RelaySet { quorum: 2, ..relays };
+		assert!(matches!(relays.fetch_chain_info(&pinned).await, Err(Error::NoQuorum)));
+	}
+}
@@ -0,0 +1,215 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Caches of fetched round signatures.
+//!
+//! Signatures never change once published, so decrypting many historical
+//! ciphertexts only needs to fetch each round once. A [`SignatureStore`] keeps
+//! the compressed signatures by chain and round; [`fetch_signature_cached`]
+//! consults it before going to the relay. Cached signatures are verified like
+//! fetched ones, so a store does not need to be trusted.
+
+use crate::{
+	beacon::{fetch_signature, verify_signature},
+	ChainInfo, Error, Transport,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{
+	collections::HashMap,
+	fs,
+	path::PathBuf,
+	sync::{Mutex, PoisonError},
+};
+use timelock::engines::EngineBLS;
+
+/// A cache of compressed round signatures
+pub trait SignatureStore {
+	/// The cached signature of a chain for a round, if any
+	///
+	/// * `chain_hash`: The hash of the chain
+	/// * `round`: The round
+	fn get(&self, chain_hash: &[u8], round: u64) -> Result<Option<Vec<u8>>, Error>;
+
+	/// Cache the signature of a chain for a round
+	///
+	/// * `chain_hash`: The hash of the chain
+	/// * `round`: The round
+	/// * `signature`: The compressed signature
+	fn put(&self, chain_hash: &[u8], round: u64, signature: &[u8]) -> Result<(), Error>;
+}
+
+/// The signatures of a [`MemoryStore`] by chain hash and round
+type Signatures = HashMap<(Vec<u8>, u64), Vec<u8>>;
+
+/// A signature store held in memory
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+	signatures: Mutex<Signatures>,
+}
+
+impl SignatureStore for MemoryStore {
+	fn get(&self, chain_hash: &[u8], round: u64) -> Result<Option<Vec<u8>>, Error> {
+		let signatures = self.signatures.lock().unwrap_or_else(PoisonError::into_inner);
+		Ok(signatures.get(&(chain_hash.to_vec(), round)).cloned())
+	}
+
+	fn put(&self, chain_hash: &[u8], round: u64, signature: &[u8]) -> Result<(), Error> {
+		let mut signatures = self.signatures.lock().unwrap_or_else(PoisonError::into_inner);
+		signatures.insert((chain_hash.to_vec(), round), signature.to_vec());
+		Ok(())
+	}
+}
+
+/// A signature store in a directory, with one file per round at
+/// `<dir>/<chain hash>/<round>`
+#[derive(Debug, Clone)]
+pub struct FileStore {
+	dir: PathBuf,
+}
+
+impl FileStore {
+	/// A store in `dir`, which is created when the first signature is cached
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	fn path(&self, chain_hash: &[u8], round: u64) -> PathBuf {
+		self.dir.join(array_bytes::bytes2hex("", chain_hash)).join(round.to_string())
+	}
+}
+
+impl SignatureStore for FileStore {
+	fn get(&self, chain_hash: &[u8], round: u64) -> Result<Option<Vec<u8>>, Error> {
+		match fs::read(self.path(chain_hash, round)) {
+			Ok(signature) => Ok(Some(signature)),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(Error::Store(e.to_string())),
+		}
+	}
+
+	fn put(&self, chain_hash: &[u8], round: u64, signature: &[u8]) -> Result<(), Error> {
+		let path = self.path(chain_hash, round);
+		let dir = path.parent().expect("The path is within the store directory.");
+		fs::create_dir_all(dir).map_err(|e| Error::Store(e.to_string()))?;
+		// write then rename, so that readers never see a partial signature
+		let partial = path.with_extension("partial");
+		fs::write(&partial, signature)
+			.and_then(|_| fs::rename(&partial, &path))
+			.map_err(|e| Error::Store(e.to_string()))
+	}
+}
+
+/// Fetch and verify the signature of a chain for a round, using and filling a
+/// cache
+///
+/// * `transport`: The transport to reach the relay with
+/// * `store`: The cache of signatures
+/// * `relay`: The base URL of the relay
+/// * `info`: The (validated) chain info of the chain
+/// * `round`: The round to fetch
+pub async fn fetch_signature_cached<E, T, S>(
+	transport: &T,
+	store: &S,
+	relay: &str,
+	info: &ChainInfo,
+	round: u64,
+) -> Result<E::SignatureGroup, Error>
+where
+	E: EngineBLS,
+	T: Transport,
+	S: SignatureStore,
+{
+	if let Some(bytes) = store.get(&info.hash, round)? {
+		let p_pub = info.public_key::<E>()?;
+		let cached = E::SignatureGroup::deserialize_compressed(&bytes[..])
+			.ok()
+			.filter(|signature| verify_signature::<E>(p_pub, round, *signature).is_ok());
+		// a corrupted entry is replaced by a fresh signature
+		if let Some(signature) = cached {
+			return Ok(signature);
+		}
+	}
+
+	let signature = fetch_signature::<E, T>(transport, relay, info, round).await?;
+	let mut bytes = Vec::new();
+	signature
+		.serialize_compressed(&mut bytes)
+		.expect("The signature must be serializable.");
+	store.put(&info.hash, round, &bytes)?;
+	Ok(signature)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacon::test::QUICKNET_BEACON_1000,
+		chain_info::test::{MockTransport, QUICKNET_HASH, QUICKNET_INFO},
+	};
+	use timelock::engines::drand::TinyBLS381;
+
+	const RELAY: &str = "https://relay.example";
+
+	async fn fetch_twice<S: SignatureStore>(store: &S) {
+		let info = ChainInfo::from_json(QUICKNET_INFO.as_bytes()).unwrap();
+		let mut transport = MockTransport::default();
+		let url = format!("{}/{}/public/1000", RELAY, QUICKNET_HASH);
+		transport.0.insert(url, QUICKNET_BEACON_1000.as_bytes().to_vec());
+
+		let fetched =
+			fetch_signature_cached::<TinyBLS381, _, _>(&transport, store, RELAY, &info, 1000)
+				.await
+				.unwrap();
+		assert!(store.get(&info.hash, 1000).unwrap().is_some());
+
+		// the second fetch is served from the store while the relay is down
+		let cached = fetch_signature_cached::<TinyBLS381, _, _>(
+			&MockTransport::default(),
+			store,
+			RELAY,
+			&info,
+			1000,
+		)
+		.await
+		.unwrap();
+		assert_eq!(fetched, cached);
+
+		// a corrupted entry is not trusted
+		store.put(&info.hash, 1000, &[0; 48]).unwrap();
+		let result = fetch_signature_cached::<TinyBLS381, _, _>(
+			&MockTransport::default(),
+			store,
+			RELAY,
+			&info,
+			1000,
+		)
+		.await;
+		assert!(matches!(result, Err(Error::Transport(_))));
+	}
+
+	#[tokio::test]
+	async fn memory_store_caches_signatures() {
+		fetch_twice(&MemoryStore::default()).await;
+	}
+
+	#[tokio::test]
+	async fn file_store_caches_signatures() {
+		let dir = std::env::temp_dir().join(format!("timelock-store-{}", std::process::id()));
+		fetch_twice(&FileStore::new(&dir)).await;
+		assert!(FileStore::new(&dir).get(&[1; 32], 1000).unwrap().is_none());
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A client that unlocks ciphertexts at a block of a Substrate chain.
+//!
+//! Ciphertexts for the Ideal Network are encrypted to a block (see
+//! [`timelock::tlock::encrypt_at_block`]). A [`ChainClient`] watches the
+//! chain's finalized head until the block is finalized, reads the block's
+//! [`Pulse`], verifies its aggregate signature and decrypts with it, so
+//! unlocking at a block is a single call to [`ChainClient::decrypt_at_block`].
+//!
+//! The chain is read through a [`FinalizedChain`], so any RPC client can
+//! drive it; [`header_number`] parses the responses of the standard
+//! `chain_getHeader` RPC.
+
+use crate::{beacon::verify_signature, idn::PublicKey, Clock, Error, Pulse};
+use serde::Deserialize;
+use std::{future::Future, time::Duration};
+use timelock::{
+	block_ciphers::BlockCipherProvider,
+	engines::{drand::TinyBLS377, EngineBLS},
+	tlock::{tld, TLECiphertext},
+};
+
+type Signature = <TinyBLS377 as EngineBLS>::SignatureGroup;
+
+/// The finalized blocks of a Substrate chain and the pulses in them
+pub trait FinalizedChain {
+	/// The number of the latest finalized block
+	fn finalized_number(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+	/// The pulse published in a finalized block
+	fn pulse_at(&self, block_number: u64) -> impl Future<Output = Result<Pulse, Error>> + Send;
+}
+
+/// A client that waits for blocks of a Substrate chain to be finalized
+pub struct ChainClient<F: FinalizedChain, C: Clock> {
+	chain: F,
+	clock: C,
+	p_pub: PublicKey,
+	block_time: u64,
+}
+
+impl<F: FinalizedChain, C: Clock> ChainClient<F, C> {
+	/// A client reading finalized blocks from `chain`
+	///
+	/// * `chain`: The chain to read finalized blocks and pulses from
+	/// * `clock`: The clock to wait with
+	/// * `p_pub`: The public key of the beacon
+	/// * `block_time`: The target number of seconds between blocks (non-zero)
+	pub fn new(chain: F, clock: C, p_pub: PublicKey, block_time: u64) -> Self {
+		Self { chain, clock, p_pub, block_time: block_time.max(1) }
+	}
+
+	/// Wait until a block is finalized and return the verified aggregate
+	/// signature of its pulse
+	///
+	/// The finalized head is polled, sleeping for the expected time until the
+	/// block between polls. Fails if the pulse is for another block or its
+	/// aggregate signature is invalid.
+	///
+	/// * `block_number`: The block to wait for
+	pub async fn wait_for_block(&self, block_number: u64) -> Result<Signature, Error> {
+		loop {
+			let finalized = self.chain.finalized_number().await?;
+			if finalized >= block_number {
+				break;
+			}
+			let remaining = (block_number - finalized).saturating_mul(self.block_time);
+			self.clock.sleep(Duration::from_secs(remaining)).await;
+		}
+
+		let pulse = self.chain.pulse_at(block_number).await?;
+		if pulse.block_number != block_number {
+			return Err(Error::InvalidResponse);
+		}
+		let signature = pulse.aggregate()?;
+		verify_signature::<TinyBLS377>(self.p_pub, block_number, signature)?;
+		Ok(signature)
+	}
+
+	/// Wait until a block is finalized and decrypt a ciphertext encrypted to it
+	///
+	/// * `ciphertext`: A ciphertext encrypted to the block
+	/// * `block_number`: The block the ciphertext is encrypted to
+	pub async fn decrypt_at_block<S: BlockCipherProvider<32>>(
+		&self,
+		ciphertext: TLECiphertext<TinyBLS377>,
+		block_number: u64,
+	) -> Result<Vec<u8>, Error> {
+		let signature = self.wait_for_block(block_number).await?;
+		tld::<TinyBLS377, S>(ciphertext, signature).map_err(|_| Error::InvalidCiphertext)
+	}
+}
+
+/// Parse the block number from a `chain_getHeader` JSON-RPC response, e.g.
+/// `{"jsonrpc": "2.0", "result": {"number": "0x1b4", ...}, "id": 1}`
+pub fn header_number(json: &[u8]) -> Result<u64, Error> {
+	#[derive(Deserialize)]
+	struct Response {
+		result: Header,
+	}
+
+	#[derive(Deserialize)]
+	struct Header {
+		number: String,
+	}
+
+	let response: Response = serde_json::from_slice(json).map_err(|_| Error::InvalidResponse)?;
+	let digits = response.result.number.strip_prefix("0x").ok_or(Error::InvalidResponse)?;
+	u64::from_str_radix(digits, 16).map_err(|_| Error::InvalidResponse)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::idn::test::{pulse, setup, Scalar};
+	use ark_std::rand::rngs::OsRng;
+	use std::sync::Mutex;
+	use timelock::{block_ciphers::AESGCMBlockCipherProvider, tlock::encrypt_at_block};
+
+	/// A chain that finalizes a block per second of sleep on its clock
+	struct MockChain {
+		finalized: Mutex<u64>,
+		validators: Vec<Scalar>,
+	}
+
+	impl FinalizedChain for &MockChain {
+		fn finalized_number(&self) -> impl Future<Output = Result<u64, Error>> + Send {
+			let finalized = *self.finalized.lock().unwrap();
+			async move { Ok(finalized) }
+		}
+
+		fn pulse_at(&self, block_number: u64) -> impl Future<Output = Result<Pulse, Error>> + Send {
+			let pulse = pulse(&self.validators, block_number);
+			async move { Ok(pulse) }
+		}
+	}
+
+	impl Clock for &MockChain {
+		fn now(&self) -> u64 {
+			*self.finalized.lock().unwrap()
+		}
+
+		fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+			*self.finalized.lock().unwrap() += duration.as_secs();
+			async {}
+		}
+	}
+
+	#[tokio::test]
+	async fn chain_client_decrypts_once_the_block_is_finalized() {
+		let (validators, p_pub) = setup();
+		let chain = MockChain { finalized: Mutex::new(999_990), validators };
+		let ciphertext = encrypt_at_block::<TinyBLS377, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			1_000_000,
+			b"unlock at block 1,000,000",
+			OsRng,
+		)
+		.unwrap();
+
+		let client = ChainClient::new(&chain, &chain, p_pub, 1);
+		let plaintext = client
+			.decrypt_at_block::<AESGCMBlockCipherProvider>(ciphertext, 1_000_000)
+			.await
+			.unwrap();
+		assert_eq!(plaintext, b"unlock at block 1,000,000".to_vec());
+		assert_eq!(*chain.finalized.lock().unwrap(), 1_000_000);
+	}
+
+	#[tokio::test]
+	async fn chain_client_rejects_invalid_pulses() {
+		let (_, p_pub) = setup();
+		// pulses signed by other validators do not verify
+		let (validators, _) = setup();
+		let chain = MockChain { finalized: Mutex::new(10), validators };
+		let client = ChainClient::new(&chain, &chain, p_pub, 6);
+		assert!(matches!(client.wait_for_block(7).await, Err(Error::InvalidSignature)));
+	}
+
+	#[test]
+	fn header_number_parses_rpc_responses() {
+		let json = br#"{"jsonrpc":"2.0","result":{"parentHash":"0x00","number":"0xf4240"},"id":1}"#;
+		assert_eq!(header_number(json), Ok(1_000_000));
+		assert_eq!(
+			header_number(br#"{"jsonrpc":"2.0","result":{"number":"42"},"id":1}"#),
+			Err(Error::InvalidResponse)
+		);
+	}
+}
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The transports used to reach beacon relays.
+
+use crate::Error;
+use std::future::Future;
+
+/// Something that can fetch the body of a URL
+pub trait Transport {
+	/// Fetch the body of `url`
+	fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send;
+}
+
+/// A transport over HTTP(S)
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+	client: reqwest::Client,
+}
+
+#[cfg(feature = "http")]
+impl HttpTransport {
+	/// A transport using an existing reqwest client (e.g. with custom timeouts)
+	pub fn new(client: reqwest::Client) -> Self {
+		Self { client }
+	}
+}
+
+#[cfg(feature = "http")]
+impl Transport for HttpTransport {
+	fn get(&self, url: &str) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+		let request = self.client.get(url);
+		async move {
+			let response = request
+				.send()
+				.await
+				.and_then(|response| response.error_for_status())
+				.map_err(|e| Error::Transport(e.to_string()))?;
+			let body = response.bytes().await.map_err(|e| Error::Transport(e.to_string()))?;
+			Ok(body.to_vec())
+		}
+	}
+}
@@ -14,18 +14,39 @@
  * limitations under the License.
  */
 
-use std::env;
-
 fn main() {
-	let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+	println!("cargo:rerun-if-changed=src/lib.rs");
+	println!("cargo:rerun-if-changed=src/armor.rs");
+	println!("cargo:rerun-if-changed=src/beacons.rs");
+	println!("cargo:rerun-if-changed=src/context.rs");
+	println!("cargo:rerun-if-changed=src/error.rs");
+	println!("cargo:rerun-if-changed=src/logging.rs");
+	println!("cargo:rerun-if-changed=src/multi.rs");
+	println!("cargo:rerun-if-changed=src/secure.rs");
+	println!("cargo:rerun-if-changed=src/stream.rs");
+	println!("cargo:rerun-if-changed=src/task.rs");
+	println!("cargo:rerun-if-changed=cbindgen.toml");
+
+	#[cfg(feature = "header")]
+	generate_header();
+}
 
+/// Write `include/timelock.h` so C and CMake consumers can point their
+/// include path at a single directory.
+#[cfg(feature = "header")]
+fn generate_header() {
+	use std::{env, fs, path::PathBuf};
+
+	let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+	let include_dir = crate_dir.join("include");
+	fs::create_dir_all(&include_dir).expect("Unable to create include directory");
+
+	let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+		.expect("Unable to read cbindgen.toml");
 	cbindgen::Builder::new()
-		.with_crate(crate_dir)
-		.with_config(cbindgen::Config::from_file("cbindgen.toml").unwrap())
+		.with_crate(&crate_dir)
+		.with_config(config)
 		.generate()
 		.expect("Unable to generate bindings")
-		.write_to_file("timelock.h");
-
-	println!("cargo:rerun-if-changed=src/lib.rs");
-	println!("cargo:rerun-if-changed=cbindgen.toml");
+		.write_to_file(include_dir.join("timelock.h"));
 }
@@ -0,0 +1,147 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! ASCII armored ciphertexts.
+//!
+//! `timelock_encrypt_armored` returns the ciphertext as a null-terminated
+//! `-----BEGIN TIMELOCK MESSAGE-----` block of base64, and
+//! `timelock_decrypt_armored` accepts one, so ciphertexts can be exchanged
+//! over text protocols without the caller implementing the framing.
+
+use super::{
+	guard, guard_or, set_last_error, timelock_ciphertext_free, timelock_decrypt, timelock_encrypt,
+	TimelockCiphertext, TimelockResult,
+};
+use std::{
+	ffi::{CStr, CString},
+	os::raw::{c_char, c_uchar},
+	ptr, slice,
+};
+use timelock::tlock::Armored;
+
+/// Encrypt a message using timelock encryption, armored
+///
+/// As `timelock_encrypt`, with the ciphertext returned as an armored string.
+///
+/// # Parameters
+/// - `armored_out`: Output pointer for the null-terminated armored ciphertext
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`; `armored_out` will be set to a string that must
+/// be freed with `timelock_string_free`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_armored(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	armored_out: *mut *mut c_char,
+) -> TimelockResult {
+	guard(|| {
+		if armored_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let mut ciphertext: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			&mut ciphertext,
+		);
+		if result != TimelockResult::Success {
+			return result;
+		}
+
+		let ct = &*ciphertext;
+		let armored = Armored::new(slice::from_raw_parts(ct.data, ct.len).to_vec()).encode();
+		timelock_ciphertext_free(ciphertext);
+		*armored_out = CString::new(armored).expect("Armor is ASCII without nulls.").into_raw();
+		TimelockResult::Success
+	})
+}
+
+/// Decrypt an armored ciphertext
+///
+/// As `timelock_decrypt`, with the ciphertext given as an armored string, e.g.
+/// from `timelock_encrypt_armored`. Headers are ignored, and CRLF line endings
+/// and surrounding whitespace are tolerated.
+///
+/// # Parameters
+/// - `armored`: Null-terminated armored ciphertext
+/// - Others as for `timelock_decrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::SerializationError`
+/// if the armor is malformed, error code on other failures
+///
+/// # Safety
+/// As for `timelock_decrypt`; `armored` must be a valid null-terminated C
+/// string
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_armored(
+	armored: *const c_char,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		if armored.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let decoded = CStr::from_ptr(armored)
+			.to_str()
+			.map_err(|_| "not valid UTF-8".to_string())
+			.and_then(|text| Armored::decode(text).map_err(|e| e.to_string()));
+		let mut data = match decoded {
+			Ok(armored) => armored.data,
+			Err(e) => {
+				set_last_error(&format!("Invalid armored ciphertext: {}", e));
+				return TimelockResult::SerializationError;
+			},
+		};
+
+		let ciphertext = TimelockCiphertext { data: data.as_mut_ptr(), len: data.len() };
+		timelock_decrypt(&ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// Free a string returned by a timelock function
+///
+/// # Safety
+/// - `string` must be a valid pointer returned by `timelock_encrypt_armored`,
+///   or null
+/// - `string` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_string_free(string: *mut c_char) {
+	guard_or((), || {
+		if !string.is_null() {
+			drop(CString::from_raw(string));
+		}
+	})
+}
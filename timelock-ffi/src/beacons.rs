@@ -0,0 +1,273 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Beacons registered by name.
+//!
+//! `timelock_register_beacon` records the public key, schedule and engine of a
+//! beacon once, e.g. at startup, and the `_named` functions look them up by
+//! name, so the public key need not be threaded through every call site. The
+//! registry is shared by all threads. Drand's `quicknet` is known without
+//! registration, unless a beacon is registered under that name.
+
+use super::{
+	clear_last_error, decrypt, encrypt, guard, set_last_error, timelock_validate_public_key_ex,
+	Encoded, TimelockBeacon, TimelockCipher, TimelockCiphertext, TimelockResult,
+};
+use std::{
+	collections::BTreeMap,
+	ffi::CStr,
+	os::raw::{c_char, c_uchar},
+	sync::Mutex,
+};
+use timelock::{
+	beacons::BeaconPreset,
+	engines::drand::{TinyBLS377, TinyBLS381},
+	rounds::RoundCalculator,
+};
+
+/// A registered beacon
+#[derive(Clone)]
+struct RegisteredBeacon {
+	/// The engine of the beacon
+	scheme: TimelockBeacon,
+	/// The compressed public key
+	public_key: Vec<u8>,
+	/// The round schedule of the beacon
+	rounds: RoundCalculator,
+}
+
+/// The beacons registered with `timelock_register_beacon`
+static REGISTRY: Mutex<BTreeMap<String, RegisteredBeacon>> = Mutex::new(BTreeMap::new());
+
+/// The scheme of the presets that can be encrypted for with `TinyBLS381`
+const QUICKNET_SCHEME: &str = "bls-unchained-g1-rfc9380";
+
+/// Read a beacon name, setting the last error on failure
+///
+/// # Safety
+/// `name` must be null or a valid null-terminated C string
+unsafe fn parse_name<'a>(name: *const c_char) -> Result<&'a str, TimelockResult> {
+	if name.is_null() {
+		set_last_error("Invalid input parameters: null beacon name");
+		return Err(TimelockResult::InvalidInput);
+	}
+	match CStr::from_ptr(name).to_str() {
+		Ok(name) if !name.is_empty() => Ok(name),
+		_ => {
+			set_last_error("Invalid beacon name: must be non-empty UTF-8");
+			Err(TimelockResult::InvalidInput)
+		},
+	}
+}
+
+/// The beacon registered as `name`, or the preset of that name, setting the
+/// last error if there is neither
+///
+/// # Safety
+/// `name` must be null or a valid null-terminated C string
+unsafe fn lookup(name: *const c_char) -> Result<RegisteredBeacon, TimelockResult> {
+	let name = parse_name(name)?;
+	let registered = REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).get(name).cloned();
+	let preset = || {
+		BeaconPreset::by_name(name)
+			.filter(|preset| preset.scheme == QUICKNET_SCHEME)
+			.map(|preset| RegisteredBeacon {
+				scheme: TimelockBeacon::QuickNet,
+				public_key: preset.public_key_bytes(),
+				rounds: preset.rounds(),
+			})
+	};
+	registered.or_else(preset).ok_or_else(|| {
+		set_last_error(&format!("Unknown beacon: {}", name));
+		TimelockResult::UnsupportedBeacon
+	})
+}
+
+/// Register a beacon under a name
+///
+/// The public key is validated for the engine of `scheme` as by
+/// `timelock_validate_public_key_ex`. Registering a name again replaces the
+/// beacon registered under it.
+///
+/// # Parameters
+/// - `name`: Null-terminated, non-empty name of the beacon
+/// - `public_key_hex`: Null-terminated hex string of the public key
+/// - `genesis`: The unix time of round 1
+/// - `period`: The number of seconds between rounds (non-zero)
+/// - `scheme`: The engine of the beacon
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `name` and `public_key_hex` must be valid null-terminated C strings
+#[no_mangle]
+pub unsafe extern "C" fn timelock_register_beacon(
+	name: *const c_char,
+	public_key_hex: *const c_char,
+	genesis: u64,
+	period: u64,
+	scheme: TimelockBeacon,
+) -> TimelockResult {
+	guard(|| {
+		let name = match parse_name(name) {
+			Ok(name) => name,
+			Err(result) => return result,
+		};
+		if period == 0 {
+			set_last_error("Invalid beacon period: must be non-zero");
+			return TimelockResult::InvalidInput;
+		}
+		let result = timelock_validate_public_key_ex(scheme, public_key_hex);
+		if result != TimelockResult::Success {
+			return result;
+		}
+
+		let public_key = match Encoded::Hex(public_key_hex)
+			.bytes("public key", TimelockResult::InvalidPublicKey)
+		{
+			Ok(public_key) => public_key,
+			Err(result) => return result,
+		};
+		let beacon =
+			RegisteredBeacon { scheme, public_key, rounds: RoundCalculator::new(genesis, period) };
+		REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).insert(name.into(), beacon);
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Encrypt a message for a registered beacon
+///
+/// As `timelock_encrypt_ex`, with the public key and engine of the beacon
+/// registered as `name`.
+///
+/// # Parameters
+/// - `name`: Null-terminated name of the beacon
+/// - Others as for `timelock_encrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::UnsupportedBeacon`
+/// if no beacon is known by `name`, error code on other failures
+///
+/// # Safety
+/// As for `timelock_encrypt`; `name` must be a valid null-terminated C string
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_named(
+	name: *const c_char,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		let beacon = match lookup(name) {
+			Ok(beacon) => beacon,
+			Err(result) => return result,
+		};
+		let encrypt = match beacon.scheme {
+			TimelockBeacon::QuickNet => encrypt::<TinyBLS381>,
+			TimelockBeacon::Idn => encrypt::<TinyBLS377>,
+		};
+		encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Raw(beacon.public_key.as_ptr(), beacon.public_key.len()),
+			secret_key,
+			TimelockCipher::AesGcm,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Decrypt a ciphertext of a registered beacon
+///
+/// As `timelock_decrypt_ex`, with the engine of the beacon registered as
+/// `name`.
+///
+/// # Parameters
+/// - `name`: Null-terminated name of the beacon
+/// - Others as for `timelock_decrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::UnsupportedBeacon`
+/// if no beacon is known by `name`, error code on other failures
+///
+/// # Safety
+/// As for `timelock_decrypt`; `name` must be a valid null-terminated C string
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_named(
+	name: *const c_char,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		let beacon = match lookup(name) {
+			Ok(beacon) => beacon,
+			Err(result) => return result,
+		};
+		let decrypt = match beacon.scheme {
+			TimelockBeacon::QuickNet => decrypt::<TinyBLS381>,
+			TimelockBeacon::Idn => decrypt::<TinyBLS377>,
+		};
+		decrypt(ciphertext, Encoded::Hex(signature_hex), plaintext_out, plaintext_len)
+	})
+}
+
+/// Get the first round of a registered beacon published at or after a time
+///
+/// This is the round to lock a ciphertext to so that it unlocks at `time`;
+/// its identity is given by `timelock_create_drand_identity`.
+///
+/// # Parameters
+/// - `name`: Null-terminated name of the beacon
+/// - `time`: Unix time in seconds
+/// - `round_out`: Output pointer for the round
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::UnsupportedBeacon`
+/// if no beacon is known by `name`, error code on other failures
+///
+/// # Safety
+/// - `name` must be a valid null-terminated C string
+/// - `round_out` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_beacon_round_after(
+	name: *const c_char,
+	time: u64,
+	round_out: *mut u64,
+) -> TimelockResult {
+	guard(|| {
+		if round_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		let beacon = match lookup(name) {
+			Ok(beacon) => beacon,
+			Err(result) => return result,
+		};
+		*round_out = beacon.rounds.round_after(time);
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
@@ -0,0 +1,1032 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Context handles holding the last error and the RNG provider.
+//!
+//! By default the last error and the RNG provider are kept per thread, which
+//! suits callers that make each call and read its error on the same OS thread.
+//! Thread pools and runtimes with green threads cannot promise that, so they
+//! create a `TimelockContext` and call the `_ctx` variant of each function:
+//! the error of the call and the RNG it draws from are those of the context,
+//! whichever thread runs it.
+//!
+//! A context may move between threads but must not be used by two calls at
+//! once.
+
+use super::{
+	guard, guard_or, set_last_error, timelock_beacon_round_after, timelock_ciphertext_clone,
+	timelock_ciphertext_from_bytes, timelock_create_drand_identity, timelock_create_identity,
+	timelock_decrypt, timelock_decrypt_alloc, timelock_decrypt_armored, timelock_decrypt_async,
+	timelock_decrypt_batch, timelock_decrypt_early, timelock_decrypt_early_secure,
+	timelock_decrypt_ex, timelock_decrypt_multi, timelock_decrypt_named, timelock_decrypt_raw,
+	timelock_decrypt_secure, timelock_decrypt_size, timelock_decrypt_stream_begin,
+	timelock_decrypt_stream_finish, timelock_decrypt_stream_update, timelock_encrypt,
+	timelock_encrypt_armored, timelock_encrypt_async, timelock_encrypt_deterministic,
+	timelock_encrypt_ex, timelock_encrypt_into, timelock_encrypt_multi, timelock_encrypt_named,
+	timelock_encrypt_opts, timelock_encrypt_raw, timelock_encrypt_stream_begin,
+	timelock_encrypt_stream_finish, timelock_encrypt_stream_update, timelock_encrypt_with_cipher,
+	timelock_estimate_ciphertext_size, timelock_register_beacon, timelock_secure_buffer_new,
+	timelock_self_test, timelock_set_rng_callback, timelock_set_rng_seed, timelock_use_os_rng,
+	timelock_validate_public_key, timelock_validate_public_key_ex, RngProvider,
+	TimelockBatchResult, TimelockBeacon, TimelockCipher, TimelockCiphertext,
+	TimelockDecryptCallback, TimelockDecryptStream, TimelockEncryptCallback,
+	TimelockEncryptOptions, TimelockEncryptStream, TimelockPlaintext, TimelockResult,
+	TimelockRngCallback, TimelockSecureBuffer, TimelockSelfTestReport, TimelockWriteCallback,
+};
+use std::{
+	cell::{Cell, RefCell},
+	ffi::CString,
+	os::raw::{c_char, c_uchar, c_void},
+	ptr,
+};
+
+/// Opaque handle holding the state of the `_ctx` functions
+pub struct TimelockContext {
+	pub(crate) last_error: RefCell<Option<CString>>,
+	pub(crate) rng: RefCell<RngProvider>,
+}
+
+impl TimelockContext {
+	const fn new() -> Self {
+		Self { last_error: RefCell::new(None), rng: RefCell::new(RngProvider::Os) }
+	}
+
+	/// The last error message, or null if there is none
+	pub(crate) fn last_error_ptr(&self) -> *const c_char {
+		self.last_error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr())
+	}
+}
+
+thread_local! {
+	/// The state of calls without a context
+	static THREAD_CONTEXT: TimelockContext = const { TimelockContext::new() };
+	/// The context of the `_ctx` call running on this thread, if any
+	static CURRENT: Cell<*const TimelockContext> = const { Cell::new(ptr::null()) };
+}
+
+/// Run `f` with the context of the current call, or of this thread outside a
+/// `_ctx` call (internal helper)
+pub(crate) fn with_context<T>(f: impl FnOnce(&TimelockContext) -> T) -> T {
+	let current = CURRENT.with(Cell::get);
+	if current.is_null() {
+		THREAD_CONTEXT.with(f)
+	} else {
+		// SAFETY: `in_context` only sets a valid context, and clears it before
+		// the context can be freed.
+		f(unsafe { &*current })
+	}
+}
+
+/// Restores the previous context of this thread when dropped
+struct Restore(*const TimelockContext);
+
+impl Drop for Restore {
+	fn drop(&mut self) {
+		CURRENT.with(|c| c.set(self.0));
+	}
+}
+
+/// Run `f` with `context` as the context of this thread
+///
+/// # Safety
+/// `context` must be null or a valid pointer returned by
+/// `timelock_context_new`
+unsafe fn in_context(
+	context: *mut TimelockContext,
+	f: impl FnOnce() -> TimelockResult,
+) -> TimelockResult {
+	if context.is_null() {
+		set_last_error("Invalid input parameters: null context");
+		return TimelockResult::InvalidInput;
+	}
+	let _restore = Restore(CURRENT.with(|c| c.replace(context)));
+	guard(f)
+}
+
+/// Create a context
+///
+/// # Returns
+/// A new context, which must be freed with `timelock_context_free`
+///
+/// # Safety
+/// This function is safe to call from any context.
+#[no_mangle]
+pub unsafe extern "C" fn timelock_context_new() -> *mut TimelockContext {
+	guard_or(ptr::null_mut(), || Box::into_raw(Box::new(TimelockContext::new())))
+}
+
+/// Free a context
+///
+/// # Safety
+/// - `context` must be a valid pointer returned by `timelock_context_new`, or
+///   null
+/// - `context` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_context_free(context: *mut TimelockContext) {
+	guard_or((), || {
+		if !context.is_null() {
+			drop(Box::from_raw(context));
+		}
+	})
+}
+
+/// Get the last error message of a context (if any)
+///
+/// # Returns
+/// Null-terminated string with the last error message, or null if no error
+///
+/// # Safety
+/// - `context` must be a valid pointer returned by `timelock_context_new`, or
+///   null
+/// - The returned pointer is valid until the next call with the context
+#[no_mangle]
+pub unsafe extern "C" fn timelock_context_get_last_error(
+	context: *const TimelockContext,
+) -> *const c_char {
+	guard_or(ptr::null(), || {
+		if context.is_null() {
+			return ptr::null();
+		}
+		(*context).last_error_ptr()
+	})
+}
+
+/// `timelock_ciphertext_from_bytes` within a context
+///
+/// # Safety
+/// As for `timelock_ciphertext_from_bytes`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_from_bytes_ctx(
+	context: *mut TimelockContext,
+	data: *const c_uchar,
+	len: usize,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || timelock_ciphertext_from_bytes(data, len, ciphertext_out))
+}
+
+/// `timelock_ciphertext_clone` within a context
+///
+/// # Safety
+/// As for `timelock_ciphertext_clone`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_clone_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || timelock_ciphertext_clone(ciphertext, ciphertext_out))
+}
+
+/// `timelock_create_drand_identity` within a context
+///
+/// # Safety
+/// As for `timelock_create_drand_identity`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_create_drand_identity_ctx(
+	context: *mut TimelockContext,
+	round_number: u64,
+	identity_out: *mut c_uchar,
+	identity_len: usize,
+) -> TimelockResult {
+	in_context(context, || timelock_create_drand_identity(round_number, identity_out, identity_len))
+}
+
+/// `timelock_create_identity` within a context
+///
+/// # Safety
+/// As for `timelock_create_identity`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_create_identity_ctx(
+	context: *mut TimelockContext,
+	data: *const c_uchar,
+	data_len: usize,
+	identity_out: *mut c_uchar,
+	identity_len: usize,
+) -> TimelockResult {
+	in_context(context, || timelock_create_identity(data, data_len, identity_out, identity_len))
+}
+
+/// `timelock_validate_public_key` within a context
+///
+/// # Safety
+/// As for `timelock_validate_public_key`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key_ctx(
+	context: *mut TimelockContext,
+	public_key_hex: *const c_char,
+) -> TimelockResult {
+	in_context(context, || timelock_validate_public_key(public_key_hex))
+}
+
+/// `timelock_validate_public_key_ex` within a context
+///
+/// # Safety
+/// As for `timelock_validate_public_key_ex`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key_ex_ctx(
+	context: *mut TimelockContext,
+	beacon: TimelockBeacon,
+	public_key_hex: *const c_char,
+) -> TimelockResult {
+	in_context(context, || timelock_validate_public_key_ex(beacon, public_key_hex))
+}
+
+/// `timelock_encrypt` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_ex` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_ex`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_ex_ctx(
+	context: *mut TimelockContext,
+	beacon: TimelockBeacon,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_ex(
+			beacon,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_deterministic` within a context
+///
+/// # Safety
+/// As for `timelock_encrypt_deterministic`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_deterministic_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	seed: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_deterministic(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			seed,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_raw` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_raw`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_raw_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key: *const c_uchar,
+	public_key_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_raw(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key,
+			public_key_len,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_armored` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_armored`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_armored_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	armored_out: *mut *mut c_char,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_armored(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			armored_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_into` within a context
+///
+/// # Safety
+/// As for `timelock_encrypt_into`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_into_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut c_uchar,
+	ciphertext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_into(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+			ciphertext_len,
+		)
+	})
+}
+
+/// `timelock_estimate_ciphertext_size` within a context
+///
+/// # Safety
+/// As for `timelock_estimate_ciphertext_size`; `context` must be a valid
+/// pointer returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_estimate_ciphertext_size_ctx(
+	context: *mut TimelockContext,
+	message_len: usize,
+	estimated_size_out: *mut usize,
+) -> TimelockResult {
+	in_context(context, || timelock_estimate_ciphertext_size(message_len, estimated_size_out))
+}
+
+/// `timelock_self_test` within a context
+///
+/// # Safety
+/// As for `timelock_self_test`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_self_test_ctx(
+	context: *mut TimelockContext,
+	report_out: *mut TimelockSelfTestReport,
+) -> TimelockResult {
+	in_context(context, || timelock_self_test(report_out))
+}
+
+/// `timelock_decrypt` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt(ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_size` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_size`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_size_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	size_out: *mut usize,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_size(ciphertext, size_out))
+}
+
+/// `timelock_decrypt_ex` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_ex`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_ex_ctx(
+	context: *mut TimelockContext,
+	beacon: TimelockBeacon,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_ex(beacon, ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_raw` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_raw`; `context` must be a valid pointer returned by
+/// `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_raw_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature: *const c_uchar,
+	signature_len: usize,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_raw(ciphertext, signature, signature_len, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_armored` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_armored`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_armored_ctx(
+	context: *mut TimelockContext,
+	armored: *const c_char,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_armored(armored, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_early` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_early`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_early(ciphertext, secret_key, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_alloc` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_alloc`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_alloc_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut *mut TimelockPlaintext,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_alloc(ciphertext, signature_hex, plaintext_out))
+}
+
+/// `timelock_decrypt_batch` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_batch`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_batch_ctx(
+	context: *mut TimelockContext,
+	ciphertexts: *const *const TimelockCiphertext,
+	count: usize,
+	signature_hex: *const c_char,
+	results: *mut TimelockBatchResult,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_batch(ciphertexts, count, signature_hex, results))
+}
+
+/// `timelock_secure_buffer_new` within a context
+///
+/// # Safety
+/// As for `timelock_secure_buffer_new`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_new_ctx(
+	context: *mut TimelockContext,
+	capacity: usize,
+	buffer_out: *mut *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	in_context(context, || timelock_secure_buffer_new(capacity, buffer_out))
+}
+
+/// `timelock_decrypt_secure` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_secure`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_secure_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	buffer: *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_secure(ciphertext, signature_hex, buffer))
+}
+
+/// `timelock_decrypt_early_secure` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_early_secure`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early_secure_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	buffer: *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_early_secure(ciphertext, secret_key, buffer))
+}
+
+/// `timelock_set_rng_callback` for a context
+///
+/// # Safety
+/// As for `timelock_set_rng_callback`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_callback_ctx(
+	context: *mut TimelockContext,
+	fill: TimelockRngCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	in_context(context, || timelock_set_rng_callback(fill, user_data))
+}
+
+/// `timelock_set_rng_seed` for a context
+///
+/// # Safety
+/// As for `timelock_set_rng_seed`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_seed_ctx(
+	context: *mut TimelockContext,
+	seed: *const c_uchar,
+	seed_len: usize,
+) -> TimelockResult {
+	in_context(context, || timelock_set_rng_seed(seed, seed_len))
+}
+
+/// `timelock_use_os_rng` for a context
+///
+/// # Safety
+/// `context` must be a valid pointer returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_use_os_rng_ctx(context: *mut TimelockContext) -> TimelockResult {
+	in_context(context, || {
+		timelock_use_os_rng();
+		TimelockResult::Success
+	})
+}
+
+/// `timelock_encrypt_stream_begin` within a context, drawing randomness from
+/// the RNG provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_begin`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_begin_ctx(
+	context: *mut TimelockContext,
+	beacon: TimelockBeacon,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockEncryptStream,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_stream_begin(
+			beacon,
+			identity,
+			identity_len,
+			public_key_hex,
+			write,
+			user_data,
+			stream_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_stream_update` within a context
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_update`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_update_ctx(
+	context: *mut TimelockContext,
+	stream: *mut TimelockEncryptStream,
+	data: *const c_uchar,
+	len: usize,
+) -> TimelockResult {
+	in_context(context, || timelock_encrypt_stream_update(stream, data, len))
+}
+
+/// `timelock_encrypt_stream_finish` within a context
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_finish`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_finish_ctx(
+	context: *mut TimelockContext,
+	stream: *mut TimelockEncryptStream,
+) -> TimelockResult {
+	in_context(context, || timelock_encrypt_stream_finish(stream))
+}
+
+/// `timelock_decrypt_stream_begin` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_begin`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_begin_ctx(
+	context: *mut TimelockContext,
+	beacon: TimelockBeacon,
+	signature_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockDecryptStream,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_stream_begin(beacon, signature_hex, write, user_data, stream_out)
+	})
+}
+
+/// `timelock_decrypt_stream_update` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_update`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_update_ctx(
+	context: *mut TimelockContext,
+	stream: *mut TimelockDecryptStream,
+	data: *const c_uchar,
+	len: usize,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_stream_update(stream, data, len))
+}
+
+/// `timelock_decrypt_stream_finish` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_finish`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_finish_ctx(
+	context: *mut TimelockContext,
+	stream: *mut TimelockDecryptStream,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_stream_finish(stream))
+}
+
+/// `timelock_register_beacon` within a context
+///
+/// # Safety
+/// As for `timelock_register_beacon`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_register_beacon_ctx(
+	context: *mut TimelockContext,
+	name: *const c_char,
+	public_key_hex: *const c_char,
+	genesis: u64,
+	period: u64,
+	scheme: TimelockBeacon,
+) -> TimelockResult {
+	in_context(context, || timelock_register_beacon(name, public_key_hex, genesis, period, scheme))
+}
+
+/// `timelock_encrypt_named` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_named`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_named_ctx(
+	context: *mut TimelockContext,
+	name: *const c_char,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_named(
+			name,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_decrypt_named` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_named`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_named_ctx(
+	context: *mut TimelockContext,
+	name: *const c_char,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_named(name, ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_beacon_round_after` within a context
+///
+/// # Safety
+/// As for `timelock_beacon_round_after`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_beacon_round_after_ctx(
+	context: *mut TimelockContext,
+	name: *const c_char,
+	time: u64,
+	round_out: *mut u64,
+) -> TimelockResult {
+	in_context(context, || timelock_beacon_round_after(name, time, round_out))
+}
+
+/// `timelock_encrypt_async` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_async`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_async_ctx(
+	context: *mut TimelockContext,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	callback: TimelockEncryptCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_async(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			callback,
+			user_data,
+		)
+	})
+}
+
+/// `timelock_decrypt_async` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_async`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_async_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	callback: TimelockDecryptCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	in_context(context, || timelock_decrypt_async(ciphertext, signature_hex, callback, user_data))
+}
+
+/// `timelock_encrypt_multi` within a context, drawing randomness from the RNG
+/// provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_multi`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_multi_ctx(
+	context: *mut TimelockContext,
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	rounds: *const u64,
+	round_count: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_multi(
+			cipher,
+			message,
+			message_len,
+			rounds,
+			round_count,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_decrypt_multi` within a context
+///
+/// # Safety
+/// As for `timelock_decrypt_multi`; `context` must be a valid pointer returned
+/// by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_multi_ctx(
+	context: *mut TimelockContext,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_decrypt_multi(ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_encrypt_with_cipher` within a context, drawing randomness from
+/// the RNG provider of the context
+///
+/// # Safety
+/// As for `timelock_encrypt_with_cipher`; `context` must be a valid pointer
+/// returned by `timelock_context_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_with_cipher_ctx(
+	context: *mut TimelockContext,
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_with_cipher(
+			cipher,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_opts`, using the state of `context`
+///
+/// # Safety
+/// As for `timelock_encrypt_opts`; `context` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_opts_ctx(
+	context: *mut TimelockContext,
+	options: *const TimelockEncryptOptions,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	in_context(context, || {
+		timelock_encrypt_opts(
+			options,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
@@ -0,0 +1,1023 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Error reporting through out-parameters.
+//!
+//! The `_e` variant of each function takes a trailing `TimelockErrorInfo`
+//! pointer, which may be null, and writes the result code and error message of
+//! the call to it. The error is neither read from nor written to the last
+//! error of the thread or of a context, so callers embedding the library in
+//! async runtimes need no shared error channel at all. Randomness is still
+//! drawn from the RNG provider of the thread.
+//!
+//! A panic must not unwind into C. The body of every extern function runs in
+//! `guard`, which catches a panic and reports it as
+//! `TimelockResult::InternalPanic` with the panic message as the error. The
+//! `_e` and `_ctx` variants are guarded by `with_error_info` and `in_context`.
+
+use super::{
+	log, timelock_beacon_round_after, timelock_ciphertext_clone, timelock_ciphertext_from_bytes,
+	timelock_create_drand_identity, timelock_create_identity, timelock_decrypt,
+	timelock_decrypt_alloc, timelock_decrypt_armored, timelock_decrypt_async,
+	timelock_decrypt_batch, timelock_decrypt_early, timelock_decrypt_early_secure,
+	timelock_decrypt_ex, timelock_decrypt_multi, timelock_decrypt_named, timelock_decrypt_raw,
+	timelock_decrypt_secure, timelock_decrypt_size, timelock_decrypt_stream_begin,
+	timelock_decrypt_stream_finish, timelock_decrypt_stream_update, timelock_encrypt,
+	timelock_encrypt_armored, timelock_encrypt_async, timelock_encrypt_deterministic,
+	timelock_encrypt_ex, timelock_encrypt_into, timelock_encrypt_multi, timelock_encrypt_named,
+	timelock_encrypt_opts, timelock_encrypt_raw, timelock_encrypt_stream_begin,
+	timelock_encrypt_stream_finish, timelock_encrypt_stream_update, timelock_encrypt_with_cipher,
+	timelock_estimate_ciphertext_size, timelock_register_beacon, timelock_secure_buffer_new,
+	timelock_self_test, timelock_set_rng_callback, timelock_set_rng_seed,
+	timelock_validate_public_key, timelock_validate_public_key_ex, with_context,
+	TimelockBatchResult, TimelockBeacon, TimelockCipher, TimelockCiphertext,
+	TimelockDecryptCallback, TimelockDecryptStream, TimelockEncryptCallback,
+	TimelockEncryptOptions, TimelockEncryptStream, TimelockLogLevel, TimelockPlaintext,
+	TimelockResult, TimelockRngCallback, TimelockSecureBuffer, TimelockSelfTestReport,
+	TimelockWriteCallback,
+};
+use std::{
+	cell::{Cell, RefCell},
+	ffi::CString,
+	os::raw::{c_char, c_uchar, c_void},
+	panic::{self, AssertUnwindSafe},
+	ptr,
+};
+
+/// Size of the message buffer of `TimelockErrorInfo`, including the
+/// terminating null
+pub const TIMELOCK_ERROR_MESSAGE_SIZE: usize = 256;
+
+/// The result of an `_e` call
+#[repr(C)]
+pub struct TimelockErrorInfo {
+	/// The result code, as returned by the call
+	pub code: TimelockResult,
+	/// Null-terminated error message, truncated to fit, or empty on success
+	pub message: [c_char; TIMELOCK_ERROR_MESSAGE_SIZE],
+}
+
+impl TimelockErrorInfo {
+	/// Write `code` and `message`, truncated to the buffer at a character
+	/// boundary
+	///
+	/// # Safety
+	/// `info` must be a valid pointer, which need not be initialized
+	unsafe fn write(info: *mut Self, code: TimelockResult, message: Option<CString>) {
+		let message = message.as_ref().map_or(&[][..], |m| m.as_bytes());
+		let mut len = message.len().min(TIMELOCK_ERROR_MESSAGE_SIZE - 1);
+		while len < message.len() && message[len] & 0xc0 == 0x80 {
+			len -= 1;
+		}
+		let out = ptr::addr_of_mut!((*info).message) as *mut c_char;
+		ptr::copy_nonoverlapping(message.as_ptr() as *const c_char, out, len);
+		*out.add(len) = 0;
+		ptr::addr_of_mut!((*info).code).write(code);
+	}
+}
+
+thread_local! {
+	/// The error of the `_e` call running on this thread, if any
+	static CAPTURE: Cell<*const RefCell<Option<CString>>> = const { Cell::new(ptr::null()) };
+}
+
+/// Store the error of the current call (internal helper)
+///
+/// Inside an `_e` call the error is kept for its `TimelockErrorInfo`,
+/// otherwise it becomes the last error of the current context.
+pub(crate) fn store_error(error: Option<CString>) {
+	let capture = CAPTURE.with(Cell::get);
+	if capture.is_null() {
+		with_context(|c| *c.last_error.borrow_mut() = error);
+	} else {
+		// SAFETY: `capture_error` clears the capture before it is dropped.
+		unsafe { *(*capture).borrow_mut() = error };
+	}
+}
+
+/// Restores the previous capture of this thread when dropped
+struct Restore(*const RefCell<Option<CString>>);
+
+impl Drop for Restore {
+	fn drop(&mut self) {
+		CAPTURE.with(|c| c.set(self.0));
+	}
+}
+
+/// Run the body of an extern function, catching a panic (internal helper)
+///
+/// A panic is reported as `TimelockResult::InternalPanic`, with its message
+/// as the error of the call.
+pub(crate) fn guard(f: impl FnOnce() -> TimelockResult) -> TimelockResult {
+	guard_or(TimelockResult::InternalPanic, f)
+}
+
+/// Run the body of an extern function, returning `default` on a panic
+/// (internal helper)
+///
+/// For functions without a result code; the panic message is still stored as
+/// the error of the call.
+pub(crate) fn guard_or<T>(default: T, f: impl FnOnce() -> T) -> T {
+	// the arguments are raw pointers whose state the caller owns, and a
+	// panicking call leaves the library's own state consistent
+	panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+		let message = payload
+			.downcast_ref::<&str>()
+			.copied()
+			.or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+			.unwrap_or("unknown panic");
+		let message = format!("Internal panic: {}", message);
+		log(TimelockLogLevel::Error, &message);
+		store_error(CString::new(message).ok());
+		default
+	})
+}
+
+/// Run `f`, returning its result and the error it stored instead of setting
+/// the last error
+pub(crate) fn capture_error(
+	f: impl FnOnce() -> TimelockResult,
+) -> (TimelockResult, Option<CString>) {
+	let error = RefCell::new(None);
+	let result = {
+		let _restore = Restore(CAPTURE.with(|c| c.replace(&error)));
+		guard(f)
+	};
+	(result, error.into_inner())
+}
+
+/// Run `f`, writing its result and error to `info` if it is not null
+///
+/// # Safety
+/// `info` must be null or a valid pointer
+pub(crate) unsafe fn with_error_info(
+	info: *mut TimelockErrorInfo,
+	f: impl FnOnce() -> TimelockResult,
+) -> TimelockResult {
+	let (result, error) = capture_error(f);
+	if !info.is_null() {
+		TimelockErrorInfo::write(info, result, error);
+	}
+	result
+}
+
+/// `timelock_ciphertext_from_bytes`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_ciphertext_from_bytes`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_from_bytes_e(
+	data: *const c_uchar,
+	len: usize,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_ciphertext_from_bytes(data, len, ciphertext_out))
+}
+
+/// `timelock_ciphertext_clone`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_ciphertext_clone`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_clone_e(
+	ciphertext: *const TimelockCiphertext,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_ciphertext_clone(ciphertext, ciphertext_out))
+}
+
+/// `timelock_create_drand_identity`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_create_drand_identity`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_create_drand_identity_e(
+	round_number: u64,
+	identity_out: *mut c_uchar,
+	identity_len: usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_create_drand_identity(round_number, identity_out, identity_len)
+	})
+}
+
+/// `timelock_create_identity`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_create_identity`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_create_identity_e(
+	data: *const c_uchar,
+	data_len: usize,
+	identity_out: *mut c_uchar,
+	identity_len: usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_create_identity(data, data_len, identity_out, identity_len)
+	})
+}
+
+/// `timelock_validate_public_key`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_validate_public_key`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key_e(
+	public_key_hex: *const c_char,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_validate_public_key(public_key_hex))
+}
+
+/// `timelock_validate_public_key_ex`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_validate_public_key_ex`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key_ex_e(
+	beacon: TimelockBeacon,
+	public_key_hex: *const c_char,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_validate_public_key_ex(beacon, public_key_hex))
+}
+
+/// `timelock_encrypt`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_ex`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_ex`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_ex_e(
+	beacon: TimelockBeacon,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_ex(
+			beacon,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_deterministic`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_deterministic`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_deterministic_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	seed: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_deterministic(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			seed,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_raw`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_raw`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_raw_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key: *const c_uchar,
+	public_key_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_raw(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key,
+			public_key_len,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_armored`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_armored`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_armored_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	armored_out: *mut *mut c_char,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_armored(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			armored_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_into`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_into`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_into_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut c_uchar,
+	ciphertext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_into(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+			ciphertext_len,
+		)
+	})
+}
+
+/// `timelock_estimate_ciphertext_size`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_estimate_ciphertext_size`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_estimate_ciphertext_size_e(
+	message_len: usize,
+	estimated_size_out: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_estimate_ciphertext_size(message_len, estimated_size_out)
+	})
+}
+
+/// `timelock_self_test`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_self_test`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_self_test_e(
+	report_out: *mut TimelockSelfTestReport,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_self_test(report_out))
+}
+
+/// `timelock_decrypt`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_e(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt(ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_size`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_size`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_size_e(
+	ciphertext: *const TimelockCiphertext,
+	size_out: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_size(ciphertext, size_out))
+}
+
+/// `timelock_decrypt_ex`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_ex`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_ex_e(
+	beacon: TimelockBeacon,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_ex(beacon, ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_raw`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_raw`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_raw_e(
+	ciphertext: *const TimelockCiphertext,
+	signature: *const c_uchar,
+	signature_len: usize,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_raw(ciphertext, signature, signature_len, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_armored`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_armored`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_armored_e(
+	armored: *const c_char,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_armored(armored, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_early`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_early`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early_e(
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_early(ciphertext, secret_key, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_decrypt_alloc`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_alloc`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_alloc_e(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut *mut TimelockPlaintext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_alloc(ciphertext, signature_hex, plaintext_out))
+}
+
+/// `timelock_decrypt_batch`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_batch`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_batch_e(
+	ciphertexts: *const *const TimelockCiphertext,
+	count: usize,
+	signature_hex: *const c_char,
+	results: *mut TimelockBatchResult,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_batch(ciphertexts, count, signature_hex, results)
+	})
+}
+
+/// `timelock_secure_buffer_new`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_secure_buffer_new`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_new_e(
+	capacity: usize,
+	buffer_out: *mut *mut TimelockSecureBuffer,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_secure_buffer_new(capacity, buffer_out))
+}
+
+/// `timelock_decrypt_secure`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_secure`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_secure_e(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	buffer: *mut TimelockSecureBuffer,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_secure(ciphertext, signature_hex, buffer))
+}
+
+/// `timelock_decrypt_early_secure`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_early_secure`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early_secure_e(
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	buffer: *mut TimelockSecureBuffer,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_early_secure(ciphertext, secret_key, buffer))
+}
+
+/// `timelock_set_rng_callback`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_set_rng_callback`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_callback_e(
+	fill: TimelockRngCallback,
+	user_data: *mut c_void,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_set_rng_callback(fill, user_data))
+}
+
+/// `timelock_set_rng_seed`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_set_rng_seed`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_seed_e(
+	seed: *const c_uchar,
+	seed_len: usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_set_rng_seed(seed, seed_len))
+}
+
+/// `timelock_encrypt_stream_begin`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_begin`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_begin_e(
+	beacon: TimelockBeacon,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockEncryptStream,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_stream_begin(
+			beacon,
+			identity,
+			identity_len,
+			public_key_hex,
+			write,
+			user_data,
+			stream_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_stream_update`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_update`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_update_e(
+	stream: *mut TimelockEncryptStream,
+	data: *const c_uchar,
+	len: usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_encrypt_stream_update(stream, data, len))
+}
+
+/// `timelock_encrypt_stream_finish`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_finish`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_finish_e(
+	stream: *mut TimelockEncryptStream,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_encrypt_stream_finish(stream))
+}
+
+/// `timelock_decrypt_stream_begin`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_begin`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_begin_e(
+	beacon: TimelockBeacon,
+	signature_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockDecryptStream,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_stream_begin(beacon, signature_hex, write, user_data, stream_out)
+	})
+}
+
+/// `timelock_decrypt_stream_update`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_update`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_update_e(
+	stream: *mut TimelockDecryptStream,
+	data: *const c_uchar,
+	len: usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_stream_update(stream, data, len))
+}
+
+/// `timelock_decrypt_stream_finish`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_stream_finish`; `error_info` must be null or a
+/// valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_finish_e(
+	stream: *mut TimelockDecryptStream,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_decrypt_stream_finish(stream))
+}
+
+/// `timelock_register_beacon`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_register_beacon`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_register_beacon_e(
+	name: *const c_char,
+	public_key_hex: *const c_char,
+	genesis: u64,
+	period: u64,
+	scheme: TimelockBeacon,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_register_beacon(name, public_key_hex, genesis, period, scheme)
+	})
+}
+
+/// `timelock_encrypt_named`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_named`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_named_e(
+	name: *const c_char,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_named(
+			name,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_decrypt_named`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_named`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_named_e(
+	name: *const c_char,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_named(name, ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_beacon_round_after`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_beacon_round_after`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_beacon_round_after_e(
+	name: *const c_char,
+	time: u64,
+	round_out: *mut u64,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || timelock_beacon_round_after(name, time, round_out))
+}
+
+/// `timelock_encrypt_async`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_async`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_async_e(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	callback: TimelockEncryptCallback,
+	user_data: *mut c_void,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_async(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			callback,
+			user_data,
+		)
+	})
+}
+
+/// `timelock_decrypt_async`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_async`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_async_e(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	callback: TimelockDecryptCallback,
+	user_data: *mut c_void,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_async(ciphertext, signature_hex, callback, user_data)
+	})
+}
+
+/// `timelock_encrypt_multi`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_multi`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_multi_e(
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	rounds: *const u64,
+	round_count: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_multi(
+			cipher,
+			message,
+			message_len,
+			rounds,
+			round_count,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_decrypt_multi`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_decrypt_multi`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_multi_e(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_decrypt_multi(ciphertext, signature_hex, plaintext_out, plaintext_len)
+	})
+}
+
+/// `timelock_encrypt_with_cipher`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_with_cipher`; `error_info` must be null or a valid
+/// pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_with_cipher_e(
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_with_cipher(
+			cipher,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
+
+/// `timelock_encrypt_opts`, reporting its error to `error_info`
+///
+/// # Safety
+/// As for `timelock_encrypt_opts`; `error_info` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_opts_e(
+	options: *const TimelockEncryptOptions,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+	error_info: *mut TimelockErrorInfo,
+) -> TimelockResult {
+	with_error_info(error_info, || {
+		timelock_encrypt_opts(
+			options,
+			message,
+			message_len,
+			identity,
+			identity_len,
+			public_key_hex,
+			secret_key,
+			ciphertext_out,
+		)
+	})
+}
@@ -21,31 +21,108 @@
 //!
 //! ## Supported Beacon Types
 //!
-//! This FFI currently supports only TinyBLS381 (Drand QuickNet beacon).
-//! Future versions will support TinyBLS377 (Ideal Network) when available
-//! in the core timelock library. The API is designed to be extensible
-//! for multiple beacon types while maintaining backward compatibility.
+//! `timelock_encrypt` and `timelock_decrypt` use TinyBLS381 (Drand QuickNet
+//! beacon). `timelock_encrypt_ex` and `timelock_decrypt_ex` take a
+//! `TimelockBeacon` selecting the engine, so C callers can also target
+//! TinyBLS377 (Ideal Network). A ciphertext records no beacon: it must be
+//! decrypted with the beacon it was encrypted for.
+//!
+//! `timelock_encrypt_opts` takes the beacon and the cipher in a
+//! `TimelockEncryptOptions`, which grows at its end as options are added.
+//!
+//! A beacon registered by name with `timelock_register_beacon` is used by the
+//! `_named` functions without passing its public key around.
+//!
+//! ## Multiple Rounds
+//!
+//! `timelock_encrypt_multi` encrypts a message to any one of several rounds,
+//! and `timelock_decrypt_multi` decrypts it with the signature of any of them.
+//!
+//! ## Streaming
+//!
+//! `timelock_encrypt_stream_*` and `timelock_decrypt_stream_*` process a
+//! message fed in pieces, writing their output to a callback, so large
+//! messages never need to be held in memory at once.
+//!
+//! ## Asynchronous Calls
+//!
+//! `timelock_encrypt_async` and `timelock_decrypt_async` run on an internal
+//! thread pool and report their result to a callback. On targets without
+//! threads, such as `wasm32-wasip1`, they run on the calling thread instead.
+//!
+//! ## Contexts
+//!
+//! The last error and the RNG provider are kept per thread. The `_ctx` variant
+//! of each function keeps them on a `TimelockContext` instead, for callers
+//! whose calls may hop between threads.
+//!
+//! ## Error Reporting
+//!
+//! The `_e` variant of each function writes its result code and error message
+//! to a caller-provided `TimelockErrorInfo`, bypassing the last error
+//! altogether.
+//!
+//! `timelock_set_log_callback` routes the same error messages, with a level, to
+//! the logging system of the host.
+//!
+//! A panic inside any function is caught at the boundary and reported as
+//! `TimelockResult::InternalPanic`, with the panic message as the error, so it
+//! never unwinds into the caller. This needs the library to be built with
+//! `panic = "unwind"`, as the `ffi` profile of the workspace does; with
+//! `panic = "abort"` a panic aborts the process.
 
 // Allow unsafe code for FFI bindings - this is necessary for C interop
 #![allow(unsafe_code)]
 
+mod armor;
+mod beacons;
+mod context;
+mod error;
+mod logging;
+mod multi;
+mod secure;
+mod stream;
+mod task;
+pub use armor::*;
+pub use beacons::*;
+use context::with_context;
+pub use context::*;
+pub use error::*;
+use error::{guard, guard_or, store_error};
+use logging::log;
+pub use logging::*;
+pub use multi::*;
+pub use secure::*;
+pub use stream::*;
+pub use task::*;
+
 use std::{
-	cell::RefCell,
 	ffi::{CStr, CString},
-	os::raw::{c_char, c_uchar},
+	mem,
+	os::raw::{c_char, c_int, c_uchar, c_void},
 	ptr, slice,
 };
 use zeroize::Zeroize;
 
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::rand::rngs::OsRng;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{rngs::OsRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use sha2::{Digest, Sha256};
 
 use timelock::{
-	block_ciphers::AESGCMBlockCipherProvider,
-	engines::{drand::TinyBLS381, EngineBLS},
+	block_ciphers::{AESGCMBlockCipherProvider, ChaCha20Poly1305BlockCipherProvider, CipherSuite},
+	engines::{
+		drand::{TinyBLS377, TinyBLS381},
+		EngineBLS,
+	},
 	ibe::fullident::Identity,
-	tlock::{tld, tle, TLECiphertext},
+	self_test,
+	tlock::{
+		buffer::{tld_len, tle_len},
+		strict, tld_batch, tld_early_into, tld_into, tle, tle_into, Error as TlockError,
+		TLECiphertext,
+	},
+	TimelockError,
 };
 
 // BLS12-381 curve element sizes - referenced from the EngineBLS implementation
@@ -134,28 +211,76 @@ pub static TIMELOCK_IDENTITY_SIZE: usize = 32;
 #[no_mangle]
 pub static TIMELOCK_SECRET_KEY_SIZE: usize = <TinyBLS381 as EngineBLS>::SECRET_KEY_SIZE;
 
-// Thread-local storage for error messages
-thread_local! {
-	static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
-}
+/// Version of the C ABI: raised whenever a function or type changes in a way
+/// that breaks callers compiled against an older header. Compare it with
+/// `timelock_get_abi_version` at startup.
+pub const TIMELOCK_ABI_VERSION: u32 = 1;
+
+/// Major version of the library the header belongs to
+pub const TIMELOCK_VERSION_MAJOR: u32 = 0;
+/// Minor version of the library the header belongs to
+pub const TIMELOCK_VERSION_MINOR: u32 = 2;
+/// Patch version of the library the header belongs to
+pub const TIMELOCK_VERSION_PATCH: u32 = 0;
 
 /// Set the last error message (internal helper)
 fn set_last_error(message: &str) {
-	LAST_ERROR.with(|e| {
-		*e.borrow_mut() = CString::new(message).ok();
-	});
+	log(TimelockLogLevel::Warn, message);
+	store_error(CString::new(message).ok());
 }
 
 /// Clear the last error message (internal helper)
 fn clear_last_error() {
-	LAST_ERROR.with(|e| {
-		*e.borrow_mut() = None;
+	store_error(None);
+}
+
+/// A caller-supplied CSPRNG: fills `len` bytes at `out` and returns 0 on
+/// success, or any other value on failure
+pub type TimelockRngCallback =
+	Option<unsafe extern "C" fn(out: *mut c_uchar, len: usize, user_data: *mut c_void) -> c_int>;
+
+/// Where encryption gets its randomness
+enum RngProvider {
+	/// The OS CSPRNG
+	Os,
+	/// A caller-supplied CSPRNG
+	Callback {
+		fill: unsafe extern "C" fn(*mut c_uchar, usize, *mut c_void) -> c_int,
+		user_data: *mut c_void,
+	},
+	/// A ChaCha20 RNG from a fixed seed, for reproducible test vectors only
+	Seeded(Box<ChaCha20Rng>),
+}
+
+/// An RNG for one encryption, seeded from the provider of the current context
+/// (internal helper)
+fn operation_rng() -> Result<ChaCha20Rng, TimelockResult> {
+	let mut seed = [0u8; 32];
+	let filled = with_context(|c| match &mut *c.rng.borrow_mut() {
+		RngProvider::Os => OsRng.try_fill_bytes(&mut seed).is_ok(),
+		// SAFETY: the caller of `timelock_set_rng_callback` guarantees that the
+		// callback can fill `len` bytes at `out` with its `user_data`.
+		RngProvider::Callback { fill, user_data } => unsafe {
+			fill(seed.as_mut_ptr(), seed.len(), *user_data) == 0
+		},
+		RngProvider::Seeded(rng) => {
+			rng.fill_bytes(&mut seed);
+			true
+		},
 	});
+	if !filled {
+		seed.zeroize();
+		set_last_error("The random number generator failed");
+		return Err(TimelockResult::EncryptionFailed);
+	}
+	let rng = ChaCha20Rng::from_seed(seed);
+	seed.zeroize();
+	Ok(rng)
 }
 
 /// Result codes for timelock operations
 #[repr(C)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TimelockResult {
 	/// Operation succeeded
 	Success = 0,
@@ -173,6 +298,87 @@ pub enum TimelockResult {
 	InvalidPublicKey = 6,
 	/// Invalid signature
 	InvalidSignature = 7,
+	/// A point is the identity or not in the prime order subgroup
+	InvalidPoint = 8,
+	/// A known-answer test of the self test failed
+	SelfTestFailed = 9,
+	/// The write callback of a stream failed
+	WriteFailed = 10,
+	/// An output buffer is too small, its required length is reported back
+	BufferTooSmall = 11,
+	/// The round number is not that of a beacon round
+	InvalidRound = 12,
+	/// The beacon is not supported by the library
+	UnsupportedBeacon = 13,
+	/// The library panicked; the panic message is the error message
+	InternalPanic = 14,
+}
+
+impl TimelockResult {
+	/// A static, null-terminated description of the code
+	fn description(&self) -> &'static [u8] {
+		match self {
+			TimelockResult::Success => b"Success\0",
+			TimelockResult::InvalidInput => b"Invalid input parameters\0",
+			TimelockResult::EncryptionFailed => b"Encryption failed\0",
+			TimelockResult::DecryptionFailed => b"Decryption failed\0",
+			TimelockResult::MemoryError => b"Memory allocation failed\0",
+			TimelockResult::SerializationError => b"Serialization failed\0",
+			TimelockResult::InvalidPublicKey => b"Invalid public key\0",
+			TimelockResult::InvalidSignature => b"Invalid signature\0",
+			TimelockResult::InvalidPoint =>
+				b"A point is the identity or not in the prime order subgroup\0",
+			TimelockResult::SelfTestFailed => b"The self test failed\0",
+			TimelockResult::WriteFailed => b"The write callback failed\0",
+			TimelockResult::BufferTooSmall => b"Output buffer too small\0",
+			TimelockResult::InvalidRound => b"Invalid round number\0",
+			TimelockResult::UnsupportedBeacon => b"Unsupported beacon\0",
+			TimelockResult::InternalPanic => b"Internal panic\0",
+		}
+	}
+}
+
+/// The beacons ciphertexts can be encrypted for
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelockBeacon {
+	/// Drand QuickNet (TinyBLS381: BLS12-381, signatures in G1)
+	QuickNet = 0,
+	/// The Ideal Network (TinyBLS377: BLS12-377, signatures in G1)
+	Idn = 1,
+}
+
+/// The ciphers messages can be encrypted with
+///
+/// The cipher is recorded in the ciphertext, so decryption needs no choice.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelockCipher {
+	/// AES-256-GCM, the fastest on CPUs with AES instructions (e.g. AES-NI)
+	AesGcm = 0,
+	/// ChaCha20-Poly1305, the fastest on CPUs without them
+	ChaCha20Poly1305 = 1,
+}
+
+impl From<&TimelockError> for TimelockResult {
+	fn from(error: &TimelockError) -> Self {
+		match error {
+			TimelockError::Tlock(TlockError::InvalidPoint) => TimelockResult::InvalidPoint,
+			TimelockError::Tlock(TlockError::BufferTooSmall) => TimelockResult::BufferTooSmall,
+			TimelockError::Tlock(
+				TlockError::DeserializationError |
+				TlockError::DeserializationErrorG1 |
+				TlockError::DeserializationErrorG2 |
+				TlockError::DeserializationErrorFr |
+				TlockError::InvalidFormat,
+			) => TimelockResult::SerializationError,
+			TimelockError::Tlock(
+				TlockError::MessageEncryptionError | TlockError::InvalidSecretKey,
+			) |
+			TimelockError::Input(_) => TimelockResult::EncryptionFailed,
+			_ => TimelockResult::DecryptionFailed,
+		}
+	}
 }
 
 /// Opaque handle for encrypted data
@@ -191,28 +397,174 @@ pub struct TimelockCiphertext {
 /// - `ciphertext` must not be used after calling this function
 #[no_mangle]
 pub unsafe extern "C" fn timelock_ciphertext_free(ciphertext: *mut TimelockCiphertext) {
-	if !ciphertext.is_null() {
-		let ct = Box::from_raw(ciphertext);
-		if !ct.data.is_null() {
-			// SAFETY: The pointer `ct.data` is originally allocated via
-			// Box::into_raw(Box<[u8]>) in the corresponding allocation site. According
-			// to Rust documentation, converting a Box<[T]> into a raw pointer and then
-			// reconstructing it with Vec::from_raw_parts using (ptr, len, len)
-			// is valid because Box<[T]> is always allocated with capacity == length.
-			// This debug assertion validates this Box<[T]> invariant in debug builds and
-			// will detect if the allocation strategy changes in future Rust versions or
-			// if memory corruption occurs.
-			let vec = Vec::from_raw_parts(ct.data, ct.len, ct.len);
-			debug_assert!(vec.capacity() == vec.len(), "Box<[T]> invariant broken: capacity != length for ciphertext buffer. This indicates either memory corruption or a change in Rust's Box<[T]> allocation strategy.");
-			// Dropping vec will free the memory.
+	guard_or((), || {
+		if !ciphertext.is_null() {
+			let ct = Box::from_raw(ciphertext);
+			if !ct.data.is_null() {
+				// SAFETY: The pointer `ct.data` is originally allocated via
+				// Box::into_raw(Box<[u8]>) in the corresponding allocation site. According
+				// to Rust documentation, converting a Box<[T]> into a raw pointer and then
+				// reconstructing it with Vec::from_raw_parts using (ptr, len, len)
+				// is valid because Box<[T]> is always allocated with capacity == length.
+				// This debug assertion validates this Box<[T]> invariant in debug builds and
+				// will detect if the allocation strategy changes in future Rust versions or
+				// if memory corruption occurs.
+				let vec = Vec::from_raw_parts(ct.data, ct.len, ct.len);
+				debug_assert!(vec.capacity() == vec.len(), "Box<[T]> invariant broken: capacity != length for ciphertext buffer. This indicates either memory corruption or a change in Rust's Box<[T]> allocation strategy.");
+				// Dropping vec will free the memory.
+			}
 		}
-	}
+	})
+}
+
+/// Hand a serialized ciphertext over to C (internal helper)
+fn into_ciphertext(boxed_data: Box<[u8]>) -> *mut TimelockCiphertext {
+	// Use Box::into_raw for safe ownership transfer to C
+	let data_len = boxed_data.len();
+	// SAFETY: We cast Box<[u8]> to *mut u8 to transfer ownership to C.
+	// The slice pointer is cast to a raw u8 pointer for C compatibility, as C APIs
+	// expect simple byte pointers rather than slice metadata. The length
+	// information is not lost, as it is stored in the `len` field of the
+	// TimelockCiphertext struct alongside the pointer. When freeing, both the
+	// pointer and length are available to reconstruct the Box<[u8]> via
+	// Vec::from_raw_parts.
+	let data_ptr = Box::into_raw(boxed_data) as *mut u8;
+	Box::into_raw(Box::new(TimelockCiphertext { data: data_ptr, len: data_len }))
+}
+
+/// Get the serialized bytes of a ciphertext
+///
+/// # Returns
+/// Pointer to `timelock_ciphertext_len` bytes, or null if `ciphertext` is null
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by a timelock function, or
+///   null
+/// - The returned pointer is valid until the ciphertext is freed
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_data(
+	ciphertext: *const TimelockCiphertext,
+) -> *const c_uchar {
+	guard_or(ptr::null(), || {
+		if ciphertext.is_null() {
+			return ptr::null();
+		}
+		(*ciphertext).data
+	})
+}
+
+/// Get the length of the serialized bytes of a ciphertext
+///
+/// # Returns
+/// The length, or 0 if `ciphertext` is null
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by a timelock function, or
+///   null
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_len(ciphertext: *const TimelockCiphertext) -> usize {
+	guard_or(0, || {
+		if ciphertext.is_null() {
+			return 0;
+		}
+		(*ciphertext).len
+	})
+}
+
+/// Create a ciphertext from serialized bytes, e.g. ones read back from storage
+///
+/// The bytes are copied but not parsed: decryption rejects malformed
+/// ciphertexts.
+///
+/// # Parameters
+/// - `data`: Pointer to the serialized ciphertext
+/// - `len`: Length of the serialized ciphertext
+/// - `ciphertext_out`: Output pointer for the ciphertext
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `data` must point to `len` bytes
+/// - `ciphertext_out` will be set to a pointer that must be freed with
+///   `timelock_ciphertext_free`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_from_bytes(
+	data: *const c_uchar,
+	len: usize,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		if data.is_null() || ciphertext_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		*ciphertext_out = into_ciphertext(slice::from_raw_parts(data, len).into());
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Copy a ciphertext
+///
+/// # Parameters
+/// - `ciphertext`: The ciphertext to copy
+/// - `ciphertext_out`: Output pointer for the copy
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by a timelock function
+/// - `ciphertext_out` will be set to a pointer that must be freed with
+///   `timelock_ciphertext_free`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_ciphertext_clone(
+	ciphertext: *const TimelockCiphertext,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		timelock_ciphertext_from_bytes((*ciphertext).data, (*ciphertext).len, ciphertext_out)
+	})
+}
+
+/// Handle for decrypted data
+#[repr(C)]
+pub struct TimelockPlaintext {
+	/// Pointer to the decrypted data
+	pub data: *mut c_uchar,
+	/// Length of the decrypted data
+	pub len: usize,
+}
+
+/// Zero and free memory allocated for a plaintext
+///
+/// # Safety
+/// - `plaintext` must be a valid pointer returned by `timelock_decrypt_alloc`
+/// - `plaintext` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_plaintext_free(plaintext: *mut TimelockPlaintext) {
+	guard_or((), || {
+		if !plaintext.is_null() {
+			let pt = Box::from_raw(plaintext);
+			if !pt.data.is_null() {
+				// SAFETY: As for ciphertexts, `pt.data` was allocated as a Box<[u8]>
+				// of `pt.len` bytes by `timelock_decrypt_alloc`.
+				let mut vec = Vec::from_raw_parts(pt.data, pt.len, pt.len);
+				vec.zeroize();
+			}
+		}
+	})
 }
 
 /// Create an identity for a given round number (Drand-style)
 ///
 /// This creates an identity by hashing the round number as used by Drand
-/// quicknet.
+/// quicknet. Rounds start at 1, so round 0 is rejected.
 ///
 /// # Parameters
 /// - `round_number`: The round number for which to create the identity
@@ -220,7 +572,8 @@ pub unsafe extern "C" fn timelock_ciphertext_free(ciphertext: *mut TimelockCiphe
 /// - `identity_len`: Length of the output buffer
 ///
 /// # Returns
-/// `TimelockResult::Success` on success, error code on failure
+/// `TimelockResult::Success` on success, `TimelockResult::InvalidRound` for
+/// round 0, error code on other failures
 ///
 /// # Safety
 /// - `identity_out` must be a valid pointer to a buffer of at least
@@ -231,22 +584,81 @@ pub unsafe extern "C" fn timelock_create_drand_identity(
 	identity_out: *mut c_uchar,
 	identity_len: usize,
 ) -> TimelockResult {
-	if identity_out.is_null() || identity_len < 32 {
-		set_last_error(
-			"Invalid identity buffer: null pointer or insufficient size (need 32 bytes)",
-		);
-		return TimelockResult::InvalidInput;
-	}
+	guard(|| {
+		if identity_out.is_null() || identity_len < 32 {
+			set_last_error(
+				"Invalid identity buffer: null pointer or insufficient size (need 32 bytes)",
+			);
+			return TimelockResult::InvalidInput;
+		}
+		if round_number == 0 {
+			set_last_error("Invalid round number: beacon rounds start at 1");
+			return TimelockResult::InvalidRound;
+		}
 
-	let mut hasher = Sha256::new();
-	hasher.update(round_number.to_be_bytes());
-	let hash = hasher.finalize();
+		let mut hasher = Sha256::new();
+		hasher.update(round_number.to_be_bytes());
+		let hash = hasher.finalize();
 
-	let output = slice::from_raw_parts_mut(identity_out, identity_len);
-	output[..32].copy_from_slice(&hash);
+		let output = slice::from_raw_parts_mut(identity_out, identity_len);
+		output[..32].copy_from_slice(&hash);
 
-	clear_last_error();
-	TimelockResult::Success
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Create an identity from arbitrary data
+///
+/// The identity is the SHA-256 digest of `data`, as for rounds: the identity
+/// of round `r` is that of its 8 byte big-endian encoding. Use this to lock to
+/// custom identities, such as commitments or block hashes, signed by a beacon
+/// that signs their digest.
+///
+/// # Parameters
+/// - `data`: Pointer to the data to lock to
+/// - `data_len`: Length of the data
+/// - `identity_out`: Output buffer for the identity (must be at least 32 bytes)
+/// - `identity_len`: Length of the output buffer
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `data` must point to `data_len` bytes, or be null if `data_len` is 0
+/// - `identity_out` must be a valid pointer to a buffer of at least
+///   `identity_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn timelock_create_identity(
+	data: *const c_uchar,
+	data_len: usize,
+	identity_out: *mut c_uchar,
+	identity_len: usize,
+) -> TimelockResult {
+	guard(|| {
+		if data.is_null() && data_len > 0 {
+			set_last_error("Invalid input parameters: null data pointer");
+			return TimelockResult::InvalidInput;
+		}
+		if identity_out.is_null() || identity_len < 32 {
+			set_last_error(
+				"Invalid identity buffer: null pointer or insufficient size (need 32 bytes)",
+			);
+			return TimelockResult::InvalidInput;
+		}
+
+		let mut hasher = Sha256::new();
+		if data_len > 0 {
+			hasher.update(slice::from_raw_parts(data, data_len));
+		}
+		let hash = hasher.finalize();
+
+		let output = slice::from_raw_parts_mut(identity_out, identity_len);
+		output[..32].copy_from_slice(&hash);
+
+		clear_last_error();
+		TimelockResult::Success
+	})
 }
 
 /// Helper function to ensure sensitive data is always cleared on error paths.
@@ -270,6 +682,140 @@ fn fail_with_zeroize(
 	result_code
 }
 
+/// A public key or signature as passed in by C, hex encoded or raw
+#[derive(Clone, Copy)]
+enum Encoded {
+	/// A null-terminated hex string
+	Hex(*const c_char),
+	/// Compressed bytes and their length
+	Raw(*const c_uchar, usize),
+}
+
+impl Encoded {
+	fn is_null(self) -> bool {
+		match self {
+			Encoded::Hex(hex) => hex.is_null(),
+			Encoded::Raw(bytes, _) => bytes.is_null(),
+		}
+	}
+
+	/// The compressed bytes, setting the last error on failure
+	///
+	/// # Safety
+	/// A hex string must be null-terminated, and raw bytes must be as long as
+	/// their length
+	unsafe fn bytes(self, what: &str, invalid: TimelockResult) -> Result<Vec<u8>, TimelockResult> {
+		let hex = match self {
+			Encoded::Hex(hex) => hex,
+			Encoded::Raw(bytes, len) => return Ok(slice::from_raw_parts(bytes, len).to_vec()),
+		};
+		let hex = CStr::from_ptr(hex).to_str().map_err(|e| {
+			set_last_error(&format!("Invalid UTF-8 in {} hex string: {}", what, e));
+			TimelockResult::InvalidInput
+		})?;
+		hex::decode(hex).map_err(|e| {
+			set_last_error(&format!("Invalid hex encoding in {}: {}", what, e));
+			invalid
+		})
+	}
+}
+
+/// Parse a BLS public key, setting the last error on failure
+///
+/// # Safety
+/// - `public_key` must be a valid hex string or byte buffer
+unsafe fn parse_public_key<E: EngineBLS>(
+	public_key: Encoded,
+) -> Result<E::PublicKeyGroup, TimelockResult> {
+	let public_key_bytes = public_key.bytes("public key", TimelockResult::InvalidPublicKey)?;
+	strict::public_key::<E>(&public_key_bytes).map_err(|e| match e {
+		TlockError::InvalidPoint => {
+			set_last_error("The public key is the identity or not in the prime order subgroup");
+			TimelockResult::InvalidPoint
+		},
+		e => {
+			set_last_error(&format!("Failed to deserialize BLS public key: {}", e));
+			TimelockResult::InvalidPublicKey
+		},
+	})
+}
+
+/// Parse a BLS signature, setting the last error on failure
+///
+/// # Safety
+/// - `signature` must be a valid hex string or byte buffer
+unsafe fn parse_signature<E: EngineBLS>(
+	signature: Encoded,
+) -> Result<E::SignatureGroup, TimelockResult> {
+	let signature_bytes = signature.bytes("signature", TimelockResult::InvalidSignature)?;
+	strict::signature::<E>(&signature_bytes).map_err(|e| match e {
+		TlockError::InvalidPoint => {
+			set_last_error("The signature is the identity or not in the prime order subgroup");
+			TimelockResult::InvalidPoint
+		},
+		e => {
+			set_last_error(&format!("Failed to deserialize BLS signature: {}", e));
+			TimelockResult::InvalidSignature
+		},
+	})
+}
+
+/// Validate a hex encoded public key
+///
+/// Checks what encryption checks, so that a misconfigured key is found at
+/// startup rather than on the first encryption.
+///
+/// # Parameters
+/// - `public_key_hex`: Null-terminated hex string of the public key
+///
+/// # Returns
+/// - `TimelockResult::Success` if the key is valid
+/// - `TimelockResult::InvalidInput` if it is null or not UTF-8
+/// - `TimelockResult::InvalidPublicKey` if it is not hex, has the wrong length
+///   or is not on the curve
+/// - `TimelockResult::InvalidPoint` if it is the identity or not in the prime
+///   order subgroup
+///
+/// # Safety
+/// - `public_key_hex` must be a valid null-terminated C string
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key(
+	public_key_hex: *const c_char,
+) -> TimelockResult {
+	guard(|| timelock_validate_public_key_ex(TimelockBeacon::QuickNet, public_key_hex))
+}
+
+/// Validate a hex encoded public key of a beacon
+///
+/// As `timelock_validate_public_key`, for the engine of `beacon`.
+///
+/// # Safety
+/// As for `timelock_validate_public_key`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_validate_public_key_ex(
+	beacon: TimelockBeacon,
+	public_key_hex: *const c_char,
+) -> TimelockResult {
+	guard(|| {
+		if public_key_hex.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		let public_key = Encoded::Hex(public_key_hex);
+		let valid = match beacon {
+			TimelockBeacon::QuickNet => parse_public_key::<TinyBLS381>(public_key).map(|_| ()),
+			TimelockBeacon::Idn => parse_public_key::<TinyBLS377>(public_key).map(|_| ()),
+		};
+		match valid {
+			Ok(()) => {
+				clear_last_error();
+				TimelockResult::Success
+			},
+			Err(result) => result,
+		}
+	})
+}
+
 /// Encrypt a message using timelock encryption
 ///
 /// # Parameters
@@ -301,11 +847,93 @@ pub unsafe extern "C" fn timelock_encrypt(
 	public_key_hex: *const c_char,
 	secret_key: *const c_uchar,
 	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		encrypt::<TinyBLS381>(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Hex(public_key_hex),
+			secret_key,
+			TimelockCipher::AesGcm,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Encrypt a message deterministically, for known-answer tests
+///
+/// As `timelock_encrypt`, with randomness drawn from a ChaCha20 RNG seeded
+/// with `seed` instead of the RNG provider, so the same inputs always give the
+/// same ciphertext. Never use a fixed seed outside test vectors: reusing a
+/// seed and secret key for two messages reveals them.
+///
+/// # Parameters
+/// - `seed`: Pointer to the 32-byte seed of the RNG
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`; `seed` must point to 32 bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_deterministic(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	seed: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		if seed.is_null() {
+			set_last_error("Invalid input parameters: null seed");
+			return TimelockResult::InvalidInput;
+		}
+		let mut seed_array = [0u8; 32];
+		seed_array.copy_from_slice(slice::from_raw_parts(seed, 32));
+		let result = encrypt::<TinyBLS381>(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Hex(public_key_hex),
+			secret_key,
+			TimelockCipher::AesGcm,
+			Some(seed_array),
+			ciphertext_out,
+		);
+		seed_array.zeroize();
+		result
+	})
+}
+
+/// `timelock_encrypt` with the engine of a beacon and `cipher`, drawing
+/// randomness from `seed` if given or from the RNG provider otherwise
+///
+/// # Safety
+/// As for `timelock_encrypt`
+#[allow(clippy::too_many_arguments)]
+unsafe fn encrypt<E: EngineBLS>(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key: Encoded,
+	secret_key: *const c_uchar,
+	cipher: TimelockCipher,
+	seed: Option<[u8; 32]>,
+	ciphertext_out: *mut *mut TimelockCiphertext,
 ) -> TimelockResult {
 	// Validate inputs
 	if message.is_null() ||
 		identity.is_null() ||
-		public_key_hex.is_null() ||
+		public_key.is_null() ||
 		secret_key.is_null() ||
 		ciphertext_out.is_null() ||
 		identity_len != 32
@@ -326,39 +954,19 @@ pub unsafe extern "C" fn timelock_encrypt(
 		ptr::copy_nonoverlapping(secret_key, secret_key_array.as_mut_ptr(), 32);
 	}
 
-	// Parse public key hex string
-	let public_key_cstr = match CStr::from_ptr(public_key_hex).to_str() {
-		Ok(s) => s,
-		Err(e) => {
-			return fail_with_zeroize(
-				&mut secret_key_array,
-				&format!("Invalid UTF-8 in public key hex string: {}", e),
-				TimelockResult::InvalidInput,
-			);
+	let public_key = match parse_public_key::<E>(public_key) {
+		Ok(pk) => pk,
+		Err(result) => {
+			secret_key_array.zeroize();
+			return result;
 		},
 	};
 
-	let public_key_bytes = match hex::decode(public_key_cstr) {
-		Ok(bytes) => bytes,
-		Err(e) => {
-			return fail_with_zeroize(
-				&mut secret_key_array,
-				&format!("Invalid hex encoding in public key: {}", e),
-				TimelockResult::InvalidPublicKey,
-			);
-		},
-	};
-
-	let public_key = match <TinyBLS381 as EngineBLS>::PublicKeyGroup::deserialize_compressed(
-		&public_key_bytes[..],
-	) {
-		Ok(pk) => pk,
-		Err(e) => {
-			return fail_with_zeroize(
-				&mut secret_key_array,
-				&format!("Failed to deserialize BLS public key: {:?}", e),
-				TimelockResult::InvalidPublicKey,
-			);
+	let rng = match seed.map_or_else(operation_rng, |seed| Ok(ChaCha20Rng::from_seed(seed))) {
+		Ok(rng) => rng,
+		Err(result) => {
+			secret_key_array.zeroize();
+			return result;
 		},
 	};
 
@@ -366,18 +974,28 @@ pub unsafe extern "C" fn timelock_encrypt(
 	let timelock_identity = Identity::new(b"", identity_slice);
 
 	// Perform encryption
-	let ciphertext = match tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
-		public_key,
-		secret_key_array,
-		message_slice,
-		timelock_identity,
-		OsRng,
-	) {
+	let ciphertext = match cipher {
+		TimelockCipher::AesGcm => tle::<E, AESGCMBlockCipherProvider, _>(
+			public_key,
+			secret_key_array,
+			message_slice,
+			timelock_identity,
+			rng,
+		),
+		TimelockCipher::ChaCha20Poly1305 => tle::<E, ChaCha20Poly1305BlockCipherProvider, _>(
+			public_key,
+			secret_key_array,
+			message_slice,
+			timelock_identity,
+			rng,
+		),
+	};
+	let ciphertext = match ciphertext {
 		Ok(ct) => ct,
 		Err(e) => {
 			return fail_with_zeroize(
 				&mut secret_key_array,
-				&format!("Timelock encryption operation failed: {:?}", e),
+				&format!("Timelock encryption operation failed: {}", e),
 				TimelockResult::EncryptionFailed,
 			);
 		},
@@ -393,26 +1011,364 @@ pub unsafe extern "C" fn timelock_encrypt(
 		return TimelockResult::SerializationError;
 	}
 
-	// Use Box::into_raw for safe ownership transfer to C
-	let boxed_data = serialized.into_boxed_slice();
-	let data_len = boxed_data.len();
-	// SAFETY: We cast Box<[u8]> to *mut u8 to transfer ownership to C.
-	// The slice pointer is cast to a raw u8 pointer for C compatibility, as C APIs
-	// expect simple byte pointers rather than slice metadata. The length
-	// information is not lost, as it is stored in the `len` field of the
-	// TimelockCiphertext struct alongside the pointer. When freeing, both the
-	// pointer and length are available to reconstruct the Box<[u8]> via
-	// Vec::from_raw_parts.
-	let data_ptr = Box::into_raw(boxed_data) as *mut u8;
-
-	let result = Box::new(TimelockCiphertext { data: data_ptr, len: data_len });
-
-	*ciphertext_out = Box::into_raw(result);
+	*ciphertext_out = into_ciphertext(serialized.into_boxed_slice());
 
 	clear_last_error();
 	TimelockResult::Success
 }
 
+/// Encrypt a message using timelock encryption for a beacon
+///
+/// As `timelock_encrypt`, with the public key and the ciphertext of the
+/// engine of `beacon`.
+///
+/// # Parameters
+/// - `beacon`: The beacon to encrypt for
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_ex(
+	beacon: TimelockBeacon,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		let encrypt = match beacon {
+			TimelockBeacon::QuickNet => encrypt::<TinyBLS381>,
+			TimelockBeacon::Idn => encrypt::<TinyBLS377>,
+		};
+		encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Hex(public_key_hex),
+			secret_key,
+			TimelockCipher::AesGcm,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Encrypt a message using timelock encryption with a chosen cipher
+///
+/// As `timelock_encrypt`, with the message encrypted with `cipher` instead of
+/// AES-GCM. The cipher is recorded in the ciphertext, so it is decrypted by
+/// `timelock_decrypt` and the other decryption functions as usual.
+///
+/// # Parameters
+/// - `cipher`: The cipher to encrypt the message with
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_with_cipher(
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		encrypt::<TinyBLS381>(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Hex(public_key_hex),
+			secret_key,
+			cipher,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Options of `timelock_encrypt_opts`
+///
+/// Zero-initialize the struct and set `struct_size` to its size, then set the
+/// options that differ from their default. Fields added in later versions go at
+/// the end and default to zero, so a caller compiled against an older header
+/// keeps working.
+#[repr(C)]
+pub struct TimelockEncryptOptions {
+	/// `sizeof(TimelockEncryptOptions)` as compiled by the caller
+	pub struct_size: usize,
+	/// The beacon to encrypt for, QuickNet by default
+	pub beacon: TimelockBeacon,
+	/// The cipher to encrypt the message with, AES-GCM by default
+	pub cipher: TimelockCipher,
+	/// Associated data to authenticate with the message. Reserved until the
+	/// core library supports it: must be null
+	pub aad: *const c_uchar,
+	/// Length of the associated data. Reserved: must be 0
+	pub aad_len: usize,
+	/// Pad the message to a multiple of this many bytes. Reserved until the
+	/// core library supports it: must be 0
+	pub padding: usize,
+}
+
+/// Read the options of `timelock_encrypt_opts`, defaulting those past the
+/// caller's `struct_size` and all of them if `options` is null (internal
+/// helper)
+///
+/// # Safety
+/// `options` must be null or point to `struct_size` bytes
+unsafe fn read_encrypt_options(
+	options: *const TimelockEncryptOptions,
+) -> Result<TimelockEncryptOptions, TimelockResult> {
+	// SAFETY: all zero bytes are the default of every field
+	let mut read: TimelockEncryptOptions = mem::zeroed();
+	read.struct_size = mem::size_of::<TimelockEncryptOptions>();
+	if options.is_null() {
+		return Ok(read);
+	}
+	let struct_size = (*options).struct_size;
+	if struct_size < mem::size_of::<usize>() {
+		set_last_error("Invalid encrypt options: struct_size is too small");
+		return Err(TimelockResult::InvalidInput);
+	}
+	ptr::copy_nonoverlapping(
+		options.cast::<u8>(),
+		(&mut read as *mut TimelockEncryptOptions).cast::<u8>(),
+		struct_size.min(read.struct_size),
+	);
+	if !read.aad.is_null() || read.aad_len != 0 {
+		set_last_error("Invalid encrypt options: associated data is not supported yet");
+		return Err(TimelockResult::InvalidInput);
+	}
+	if read.padding != 0 {
+		set_last_error("Invalid encrypt options: padding is not supported yet");
+		return Err(TimelockResult::InvalidInput);
+	}
+	Ok(read)
+}
+
+/// Encrypt a message using timelock encryption with options
+///
+/// As `timelock_encrypt`, with the beacon and cipher taken from `options`.
+/// New options are added to `TimelockEncryptOptions` rather than as new
+/// functions.
+///
+/// # Parameters
+/// - `options`: Pointer to the options, or null for the defaults
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`; `options` must be null or point to
+/// `options->struct_size` bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::InvalidInput` if an
+/// option is invalid or not supported yet, error code on other failures
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_opts(
+	options: *const TimelockEncryptOptions,
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		let options = match read_encrypt_options(options) {
+			Ok(options) => options,
+			Err(result) => return result,
+		};
+		let encrypt = match options.beacon {
+			TimelockBeacon::QuickNet => encrypt::<TinyBLS381>,
+			TimelockBeacon::Idn => encrypt::<TinyBLS377>,
+		};
+		encrypt(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Hex(public_key_hex),
+			secret_key,
+			options.cipher,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Encrypt a message using timelock encryption, with a raw public key
+///
+/// As `timelock_encrypt`, with the compressed public key as bytes instead of
+/// a hex string.
+///
+/// # Parameters
+/// - `public_key`: Pointer to the compressed public key
+/// - `public_key_len`: Length of the public key (96 bytes for QuickNet)
+/// - Others as for `timelock_encrypt`
+///
+/// # Safety
+/// As for `timelock_encrypt`; `public_key` must point to `public_key_len`
+/// bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_raw(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key: *const c_uchar,
+	public_key_len: usize,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		encrypt::<TinyBLS381>(
+			message,
+			message_len,
+			identity,
+			identity_len,
+			Encoded::Raw(public_key, public_key_len),
+			secret_key,
+			TimelockCipher::AesGcm,
+			None,
+			ciphertext_out,
+		)
+	})
+}
+
+/// Encrypt a message using timelock encryption into a caller-provided buffer
+///
+/// Unlike `timelock_encrypt`, the serialized ciphertext is written to
+/// `ciphertext_out` and no memory is allocated for it. The ciphertext is the
+/// same as the one `timelock_encrypt` produces.
+///
+/// # Parameters
+/// - `message`: Pointer to the message to encrypt
+/// - `message_len`: Length of the message
+/// - `identity`: Pointer to the identity (32 bytes)
+/// - `identity_len`: Length of the identity (must be 32)
+/// - `public_key_hex`: Null-terminated hex string of the public key
+/// - `secret_key`: 32-byte secret key for encryption
+/// - `ciphertext_out`: Output buffer for the serialized ciphertext
+/// - `ciphertext_len`: Pointer to the length of the output buffer, updated with
+///   the actual length, or with the required length if the buffer is too small
+///
+/// # Safety
+/// - All pointer parameters must be valid
+/// - `message` must point to `message_len` bytes
+/// - `identity` must point to 32 bytes
+/// - `secret_key` must point to 32 bytes
+/// - `public_key_hex` must be a valid null-terminated C string
+/// - `ciphertext_out` must point to a buffer of at least `*ciphertext_len`
+///   bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::BufferTooSmall` if
+/// the buffer is too small, error code on other failures
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_into(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut c_uchar,
+	ciphertext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		// Validate inputs
+		if message.is_null() ||
+			identity.is_null() ||
+			public_key_hex.is_null() ||
+			secret_key.is_null() ||
+			ciphertext_out.is_null() ||
+			ciphertext_len.is_null() ||
+			identity_len != 32
+		{
+			set_last_error(
+				"Invalid input parameters: null pointers or incorrect identity length (need 32 bytes)",
+			);
+			return TimelockResult::InvalidInput;
+		}
+
+		// Check the output buffer before touching the secret key
+		let required = tle_len::<TinyBLS381>(message_len);
+		if *ciphertext_len < required {
+			*ciphertext_len = required;
+			set_last_error("Output buffer too small for the ciphertext");
+			return TimelockResult::BufferTooSmall;
+		}
+
+		let message_slice = slice::from_raw_parts(message, message_len);
+		let identity_slice = slice::from_raw_parts(identity, identity_len);
+		let output = slice::from_raw_parts_mut(ciphertext_out, *ciphertext_len);
+
+		let mut secret_key_array = [0u8; 32];
+		unsafe {
+			ptr::copy_nonoverlapping(secret_key, secret_key_array.as_mut_ptr(), 32);
+		}
+
+		let public_key = match parse_public_key::<TinyBLS381>(Encoded::Hex(public_key_hex)) {
+			Ok(pk) => pk,
+			Err(result) => {
+				secret_key_array.zeroize();
+				return result;
+			},
+		};
+
+		let rng = match operation_rng() {
+			Ok(rng) => rng,
+			Err(result) => {
+				secret_key_array.zeroize();
+				return result;
+			},
+		};
+
+		let timelock_identity = Identity::new(b"", identity_slice);
+		let result = tle_into::<TinyBLS381, _>(
+			public_key,
+			secret_key_array,
+			message_slice,
+			&timelock_identity,
+			rng,
+			output,
+		);
+		secret_key_array.zeroize();
+
+		match result {
+			Ok(len) => {
+				*ciphertext_len = len;
+				clear_last_error();
+				TimelockResult::Success
+			},
+			Err(e) => {
+				set_last_error(&format!("Timelock encryption operation failed: {}", e));
+				TimelockResult::EncryptionFailed
+			},
+		}
+	})
+}
+
 /// Estimate the size of the ciphertext for a given message length
 ///
 /// This function provides an estimate of the serialized ciphertext size,
@@ -432,43 +1388,114 @@ pub unsafe extern "C" fn timelock_estimate_ciphertext_size(
 	message_len: usize,
 	estimated_size_out: *mut usize,
 ) -> TimelockResult {
-	if estimated_size_out.is_null() {
-		set_last_error("Null output pointer for estimated size");
-		return TimelockResult::InvalidInput;
-	}
+	guard(|| {
+		if estimated_size_out.is_null() {
+			set_last_error("Null output pointer for estimated size");
+			return TimelockResult::InvalidInput;
+		}
+
+		// Estimate ciphertext size as message length plus the predefined overhead
+		// constant
+		let overhead = TIMELOCK_CIPHERTEXT_OVERHEAD;
+		match message_len.checked_add(overhead) {
+			Some(total) => {
+				*estimated_size_out = total;
+				clear_last_error();
+				TimelockResult::Success
+			},
+			None => {
+				set_last_error("Integer overflow when estimating ciphertext size");
+				TimelockResult::InvalidInput
+			},
+		}
+	})
+}
+
+/// The outcome of the self test
+#[repr(C)]
+#[derive(Debug, Default, PartialEq)]
+pub struct TimelockSelfTestReport {
+	/// The number of known-answer tests run
+	pub checks: usize,
+	/// The number of known-answer tests which failed
+	pub failures: usize,
+}
 
-	// Estimate ciphertext size as message length plus the predefined overhead
-	// constant
-	let overhead = TIMELOCK_CIPHERTEXT_OVERHEAD;
-	match message_len.checked_add(overhead) {
-		Some(total) => {
-			*estimated_size_out = total;
+/// Run the known-answer tests of every compiled-in engine and cipher
+///
+/// Intended as a power-on self test: call it once before any other operation
+/// and refuse to continue if it fails. On failure the last error names each
+/// failing engine and cipher.
+///
+/// # Parameters
+/// - `report_out`: Optional output pointer for the counts of checks and
+///   failures (may be null)
+///
+/// # Returns
+/// `TimelockResult::Success` if every test passed,
+/// `TimelockResult::SelfTestFailed` otherwise
+///
+/// # Safety
+/// - `report_out` must be null or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_self_test(
+	report_out: *mut TimelockSelfTestReport,
+) -> TimelockResult {
+	guard(|| {
+		let report = self_test();
+		if !report_out.is_null() {
+			*report_out = TimelockSelfTestReport {
+				checks: report.checks.len(),
+				failures: report.failures().count(),
+			};
+		}
+		if report.passed() {
 			clear_last_error();
-			TimelockResult::Success
-		},
-		None => {
-			set_last_error("Integer overflow when estimating ciphertext size");
-			TimelockResult::InvalidInput
-		},
-	}
+			return TimelockResult::Success;
+		}
+
+		let failures = report
+			.failures()
+			.map(|check| {
+				format!(
+					"{}/{}: {}",
+					check.engine,
+					check.cipher_suite,
+					check.failure.map(|f| f.to_string()).unwrap_or_default()
+				)
+			})
+			.collect::<Vec<_>>();
+		set_last_error(&format!("Self test failed: {}", failures.join(", ")));
+		TimelockResult::SelfTestFailed
+	})
 }
 
 /// Decrypt a timelock-encrypted ciphertext
 ///
+/// The output buffer is sized in two calls: with `plaintext_out` null, only
+/// `*plaintext_len` is set, to the length of the plaintext, and the signature
+/// is not needed; then with a buffer of that length. A buffer that is too
+/// small gives `TimelockResult::BufferTooSmall`, with the required length in
+/// `*plaintext_len`.
+///
 /// # Parameters
 /// - `ciphertext`: Pointer to the encrypted ciphertext
 /// - `signature_hex`: Null-terminated hex string of the signature
-/// - `plaintext_out`: Output buffer for the decrypted plaintext
+/// - `plaintext_out`: Output buffer for the decrypted plaintext, or null to
+///   query its length
 /// - `plaintext_len`: Pointer to the length of the output buffer, updated with
-///   actual length
+///   the actual length, or with the required length if the buffer is too small
+///   or null
 ///
 /// # Returns
 /// `TimelockResult::Success` on success, error code on failure
 ///
 /// # Safety
 /// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
-/// - `signature_hex` must be a valid null-terminated C string
-/// - `plaintext_out` must point to a buffer of at least `*plaintext_len` bytes
+/// - `signature_hex` must be a valid null-terminated C string, unless
+///   `plaintext_out` is null
+/// - `plaintext_out` must be null or point to a buffer of at least
+///   `*plaintext_len` bytes
 /// - `plaintext_len` must be a valid pointer
 #[no_mangle]
 pub unsafe extern "C" fn timelock_decrypt(
@@ -476,13 +1503,76 @@ pub unsafe extern "C" fn timelock_decrypt(
 	signature_hex: *const c_char,
 	plaintext_out: *mut c_uchar,
 	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		decrypt::<TinyBLS381>(ciphertext, Encoded::Hex(signature_hex), plaintext_out, plaintext_len)
+	})
+}
+
+/// Get the length of the plaintext of a ciphertext
+///
+/// The length `timelock_decrypt` requires of its output buffer, as set by
+/// `timelock_decrypt` with a null `plaintext_out`. The ciphertext is parsed
+/// but not decrypted.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `size_out`: Output pointer for the length of the plaintext
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+/// - `size_out` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_size(
+	ciphertext: *const TimelockCiphertext,
+	size_out: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() || size_out.is_null() || (*ciphertext).data.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		plaintext_size::<TinyBLS381>(&*ciphertext, size_out)
+	})
+}
+
+/// Set `size_out` to the length of the plaintext of `ct` (internal helper)
+///
+/// # Safety
+/// `ct` must hold a valid data pointer and `size_out` must be a valid pointer
+unsafe fn plaintext_size<E: EngineBLS>(
+	ct: &TimelockCiphertext,
+	size_out: *mut usize,
+) -> TimelockResult {
+	match tld_len::<E>(slice::from_raw_parts(ct.data, ct.len)) {
+		Ok(len) => {
+			*size_out = len;
+			clear_last_error();
+			TimelockResult::Success
+		},
+		Err(e) => {
+			let error = TimelockError::from(e);
+			set_last_error(&format!("Invalid ciphertext: {}", error));
+			TimelockResult::from(&error)
+		},
+	}
+}
+
+/// `timelock_decrypt` with the engine of a beacon
+///
+/// # Safety
+/// As for `timelock_decrypt`
+unsafe fn decrypt<E: EngineBLS>(
+	ciphertext: *const TimelockCiphertext,
+	signature: Encoded,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
 ) -> TimelockResult {
 	// Validate inputs
-	if ciphertext.is_null() ||
-		signature_hex.is_null() ||
-		plaintext_out.is_null() ||
-		plaintext_len.is_null()
-	{
+	if ciphertext.is_null() || plaintext_len.is_null() {
 		set_last_error("Invalid input parameters: null pointers not allowed");
 		return TimelockResult::InvalidInput;
 	}
@@ -492,73 +1582,478 @@ pub unsafe extern "C" fn timelock_decrypt(
 		set_last_error("Invalid ciphertext: null data pointer");
 		return TimelockResult::InvalidInput;
 	}
+	if plaintext_out.is_null() {
+		return plaintext_size::<E>(ct, plaintext_len);
+	}
+	if signature.is_null() {
+		set_last_error("Invalid input parameters: null pointers not allowed");
+		return TimelockResult::InvalidInput;
+	}
 
-	// Parse signature hex string
-	let signature_cstr = match CStr::from_ptr(signature_hex).to_str() {
-		Ok(s) => s,
-		Err(_) => {
-			set_last_error("Invalid UTF-8 in signature hex string");
-			return TimelockResult::InvalidInput;
-		},
+	let signature = match parse_signature::<E>(signature) {
+		Ok(signature) => signature,
+		Err(result) => return result,
 	};
 
-	let signature_bytes = match hex::decode(signature_cstr) {
-		Ok(bytes) => bytes,
-		Err(_) => {
-			set_last_error("Invalid hex encoding in signature");
-			return TimelockResult::InvalidSignature;
+	// Decrypt straight into the output buffer
+	let ciphertext_slice = slice::from_raw_parts(ct.data, ct.len);
+	let output = slice::from_raw_parts_mut(plaintext_out, *plaintext_len);
+	match tld_into::<E>(ciphertext_slice, signature, output) {
+		Ok(len) => *plaintext_len = len,
+		Err(TlockError::BufferTooSmall) => {
+			// the ciphertext parsed, so its length is known
+			*plaintext_len = tld_len::<E>(ciphertext_slice).unwrap_or(0);
+			set_last_error("Output buffer too small for the plaintext");
+			return TimelockResult::BufferTooSmall;
 		},
-	};
-
-	let signature = match <TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(
-		&signature_bytes[..],
-	) {
-		Ok(sig) => sig,
 		Err(e) => {
-			set_last_error(&format!("Failed to deserialize BLS signature: {:?}", e));
-			return TimelockResult::InvalidSignature;
+			let error = TimelockError::from(e);
+			set_last_error(&format!("Timelock decryption failed: {}", error));
+			return TimelockResult::from(&error);
 		},
-	};
+	}
 
-	// Deserialize ciphertext
-	let ciphertext_slice = slice::from_raw_parts(ct.data, ct.len);
-	let timelock_ciphertext: TLECiphertext<TinyBLS381> =
-		match TLECiphertext::deserialize_compressed(ciphertext_slice) {
-			Ok(ct) => ct,
+	clear_last_error();
+	TimelockResult::Success
+}
+
+/// Decrypt a timelock-encrypted ciphertext of a beacon
+///
+/// As `timelock_decrypt`, with the signature and the ciphertext of the engine
+/// of `beacon`.
+///
+/// # Parameters
+/// - `beacon`: The beacon the ciphertext was encrypted for
+/// - Others as for `timelock_decrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// As for `timelock_decrypt`; `ciphertext` may also be returned by
+/// `timelock_encrypt_ex`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_ex(
+	beacon: TimelockBeacon,
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		let decrypt = match beacon {
+			TimelockBeacon::QuickNet => decrypt::<TinyBLS381>,
+			TimelockBeacon::Idn => decrypt::<TinyBLS377>,
+		};
+		decrypt(ciphertext, Encoded::Hex(signature_hex), plaintext_out, plaintext_len)
+	})
+}
+
+/// Decrypt a timelock-encrypted ciphertext, with a raw signature
+///
+/// As `timelock_decrypt`, with the compressed signature as bytes instead of a
+/// hex string.
+///
+/// # Parameters
+/// - `signature`: Pointer to the compressed signature
+/// - `signature_len`: Length of the signature (48 bytes for QuickNet)
+/// - Others as for `timelock_decrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// As for `timelock_decrypt`; `signature` must point to `signature_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_raw(
+	ciphertext: *const TimelockCiphertext,
+	signature: *const c_uchar,
+	signature_len: usize,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		decrypt::<TinyBLS381>(
+			ciphertext,
+			Encoded::Raw(signature, signature_len),
+			plaintext_out,
+			plaintext_len,
+		)
+	})
+}
+
+/// Decrypt a ciphertext early with the ephemeral secret key it was encrypted
+/// with
+///
+/// This bypasses the timelock, so it works before the beacon signs the round,
+/// for callers who retained the secret key passed to `timelock_encrypt`.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `secret_key`: Pointer to the ephemeral secret key (32 bytes)
+/// - `plaintext_out`: Output buffer for the decrypted plaintext, or null to
+///   query its length as for `timelock_decrypt`
+/// - `plaintext_len`: Pointer to the length of the output buffer, updated with
+///   the actual length, or with the required length if the buffer is too small
+///   or null
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+/// - `secret_key` must point to 32 bytes, unless `plaintext_out` is null
+/// - `plaintext_out` must be null or point to a buffer of at least
+///   `*plaintext_len` bytes
+/// - `plaintext_len` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early(
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() || plaintext_len.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let ct = &*ciphertext;
+		if ct.data.is_null() {
+			set_last_error("Invalid ciphertext: null data pointer");
+			return TimelockResult::InvalidInput;
+		}
+		if plaintext_out.is_null() {
+			return plaintext_size::<TinyBLS381>(ct, plaintext_len);
+		}
+		if secret_key.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let mut key = [0u8; 32];
+		key.copy_from_slice(slice::from_raw_parts(secret_key, 32));
+
+		let ciphertext_slice = slice::from_raw_parts(ct.data, ct.len);
+		let output = slice::from_raw_parts_mut(plaintext_out, *plaintext_len);
+		let result = tld_early_into::<TinyBLS381>(ciphertext_slice, &key, output);
+		key.zeroize();
+		match result {
+			Ok(len) => *plaintext_len = len,
+			Err(TlockError::BufferTooSmall) => {
+				*plaintext_len = tld_len::<TinyBLS381>(ciphertext_slice).unwrap_or(0);
+				set_last_error("Output buffer too small for the plaintext");
+				return TimelockResult::BufferTooSmall;
+			},
 			Err(e) => {
-				set_last_error(&format!("Failed to deserialize ciphertext: {:?}", e));
-				return TimelockResult::SerializationError;
+				let error = TimelockError::from(e);
+				set_last_error(&format!("Early decryption failed: {}", error));
+				return TimelockResult::from(&error);
 			},
+		}
+
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Decrypt a timelock-encrypted ciphertext into a newly allocated plaintext
+///
+/// Unlike `timelock_decrypt`, the caller does not provide the output buffer:
+/// the plaintext is allocated with its exact length.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `signature_hex`: Null-terminated hex string of the signature
+/// - `plaintext_out`: Output pointer for the decrypted plaintext
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+/// - `signature_hex` must be a valid null-terminated C string
+/// - `plaintext_out` will be set to a pointer that must be freed with
+///   `timelock_plaintext_free`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_alloc(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut *mut TimelockPlaintext,
+) -> TimelockResult {
+	guard(|| {
+		// Validate inputs
+		if ciphertext.is_null() || signature_hex.is_null() || plaintext_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let ct = &*ciphertext;
+		if ct.data.is_null() {
+			set_last_error("Invalid ciphertext: null data pointer");
+			return TimelockResult::InvalidInput;
+		}
+
+		let signature = match parse_signature::<TinyBLS381>(Encoded::Hex(signature_hex)) {
+			Ok(signature) => signature,
+			Err(result) => return result,
 		};
 
-	// Perform decryption
-	let plaintext_result = match tld::<TinyBLS381, AESGCMBlockCipherProvider>(
-		timelock_ciphertext,
-		signature,
-	) {
-		Ok(plaintext) => plaintext,
-		Err(_) => {
-			set_last_error("Timelock decryption failed: signature may be invalid, round may be in the future, or ciphertext may be corrupted");
-			return TimelockResult::DecryptionFailed;
-		},
-	};
+		// The length of the plaintext is known once the ciphertext parses, so the
+		// buffer is allocated with its exact length and never reallocated
+		let ciphertext_slice = slice::from_raw_parts(ct.data, ct.len);
+		let decrypted = tld_len::<TinyBLS381>(ciphertext_slice).and_then(|len| {
+			let mut plaintext = vec![0u8; len].into_boxed_slice();
+			match tld_into::<TinyBLS381>(ciphertext_slice, signature, &mut plaintext) {
+				Ok(_) => Ok(plaintext),
+				Err(e) => {
+					plaintext.zeroize();
+					Err(e)
+				},
+			}
+		});
+		let plaintext = match decrypted {
+			Ok(plaintext) => plaintext,
+			Err(e) => {
+				let error = TimelockError::from(e);
+				set_last_error(&format!("Timelock decryption failed: {}", error));
+				return TimelockResult::from(&error);
+			},
+		};
+
+		*plaintext_out = into_plaintext(plaintext);
+
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Hand a plaintext over to C (internal helper)
+fn into_plaintext(plaintext: Box<[u8]>) -> *mut TimelockPlaintext {
+	let data_len = plaintext.len();
+	// SAFETY: Ownership of the Box<[u8]> is transferred to C as for ciphertexts;
+	// `timelock_plaintext_free` reconstructs it from the pointer and length.
+	let data_ptr = Box::into_raw(plaintext) as *mut u8;
+	Box::into_raw(Box::new(TimelockPlaintext { data: data_ptr, len: data_len }))
+}
+
+/// The outcome of decrypting one ciphertext of a batch
+#[repr(C)]
+pub struct TimelockBatchResult {
+	/// `TimelockResult::Success` if the ciphertext was decrypted, error code
+	/// otherwise
+	pub result: TimelockResult,
+	/// The plaintext, which must be freed with `timelock_plaintext_free`, or
+	/// null if the ciphertext was not decrypted
+	pub plaintext: *mut TimelockPlaintext,
+}
 
-	// Check if output buffer is large enough
-	if *plaintext_len < plaintext_result.len() {
-		*plaintext_len = plaintext_result.len();
-		return TimelockResult::MemoryError;
+/// Parse one ciphertext of a batch (internal helper)
+///
+/// # Safety
+/// `ciphertext` must be null or a valid pointer returned by `timelock_encrypt`
+unsafe fn parse_ciphertext(
+	ciphertext: *const TimelockCiphertext,
+) -> Result<TLECiphertext<TinyBLS381>, TimelockResult> {
+	if ciphertext.is_null() || (*ciphertext).data.is_null() {
+		return Err(TimelockResult::InvalidInput);
 	}
+	let ct = &*ciphertext;
+	strict::ciphertext::<TinyBLS381>(slice::from_raw_parts(ct.data, ct.len))
+		.map_err(|e| TimelockResult::from(&TimelockError::from(e)))
+}
 
-	// Copy result to output buffer
-	let output = slice::from_raw_parts_mut(plaintext_out, *plaintext_len);
-	output[..plaintext_result.len()].copy_from_slice(&plaintext_result);
-	*plaintext_len = plaintext_result.len();
+/// Decrypt a batch of ciphertexts locked to the same round
+///
+/// The signature is parsed and validated once, and prepared for the pairing
+/// once for the whole batch. Each ciphertext is decrypted independently into
+/// a newly allocated plaintext: one that fails does not stop the others.
+///
+/// # Parameters
+/// - `ciphertexts`: Array of `count` pointers to ciphertexts
+/// - `count`: Number of ciphertexts
+/// - `signature_hex`: Null-terminated hex string of the signature of the round
+/// - `results`: Output array of `count` results, in the order of `ciphertexts`
+///
+/// # Returns
+/// `TimelockResult::Success` if every ciphertext was decrypted,
+/// `TimelockResult::DecryptionFailed` if any was not (see `results`), or the
+/// error code of the call if none was attempted
+///
+/// # Safety
+/// - `ciphertexts` must point to `count` pointers, each null or returned by
+///   `timelock_encrypt`
+/// - `signature_hex` must be a valid null-terminated C string
+/// - `results` must point to space for `count` results; each plaintext set in
+///   it must be freed with `timelock_plaintext_free`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_batch(
+	ciphertexts: *const *const TimelockCiphertext,
+	count: usize,
+	signature_hex: *const c_char,
+	results: *mut TimelockBatchResult,
+) -> TimelockResult {
+	guard(|| {
+		if signature_hex.is_null() || (count > 0 && (ciphertexts.is_null() || results.is_null())) {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
 
-	clear_last_error();
-	TimelockResult::Success
+		let signature = match parse_signature::<TinyBLS381>(Encoded::Hex(signature_hex)) {
+			Ok(signature) => signature,
+			Err(result) => return result,
+		};
+		if count == 0 {
+			clear_last_error();
+			return TimelockResult::Success;
+		}
+
+		// Ciphertexts that do not parse are reported without being decrypted, and
+		// the others are decrypted in one batch per cipher
+		let mut outcomes = Vec::with_capacity(count);
+		let (mut aes_gcm, mut chacha) = (Vec::new(), Vec::new());
+		for &ciphertext in slice::from_raw_parts(ciphertexts, count) {
+			let parsed = parse_ciphertext(ciphertext).and_then(|ciphertext| {
+				CipherSuite::from_id(&ciphertext.cipher_suite)
+					.map(|suite| (suite, ciphertext))
+					.ok_or(TimelockResult::SerializationError)
+			});
+			match parsed {
+				Ok((suite, ciphertext)) => {
+					match suite {
+						CipherSuite::AesGcm => aes_gcm.push(ciphertext),
+						CipherSuite::ChaCha20Poly1305 => chacha.push(ciphertext),
+					}
+					outcomes.push(Ok(suite));
+				},
+				Err(result) => outcomes.push(Err(result)),
+			}
+		}
+		let mut aes_gcm =
+			tld_batch::<TinyBLS381, AESGCMBlockCipherProvider>(&aes_gcm, signature).into_iter();
+		let mut chacha =
+			tld_batch::<TinyBLS381, ChaCha20Poly1305BlockCipherProvider>(&chacha, signature)
+				.into_iter();
+
+		let mut failures = 0;
+		for (i, outcome) in outcomes.into_iter().enumerate() {
+			let decrypted = outcome.map(|suite| match suite {
+				CipherSuite::AesGcm => aes_gcm.next(),
+				CipherSuite::ChaCha20Poly1305 => chacha.next(),
+			});
+			let result = decrypted.and_then(|decrypted| match decrypted {
+				Some(Ok(plaintext)) => Ok(plaintext),
+				Some(Err(e)) => Err(TimelockResult::from(&TimelockError::from(e))),
+				None => Err(TimelockResult::DecryptionFailed),
+			});
+			let result = match result {
+				Ok(plaintext) => TimelockBatchResult {
+					result: TimelockResult::Success,
+					plaintext: into_plaintext(plaintext.into_boxed_slice()),
+				},
+				Err(result) => {
+					failures += 1;
+					TimelockBatchResult { result, plaintext: ptr::null_mut() }
+				},
+			};
+			// the results may be uninitialized, so they are written without being
+			// read
+			results.add(i).write(result);
+		}
+
+		if failures > 0 {
+			set_last_error(&format!("{} of {} ciphertexts failed to decrypt", failures, count));
+			return TimelockResult::DecryptionFailed;
+		}
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Use a caller-supplied CSPRNG for encryption on the calling thread
+///
+/// Each encryption asks the callback for a 32 byte seed. Use this on platforms
+/// without an OS random number generator the library can reach.
+///
+/// # Parameters
+/// - `fill`: Callback filling `len` bytes at `out`, returning 0 on success
+/// - `user_data`: Passed to every call of `fill`
+///
+/// # Safety
+/// - `fill` must write at most `len` bytes at `out`
+/// - `user_data` must remain valid for as long as the callback is in use
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::InvalidInput` if
+/// `fill` is null
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_callback(
+	fill: TimelockRngCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	guard(|| {
+		let Some(fill) = fill else {
+			set_last_error("Invalid input parameters: null RNG callback");
+			return TimelockResult::InvalidInput;
+		};
+		with_context(|c| *c.rng.borrow_mut() = RngProvider::Callback { fill, user_data });
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Seed the RNG used for encryption on the calling thread
+///
+/// Encryption becomes deterministic, for generating known-answer test vectors.
+/// Never encrypt real messages with a fixed seed.
+///
+/// # Parameters
+/// - `seed`: Pointer to the seed (32 bytes)
+/// - `seed_len`: Length of the seed (must be 32)
+///
+/// # Safety
+/// `seed` must point to `seed_len` bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_rng_seed(
+	seed: *const c_uchar,
+	seed_len: usize,
+) -> TimelockResult {
+	guard(|| {
+		if seed.is_null() || seed_len != 32 {
+			set_last_error(
+				"Invalid input parameters: null pointer or incorrect seed length (need 32 bytes)",
+			);
+			return TimelockResult::InvalidInput;
+		}
+		let mut seed_array = [0u8; 32];
+		seed_array.copy_from_slice(slice::from_raw_parts(seed, seed_len));
+		let rng = Box::new(ChaCha20Rng::from_seed(seed_array));
+		seed_array.zeroize();
+		with_context(|c| *c.rng.borrow_mut() = RngProvider::Seeded(rng));
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Use the OS CSPRNG (the default) for encryption on the calling thread
+///
+/// # Safety
+/// This function is safe to call from any context.
+#[no_mangle]
+pub unsafe extern "C" fn timelock_use_os_rng() {
+	guard_or((), || {
+		with_context(|c| *c.rng.borrow_mut() = RngProvider::Os);
+	})
 }
 
-/// Get the last error message (if any)
+/// Get the last error message (if any) of the calling thread
+///
+/// The errors of `_ctx` calls are kept on their context instead, see
+/// `timelock_context_get_last_error`.
 ///
 /// # Returns
 /// Null-terminated string with the last error message, or null if no error
@@ -567,11 +2062,42 @@ pub unsafe extern "C" fn timelock_decrypt(
 /// The returned pointer is valid until the next call to any timelock function
 #[no_mangle]
 pub unsafe extern "C" fn timelock_get_last_error() -> *const c_char {
-	LAST_ERROR.with(|e| {
-		if let Some(ref cstring) = *e.borrow() {
-			cstring.as_ptr()
+	guard_or(ptr::null(), || with_context(|c| c.last_error_ptr()))
+}
+
+/// Describe a result code
+///
+/// Unlike `timelock_get_last_error`, the description only depends on the
+/// code, so it can be used after further calls and from any thread.
+///
+/// # Returns
+/// Null-terminated string describing `result` (static, no need to free)
+///
+/// # Safety
+/// This function is safe to call from any context.
+#[no_mangle]
+pub unsafe extern "C" fn timelock_result_to_string(result: TimelockResult) -> *const c_char {
+	guard_or(ptr::null(), || result.description().as_ptr() as *const c_char)
+}
+
+/// Check whether a beacon is supported
+///
+/// `TimelockBeacon` parameters must hold one of its values, so a beacon read
+/// from configuration as an integer should be checked before it is converted.
+///
+/// # Returns
+/// `TimelockResult::Success` if `beacon` is the value of a `TimelockBeacon`,
+/// `TimelockResult::UnsupportedBeacon` otherwise
+///
+/// # Safety
+/// This function is safe to call from any context.
+#[no_mangle]
+pub unsafe extern "C" fn timelock_check_beacon(beacon: u32) -> TimelockResult {
+	guard(|| {
+		if beacon == TimelockBeacon::QuickNet as u32 || beacon == TimelockBeacon::Idn as u32 {
+			TimelockResult::Success
 		} else {
-			ptr::null()
+			TimelockResult::UnsupportedBeacon
 		}
 	})
 }
@@ -586,8 +2112,52 @@ pub unsafe extern "C" fn timelock_get_last_error() -> *const c_char {
 /// Null-terminated string with the version (static, no need to free)
 #[no_mangle]
 pub unsafe extern "C" fn timelock_get_version() -> *const c_char {
-	static VERSION: &[u8] = concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes();
-	VERSION.as_ptr() as *const c_char
+	guard_or(ptr::null(), || {
+		static VERSION: &[u8] = concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes();
+		VERSION.as_ptr() as *const c_char
+	})
+}
+
+/// Get the version of the timelock library as numbers
+///
+/// Compare with `TIMELOCK_VERSION_MAJOR`, `TIMELOCK_VERSION_MINOR` and
+/// `TIMELOCK_VERSION_PATCH` of the header the caller was compiled against.
+///
+/// # Parameters
+/// - `major`, `minor`, `patch`: Output pointers for the version numbers, each
+///   of which may be null
+///
+/// # Safety
+/// Each pointer must be null or valid
+#[no_mangle]
+pub unsafe extern "C" fn timelock_get_version_info(
+	major: *mut u32,
+	minor: *mut u32,
+	patch: *mut u32,
+) {
+	guard_or((), || {
+		for (out, value) in [
+			(major, TIMELOCK_VERSION_MAJOR),
+			(minor, TIMELOCK_VERSION_MINOR),
+			(patch, TIMELOCK_VERSION_PATCH),
+		] {
+			if !out.is_null() {
+				*out = value;
+			}
+		}
+	})
+}
+
+/// Get the version of the C ABI of the library
+///
+/// A caller compiled against a header whose `TIMELOCK_ABI_VERSION` differs
+/// must not use the library.
+///
+/// # Safety
+/// This function is safe to call from any context.
+#[no_mangle]
+pub unsafe extern "C" fn timelock_get_abi_version() -> u32 {
+	guard_or(0, || TIMELOCK_ABI_VERSION)
 }
 
 /// Initialize the timelock library
@@ -604,16 +2174,18 @@ pub unsafe extern "C" fn timelock_get_version() -> *const c_char {
 /// `TimelockResult::Success` on success
 #[no_mangle]
 pub unsafe extern "C" fn timelock_init() -> TimelockResult {
-	// Validate cryptographic constants match the underlying library
-	if let Err(err) = validate_cryptographic_constants() {
-		set_last_error(&format!("Cryptographic constant validation failed: {}", err));
-		return TimelockResult::InvalidInput;
-	}
+	guard(|| {
+		// Validate cryptographic constants match the underlying library
+		if let Err(err) = validate_cryptographic_constants() {
+			set_last_error(&format!("Cryptographic constant validation failed: {}", err));
+			return TimelockResult::InvalidInput;
+		}
 
-	// Initialize any global state if needed
-	// For now, just clear any existing error state
-	clear_last_error();
-	TimelockResult::Success
+		// Initialize any global state if needed
+		// For now, just clear any existing error state
+		clear_last_error();
+		TimelockResult::Success
+	})
 }
 
 /// Clean up the timelock library
@@ -626,8 +2198,10 @@ pub unsafe extern "C" fn timelock_init() -> TimelockResult {
 /// internal library state. No special safety requirements for the caller.
 #[no_mangle]
 pub unsafe extern "C" fn timelock_cleanup() {
-	// Clean up any global resources
-	clear_last_error();
+	guard_or((), || {
+		// Clean up any global resources
+		clear_last_error();
+	})
 }
 
 #[cfg(test)]
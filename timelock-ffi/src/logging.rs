@@ -0,0 +1,105 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Routing of library diagnostics to the host.
+//!
+//! Every error a call stores, whether in the last error of a thread, of a
+//! context or in a `TimelockErrorInfo`, is also passed to the log callback set
+//! with `timelock_set_log_callback`, if any. Failed input validation and
+//! deserialization are logged at `TimelockLogLevel::Warn`, as they are errors
+//! of the caller; a caught panic is logged at `TimelockLogLevel::Error`.
+//!
+//! Unlike the RNG provider, the log callback is shared by all threads, as a
+//! host usually has a single logging system.
+
+use super::guard_or;
+use std::{
+	ffi::CString,
+	os::raw::{c_char, c_void},
+	sync::RwLock,
+};
+
+/// Severity of a log message
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelockLogLevel {
+	/// The library failed, such as by panicking
+	Error = 0,
+	/// A call failed on its inputs, such as a null pointer or a malformed key
+	Warn = 1,
+	/// Informational messages
+	Info = 2,
+	/// Detailed diagnostics
+	Debug = 3,
+}
+
+/// Receives a log message, which is null-terminated and valid during the
+/// callback only
+pub type TimelockLogCallback = Option<
+	unsafe extern "C" fn(level: TimelockLogLevel, message: *const c_char, user_data: *mut c_void),
+>;
+
+/// The log callback and its user data
+#[derive(Clone, Copy)]
+struct Logger {
+	log: unsafe extern "C" fn(TimelockLogLevel, *const c_char, *mut c_void),
+	user_data: *mut c_void,
+}
+
+// SAFETY: the caller of `timelock_set_log_callback` guarantees that the
+// callback can be called from any thread with its `user_data`.
+unsafe impl Send for Logger {}
+unsafe impl Sync for Logger {}
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// Pass `message` to the log callback, if any (internal helper)
+pub(crate) fn log(level: TimelockLogLevel, message: &str) {
+	// copied out so the callback may itself set the log callback
+	let logger = *LOGGER.read().unwrap_or_else(|e| e.into_inner());
+	let Some(Logger { log, user_data }) = logger else {
+		return;
+	};
+	if let Ok(message) = CString::new(message) {
+		// SAFETY: see `Logger`
+		unsafe { log(level, message.as_ptr(), user_data) };
+	}
+}
+
+/// Route library diagnostics to a callback
+///
+/// The callback receives every error message a call stores, along with its
+/// level, and may be called from any thread, including the threads of
+/// `timelock_encrypt_async` and `timelock_decrypt_async`. It replaces any
+/// previous callback for the whole process; pass null to stop logging.
+///
+/// # Parameters
+/// - `log`: Callback receiving each message, or null
+/// - `user_data`: Passed to every call of `log`
+///
+/// # Safety
+/// - `log` must be safe to call from any thread, concurrently
+/// - `user_data` must remain valid for as long as the callback is in use
+#[no_mangle]
+pub unsafe extern "C" fn timelock_set_log_callback(
+	log: TimelockLogCallback,
+	user_data: *mut c_void,
+) {
+	guard_or((), || {
+		*LOGGER.write().unwrap_or_else(|e| e.into_inner()) =
+			log.map(|log| Logger { log, user_data });
+	})
+}
@@ -0,0 +1,293 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Encryption to any one of several rounds.
+//!
+//! `timelock_encrypt_multi` shares the key of a message across a list of
+//! rounds with a threshold of one, so the signature of whichever round is
+//! published first decrypts it. Schedulers use this for grace windows: a
+//! message locked to rounds `r` to `r + k` still opens if round `r` is missed.
+//! `timelock_decrypt_multi` takes the signature of any single round of the
+//! list and finds the share it opens. As with `timelock_encrypt_with_cipher`,
+//! the cipher is chosen at encryption and recorded in the ciphertext.
+
+use super::{
+	clear_last_error, guard, into_ciphertext, operation_rng, parse_public_key, parse_signature,
+	set_last_error, Encoded, TimelockCipher, TimelockCiphertext, TimelockResult, AES_GCM_TAG_SIZE,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use std::{
+	os::raw::{c_char, c_uchar},
+	ptr, slice,
+};
+use timelock::{
+	block_ciphers::{
+		AESGCMBlockCipherProvider, AESOutput, BlockCipherProvider,
+		ChaCha20Poly1305BlockCipherProvider, CipherSuite,
+	},
+	engines::drand::TinyBLS381,
+	ibe::fullident::Identity,
+	tlock::{sharded::ShardedCiphertext, tld_sharded, tle_sharded, ShareTarget},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The most rounds a message can be encrypted to, as shares are indexed by a
+/// byte
+pub const TIMELOCK_MAX_ROUNDS: usize = 255;
+
+/// The size of the authentication tag of ChaCha20-Poly1305
+const CHACHA20_POLY1305_TAG_SIZE: usize = 16;
+
+/// The body of a ciphertext encrypted with ChaCha20-Poly1305
+type ChaChaOutput = <ChaCha20Poly1305BlockCipherProvider as BlockCipherProvider<32>>::Ciphertext;
+
+/// Parse a ciphertext of `timelock_encrypt_multi` (internal helper)
+///
+/// # Safety
+/// `ct.data` must point to `ct.len` bytes
+unsafe fn parse_multi(
+	ct: &TimelockCiphertext,
+) -> Result<ShardedCiphertext<TinyBLS381>, TimelockResult> {
+	let mut bytes = slice::from_raw_parts(ct.data, ct.len);
+	ShardedCiphertext::<TinyBLS381>::deserialize_compressed(&mut bytes).map_err(|_| {
+		set_last_error("Invalid ciphertext: not a multi-round ciphertext");
+		TimelockResult::SerializationError
+	})
+}
+
+/// The length of the message of a body encrypted with `suite` (internal
+/// helper)
+fn message_len(suite: CipherSuite, mut body: &[u8]) -> Option<usize> {
+	match suite {
+		CipherSuite::AesGcm => AESOutput::deserialize_compressed(&mut body)
+			.ok()?
+			.ciphertext
+			.len()
+			.checked_sub(AES_GCM_TAG_SIZE),
+		CipherSuite::ChaCha20Poly1305 => ChaChaOutput::deserialize_compressed(&mut body)
+			.ok()?
+			.ciphertext
+			.len()
+			.checked_sub(CHACHA20_POLY1305_TAG_SIZE),
+	}
+}
+
+/// Encrypt a message to any one of several rounds
+///
+/// The message can be decrypted with `timelock_decrypt_multi` and the
+/// signature of any of the rounds. The identity of each round is that given by
+/// `timelock_create_drand_identity`.
+///
+/// # Parameters
+/// - `cipher`: The cipher to encrypt the message with
+/// - `message`: Pointer to the message to encrypt
+/// - `message_len`: Length of the message
+/// - `rounds`: Pointer to the rounds to encrypt to
+/// - `round_count`: Number of rounds (1 to `TIMELOCK_MAX_ROUNDS`)
+/// - `public_key_hex`: Null-terminated hex string of the public key
+/// - `secret_key`: 32-byte secret key for encryption
+/// - `ciphertext_out`: Output pointer for the encrypted ciphertext
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::InvalidRound` if a
+/// round is 0, error code on other failures
+///
+/// # Safety
+/// - `rounds` must point to `round_count` rounds
+/// - Others as for `timelock_encrypt`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_multi(
+	cipher: TimelockCipher,
+	message: *const c_uchar,
+	message_len: usize,
+	rounds: *const u64,
+	round_count: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	ciphertext_out: *mut *mut TimelockCiphertext,
+) -> TimelockResult {
+	guard(|| {
+		if message.is_null() ||
+			rounds.is_null() ||
+			public_key_hex.is_null() ||
+			secret_key.is_null() ||
+			ciphertext_out.is_null()
+		{
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		if round_count == 0 || round_count > TIMELOCK_MAX_ROUNDS {
+			set_last_error("Invalid input parameters: need 1 to 255 rounds");
+			return TimelockResult::InvalidInput;
+		}
+		let rounds = slice::from_raw_parts(rounds, round_count);
+		if rounds.contains(&0) {
+			set_last_error("Invalid round number: beacon rounds start at 1");
+			return TimelockResult::InvalidRound;
+		}
+
+		let p_pub = match parse_public_key::<TinyBLS381>(Encoded::Hex(public_key_hex)) {
+			Ok(pk) => pk,
+			Err(result) => return result,
+		};
+		let rng = match operation_rng() {
+			Ok(rng) => rng,
+			Err(result) => return result,
+		};
+		let targets: Vec<ShareTarget<TinyBLS381>> = rounds
+			.iter()
+			.map(|round| {
+				let identity = Sha256::digest(round.to_be_bytes());
+				ShareTarget { p_pub, id: Identity::new(b"", &identity) }
+			})
+			.collect();
+
+		let mut secret_key_array = [0u8; 32];
+		ptr::copy_nonoverlapping(secret_key, secret_key_array.as_mut_ptr(), 32);
+		let message = slice::from_raw_parts(message, message_len);
+		let ciphertext = match cipher {
+			TimelockCipher::AesGcm => tle_sharded::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+				&targets,
+				1,
+				secret_key_array,
+				message,
+				rng,
+			),
+			TimelockCipher::ChaCha20Poly1305 => tle_sharded::<
+				TinyBLS381,
+				ChaCha20Poly1305BlockCipherProvider,
+				_,
+			>(&targets, 1, secret_key_array, message, rng),
+		};
+		secret_key_array.zeroize();
+		let ciphertext = match ciphertext {
+			Ok(ct) => ct,
+			Err(e) => {
+				set_last_error(&format!("Timelock encryption operation failed: {}", e));
+				return TimelockResult::EncryptionFailed;
+			},
+		};
+
+		let mut serialized = Vec::new();
+		if ciphertext.serialize_compressed(&mut serialized).is_err() {
+			set_last_error("Failed to serialize ciphertext");
+			return TimelockResult::SerializationError;
+		}
+
+		*ciphertext_out = into_ciphertext(serialized.into_boxed_slice());
+
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Decrypt a ciphertext of `timelock_encrypt_multi`
+///
+/// As `timelock_decrypt`, with the signature of any one of the rounds the
+/// ciphertext was encrypted to. The ciphertext is decrypted with the cipher
+/// recorded in it. Each share is tried in turn, so this costs up to one pairing
+/// per round.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the ciphertext of `timelock_encrypt_multi`
+/// - `signature_hex`: Null-terminated hex string of the signature of a round
+/// - `plaintext_out`: Output buffer for decrypted plaintext, or null to query
+///   the length of the plaintext
+/// - `plaintext_len`: Pointer to the length of the output buffer, updated with
+///   the actual length
+///
+/// # Returns
+/// `TimelockResult::Success` on success, `TimelockResult::DecryptionFailed`
+/// if the signature is not that of any of the rounds, error code on other
+/// failures
+///
+/// # Safety
+/// As for `timelock_decrypt`; `ciphertext` must be a valid pointer returned by
+/// `timelock_encrypt_multi`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_multi(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	plaintext_out: *mut c_uchar,
+	plaintext_len: *mut usize,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() || (*ciphertext).data.is_null() || plaintext_len.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		let ciphertext = match parse_multi(&*ciphertext) {
+			Ok(ct) => ct,
+			Err(result) => return result,
+		};
+
+		let Some(suite) = CipherSuite::from_id(&ciphertext.cipher_suite) else {
+			set_last_error("Invalid ciphertext: unknown cipher suite");
+			return TimelockResult::SerializationError;
+		};
+
+		if plaintext_out.is_null() {
+			return match message_len(suite, &ciphertext.body) {
+				Some(len) => {
+					*plaintext_len = len;
+					clear_last_error();
+					TimelockResult::Success
+				},
+				None => {
+					set_last_error("Invalid ciphertext: malformed body");
+					TimelockResult::SerializationError
+				},
+			};
+		}
+		if signature_hex.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		let signature = match parse_signature::<TinyBLS381>(Encoded::Hex(signature_hex)) {
+			Ok(signature) => signature,
+			Err(result) => return result,
+		};
+
+		// the share a signature opens is not recorded, so the signature is
+		// offered for each; shares it does not open are skipped
+		let signatures: Vec<(u8, _)> =
+			ciphertext.header.shares.iter().map(|share| (share.index, signature)).collect();
+		let plaintext = match suite {
+			CipherSuite::AesGcm =>
+				tld_sharded::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, &signatures),
+			CipherSuite::ChaCha20Poly1305 => tld_sharded::<
+				TinyBLS381,
+				ChaCha20Poly1305BlockCipherProvider,
+			>(ciphertext, &signatures),
+		};
+		let Ok(plaintext) = plaintext.map(Zeroizing::new) else {
+			set_last_error("Timelock decryption failed: the signature is not that of any round");
+			return TimelockResult::DecryptionFailed;
+		};
+
+		if *plaintext_len < plaintext.len() {
+			*plaintext_len = plaintext.len();
+			set_last_error("Output buffer too small for the plaintext");
+			return TimelockResult::BufferTooSmall;
+		}
+		ptr::copy_nonoverlapping(plaintext.as_ptr(), plaintext_out, plaintext.len());
+		*plaintext_len = plaintext.len();
+
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
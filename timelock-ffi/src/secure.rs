@@ -0,0 +1,235 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Buffers for secrets that are zeroed when freed.
+//!
+//! `timelock_decrypt` writes the plaintext into memory the caller owns, which
+//! is often left in the heap after it is freed. A `TimelockSecureBuffer` is
+//! allocated by the library and zeroed on every path that frees or replaces
+//! its memory, so decrypting into one with `timelock_decrypt_secure` or
+//! `timelock_decrypt_early_secure` leaves no copy of the plaintext behind.
+
+use super::{
+	clear_last_error, decrypt, guard, guard_or, set_last_error, timelock_decrypt_early, Encoded,
+	TimelockCiphertext, TimelockResult,
+};
+use std::{
+	os::raw::{c_char, c_uchar},
+	ptr, slice,
+};
+use timelock::{engines::drand::TinyBLS381, tlock::buffer::tld_len};
+use zeroize::Zeroize;
+
+/// Opaque handle for a buffer that is zeroed when freed
+pub struct TimelockSecureBuffer {
+	data: Box<[u8]>,
+	len: usize,
+}
+
+impl Drop for TimelockSecureBuffer {
+	fn drop(&mut self) {
+		self.data.zeroize();
+	}
+}
+
+impl TimelockSecureBuffer {
+	/// Write the plaintext of `ciphertext` with `decrypt`, growing the buffer
+	/// to the length of the plaintext first if needed
+	///
+	/// # Safety
+	/// `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+	unsafe fn decrypt_with(
+		&mut self,
+		ciphertext: *const TimelockCiphertext,
+		decrypt: impl FnOnce(*mut c_uchar, *mut usize) -> TimelockResult,
+	) -> TimelockResult {
+		let ct = &*ciphertext;
+		if !ct.data.is_null() {
+			// a ciphertext that does not parse is reported by `decrypt`
+			let ciphertext = slice::from_raw_parts(ct.data, ct.len);
+			match tld_len::<TinyBLS381>(ciphertext) {
+				Ok(required) if required > self.data.len() => {
+					// the old allocation is zeroed before it is dropped
+					self.data.zeroize();
+					self.data = vec![0u8; required].into_boxed_slice();
+				},
+				_ => {},
+			}
+		}
+
+		let mut len = self.data.len();
+		let result = decrypt(self.data.as_mut_ptr(), &mut len);
+		if result == TimelockResult::Success {
+			self.len = len;
+		} else {
+			self.data.zeroize();
+			self.len = 0;
+		}
+		result
+	}
+}
+
+/// Allocate a secure buffer
+///
+/// # Parameters
+/// - `capacity`: Initial capacity in bytes; decryption grows the buffer as
+///   needed
+/// - `buffer_out`: Output pointer for the buffer
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// `buffer_out` will be set to a pointer that must be freed with
+/// `timelock_secure_buffer_free`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_new(
+	capacity: usize,
+	buffer_out: *mut *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	guard(|| {
+		if buffer_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		let data = vec![0u8; capacity].into_boxed_slice();
+		*buffer_out = Box::into_raw(Box::new(TimelockSecureBuffer { data, len: 0 }));
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Get the contents of a secure buffer
+///
+/// # Returns
+/// Pointer to `timelock_secure_buffer_len` bytes, or null if `buffer` is null
+///
+/// # Safety
+/// - `buffer` must be a valid pointer returned by `timelock_secure_buffer_new`,
+///   or null
+/// - The returned pointer is valid until the buffer is next written or freed
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_data(
+	buffer: *const TimelockSecureBuffer,
+) -> *const c_uchar {
+	guard_or(ptr::null(), || {
+		if buffer.is_null() {
+			return ptr::null();
+		}
+		(*buffer).data.as_ptr()
+	})
+}
+
+/// Get the length of the contents of a secure buffer
+///
+/// # Returns
+/// The length, or 0 if `buffer` is null
+///
+/// # Safety
+/// `buffer` must be a valid pointer returned by `timelock_secure_buffer_new`,
+/// or null
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_len(buffer: *const TimelockSecureBuffer) -> usize {
+	guard_or(0, || {
+		if buffer.is_null() {
+			return 0;
+		}
+		(*buffer).len
+	})
+}
+
+/// Zero and free a secure buffer
+///
+/// # Safety
+/// - `buffer` must be a valid pointer returned by `timelock_secure_buffer_new`,
+///   or null
+/// - `buffer` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_secure_buffer_free(buffer: *mut TimelockSecureBuffer) {
+	guard_or((), || {
+		if !buffer.is_null() {
+			drop(Box::from_raw(buffer));
+		}
+	})
+}
+
+/// Decrypt a timelock-encrypted ciphertext into a secure buffer
+///
+/// As `timelock_decrypt`, with the plaintext written to `buffer`, which grows
+/// to fit it. On failure the buffer is zeroed and left empty.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `signature_hex`: Null-terminated hex string of the signature
+/// - `buffer`: The buffer to write the plaintext to
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+/// - `signature_hex` must be a valid null-terminated C string
+/// - `buffer` must be a valid pointer returned by `timelock_secure_buffer_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_secure(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	buffer: *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() || signature_hex.is_null() || buffer.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		(*buffer).decrypt_with(ciphertext, |out, len| {
+			decrypt::<TinyBLS381>(ciphertext, Encoded::Hex(signature_hex), out, len)
+		})
+	})
+}
+
+/// Decrypt a ciphertext early into a secure buffer
+///
+/// As `timelock_decrypt_early`, with the plaintext written to `buffer`, which
+/// grows to fit it. On failure the buffer is zeroed and left empty.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `secret_key`: Pointer to the ephemeral secret key (32 bytes)
+/// - `buffer`: The buffer to write the plaintext to
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+///
+/// # Safety
+/// - `ciphertext` must be a valid pointer returned by `timelock_encrypt`
+/// - `secret_key` must point to 32 bytes
+/// - `buffer` must be a valid pointer returned by `timelock_secure_buffer_new`
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_early_secure(
+	ciphertext: *const TimelockCiphertext,
+	secret_key: *const c_uchar,
+	buffer: *mut TimelockSecureBuffer,
+) -> TimelockResult {
+	guard(|| {
+		if ciphertext.is_null() || secret_key.is_null() || buffer.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		(*buffer).decrypt_with(ciphertext, |out, len| {
+			timelock_decrypt_early(ciphertext, secret_key, out, len)
+		})
+	})
+}
@@ -0,0 +1,486 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Streaming chunked encryption and decryption.
+//!
+//! Messages too large for one contiguous buffer are fed to a stream in pieces
+//! of any size, and the stream hands its output to a write callback as it is
+//! produced. The ciphertext is the chunked stream of the core library (see
+//! `timelock::tlock::stream`): a timelock encrypted payload key followed by
+//! the message in authenticated chunks of 64 KiB.
+//!
+//! A stream is finished with `timelock_*_stream_finish` or abandoned with
+//! `timelock_*_stream_free`; both free it. A decryption stream writes each
+//! chunk out as soon as it is authenticated, so the output is only complete
+//! (and not truncated) once `timelock_decrypt_stream_finish` succeeds.
+
+use super::{
+	clear_last_error, guard, guard_or, operation_rng, parse_public_key, parse_signature,
+	set_last_error, Encoded, TimelockBeacon, TimelockResult,
+};
+use ark_serialize::CanonicalDeserialize;
+use std::{
+	io::{self, Write},
+	os::raw::{c_char, c_int, c_uchar, c_void},
+	slice,
+};
+use timelock::{
+	block_ciphers::AESGCMBlockCipherProvider,
+	engines::{
+		drand::{TinyBLS377, TinyBLS381},
+		EngineBLS,
+	},
+	ibe::fullident::Identity,
+	tlock::{TLECiphertext, TldWriter, TleWriter},
+};
+
+/// The most bytes a stream header may take before it is rejected
+const MAX_HEADER_SIZE: usize = 4096;
+
+/// A caller-supplied sink: consumes `len` bytes at `data` and returns 0 on
+/// success
+pub type TimelockWriteCallback =
+	Option<unsafe extern "C" fn(data: *const c_uchar, len: usize, user_data: *mut c_void) -> c_int>;
+
+/// The write callback of a stream
+#[derive(Clone, Copy)]
+struct CallbackWriter {
+	write: unsafe extern "C" fn(data: *const c_uchar, len: usize, user_data: *mut c_void) -> c_int,
+	user_data: *mut c_void,
+}
+
+impl Write for CallbackWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		// SAFETY: the caller of `timelock_*_stream_begin` guarantees that the
+		// callback can consume `len` bytes at `data` with its `user_data`.
+		match unsafe { (self.write)(buf.as_ptr(), buf.len(), self.user_data) } {
+			0 => Ok(buf.len()),
+			_ => Err(io::Error::other("the write callback failed")),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Report a failed stream operation
+fn fail(error: io::Error) -> TimelockResult {
+	match error.kind() {
+		io::ErrorKind::InvalidData => {
+			set_last_error(&format!("Timelock decryption failed: {}", error));
+			TimelockResult::DecryptionFailed
+		},
+		_ => {
+			set_last_error(&format!("Writing the stream output failed: {}", error));
+			TimelockResult::WriteFailed
+		},
+	}
+}
+
+/// Opaque handle for a streaming encryption
+pub struct TimelockEncryptStream {
+	writer: TleWriter<CallbackWriter>,
+}
+
+/// Start encrypting a message in pieces
+///
+/// The header of the ciphertext, holding the timelock encrypted payload key,
+/// is written to the callback before this returns.
+///
+/// # Parameters
+/// - `beacon`: The beacon to encrypt for
+/// - `identity`: Pointer to the identity (32 bytes)
+/// - `identity_len`: Length of the identity (must be 32)
+/// - `public_key_hex`: Null-terminated hex string of the public key
+/// - `write`: Callback consuming the ciphertext, returning 0 on success
+/// - `user_data`: Passed to every call of `write`
+/// - `stream_out`: Output pointer for the stream
+///
+/// # Safety
+/// - `identity` must point to 32 bytes
+/// - `public_key_hex` must be a valid null-terminated C string
+/// - `write` must read at most `len` bytes at `data`
+/// - `user_data` must remain valid until the stream is finished or freed
+/// - `stream_out` will be set to a pointer that must be passed to
+///   `timelock_encrypt_stream_finish` or `timelock_encrypt_stream_free`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_begin(
+	beacon: TimelockBeacon,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockEncryptStream,
+) -> TimelockResult {
+	guard(|| {
+		let begin = match beacon {
+			TimelockBeacon::QuickNet => encrypt_stream_begin::<TinyBLS381>,
+			TimelockBeacon::Idn => encrypt_stream_begin::<TinyBLS377>,
+		};
+		begin(identity, identity_len, public_key_hex, write, user_data, stream_out)
+	})
+}
+
+/// `timelock_encrypt_stream_begin` with the engine of a beacon
+///
+/// # Safety
+/// As for `timelock_encrypt_stream_begin`
+unsafe fn encrypt_stream_begin<E: EngineBLS>(
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockEncryptStream,
+) -> TimelockResult {
+	let Some(write) = write else {
+		set_last_error("Invalid input parameters: null write callback");
+		return TimelockResult::InvalidInput;
+	};
+	if identity.is_null() || public_key_hex.is_null() || stream_out.is_null() || identity_len != 32
+	{
+		set_last_error(
+			"Invalid input parameters: null pointers or incorrect identity length (need 32 bytes)",
+		);
+		return TimelockResult::InvalidInput;
+	}
+
+	let public_key = match parse_public_key::<E>(Encoded::Hex(public_key_hex)) {
+		Ok(pk) => pk,
+		Err(result) => return result,
+	};
+	let rng = match operation_rng() {
+		Ok(rng) => rng,
+		Err(result) => return result,
+	};
+
+	let identity = Identity::new(b"", slice::from_raw_parts(identity, identity_len));
+	let output = CallbackWriter { write, user_data };
+	let writer = match TleWriter::new::<E, AESGCMBlockCipherProvider, _>(
+		public_key, identity, output, rng,
+	) {
+		Ok(writer) => writer,
+		Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+			set_last_error(&format!("Timelock encryption operation failed: {}", e));
+			return TimelockResult::EncryptionFailed;
+		},
+		Err(e) => return fail(e),
+	};
+
+	*stream_out = Box::into_raw(Box::new(TimelockEncryptStream { writer }));
+	clear_last_error();
+	TimelockResult::Success
+}
+
+/// Encrypt the next piece of the message
+///
+/// # Parameters
+/// - `stream`: The stream
+/// - `data`: Pointer to the piece
+/// - `len`: Length of the piece
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_encrypt_stream_begin`
+/// - `data` must point to `len` bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_update(
+	stream: *mut TimelockEncryptStream,
+	data: *const c_uchar,
+	len: usize,
+) -> TimelockResult {
+	guard(|| {
+		if stream.is_null() || (data.is_null() && len > 0) {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		if len == 0 {
+			clear_last_error();
+			return TimelockResult::Success;
+		}
+		match (*stream).writer.write_all(slice::from_raw_parts(data, len)) {
+			Ok(()) => {
+				clear_last_error();
+				TimelockResult::Success
+			},
+			Err(e) => fail(e),
+		}
+	})
+}
+
+/// Encrypt the last chunk of the message and free the stream
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_encrypt_stream_begin`
+/// - `stream` must not be used after calling this function
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_finish(
+	stream: *mut TimelockEncryptStream,
+) -> TimelockResult {
+	guard(|| {
+		if stream.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		match Box::from_raw(stream).writer.finish() {
+			Ok(_) => {
+				clear_last_error();
+				TimelockResult::Success
+			},
+			Err(e) => fail(e),
+		}
+	})
+}
+
+/// Abandon a streaming encryption, freeing the stream
+///
+/// The ciphertext written so far lacks its last chunk, so it fails to
+/// decrypt.
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_encrypt_stream_begin`, or null
+/// - `stream` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_stream_free(stream: *mut TimelockEncryptStream) {
+	guard_or((), || {
+		if !stream.is_null() {
+			drop(Box::from_raw(stream));
+		}
+	})
+}
+
+/// The signature of a decryption stream, in the engine of its beacon
+enum StreamSignature {
+	QuickNet(<TinyBLS381 as EngineBLS>::SignatureGroup),
+	Idn(<TinyBLS377 as EngineBLS>::SignatureGroup),
+}
+
+/// The state of a decryption stream
+enum DecryptState {
+	/// The header is still being received
+	Header { signature: StreamSignature, header: Vec<u8>, output: CallbackWriter },
+	/// The payload key is known and chunks are being decrypted
+	Payload(Box<TldWriter<CallbackWriter>>),
+}
+
+/// Opaque handle for a streaming decryption
+pub struct TimelockDecryptStream {
+	state: DecryptState,
+}
+
+/// Start decrypting a ciphertext in pieces
+///
+/// # Parameters
+/// - `beacon`: The beacon the ciphertext was encrypted for
+/// - `signature_hex`: Null-terminated hex string of the signature
+/// - `write`: Callback consuming the plaintext, returning 0 on success
+/// - `user_data`: Passed to every call of `write`
+/// - `stream_out`: Output pointer for the stream
+///
+/// # Safety
+/// - `signature_hex` must be a valid null-terminated C string
+/// - `write` must read at most `len` bytes at `data`
+/// - `user_data` must remain valid until the stream is finished or freed
+/// - `stream_out` will be set to a pointer that must be passed to
+///   `timelock_decrypt_stream_finish` or `timelock_decrypt_stream_free`
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_begin(
+	beacon: TimelockBeacon,
+	signature_hex: *const c_char,
+	write: TimelockWriteCallback,
+	user_data: *mut c_void,
+	stream_out: *mut *mut TimelockDecryptStream,
+) -> TimelockResult {
+	guard(|| {
+		let Some(write) = write else {
+			set_last_error("Invalid input parameters: null write callback");
+			return TimelockResult::InvalidInput;
+		};
+		if signature_hex.is_null() || stream_out.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let signature = match beacon {
+			TimelockBeacon::QuickNet => parse_signature::<TinyBLS381>(Encoded::Hex(signature_hex))
+				.map(StreamSignature::QuickNet),
+			TimelockBeacon::Idn =>
+				parse_signature::<TinyBLS377>(Encoded::Hex(signature_hex)).map(StreamSignature::Idn),
+		};
+		let signature = match signature {
+			Ok(signature) => signature,
+			Err(result) => return result,
+		};
+
+		let output = CallbackWriter { write, user_data };
+		let state = DecryptState::Header { signature, header: Vec::new(), output };
+		*stream_out = Box::into_raw(Box::new(TimelockDecryptStream { state }));
+		clear_last_error();
+		TimelockResult::Success
+	})
+}
+
+/// Parse the header of a stream once it is complete, returning the writer of
+/// its payload and the number of header bytes
+///
+/// A truncated header fails to deserialize like a malformed one, so the header
+/// is only rejected once it has grown to `MAX_HEADER_SIZE` bytes; a header
+/// that parses consumes the same bytes whatever follows it.
+fn unlock<E: EngineBLS>(
+	header: &[u8],
+	signature: E::SignatureGroup,
+	output: CallbackWriter,
+) -> io::Result<Option<(TldWriter<CallbackWriter>, usize)>> {
+	let mut reader = header;
+	match TLECiphertext::<E>::deserialize_compressed(&mut reader) {
+		Ok(ciphertext) => {
+			let len = header.len() - reader.len();
+			let writer =
+				TldWriter::new::<E, AESGCMBlockCipherProvider>(ciphertext, signature, output)?;
+			Ok(Some((writer, len)))
+		},
+		Err(_) if header.len() < MAX_HEADER_SIZE => Ok(None),
+		Err(_) => Err(io::Error::new(io::ErrorKind::InvalidData, "the header is malformed")),
+	}
+}
+
+impl TimelockDecryptStream {
+	/// Decrypt the next piece of the ciphertext
+	fn update(&mut self, data: &[u8]) -> io::Result<()> {
+		let (signature, header, output) = match &mut self.state {
+			DecryptState::Header { signature, header, output } => (signature, header, output),
+			DecryptState::Payload(writer) => return writer.write_all(data),
+		};
+		header.extend_from_slice(data);
+		let unlocked = match signature {
+			StreamSignature::QuickNet(signature) =>
+				unlock::<TinyBLS381>(header, *signature, *output),
+			StreamSignature::Idn(signature) => unlock::<TinyBLS377>(header, *signature, *output),
+		}?;
+		if let Some((mut writer, len)) = unlocked {
+			writer.write_all(&header[len..])?;
+			self.state = DecryptState::Payload(Box::new(writer));
+		}
+		Ok(())
+	}
+}
+
+/// Decrypt the next piece of the ciphertext
+///
+/// Authenticated chunks of the message are written to the callback.
+///
+/// # Parameters
+/// - `stream`: The stream
+/// - `data`: Pointer to the piece
+/// - `len`: Length of the piece
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_decrypt_stream_begin`
+/// - `data` must point to `len` bytes
+///
+/// # Returns
+/// `TimelockResult::Success` on success, error code on failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_update(
+	stream: *mut TimelockDecryptStream,
+	data: *const c_uchar,
+	len: usize,
+) -> TimelockResult {
+	guard(|| {
+		if stream.is_null() || (data.is_null() && len > 0) {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		if len == 0 {
+			clear_last_error();
+			return TimelockResult::Success;
+		}
+		match (*stream).update(slice::from_raw_parts(data, len)) {
+			Ok(()) => {
+				clear_last_error();
+				TimelockResult::Success
+			},
+			Err(e) => fail(e),
+		}
+	})
+}
+
+/// Authenticate the last chunk of the ciphertext and free the stream
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_decrypt_stream_begin`
+/// - `stream` must not be used after calling this function
+///
+/// # Returns
+/// `TimelockResult::Success` if the whole message was decrypted, error code on
+/// failure
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_finish(
+	stream: *mut TimelockDecryptStream,
+) -> TimelockResult {
+	guard(|| {
+		if stream.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+		match Box::from_raw(stream).state {
+			DecryptState::Header { .. } => {
+				set_last_error("Timelock decryption failed: the ciphertext ends in its header");
+				TimelockResult::DecryptionFailed
+			},
+			DecryptState::Payload(writer) => match writer.finish() {
+				Ok(_) => {
+					clear_last_error();
+					TimelockResult::Success
+				},
+				Err(e) => fail(e),
+			},
+		}
+	})
+}
+
+/// Abandon a streaming decryption, freeing the stream
+///
+/// # Safety
+/// - `stream` must be a valid pointer returned by
+///   `timelock_decrypt_stream_begin`, or null
+/// - `stream` must not be used after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_stream_free(stream: *mut TimelockDecryptStream) {
+	guard_or((), || {
+		if !stream.is_null() {
+			drop(Box::from_raw(stream));
+		}
+	})
+}
@@ -0,0 +1,272 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Encryption and decryption on a thread pool.
+//!
+//! Hosts that cannot block their main thread on pairings, such as GUIs and game
+//! engines, call `timelock_encrypt_async` or `timelock_decrypt_async`, which
+//! copy their inputs, queue the work on an internal pool of threads and return
+//! at once. The callback is invoked exactly once, on a pool thread, if and only
+//! if the call returns `TimelockResult::Success`.
+//!
+//! On WebAssembly targets without threads, such as `wasm32-wasip1`, there is
+//! no pool: the job runs on the calling thread and the callback is invoked
+//! before the call returns.
+//!
+//! The randomness of an asynchronous encryption is drawn from the RNG provider
+//! of the calling thread or context before the call returns, so it is the same
+//! as for a synchronous call.
+//!
+//! A panic in the work of a job is caught as in a synchronous call and passed
+//! to the callback as `TimelockResult::InternalPanic`. A panic that escapes a
+//! job anyway is caught by the pool thread, which goes on to the next job.
+
+use super::{
+	encrypt, error::capture_error, guard, operation_rng, set_last_error, timelock_decrypt_alloc,
+	Encoded, TimelockCipher, TimelockCiphertext, TimelockPlaintext, TimelockResult,
+};
+use ark_std::rand::RngCore;
+use std::{
+	ffi::{CStr, CString},
+	os::raw::{c_char, c_uchar, c_void},
+	ptr, slice,
+};
+use timelock::engines::drand::TinyBLS381;
+use zeroize::Zeroizing;
+
+/// Receives the result of `timelock_encrypt_async`: the ciphertext on success,
+/// which the callback owns and must free with `timelock_ciphertext_free`, or
+/// null and the error message, which is valid during the callback only
+pub type TimelockEncryptCallback = Option<
+	unsafe extern "C" fn(
+		result: TimelockResult,
+		ciphertext: *mut TimelockCiphertext,
+		error: *const c_char,
+		user_data: *mut c_void,
+	),
+>;
+
+/// Receives the result of `timelock_decrypt_async`: the plaintext on success,
+/// which the callback owns and must free with `timelock_plaintext_free`, or
+/// null and the error message, which is valid during the callback only
+pub type TimelockDecryptCallback = Option<
+	unsafe extern "C" fn(
+		result: TimelockResult,
+		plaintext: *mut TimelockPlaintext,
+		error: *const c_char,
+		user_data: *mut c_void,
+	),
+>;
+
+/// A job of the pool
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The user data of a callback, which the caller lets the pool threads use
+struct UserData(*mut c_void);
+
+// SAFETY: the callers of the `_async` functions guarantee that their user data
+// may be used from the pool threads.
+unsafe impl Send for UserData {}
+
+impl UserData {
+	fn get(&self) -> *mut c_void {
+		self.0
+	}
+}
+
+/// Run a job at once on targets without threads, such as `wasm32-wasip1`
+/// (internal helper)
+#[cfg(all(target_family = "wasm", not(target_feature = "atomics")))]
+pub(crate) fn spawn(job: Job) -> Result<(), TimelockResult> {
+	job();
+	Ok(())
+}
+
+/// Queue a job on the pool, starting the pool on first use (internal helper)
+#[cfg(not(all(target_family = "wasm", not(target_feature = "atomics"))))]
+pub(crate) fn spawn(job: Job) -> Result<(), TimelockResult> {
+	use std::{
+		panic::{self, AssertUnwindSafe},
+		sync::{mpsc, Arc, Mutex, OnceLock},
+		thread,
+	};
+
+	static POOL: OnceLock<Option<mpsc::Sender<Job>>> = OnceLock::new();
+	let pool = POOL.get_or_init(|| {
+		let (sender, receiver) = mpsc::channel::<Job>();
+		let receiver = Arc::new(Mutex::new(receiver));
+		let workers = thread::available_parallelism().map_or(1, |n| n.get());
+		let started = (0..workers)
+			.filter(|i| {
+				let receiver = Arc::clone(&receiver);
+				thread::Builder::new()
+					.name(format!("timelock-{}", i))
+					.spawn(move || loop {
+						let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+						match job {
+							// a panicking job must not take its thread down with it
+							Ok(job) => drop(panic::catch_unwind(AssertUnwindSafe(job))),
+							Err(_) => break,
+						}
+					})
+					.is_ok()
+			})
+			.count();
+		(started > 0).then_some(sender)
+	});
+	match pool.as_ref().map(|sender| sender.send(job)) {
+		Some(Ok(())) => Ok(()),
+		_ => {
+			set_last_error("The worker threads could not be started");
+			Err(TimelockResult::MemoryError)
+		},
+	}
+}
+
+/// The error message of a failed job, or null (internal helper)
+fn error_ptr(error: &Option<CString>) -> *const c_char {
+	error.as_ref().map_or(ptr::null(), |e| e.as_ptr())
+}
+
+/// Encrypt a message on the thread pool
+///
+/// As `timelock_encrypt`, with the ciphertext passed to `callback` instead of
+/// returned. The inputs are copied, so they may be freed once this returns.
+///
+/// # Parameters
+/// - `callback`: Invoked with the result on a pool thread
+/// - `user_data`: Passed to `callback`
+/// - Others as for `timelock_encrypt`
+///
+/// # Returns
+/// `TimelockResult::Success` if the encryption was queued, error code
+/// otherwise, in which case `callback` is not invoked
+///
+/// # Safety
+/// As for `timelock_encrypt`; `user_data` must be usable from any thread
+#[no_mangle]
+pub unsafe extern "C" fn timelock_encrypt_async(
+	message: *const c_uchar,
+	message_len: usize,
+	identity: *const c_uchar,
+	identity_len: usize,
+	public_key_hex: *const c_char,
+	secret_key: *const c_uchar,
+	callback: TimelockEncryptCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	guard(|| {
+		let Some(callback) = callback else {
+			set_last_error("Invalid input parameters: null callback");
+			return TimelockResult::InvalidInput;
+		};
+		if message.is_null() ||
+			identity.is_null() ||
+			public_key_hex.is_null() ||
+			secret_key.is_null() ||
+			identity_len != 32
+		{
+			set_last_error(
+				"Invalid input parameters: null pointers or incorrect identity length (need 32 bytes)",
+			);
+			return TimelockResult::InvalidInput;
+		}
+
+		let message = Zeroizing::new(slice::from_raw_parts(message, message_len).to_vec());
+		let identity = slice::from_raw_parts(identity, identity_len).to_vec();
+		let public_key_hex = CStr::from_ptr(public_key_hex).to_owned();
+		let secret_key = Zeroizing::new(slice::from_raw_parts(secret_key, 32).to_vec());
+		let mut seed = Zeroizing::new([0u8; 32]);
+		match operation_rng() {
+			Ok(mut rng) => rng.fill_bytes(&mut *seed),
+			Err(result) => return result,
+		}
+		let user_data = UserData(user_data);
+
+		spawn(Box::new(move || {
+			let mut ciphertext = ptr::null_mut();
+			// catches a panic of the encryption, so the callback is still invoked
+			let (result, error) = capture_error(|| {
+				encrypt::<TinyBLS381>(
+					message.as_ptr(),
+					message.len(),
+					identity.as_ptr(),
+					identity.len(),
+					Encoded::Hex(public_key_hex.as_ptr()),
+					secret_key.as_ptr(),
+					TimelockCipher::AesGcm,
+					Some(*seed),
+					&mut ciphertext,
+				)
+			});
+			callback(result, ciphertext, error_ptr(&error), user_data.get());
+		}))
+		.map_or_else(|result| result, |()| TimelockResult::Success)
+	})
+}
+
+/// Decrypt a ciphertext on the thread pool
+///
+/// As `timelock_decrypt_alloc`, with the plaintext passed to `callback`
+/// instead of returned. The inputs are copied, so they may be freed once this
+/// returns.
+///
+/// # Parameters
+/// - `ciphertext`: Pointer to the encrypted ciphertext
+/// - `signature_hex`: Null-terminated hex string of the signature
+/// - `callback`: Invoked with the result on a pool thread
+/// - `user_data`: Passed to `callback`
+///
+/// # Returns
+/// `TimelockResult::Success` if the decryption was queued, error code
+/// otherwise, in which case `callback` is not invoked
+///
+/// # Safety
+/// As for `timelock_decrypt_alloc`; `user_data` must be usable from any thread
+#[no_mangle]
+pub unsafe extern "C" fn timelock_decrypt_async(
+	ciphertext: *const TimelockCiphertext,
+	signature_hex: *const c_char,
+	callback: TimelockDecryptCallback,
+	user_data: *mut c_void,
+) -> TimelockResult {
+	guard(|| {
+		let Some(callback) = callback else {
+			set_last_error("Invalid input parameters: null callback");
+			return TimelockResult::InvalidInput;
+		};
+		if ciphertext.is_null() || (*ciphertext).data.is_null() || signature_hex.is_null() {
+			set_last_error("Invalid input parameters: null pointers not allowed");
+			return TimelockResult::InvalidInput;
+		}
+
+		let ct = &*ciphertext;
+		let mut data = slice::from_raw_parts(ct.data, ct.len).to_vec();
+		let signature_hex = CStr::from_ptr(signature_hex).to_owned();
+		let user_data = UserData(user_data);
+
+		spawn(Box::new(move || {
+			let ciphertext = TimelockCiphertext { data: data.as_mut_ptr(), len: data.len() };
+			let mut plaintext = ptr::null_mut();
+			// catches a panic of the decryption, so the callback is still invoked
+			let (result, error) = capture_error(|| {
+				timelock_decrypt_alloc(&ciphertext, signature_hex.as_ptr(), &mut plaintext)
+			});
+			callback(result, plaintext, error_ptr(&error), user_data.get());
+		}))
+		.map_or_else(|result| result, |()| TimelockResult::Success)
+	})
+}
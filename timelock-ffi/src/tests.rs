@@ -37,7 +37,11 @@
 //! current production key from the official Drand API.
 
 use super::*;
-use std::{ffi::CString, sync::Arc, thread};
+use std::{
+	ffi::{CStr, CString},
+	sync::{mpsc, Arc, Mutex},
+	thread,
+};
 
 // Cryptographic component sizes and protocol overhead constants
 // Import shared constants from main library
@@ -193,6 +197,68 @@ fn test_error_codes() {
 	assert_eq!(TimelockResult::SerializationError as i32, 5);
 	assert_eq!(TimelockResult::InvalidPublicKey as i32, 6);
 	assert_eq!(TimelockResult::InvalidSignature as i32, 7);
+	assert_eq!(TimelockResult::InvalidPoint as i32, 8);
+	assert_eq!(TimelockResult::SelfTestFailed as i32, 9);
+	assert_eq!(TimelockResult::WriteFailed as i32, 10);
+	assert_eq!(TimelockResult::BufferTooSmall as i32, 11);
+	assert_eq!(TimelockResult::InvalidRound as i32, 12);
+	assert_eq!(TimelockResult::UnsupportedBeacon as i32, 13);
+	assert_eq!(TimelockResult::InternalPanic as i32, 14);
+}
+
+#[test]
+fn test_panics_are_caught() {
+	assert_eq!(crate::guard(|| panic!("boom")), TimelockResult::InternalPanic);
+	let error = unsafe { CStr::from_ptr(timelock_get_last_error()) };
+	assert_eq!(error.to_str().unwrap(), "Internal panic: boom");
+
+	assert_eq!(crate::guard_or(7, || panic!("{}", String::from("boom"))), 7);
+	assert_eq!(crate::guard(|| TimelockResult::Success), TimelockResult::Success);
+}
+
+#[test]
+fn test_log_callback() {
+	static LOGGED: Mutex<Vec<(TimelockLogLevel, String)>> = Mutex::new(Vec::new());
+	unsafe extern "C" fn log(level: TimelockLogLevel, message: *const c_char, _: *mut c_void) {
+		let message = CStr::from_ptr(message).to_str().unwrap().to_owned();
+		LOGGED.lock().unwrap().push((level, message));
+	}
+
+	unsafe {
+		timelock_set_log_callback(Some(log), ptr::null_mut());
+		assert_eq!(timelock_set_rng_callback(None, ptr::null_mut()), TimelockResult::InvalidInput);
+		assert_eq!(crate::guard(|| panic!("logged")), TimelockResult::InternalPanic);
+		timelock_set_log_callback(None, ptr::null_mut());
+	}
+
+	// other tests may log concurrently
+	let logged = LOGGED.lock().unwrap();
+	assert!(logged.contains(&(
+		TimelockLogLevel::Warn,
+		"Invalid input parameters: null RNG callback".to_owned()
+	)));
+	assert!(logged.contains(&(TimelockLogLevel::Error, "Internal panic: logged".to_owned())));
+}
+
+#[test]
+fn test_result_to_string() {
+	let describe = |result| unsafe {
+		CStr::from_ptr(timelock_result_to_string(result)).to_str().unwrap().to_owned()
+	};
+	assert_eq!(describe(TimelockResult::Success), "Success");
+	assert_eq!(describe(TimelockResult::BufferTooSmall), "Output buffer too small");
+	assert_eq!(describe(TimelockResult::MemoryError), "Memory allocation failed");
+	assert_eq!(describe(TimelockResult::UnsupportedBeacon), "Unsupported beacon");
+}
+
+#[test]
+fn test_check_beacon() {
+	unsafe {
+		assert_eq!(timelock_check_beacon(0), TimelockResult::Success);
+		assert_eq!(timelock_check_beacon(1), TimelockResult::Success);
+		assert_eq!(timelock_check_beacon(2), TimelockResult::UnsupportedBeacon);
+		assert_eq!(timelock_check_beacon(u32::MAX), TimelockResult::UnsupportedBeacon);
+	}
 }
 
 #[test]
@@ -230,6 +296,20 @@ fn test_version_function() {
 	}
 }
 
+#[test]
+fn test_version_info() {
+	let (mut major, mut minor, mut patch) = (u32::MAX, u32::MAX, u32::MAX);
+	unsafe {
+		timelock_get_version_info(&mut major, &mut minor, &mut patch);
+		timelock_get_version_info(ptr::null_mut(), ptr::null_mut(), &mut patch);
+		assert_eq!(timelock_get_abi_version(), TIMELOCK_ABI_VERSION);
+	}
+	// the constants of the header follow the crate version
+	assert_eq!(major.to_string(), env!("CARGO_PKG_VERSION_MAJOR"));
+	assert_eq!(minor.to_string(), env!("CARGO_PKG_VERSION_MINOR"));
+	assert_eq!(patch.to_string(), env!("CARGO_PKG_VERSION_PATCH"));
+}
+
 #[test]
 fn test_estimate_ciphertext_size() {
 	unsafe {
@@ -283,12 +363,56 @@ fn test_identity_creation_invalid_buffer() {
 	assert_eq!(result, TimelockResult::InvalidInput);
 }
 
+#[test]
+fn test_identity_creation_round_zero() {
+	let mut identity = [0u8; 32];
+	let result = unsafe { timelock_create_drand_identity(0, identity.as_mut_ptr(), 32) };
+	assert_eq!(result, TimelockResult::InvalidRound);
+	assert_eq!(identity, [0u8; 32]);
+}
+
 #[test]
 fn test_identity_creation_null_buffer() {
 	let result = unsafe { timelock_create_drand_identity(1000, ptr::null_mut(), 32) };
 	assert_eq!(result, TimelockResult::InvalidInput);
 }
 
+#[test]
+fn test_identity_from_data() {
+	let mut drand = [0u8; 32];
+	let mut identity = [0u8; 32];
+	unsafe {
+		// the identity of a round is that of its big-endian encoding
+		assert_eq!(
+			timelock_create_drand_identity(1000, drand.as_mut_ptr(), 32),
+			TimelockResult::Success
+		);
+		let round = 1000u64.to_be_bytes();
+		let result =
+			timelock_create_identity(round.as_ptr(), round.len(), identity.as_mut_ptr(), 32);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(identity, drand);
+
+		let commitment = b"a block hash or commitment of any length";
+		let result = timelock_create_identity(
+			commitment.as_ptr(),
+			commitment.len(),
+			identity.as_mut_ptr(),
+			32,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(identity[..], Sha256::digest(commitment)[..]);
+
+		let result = timelock_create_identity(ptr::null(), 0, identity.as_mut_ptr(), 32);
+		assert_eq!(result, TimelockResult::Success);
+		let result = timelock_create_identity(ptr::null(), 1, identity.as_mut_ptr(), 32);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		let result =
+			timelock_create_identity(round.as_ptr(), round.len(), identity.as_mut_ptr(), 31);
+		assert_eq!(result, TimelockResult::InvalidInput);
+	}
+}
+
 #[test]
 fn test_encrypt_invalid_inputs() {
 	let message = b"test";
@@ -436,6 +560,70 @@ fn test_memory_management() {
 	}
 }
 
+#[test]
+fn test_ciphertext_accessors() {
+	let message = b"Hello, handles!";
+	let identity = [4u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	let secret_key = [2u8; 32];
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let data = timelock_ciphertext_data(ciphertext_ptr);
+		let len = timelock_ciphertext_len(ciphertext_ptr);
+		assert_eq!((data, len), ((*ciphertext_ptr).data as *const u8, (*ciphertext_ptr).len));
+		let bytes = slice::from_raw_parts(data, len).to_vec();
+
+		// a copy and a ciphertext read back from its bytes outlive the original
+		let mut clone: *mut TimelockCiphertext = ptr::null_mut();
+		assert_eq!(timelock_ciphertext_clone(ciphertext_ptr, &mut clone), TimelockResult::Success);
+		let mut restored: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_ciphertext_from_bytes(bytes.as_ptr(), bytes.len(), &mut restored);
+		assert_eq!(result, TimelockResult::Success);
+		timelock_ciphertext_free(ciphertext_ptr);
+
+		for ciphertext in [clone, restored] {
+			assert_ne!(timelock_ciphertext_data(ciphertext), data);
+			let copy = slice::from_raw_parts(
+				timelock_ciphertext_data(ciphertext),
+				timelock_ciphertext_len(ciphertext),
+			);
+			assert_eq!(copy, &bytes[..]);
+			let mut plaintext = [0u8; 64];
+			let mut plaintext_len = plaintext.len();
+			let result = timelock_decrypt_early(
+				ciphertext,
+				secret_key.as_ptr(),
+				plaintext.as_mut_ptr(),
+				&mut plaintext_len,
+			);
+			assert_eq!(result, TimelockResult::Success);
+			assert_eq!(&plaintext[..plaintext_len], message);
+			timelock_ciphertext_free(ciphertext);
+		}
+
+		assert!(timelock_ciphertext_data(ptr::null()).is_null());
+		assert_eq!(timelock_ciphertext_len(ptr::null()), 0);
+		let mut out: *mut TimelockCiphertext = ptr::null_mut();
+		assert_eq!(timelock_ciphertext_clone(ptr::null(), &mut out), TimelockResult::InvalidInput);
+		assert_eq!(
+			timelock_ciphertext_from_bytes(ptr::null(), 0, &mut out),
+			TimelockResult::InvalidInput
+		);
+		assert!(out.is_null());
+	}
+}
+
 #[test]
 fn test_ciphertext_free_null() {
 	// Should handle null pointer gracefully
@@ -838,3 +1026,1708 @@ fn test_cryptographic_constants_match_library() {
 		crate::SERIALIZATION_OVERHEAD
 	);
 }
+
+#[test]
+fn test_encrypt_into_decrypt_roundtrip() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, caller-provided buffers!";
+	let identity = [3u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	let encrypt = |buffer: &mut [u8], len: &mut usize| unsafe {
+		timelock_encrypt_into(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			buffer.as_mut_ptr(),
+			len,
+		)
+	};
+
+	// A buffer that is too small reports the required length
+	let mut small = [0u8; 16];
+	let mut ciphertext_len = small.len();
+	assert_eq!(encrypt(&mut small, &mut ciphertext_len), TimelockResult::BufferTooSmall);
+	let mut buffer = vec![0u8; ciphertext_len];
+	assert_eq!(encrypt(&mut buffer, &mut ciphertext_len), TimelockResult::Success);
+	assert_eq!(ciphertext_len, buffer.len());
+
+	let ciphertext = TimelockCiphertext { data: buffer.as_mut_ptr(), len: ciphertext_len };
+	let mut plaintext = [0u8; 8];
+	let mut plaintext_len = plaintext.len();
+	let result = unsafe {
+		timelock_decrypt(&ciphertext, sig_hex.as_ptr(), plaintext.as_mut_ptr(), &mut plaintext_len)
+	};
+	assert_eq!(result, TimelockResult::BufferTooSmall);
+	assert_eq!(plaintext_len, message.len());
+
+	let mut plaintext = vec![0u8; plaintext_len];
+	let result = unsafe {
+		timelock_decrypt(&ciphertext, sig_hex.as_ptr(), plaintext.as_mut_ptr(), &mut plaintext_len)
+	};
+	assert_eq!(result, TimelockResult::Success);
+	assert_eq!(&plaintext[..plaintext_len], message);
+}
+
+#[test]
+fn test_decrypt_size_query() {
+	let message = b"Hello, two calls!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		// a null output only reports the length, without needing the signature
+		let mut size = 0;
+		assert_eq!(timelock_decrypt_size(ciphertext_ptr, &mut size), TimelockResult::Success);
+		assert_eq!(size, message.len());
+		let mut plaintext_len = 0;
+		let result =
+			timelock_decrypt(ciphertext_ptr, ptr::null(), ptr::null_mut(), &mut plaintext_len);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(plaintext_len, message.len());
+		let mut plaintext_len = 0;
+		let result = timelock_decrypt_ex(
+			TimelockBeacon::QuickNet,
+			ciphertext_ptr,
+			ptr::null(),
+			ptr::null_mut(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(plaintext_len, message.len());
+
+		// decrypting early follows the same convention
+		let mut plaintext_len = 0;
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			ptr::null(),
+			ptr::null_mut(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(plaintext_len, message.len());
+		let mut plaintext = vec![0u8; plaintext_len];
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			secret_key.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..], message);
+
+		// the signature is still required to decrypt
+		let result = timelock_decrypt(
+			ciphertext_ptr,
+			ptr::null(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert_eq!(
+			timelock_decrypt_size(ciphertext_ptr, ptr::null_mut()),
+			TimelockResult::InvalidInput
+		);
+		timelock_ciphertext_free(ciphertext_ptr);
+
+		let mut garbage = [7u8; 16];
+		let ciphertext = TimelockCiphertext { data: garbage.as_mut_ptr(), len: garbage.len() };
+		let result = timelock_decrypt_size(&ciphertext, &mut size);
+		assert_eq!(result, TimelockResult::SerializationError);
+	}
+}
+
+#[test]
+fn test_named_beacons() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, named beacons!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	let name = CString::new("test-named-beacons").unwrap();
+	let encrypt = |name: &CString, ciphertext_out: &mut *mut TimelockCiphertext| unsafe {
+		timelock_encrypt_named(
+			name.as_ptr(),
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			secret_key.as_ptr(),
+			ciphertext_out,
+		)
+	};
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		assert_eq!(encrypt(&name, &mut ciphertext_ptr), TimelockResult::UnsupportedBeacon);
+		assert!(ciphertext_ptr.is_null());
+
+		// the public key and period are checked
+		let register = |public_key_hex: &CString, period, scheme| {
+			timelock_register_beacon(name.as_ptr(), public_key_hex.as_ptr(), 1000, period, scheme)
+		};
+		assert_eq!(register(&pk_hex, 0, TimelockBeacon::QuickNet), TimelockResult::InvalidInput);
+		assert_eq!(register(&pk_hex, 3, TimelockBeacon::Idn), TimelockResult::InvalidPublicKey);
+		let result = timelock_register_beacon(
+			ptr::null(),
+			pk_hex.as_ptr(),
+			1000,
+			3,
+			TimelockBeacon::QuickNet,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert_eq!(register(&pk_hex, 3, TimelockBeacon::QuickNet), TimelockResult::Success);
+
+		assert_eq!(encrypt(&name, &mut ciphertext_ptr), TimelockResult::Success);
+		let mut plaintext = vec![0u8; message.len()];
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_named(
+			name.as_ptr(),
+			ciphertext_ptr,
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+		timelock_ciphertext_free(ciphertext_ptr);
+
+		// rounds follow the registered schedule
+		let mut round = 0;
+		let result = timelock_beacon_round_after(name.as_ptr(), 1004, &mut round);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(round, 3);
+
+		// quicknet is known without registration
+		let quicknet = CString::new("quicknet").unwrap();
+		assert_eq!(encrypt(&quicknet, &mut ciphertext_ptr), TimelockResult::Success);
+		timelock_ciphertext_free(ciphertext_ptr);
+		let result = timelock_beacon_round_after(quicknet.as_ptr(), 1692803367, &mut round);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(round, 1);
+		let mainnet = CString::new("mainnet").unwrap();
+		assert_eq!(encrypt(&mainnet, &mut ciphertext_ptr), TimelockResult::UnsupportedBeacon);
+	}
+}
+
+/// The result of an asynchronous call: the code, the ciphertext or plaintext,
+/// and the error message. Each call is passed a boxed sender of its own, which
+/// the callback frees, since the pool thread may still be in `send` when the
+/// test returns.
+type AsyncResult = (TimelockResult, usize, Option<String>);
+
+unsafe extern "C" fn send_ciphertext(
+	result: TimelockResult,
+	ciphertext: *mut TimelockCiphertext,
+	error: *const c_char,
+	user_data: *mut c_void,
+) {
+	let sender = Box::from_raw(user_data as *mut mpsc::Sender<AsyncResult>);
+	let error = (!error.is_null()).then(|| CStr::from_ptr(error).to_str().unwrap().to_owned());
+	sender.send((result, ciphertext as usize, error)).unwrap();
+}
+
+unsafe extern "C" fn send_plaintext(
+	result: TimelockResult,
+	plaintext: *mut TimelockPlaintext,
+	error: *const c_char,
+	user_data: *mut c_void,
+) {
+	let sender = Box::from_raw(user_data as *mut mpsc::Sender<AsyncResult>);
+	let error = (!error.is_null()).then(|| CStr::from_ptr(error).to_str().unwrap().to_owned());
+	sender.send((result, plaintext as usize, error)).unwrap();
+}
+
+#[test]
+fn test_async_roundtrip() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, thread pool!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let sign = |identity: &[u8]| {
+		let signature = Identity::new(b"", identity).extract::<TinyBLS381>(beacon_secret).0;
+		let mut signature_bytes = Vec::new();
+		signature.serialize_compressed(&mut signature_bytes).unwrap();
+		CString::new(hex::encode(signature_bytes)).unwrap()
+	};
+
+	let (sender, receiver) = mpsc::channel::<AsyncResult>();
+	let user_data = || Box::into_raw(Box::new(sender.clone())) as *mut c_void;
+	unsafe {
+		let result = timelock_encrypt_async(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			Some(send_ciphertext),
+			user_data(),
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let (result, ciphertext, error) = receiver.recv().unwrap();
+		assert_eq!((result, error), (TimelockResult::Success, None));
+		let ciphertext = ciphertext as *mut TimelockCiphertext;
+
+		let result = timelock_decrypt_async(
+			ciphertext,
+			sign(&identity).as_ptr(),
+			Some(send_plaintext),
+			user_data(),
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let (result, plaintext, error) = receiver.recv().unwrap();
+		assert_eq!((result, error), (TimelockResult::Success, None));
+		let plaintext = plaintext as *mut TimelockPlaintext;
+		assert_eq!(slice::from_raw_parts((*plaintext).data, (*plaintext).len), message);
+		timelock_plaintext_free(plaintext);
+
+		// failures are reported to the callback, with their message
+		let result = timelock_decrypt_async(
+			ciphertext,
+			sign(&[5u8; 32]).as_ptr(),
+			Some(send_plaintext),
+			user_data(),
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let (result, plaintext, error) = receiver.recv().unwrap();
+		assert_eq!(result, TimelockResult::DecryptionFailed);
+		assert_eq!(plaintext, 0);
+		assert!(error.unwrap().starts_with("Timelock decryption failed"));
+
+		// invalid calls are not queued
+		let result =
+			timelock_decrypt_async(ciphertext, sign(&identity).as_ptr(), None, ptr::null_mut());
+		assert_eq!(result, TimelockResult::InvalidInput);
+		let result =
+			timelock_decrypt_async(ptr::null(), ptr::null(), Some(send_plaintext), ptr::null_mut());
+		assert_eq!(result, TimelockResult::InvalidInput);
+		timelock_ciphertext_free(ciphertext);
+	}
+	assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn test_async_jobs_survive_panics() {
+	let (sender, receiver) = mpsc::channel();
+
+	// a panic in the work of a job is passed on, as the callbacks receive it
+	let send = sender.clone();
+	let job = move || {
+		let (result, error) = crate::error::capture_error(|| panic!("in a job"));
+		send.send((result, error.map(|e| e.into_string().unwrap()))).unwrap();
+	};
+	assert_eq!(crate::task::spawn(Box::new(job)), Ok(()));
+	let (result, error) = receiver.recv().unwrap();
+	assert_eq!(result, TimelockResult::InternalPanic);
+	assert_eq!(error.as_deref(), Some("Internal panic: in a job"));
+
+	// a panic that escapes a job leaves every pool thread running
+	let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+	for _ in 0..workers {
+		assert_eq!(crate::task::spawn(Box::new(|| panic!("escaped"))), Ok(()));
+	}
+	let job = move || sender.send((TimelockResult::Success, None)).unwrap();
+	assert_eq!(crate::task::spawn(Box::new(job)), Ok(()));
+	let received = receiver.recv_timeout(std::time::Duration::from_secs(60));
+	assert_eq!(received, Ok((TimelockResult::Success, None)));
+}
+
+#[test]
+fn test_decrypt_alloc_roundtrip() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, allocated plaintexts!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let sign = |identity: &[u8]| {
+		let signature = Identity::new(b"", identity).extract::<TinyBLS381>(beacon_secret).0;
+		let mut signature_bytes = Vec::new();
+		signature.serialize_compressed(&mut signature_bytes).unwrap();
+		CString::new(hex::encode(signature_bytes)).unwrap()
+	};
+
+	let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+	let result = unsafe {
+		timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		)
+	};
+	assert_eq!(result, TimelockResult::Success);
+
+	unsafe {
+		let mut plaintext_ptr: *mut TimelockPlaintext = ptr::null_mut();
+		let result =
+			timelock_decrypt_alloc(ciphertext_ptr, sign(&identity).as_ptr(), &mut plaintext_ptr);
+		assert_eq!(result, TimelockResult::Success);
+		let plaintext = &*plaintext_ptr;
+		assert_eq!(slice::from_raw_parts(plaintext.data, plaintext.len), message);
+		timelock_plaintext_free(plaintext_ptr);
+
+		// the signature of another identity does not decrypt, and nothing is allocated
+		let mut plaintext_ptr: *mut TimelockPlaintext = ptr::null_mut();
+		let result =
+			timelock_decrypt_alloc(ciphertext_ptr, sign(&[5u8; 32]).as_ptr(), &mut plaintext_ptr);
+		assert_eq!(result, TimelockResult::DecryptionFailed);
+		assert!(plaintext_ptr.is_null());
+
+		let result =
+			timelock_decrypt_alloc(ciphertext_ptr, sign(&identity).as_ptr(), ptr::null_mut());
+		assert_eq!(result, TimelockResult::InvalidInput);
+
+		timelock_plaintext_free(ptr::null_mut());
+		timelock_ciphertext_free(ciphertext_ptr);
+	}
+}
+
+#[test]
+fn test_encrypt_deterministic() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, known answers!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	let encrypt = |pk_hex: &CString, seed: &[u8; 32]| unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt_deterministic(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			seed.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		ciphertext_ptr
+	};
+	let bytes = |ciphertext: *mut TimelockCiphertext| unsafe {
+		let bytes = slice::from_raw_parts((*ciphertext).data, (*ciphertext).len).to_vec();
+		timelock_ciphertext_free(ciphertext);
+		bytes
+	};
+
+	// the same seed gives the same ciphertext, whatever the RNG provider
+	let ciphertext = encrypt(&pk_hex, &[7u8; 32]);
+	unsafe {
+		let mut plaintext = vec![0u8; message.len()];
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt(
+			ciphertext,
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+	}
+	let first = bytes(ciphertext);
+	unsafe { timelock_set_rng_seed([9u8; 32].as_ptr(), 32) };
+	assert_eq!(bytes(encrypt(&pk_hex, &[7u8; 32])), first);
+	unsafe { timelock_use_os_rng() };
+	assert_ne!(bytes(encrypt(&pk_hex, &[8u8; 32])), first);
+
+	// a known answer for the QuickNet public key
+	let quicknet = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	let digest = Sha256::digest(bytes(encrypt(&quicknet, &[7u8; 32])));
+	assert_eq!(
+		hex::encode(digest),
+		"9a70f35dc09fe1662aeb13f56b71980568fc4c72abbb0ed1169f5883ae4ad3c1"
+	);
+
+	let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+	let result = unsafe {
+		timelock_encrypt_deterministic(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			ptr::null(),
+			&mut ciphertext_ptr,
+		)
+	};
+	assert_eq!(result, TimelockResult::InvalidInput);
+	assert!(ciphertext_ptr.is_null());
+}
+
+#[test]
+fn test_armored_roundtrip() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, armored ciphertexts!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	unsafe {
+		let mut armored_ptr: *mut c_char = ptr::null_mut();
+		let result = timelock_encrypt_armored(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut armored_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let armored = CStr::from_ptr(armored_ptr).to_str().unwrap().to_owned();
+		timelock_string_free(armored_ptr);
+		assert!(armored.starts_with("-----BEGIN TIMELOCK MESSAGE-----\n"));
+		assert!(armored.ends_with("-----END TIMELOCK MESSAGE-----\n"));
+
+		let decrypt = |armored: &str, plaintext: &mut [u8], plaintext_len: &mut usize| {
+			let armored = CString::new(armored).unwrap();
+			timelock_decrypt_armored(
+				armored.as_ptr(),
+				sig_hex.as_ptr(),
+				plaintext.as_mut_ptr(),
+				plaintext_len,
+			)
+		};
+
+		// as if it had been pasted from an email client
+		let mut plaintext = vec![0u8; message.len()];
+		let mut plaintext_len = plaintext.len();
+		let pasted = format!("\r\n  {}  ", armored.replace('\n', "\r\n"));
+		assert_eq!(decrypt(&pasted, &mut plaintext, &mut plaintext_len), TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+
+		let mut plaintext_len = 4;
+		let result = decrypt(&armored, &mut plaintext, &mut plaintext_len);
+		assert_eq!(result, TimelockResult::BufferTooSmall);
+		assert_eq!(plaintext_len, message.len());
+
+		let mut plaintext_len = plaintext.len();
+		let result =
+			decrypt(&armored.replace("BEGIN", "START"), &mut plaintext, &mut plaintext_len);
+		assert_eq!(result, TimelockResult::SerializationError);
+		let error = CStr::from_ptr(timelock_get_last_error()).to_str().unwrap();
+		assert!(error.starts_with("Invalid armored ciphertext"));
+
+		let result = timelock_decrypt_armored(
+			ptr::null(),
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		timelock_string_free(ptr::null_mut());
+	}
+}
+
+#[test]
+fn test_decrypt_early() {
+	let message = b"Hello before the round!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+
+	let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+	unsafe {
+		let result = timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		let mut plaintext = [0u8; 64];
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			secret_key.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+
+		// the required length is reported for a small buffer
+		let mut plaintext_len = 4;
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			secret_key.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::BufferTooSmall);
+		assert_eq!(plaintext_len, message.len());
+
+		// another key does not decrypt
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			[3u8; 32].as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::DecryptionFailed);
+
+		let result = timelock_decrypt_early(
+			ciphertext_ptr,
+			ptr::null(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		timelock_ciphertext_free(ciphertext_ptr);
+	}
+}
+
+#[test]
+fn test_raw_keys_and_signatures() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, raw bytes!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let mut public_key = Vec::new();
+	(<TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret)
+		.serialize_compressed(&mut public_key)
+		.unwrap();
+	assert_eq!(public_key.len(), 96);
+	let mut signature = Vec::new();
+	Identity::new(b"", &identity)
+		.extract::<TinyBLS381>(beacon_secret)
+		.0
+		.serialize_compressed(&mut signature)
+		.unwrap();
+	assert_eq!(signature.len(), 48);
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt_raw(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			public_key.as_ptr(),
+			public_key.len(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		// a raw signature decrypts, and so does its hex encoding
+		let mut plaintext = [0u8; 64];
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_raw(
+			ciphertext_ptr,
+			signature.as_ptr(),
+			signature.len(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+		let sig_hex = CString::new(hex::encode(&signature)).unwrap();
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt(
+			ciphertext_ptr,
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		// truncated keys and signatures are rejected
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_raw(
+			ciphertext_ptr,
+			signature.as_ptr(),
+			signature.len() - 1,
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::InvalidSignature);
+		timelock_ciphertext_free(ciphertext_ptr);
+
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt_raw(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			public_key.as_ptr(),
+			public_key.len() - 1,
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::InvalidPublicKey);
+		let result = timelock_encrypt_raw(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			ptr::null(),
+			0,
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert!(ciphertext_ptr.is_null());
+	}
+}
+
+#[test]
+fn test_secure_buffer() {
+	use ark_ec::PrimeGroup;
+
+	let message = [7u8; 100];
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let mut public_key = Vec::new();
+	(<TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret)
+		.serialize_compressed(&mut public_key)
+		.unwrap();
+	let pk_hex = CString::new(hex::encode(public_key)).unwrap();
+	let sign = |identity: &[u8]| {
+		let mut signature = Vec::new();
+		Identity::new(b"", identity)
+			.extract::<TinyBLS381>(beacon_secret)
+			.0
+			.serialize_compressed(&mut signature)
+			.unwrap();
+		CString::new(hex::encode(signature)).unwrap()
+	};
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		// the buffer grows to fit the plaintext
+		let mut buffer: *mut TimelockSecureBuffer = ptr::null_mut();
+		assert_eq!(timelock_secure_buffer_new(16, &mut buffer), TimelockResult::Success);
+		assert_eq!(timelock_secure_buffer_len(buffer), 0);
+		let result = timelock_decrypt_secure(ciphertext_ptr, sign(&identity).as_ptr(), buffer);
+		assert_eq!(result, TimelockResult::Success);
+		let contents = slice::from_raw_parts(
+			timelock_secure_buffer_data(buffer),
+			timelock_secure_buffer_len(buffer),
+		);
+		assert_eq!(contents, message);
+
+		// and is emptied and zeroed when decryption fails
+		let data = timelock_secure_buffer_data(buffer);
+		let result = timelock_decrypt_secure(ciphertext_ptr, sign(&[5u8; 32]).as_ptr(), buffer);
+		assert_eq!(result, TimelockResult::DecryptionFailed);
+		assert_eq!(timelock_secure_buffer_len(buffer), 0);
+		assert_eq!(timelock_secure_buffer_data(buffer), data);
+		assert!(slice::from_raw_parts(data, message.len()).iter().all(|&b| b == 0));
+
+		let result = timelock_decrypt_early_secure(ciphertext_ptr, secret_key.as_ptr(), buffer);
+		assert_eq!(result, TimelockResult::Success);
+		let contents = slice::from_raw_parts(
+			timelock_secure_buffer_data(buffer),
+			timelock_secure_buffer_len(buffer),
+		);
+		assert_eq!(contents, message);
+
+		let result = timelock_decrypt_secure(ciphertext_ptr, ptr::null(), buffer);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		timelock_secure_buffer_free(buffer);
+		timelock_secure_buffer_free(ptr::null_mut());
+		assert!(timelock_secure_buffer_data(ptr::null()).is_null());
+		timelock_ciphertext_free(ciphertext_ptr);
+	}
+}
+
+#[test]
+fn test_decrypt_batch() {
+	use ark_ec::PrimeGroup;
+
+	let identity = [4u8; 32];
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	let encrypt = |message: &[u8], identity: &[u8; 32]| unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			[2u8; 32].as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		ciphertext_ptr as *const TimelockCiphertext
+	};
+	let messages: [&[u8]; 3] = [b"first", b"", b"third message"];
+	let mut garbage = [7u8; 64];
+	let garbage = TimelockCiphertext { data: garbage.as_mut_ptr(), len: garbage.len() };
+	let ciphertexts = [
+		encrypt(messages[0], &identity),
+		encrypt(messages[1], &identity),
+		encrypt(b"another round", &[5u8; 32]),
+		ptr::null(),
+		&garbage as *const TimelockCiphertext,
+		encrypt(messages[2], &identity),
+	];
+
+	unsafe {
+		let mut results: Vec<TimelockBatchResult> = Vec::with_capacity(ciphertexts.len());
+		let result = timelock_decrypt_batch(
+			ciphertexts.as_ptr(),
+			ciphertexts.len(),
+			sig_hex.as_ptr(),
+			results.as_mut_ptr(),
+		);
+		results.set_len(ciphertexts.len());
+		assert_eq!(result, TimelockResult::DecryptionFailed);
+		assert!(!timelock_get_last_error().is_null());
+
+		let expected = [
+			TimelockResult::Success,
+			TimelockResult::Success,
+			TimelockResult::DecryptionFailed,
+			TimelockResult::InvalidInput,
+			TimelockResult::SerializationError,
+			TimelockResult::Success,
+		];
+		let mut decrypted = messages.iter();
+		for (result, expected) in results.iter().zip(expected) {
+			assert_eq!(result.result, expected);
+			if expected == TimelockResult::Success {
+				let plaintext = &*result.plaintext;
+				let bytes = slice::from_raw_parts(plaintext.data, plaintext.len);
+				assert_eq!(bytes, *decrypted.next().unwrap());
+				timelock_plaintext_free(result.plaintext);
+			} else {
+				assert!(result.plaintext.is_null());
+			}
+		}
+
+		// a batch of ciphertexts that all decrypt succeeds
+		let mut results: Vec<TimelockBatchResult> = Vec::with_capacity(2);
+		let result = timelock_decrypt_batch(
+			ciphertexts[..2].as_ptr(),
+			2,
+			sig_hex.as_ptr(),
+			results.as_mut_ptr(),
+		);
+		results.set_len(2);
+		assert_eq!(result, TimelockResult::Success);
+		results.iter().for_each(|result| timelock_plaintext_free(result.plaintext));
+
+		let result = timelock_decrypt_batch(ptr::null(), 0, sig_hex.as_ptr(), ptr::null_mut());
+		assert_eq!(result, TimelockResult::Success);
+
+		for i in [0, 1, 2, 5] {
+			timelock_ciphertext_free(ciphertexts[i] as *mut TimelockCiphertext);
+		}
+	}
+}
+
+/// Encrypt with `timelock_encrypt_ex` for a local beacon of the engine `E`, and
+/// decrypt with `timelock_decrypt_ex`
+fn beacon_roundtrip<E: EngineBLS>(beacon: TimelockBeacon, other: TimelockBeacon) {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, beacons!";
+	let identity = [6u8; 32];
+	let secret_key = [2u8; 32];
+
+	let beacon_secret = E::Scalar::from(42u64);
+	let mut public_key_bytes = Vec::new();
+	(E::PublicKeyGroup::generator() * beacon_secret)
+		.serialize_compressed(&mut public_key_bytes)
+		.unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let mut signature_bytes = Vec::new();
+	Identity::new(b"", &identity)
+		.extract::<E>(beacon_secret)
+		.0
+		.serialize_compressed(&mut signature_bytes)
+		.unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt_ex(
+			beacon,
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+
+		let mut plaintext = [0u8; 64];
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_ex(
+			beacon,
+			ciphertext_ptr,
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+
+		// the signature is not a point of the other beacon's engine
+		let mut plaintext_len = plaintext.len();
+		let result = timelock_decrypt_ex(
+			other,
+			ciphertext_ptr,
+			sig_hex.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_ne!(result, TimelockResult::Success);
+		timelock_ciphertext_free(ciphertext_ptr);
+	}
+}
+
+#[test]
+fn test_beacon_selection() {
+	beacon_roundtrip::<TinyBLS381>(TimelockBeacon::QuickNet, TimelockBeacon::Idn);
+	beacon_roundtrip::<TinyBLS377>(TimelockBeacon::Idn, TimelockBeacon::QuickNet);
+}
+
+/// A write callback appending to the `Vec<u8>` at `user_data`
+unsafe extern "C" fn collect(data: *const c_uchar, len: usize, user_data: *mut c_void) -> c_int {
+	(*(user_data as *mut Vec<u8>)).extend_from_slice(slice::from_raw_parts(data, len));
+	0
+}
+
+/// Decrypt `ciphertext` fed in pieces of `piece` bytes with a decryption stream
+unsafe fn decrypt_stream(
+	ciphertext: &[u8],
+	signature_hex: &CString,
+	piece: usize,
+) -> Result<Vec<u8>, TimelockResult> {
+	let mut plaintext = Vec::new();
+	let mut stream: *mut TimelockDecryptStream = ptr::null_mut();
+	let result = timelock_decrypt_stream_begin(
+		TimelockBeacon::QuickNet,
+		signature_hex.as_ptr(),
+		Some(collect),
+		&mut plaintext as *mut Vec<u8> as *mut c_void,
+		&mut stream,
+	);
+	assert_eq!(result, TimelockResult::Success);
+	for chunk in ciphertext.chunks(piece) {
+		let result = timelock_decrypt_stream_update(stream, chunk.as_ptr(), chunk.len());
+		if result != TimelockResult::Success {
+			timelock_decrypt_stream_free(stream);
+			return Err(result);
+		}
+	}
+	match timelock_decrypt_stream_finish(stream) {
+		TimelockResult::Success => Ok(plaintext),
+		result => Err(result),
+	}
+}
+
+#[test]
+fn test_stream_roundtrip() {
+	use ark_ec::PrimeGroup;
+	use timelock::tlock::TldReader;
+
+	let message: Vec<u8> = (0..150_000u32).map(|i| (i % 251) as u8).collect();
+	let identity = [7u8; 32];
+
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let mut public_key_bytes = Vec::new();
+	(<TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret)
+		.serialize_compressed(&mut public_key_bytes)
+		.unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	unsafe {
+		let mut ciphertext = Vec::new();
+		let mut stream: *mut TimelockEncryptStream = ptr::null_mut();
+		let result = timelock_encrypt_stream_begin(
+			TimelockBeacon::QuickNet,
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			Some(collect),
+			&mut ciphertext as *mut Vec<u8> as *mut c_void,
+			&mut stream,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		for piece in message.chunks(1000) {
+			let result = timelock_encrypt_stream_update(stream, piece.as_ptr(), piece.len());
+			assert_eq!(result, TimelockResult::Success);
+		}
+		assert_eq!(timelock_encrypt_stream_finish(stream), TimelockResult::Success);
+
+		// the core reader decrypts the stream
+		let mut reader =
+			TldReader::new::<TinyBLS381, AESGCMBlockCipherProvider>(&ciphertext[..], signature)
+				.unwrap();
+		let mut plaintext = Vec::new();
+		std::io::Read::read_to_end(&mut reader, &mut plaintext).unwrap();
+		assert_eq!(plaintext, message);
+
+		// and so does the decryption stream, whatever the size of the pieces
+		for piece in [1, 999, 1 << 20] {
+			assert_eq!(decrypt_stream(&ciphertext, &sig_hex, piece).as_ref(), Ok(&message));
+		}
+
+		// a modified byte fails to authenticate
+		let mut tampered = ciphertext.clone();
+		let last = tampered.len() - 1;
+		tampered[last] ^= 1;
+		assert_eq!(
+			decrypt_stream(&tampered, &sig_hex, 4096),
+			Err(TimelockResult::DecryptionFailed)
+		);
+		// and a truncated stream is rejected
+		assert_eq!(
+			decrypt_stream(&ciphertext[..ciphertext.len() - 1], &sig_hex, 4096),
+			Err(TimelockResult::DecryptionFailed)
+		);
+		assert_eq!(
+			decrypt_stream(&ciphertext[..16], &sig_hex, 4096),
+			Err(TimelockResult::DecryptionFailed)
+		);
+	}
+}
+
+#[test]
+fn test_stream_write_failure() {
+	unsafe extern "C" fn refuse(_: *const c_uchar, _: usize, _: *mut c_void) -> c_int {
+		1
+	}
+
+	let identity = [7u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	unsafe {
+		let mut stream: *mut TimelockEncryptStream = ptr::null_mut();
+		let result = timelock_encrypt_stream_begin(
+			TimelockBeacon::QuickNet,
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			Some(refuse),
+			ptr::null_mut(),
+			&mut stream,
+		);
+		assert_eq!(result, TimelockResult::WriteFailed);
+		assert!(stream.is_null());
+
+		let result = timelock_encrypt_stream_begin(
+			TimelockBeacon::QuickNet,
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			None,
+			ptr::null_mut(),
+			&mut stream,
+		);
+		assert_eq!(result, TimelockResult::InvalidInput);
+	}
+}
+
+#[test]
+fn test_context_errors() {
+	unsafe {
+		let context = timelock_context_new();
+		assert!(timelock_context_get_last_error(context).is_null());
+
+		// the error of a failed call is kept on its context, not the thread
+		timelock_create_drand_identity(1000, ptr::null_mut(), 32);
+		let thread_error = CStr::from_ptr(timelock_get_last_error()).to_owned();
+		let result = timelock_estimate_ciphertext_size_ctx(context, 10, ptr::null_mut());
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert_eq!(CStr::from_ptr(timelock_get_last_error()), thread_error.as_c_str());
+		let error = CStr::from_ptr(timelock_context_get_last_error(context));
+		assert!(error.to_str().unwrap().contains("estimated size"));
+
+		// and read back on another thread
+		let address = context as usize;
+		thread::spawn(move || {
+			let context = address as *mut TimelockContext;
+			assert!(!timelock_context_get_last_error(context).is_null());
+			let mut identity = [0u8; 32];
+			let result =
+				timelock_create_drand_identity_ctx(context, 1000, identity.as_mut_ptr(), 32);
+			assert_eq!(result, TimelockResult::Success);
+			assert!(timelock_context_get_last_error(context).is_null());
+		})
+		.join()
+		.unwrap();
+
+		let mut size = 0;
+		let result = timelock_estimate_ciphertext_size_ctx(ptr::null_mut(), 10, &mut size);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		timelock_context_free(context);
+		timelock_context_free(ptr::null_mut());
+	}
+}
+
+#[test]
+fn test_error_info() {
+	let message = |info: &TimelockErrorInfo| unsafe {
+		CStr::from_ptr(info.message.as_ptr()).to_str().unwrap().to_owned()
+	};
+	let mut info = TimelockErrorInfo { code: TimelockResult::Success, message: [1; 256] };
+	let mut identity = [0u8; 32];
+	unsafe {
+		// the error of a failed call is written to the info, not the thread
+		timelock_create_drand_identity(1000, ptr::null_mut(), 32);
+		let thread_error = CStr::from_ptr(timelock_get_last_error()).to_owned();
+		let result = timelock_create_drand_identity_e(0, identity.as_mut_ptr(), 32, &mut info);
+		assert_eq!(result, TimelockResult::InvalidRound);
+		assert_eq!(info.code, TimelockResult::InvalidRound);
+		assert_eq!(message(&info), "Invalid round number: beacon rounds start at 1");
+		assert_eq!(CStr::from_ptr(timelock_get_last_error()), thread_error.as_c_str());
+
+		// success leaves an empty message, and the info is optional
+		let result = timelock_create_drand_identity_e(1000, identity.as_mut_ptr(), 32, &mut info);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(info.code, TimelockResult::Success);
+		assert_eq!(message(&info), "");
+		let result =
+			timelock_create_drand_identity_e(0, identity.as_mut_ptr(), 32, ptr::null_mut());
+		assert_eq!(result, TimelockResult::InvalidRound);
+		assert_eq!(CStr::from_ptr(timelock_get_last_error()), thread_error.as_c_str());
+	}
+
+	// long messages are truncated at a character boundary
+	let long = "é".repeat(TIMELOCK_ERROR_MESSAGE_SIZE);
+	let result = unsafe {
+		crate::error::with_error_info(&mut info, || {
+			set_last_error(&long);
+			TimelockResult::DecryptionFailed
+		})
+	};
+	assert_eq!(result, TimelockResult::DecryptionFailed);
+	assert_eq!(message(&info), "é".repeat(TIMELOCK_ERROR_MESSAGE_SIZE / 2 - 1));
+}
+
+#[test]
+fn test_context_rng() {
+	let message = b"Hello, contexts!";
+	let identity = [6u8; 32];
+	let secret_key = [2u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	let seed = [8u8; 32];
+
+	let encrypt = |context: *mut TimelockContext| unsafe {
+		let mut ciphertext_ptr: *mut TimelockCiphertext = ptr::null_mut();
+		let result = timelock_encrypt_ctx(
+			context,
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			&mut ciphertext_ptr,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		let ciphertext = &*ciphertext_ptr;
+		let bytes = slice::from_raw_parts(ciphertext.data, ciphertext.len).to_vec();
+		timelock_ciphertext_free(ciphertext_ptr);
+		bytes
+	};
+
+	unsafe {
+		// contexts seeded alike encrypt alike, whatever the thread's provider
+		let first = timelock_context_new();
+		let second = timelock_context_new();
+		assert_eq!(timelock_set_rng_seed_ctx(first, seed.as_ptr(), 32), TimelockResult::Success);
+		assert_eq!(timelock_set_rng_seed_ctx(second, seed.as_ptr(), 32), TimelockResult::Success);
+		timelock_use_os_rng();
+		assert_eq!(encrypt(first), encrypt(second));
+
+		// the OS RNG of a context does not repeat
+		assert_eq!(timelock_use_os_rng_ctx(first), TimelockResult::Success);
+		assert_ne!(encrypt(first), encrypt(first));
+
+		let result = timelock_set_rng_seed_ctx(first, seed.as_ptr(), 16);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert!(!timelock_context_get_last_error(first).is_null());
+
+		timelock_context_free(first);
+		timelock_context_free(second);
+	}
+}
+
+#[test]
+fn test_validate_public_key() {
+	use ark_ec::PrimeGroup;
+	use ark_ff::Zero;
+
+	let hex_of = |bytes: &[u8]| CString::new(hex::encode(bytes)).unwrap();
+	let mut idn_key = Vec::new();
+	<TinyBLS377 as EngineBLS>::PublicKeyGroup::generator()
+		.serialize_compressed(&mut idn_key)
+		.unwrap();
+	let mut identity = Vec::new();
+	<TinyBLS381 as EngineBLS>::PublicKeyGroup::zero()
+		.serialize_compressed(&mut identity)
+		.unwrap();
+	let quicknet = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	let key = hex::decode(DRAND_QUICKNET_PK_HEX).unwrap();
+
+	unsafe {
+		assert_eq!(timelock_validate_public_key(quicknet.as_ptr()), TimelockResult::Success);
+		assert!(timelock_get_last_error().is_null());
+		let result =
+			timelock_validate_public_key_ex(TimelockBeacon::Idn, hex_of(&idn_key).as_ptr());
+		assert_eq!(result, TimelockResult::Success);
+
+		// a key of the other beacon is rejected
+		let result = timelock_validate_public_key_ex(TimelockBeacon::Idn, quicknet.as_ptr());
+		assert_eq!(result, TimelockResult::InvalidPublicKey);
+		assert_eq!(
+			timelock_validate_public_key(hex_of(&idn_key).as_ptr()),
+			TimelockResult::InvalidPublicKey
+		);
+
+		let not_hex = CString::new("not hex").unwrap();
+		assert_eq!(
+			timelock_validate_public_key(not_hex.as_ptr()),
+			TimelockResult::InvalidPublicKey
+		);
+		assert!(!timelock_get_last_error().is_null());
+		assert_eq!(
+			timelock_validate_public_key(hex_of(&key[..95]).as_ptr()),
+			TimelockResult::InvalidPublicKey
+		);
+		assert_eq!(
+			timelock_validate_public_key(hex_of(&identity).as_ptr()),
+			TimelockResult::InvalidPoint
+		);
+		assert_eq!(timelock_validate_public_key(ptr::null()), TimelockResult::InvalidInput);
+	}
+}
+
+#[test]
+fn test_identity_points_are_rejected() {
+	use ark_ff::Zero;
+
+	let message = b"strict";
+	let identity = [3u8; 32];
+	let secret_key = [2u8; 32];
+	let hex_of = |point: &dyn Fn(&mut Vec<u8>)| {
+		let mut bytes = Vec::new();
+		point(&mut bytes);
+		CString::new(hex::encode(bytes)).unwrap()
+	};
+	let pk_hex = hex_of(&|bytes| {
+		<TinyBLS381 as EngineBLS>::PublicKeyGroup::zero()
+			.serialize_compressed(bytes)
+			.unwrap()
+	});
+	let sig_hex = hex_of(&|bytes| {
+		<TinyBLS381 as EngineBLS>::SignatureGroup::zero()
+			.serialize_compressed(bytes)
+			.unwrap()
+	});
+
+	let mut buffer = [0u8; 512];
+	let mut ciphertext_len = buffer.len();
+	let result = unsafe {
+		timelock_encrypt_into(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			buffer.as_mut_ptr(),
+			&mut ciphertext_len,
+		)
+	};
+	assert_eq!(result, TimelockResult::InvalidPoint);
+
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+	let mut ciphertext_len = buffer.len();
+	let result = unsafe {
+		timelock_encrypt_into(
+			message.as_ptr(),
+			message.len(),
+			identity.as_ptr(),
+			identity.len(),
+			pk_hex.as_ptr(),
+			secret_key.as_ptr(),
+			buffer.as_mut_ptr(),
+			&mut ciphertext_len,
+		)
+	};
+	assert_eq!(result, TimelockResult::Success);
+
+	let ciphertext = TimelockCiphertext { data: buffer.as_mut_ptr(), len: ciphertext_len };
+	let mut plaintext = [0u8; 64];
+	let mut plaintext_len = plaintext.len();
+	let result = unsafe {
+		timelock_decrypt(&ciphertext, sig_hex.as_ptr(), plaintext.as_mut_ptr(), &mut plaintext_len)
+	};
+	assert_eq!(result, TimelockResult::InvalidPoint);
+}
+
+#[test]
+fn test_rng_providers() {
+	let message = b"Known answer";
+	let identity = [4u8; 32];
+	let secret_key = [5u8; 32];
+	let pk_hex = CString::new(DRAND_QUICKNET_PK_HEX).unwrap();
+
+	let encrypt = || {
+		let mut buffer = [0u8; 512];
+		let mut ciphertext_len = buffer.len();
+		let result = unsafe {
+			timelock_encrypt_into(
+				message.as_ptr(),
+				message.len(),
+				identity.as_ptr(),
+				identity.len(),
+				pk_hex.as_ptr(),
+				secret_key.as_ptr(),
+				buffer.as_mut_ptr(),
+				&mut ciphertext_len,
+			)
+		};
+		(result, buffer[..ciphertext_len].to_vec())
+	};
+
+	// A fixed seed makes encryption reproducible
+	let seed = [6u8; 32];
+	assert_eq!(
+		unsafe { timelock_set_rng_seed(seed.as_ptr(), seed.len()) },
+		TimelockResult::Success
+	);
+	let (result, first) = encrypt();
+	assert_eq!(result, TimelockResult::Success);
+	assert_ne!(encrypt().1, first);
+	unsafe { timelock_set_rng_seed(seed.as_ptr(), seed.len()) };
+	assert_eq!(encrypt().1, first);
+	assert_eq!(unsafe { timelock_set_rng_seed(seed.as_ptr(), 31) }, TimelockResult::InvalidInput);
+
+	// A caller-supplied CSPRNG is asked for the seed of each encryption
+	unsafe extern "C" fn fill(out: *mut c_uchar, len: usize, user_data: *mut c_void) -> c_int {
+		let calls = &mut *(user_data as *mut usize);
+		*calls += 1;
+		if *calls > 2 {
+			return 1;
+		}
+		ptr::write_bytes(out, 7, len);
+		0
+	}
+	let mut calls = 0usize;
+	let user_data = &mut calls as *mut usize as *mut c_void;
+	assert_eq!(
+		unsafe { timelock_set_rng_callback(Some(fill), user_data) },
+		TimelockResult::Success
+	);
+	let (result, with_callback) = encrypt();
+	assert_eq!(result, TimelockResult::Success);
+	assert_eq!(encrypt().1, with_callback);
+	assert_eq!(encrypt().0, TimelockResult::EncryptionFailed);
+	assert_eq!(calls, 3);
+	assert_eq!(
+		unsafe { timelock_set_rng_callback(None, ptr::null_mut()) },
+		TimelockResult::InvalidInput
+	);
+
+	unsafe { timelock_use_os_rng() };
+	let (result, from_os) = encrypt();
+	assert_eq!(result, TimelockResult::Success);
+	assert_ne!(from_os, with_callback);
+	assert_ne!(encrypt().1, from_os);
+}
+
+#[test]
+fn test_timelock_errors_map_to_result_codes() {
+	let code = |e: TlockError| TimelockResult::from(&TimelockError::from(e));
+	assert_eq!(code(TlockError::InvalidPoint), TimelockResult::InvalidPoint);
+	assert_eq!(code(TlockError::BufferTooSmall), TimelockResult::BufferTooSmall);
+	assert_eq!(code(TlockError::InvalidFormat), TimelockResult::SerializationError);
+	assert_eq!(code(TlockError::InvalidSignature), TimelockResult::DecryptionFailed);
+	assert_eq!(code(TlockError::MessageEncryptionError), TimelockResult::EncryptionFailed);
+}
+
+#[test]
+fn test_self_test() {
+	unsafe {
+		let mut report = TimelockSelfTestReport::default();
+		assert_eq!(timelock_self_test(&mut report), TimelockResult::Success);
+		assert_eq!(report, TimelockSelfTestReport { checks: 4, failures: 0 });
+		assert!(timelock_get_last_error().is_null());
+		assert_eq!(timelock_self_test(ptr::null_mut()), TimelockResult::Success);
+	}
+}
+
+#[test]
+fn test_multi_round() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, grace window!";
+	let rounds = [1000u64, 1001, 1002];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signatures are known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let sign = |round: u64| {
+		let mut identity = [0u8; 32];
+		unsafe { timelock_create_drand_identity(round, identity.as_mut_ptr(), identity.len()) };
+		let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+		let mut signature_bytes = Vec::new();
+		signature.serialize_compressed(&mut signature_bytes).unwrap();
+		CString::new(hex::encode(signature_bytes)).unwrap()
+	};
+
+	unsafe {
+		let encrypt = |cipher, rounds: &[u64], ciphertext: &mut *mut TimelockCiphertext| {
+			timelock_encrypt_multi(
+				cipher,
+				message.as_ptr(),
+				message.len(),
+				rounds.as_ptr(),
+				rounds.len(),
+				pk_hex.as_ptr(),
+				secret_key.as_ptr(),
+				ciphertext,
+			)
+		};
+		for cipher in [TimelockCipher::AesGcm, TimelockCipher::ChaCha20Poly1305] {
+			let mut ciphertext = ptr::null_mut();
+			assert_eq!(encrypt(cipher, &rounds, &mut ciphertext), TimelockResult::Success);
+
+			let mut plaintext_len = 0;
+			let result = timelock_decrypt_multi(
+				ciphertext,
+				ptr::null(),
+				ptr::null_mut(),
+				&mut plaintext_len,
+			);
+			assert_eq!(result, TimelockResult::Success);
+			assert_eq!(plaintext_len, message.len());
+
+			// the signature of any one round decrypts
+			for round in rounds {
+				let mut plaintext = vec![0u8; message.len()];
+				let mut plaintext_len = plaintext.len();
+				let result = timelock_decrypt_multi(
+					ciphertext,
+					sign(round).as_ptr(),
+					plaintext.as_mut_ptr(),
+					&mut plaintext_len,
+				);
+				assert_eq!(result, TimelockResult::Success);
+				assert_eq!(&plaintext[..plaintext_len], message);
+			}
+
+			let mut plaintext = vec![0u8; message.len()];
+			let mut plaintext_len = plaintext.len();
+			let result = timelock_decrypt_multi(
+				ciphertext,
+				sign(1003).as_ptr(),
+				plaintext.as_mut_ptr(),
+				&mut plaintext_len,
+			);
+			assert_eq!(result, TimelockResult::DecryptionFailed);
+
+			let mut plaintext_len = 4;
+			let result = timelock_decrypt_multi(
+				ciphertext,
+				sign(1001).as_ptr(),
+				plaintext.as_mut_ptr(),
+				&mut plaintext_len,
+			);
+			assert_eq!(result, TimelockResult::BufferTooSmall);
+			assert_eq!(plaintext_len, message.len());
+			timelock_ciphertext_free(ciphertext);
+		}
+
+		let aes = TimelockCipher::AesGcm;
+		let mut ciphertext = ptr::null_mut();
+		assert_eq!(encrypt(aes, &[1000, 0], &mut ciphertext), TimelockResult::InvalidRound);
+		assert_eq!(encrypt(aes, &[], &mut ciphertext), TimelockResult::InvalidInput);
+		assert_eq!(encrypt(aes, &[1; 256], &mut ciphertext), TimelockResult::InvalidInput);
+		assert!(ciphertext.is_null());
+	}
+}
+
+#[test]
+fn test_cipher_selection() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, ChaCha!";
+	let identity = [4u8; 32];
+	let secret_key = [2u8; 32];
+
+	// A local beacon, so the round signature is known
+	let beacon_secret = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * beacon_secret;
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+	let signature = Identity::new(b"", &identity).extract::<TinyBLS381>(beacon_secret).0;
+	let mut signature_bytes = Vec::new();
+	signature.serialize_compressed(&mut signature_bytes).unwrap();
+	let sig_hex = CString::new(hex::encode(signature_bytes)).unwrap();
+
+	unsafe {
+		let encrypt = |cipher| {
+			let mut ciphertext = ptr::null_mut();
+			let result = timelock_encrypt_with_cipher(
+				cipher,
+				message.as_ptr(),
+				message.len(),
+				identity.as_ptr(),
+				identity.len(),
+				pk_hex.as_ptr(),
+				secret_key.as_ptr(),
+				&mut ciphertext,
+			);
+			assert_eq!(result, TimelockResult::Success);
+			ciphertext as *const TimelockCiphertext
+		};
+		let aes_gcm = encrypt(TimelockCipher::AesGcm);
+		let chacha = encrypt(TimelockCipher::ChaCha20Poly1305);
+
+		// the cipher is recorded in the ciphertext
+		let suite = |ct: *const TimelockCiphertext| {
+			let data = slice::from_raw_parts((*ct).data, (*ct).len);
+			data[data.len() - 8..].to_vec()
+		};
+		assert_eq!(suite(aes_gcm), b"AES_GCM_");
+		assert_eq!(suite(chacha), b"CHACHA20");
+
+		let mut plaintext_len = 0;
+		let result = timelock_decrypt(chacha, ptr::null(), ptr::null_mut(), &mut plaintext_len);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(plaintext_len, message.len());
+
+		let mut plaintext = vec![0u8; message.len()];
+		let result =
+			timelock_decrypt(chacha, sig_hex.as_ptr(), plaintext.as_mut_ptr(), &mut plaintext_len);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+
+		let mut plaintext = vec![0u8; message.len()];
+		let result = timelock_decrypt_early(
+			chacha,
+			secret_key.as_ptr(),
+			plaintext.as_mut_ptr(),
+			&mut plaintext_len,
+		);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(&plaintext[..plaintext_len], message);
+
+		// a batch may mix ciphers
+		let ciphertexts = [chacha, aes_gcm, chacha];
+		let mut results: Vec<TimelockBatchResult> = Vec::with_capacity(ciphertexts.len());
+		let result = timelock_decrypt_batch(
+			ciphertexts.as_ptr(),
+			ciphertexts.len(),
+			sig_hex.as_ptr(),
+			results.as_mut_ptr(),
+		);
+		results.set_len(ciphertexts.len());
+		assert_eq!(result, TimelockResult::Success);
+		for result in results {
+			let plaintext = &*result.plaintext;
+			assert_eq!(slice::from_raw_parts(plaintext.data, plaintext.len), message);
+			timelock_plaintext_free(result.plaintext);
+		}
+
+		timelock_ciphertext_free(aes_gcm as *mut TimelockCiphertext);
+		timelock_ciphertext_free(chacha as *mut TimelockCiphertext);
+	}
+}
+
+#[test]
+fn test_encrypt_options() {
+	use ark_ec::PrimeGroup;
+
+	let message = b"Hello, options!";
+	let identity = [5u8; 32];
+	let secret_key = [3u8; 32];
+
+	let public_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() *
+		<TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+	let mut public_key_bytes = Vec::new();
+	public_key.serialize_compressed(&mut public_key_bytes).unwrap();
+	let pk_hex = CString::new(hex::encode(public_key_bytes)).unwrap();
+
+	unsafe {
+		let encrypt = |options: *const TimelockEncryptOptions| {
+			let mut ciphertext = ptr::null_mut();
+			let result = timelock_encrypt_opts(
+				options,
+				message.as_ptr(),
+				message.len(),
+				identity.as_ptr(),
+				identity.len(),
+				pk_hex.as_ptr(),
+				secret_key.as_ptr(),
+				&mut ciphertext,
+			);
+			(result, ciphertext)
+		};
+		let suite = |ct: *mut TimelockCiphertext| {
+			let data = slice::from_raw_parts((*ct).data, (*ct).len);
+			let suite = data[data.len() - 8..].to_vec();
+			let mut plaintext = vec![0u8; message.len()];
+			let mut plaintext_len = plaintext.len();
+			let result = timelock_decrypt_early(
+				ct,
+				secret_key.as_ptr(),
+				plaintext.as_mut_ptr(),
+				&mut plaintext_len,
+			);
+			assert_eq!(result, TimelockResult::Success);
+			assert_eq!(&plaintext[..plaintext_len], message);
+			timelock_ciphertext_free(ct);
+			suite
+		};
+
+		// null options are the defaults
+		let (result, ciphertext) = encrypt(ptr::null());
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(suite(ciphertext), b"AES_GCM_");
+
+		let mut options: TimelockEncryptOptions = std::mem::zeroed();
+		options.struct_size = std::mem::size_of::<TimelockEncryptOptions>();
+		options.cipher = TimelockCipher::ChaCha20Poly1305;
+		let (result, ciphertext) = encrypt(&options);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(suite(ciphertext), b"CHACHA20");
+
+		// the options past the struct of an older caller are defaulted
+		options.padding = 16;
+		options.struct_size = std::mem::offset_of!(TimelockEncryptOptions, aad);
+		let (result, ciphertext) = encrypt(&options);
+		assert_eq!(result, TimelockResult::Success);
+		assert_eq!(suite(ciphertext), b"CHACHA20");
+
+		options.struct_size = std::mem::size_of::<TimelockEncryptOptions>();
+		let (result, ciphertext) = encrypt(&options);
+		assert_eq!(result, TimelockResult::InvalidInput);
+		assert!(ciphertext.is_null());
+
+		options.padding = 0;
+		options.struct_size = 0;
+		let (result, _) = encrypt(&options);
+		assert_eq!(result, TimelockResult::InvalidInput);
+	}
+}
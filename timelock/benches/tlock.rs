@@ -92,5 +92,28 @@ fn tlock_split(c: &mut Criterion) {
 	decrypt_group.finish();
 }
 
-criterion_group!(benches, tlock_split);
+/// Benchmarks repeated encryption to one identity with and without an
+/// [`EncryptionContext`]
+fn tlock_context(c: &mut Criterion) {
+	let s = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+	let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * s;
+	let id = Identity::new(b"", &[1, 2, 3]);
+	let mut context = EncryptionContext::<TinyBLS381>::new(p_pub);
+	context.prepare(&id);
+
+	let mut group = c.benchmark_group("tlock_encrypt_repeated");
+	group.bench_function("tle", |b| {
+		b.iter(|| tlock_encrypt::<TinyBLS381>([2; 32], p_pub, black_box(b"message"), id.clone()))
+	});
+	group.bench_function("context", |b| {
+		b.iter(|| {
+			context
+				.encrypt::<AESGCMBlockCipherProvider, _>(&id, [2; 32], black_box(b"message"), OsRng)
+				.unwrap()
+		})
+	});
+	group.finish();
+}
+
+criterion_group!(benches, tlock_split, tlock_context);
 criterion_main!(benches);
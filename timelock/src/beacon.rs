@@ -0,0 +1,171 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The signing side of a beacon.
+//!
+//! A self-hosted beacon holds a master secret and, every round, publishes its
+//! signature of [`Identity::from_round`]. The signature is the IBE secret of
+//! the round, so it decrypts every ciphertext encrypted to the round with
+//! [`crate::tlock::tld`]; [`BeaconSigner::sign_bytes`] outputs it in the
+//! compressed form that clients and [`crate::tlock::strict::signature`] read.
+//! The hash of the signature is the randomness of the round (see
+//! [`Randomness`]).
+
+use crate::{
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	rounds::RoundCalculator,
+	tlock::{BeaconConfig, Randomness},
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// The master secret of a beacon, signing its rounds
+///
+/// The secret is wiped when the signer is dropped.
+pub struct BeaconSigner<E: EngineBLS> {
+	secret: E::Scalar,
+	p_pub: E::PublicKeyGroup,
+}
+
+impl<E: EngineBLS> BeaconSigner<E> {
+	/// A signer with a fresh master secret
+	///
+	/// * `rng`: A CSPRNG
+	pub fn generate<R: Rng + CryptoRng>(mut rng: R) -> Self {
+		Self::new(E::generate(&mut rng))
+	}
+
+	/// A signer with an existing master secret
+	///
+	/// * `secret`: The master secret of the beacon
+	pub fn new(secret: E::Scalar) -> Self {
+		Self { secret, p_pub: E::PublicKeyGroup::generator() * secret }
+	}
+
+	/// The public key of the beacon, P_pub = sP
+	pub fn public_key(&self) -> E::PublicKeyGroup {
+		self.p_pub
+	}
+
+	/// The compressed public key of the beacon
+	pub fn public_key_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		self.p_pub
+			.serialize_compressed(&mut bytes)
+			.expect("The public key must be serializable.");
+		bytes
+	}
+
+	/// The configuration clients encrypt for the beacon with
+	///
+	/// * `genesis`: The unix time of round 1
+	/// * `period`: The number of seconds between rounds (non-zero)
+	pub fn config(&self, genesis: u64, period: u64) -> BeaconConfig<E> {
+		BeaconConfig { p_pub: self.p_pub, rounds: RoundCalculator::new(genesis, period) }
+	}
+
+	/// The signature of a round
+	///
+	/// * `round`: The round to sign
+	pub fn sign(&self, round: u64) -> E::SignatureGroup {
+		Identity::from_round(round).extract::<E>(self.secret).0
+	}
+
+	/// The compressed signature of a round
+	///
+	/// * `round`: The round to sign
+	pub fn sign_bytes(&self, round: u64) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		self.sign(round)
+			.serialize_compressed(&mut bytes)
+			.expect("The signature must be serializable.");
+		bytes
+	}
+
+	/// The randomness of a round, the hash of its compressed signature
+	///
+	/// * `round`: The round
+	pub fn randomness(&self, round: u64) -> Randomness {
+		Randomness::from_signature::<E>(&self.sign(round))
+	}
+}
+
+impl<E: EngineBLS> Drop for BeaconSigner<E> {
+	fn drop(&mut self) {
+		self.secret.zeroize();
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::{TinyBLS377, TinyBLS381},
+		tlock::{encrypt_at_time, strict, tld, verify_signature},
+	};
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+	fn signs_rounds_that_decrypt<E: EngineBLS>() {
+		let mut rng = StdRng::seed_from_u64(2367);
+		let signer = BeaconSigner::<E>::generate(&mut rng);
+		assert_eq!(strict::public_key::<E>(&signer.public_key_bytes()), Ok(signer.public_key()));
+
+		let config = signer.config(1_700_000_000, 3);
+		let (ciphertext, round) = encrypt_at_time::<E, AESGCMBlockCipherProvider, _>(
+			&config,
+			1_700_000_300,
+			b"self-hosted",
+			&mut rng,
+		)
+		.unwrap();
+		assert_eq!(round, 101);
+
+		let signature = strict::signature::<E>(&signer.sign_bytes(round)).unwrap();
+		assert_eq!(signature, signer.sign(round));
+		assert_eq!(verify_signature::<E>(signer.public_key(), round, signature), Ok(()));
+		assert_eq!(
+			tld::<E, AESGCMBlockCipherProvider>(ciphertext, signature),
+			Ok(b"self-hosted".to_vec())
+		);
+		assert_ne!(signer.randomness(round), signer.randomness(round + 1));
+	}
+
+	#[test]
+	pub fn signer_signs_rounds_that_decrypt() {
+		signs_rounds_that_decrypt::<TinyBLS381>();
+		signs_rounds_that_decrypt::<TinyBLS377>();
+	}
+
+	#[test]
+	pub fn signer_matches_the_beacon_of_a_known_secret() {
+		// the same secret always yields the same key and signatures
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::from(2367u64);
+		let signer = BeaconSigner::<TinyBLS381>::new(secret);
+		assert_eq!(
+			signer.public_key(),
+			<TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret
+		);
+		assert_eq!(signer.sign(7), Identity::from_round(7).public::<TinyBLS381>() * secret);
+		assert_eq!(signer.sign_bytes(7), BeaconSigner::<TinyBLS381>::new(secret).sign_bytes(7));
+	}
+}
@@ -0,0 +1,159 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The parameters of well-known beacons.
+//!
+//! Each preset carries the chain info of the beacon as published by its
+//! operators, and is checked against its chain hash in the tests, so the public
+//! keys need not be copied into applications by hand.
+
+use crate::{
+	engines::EngineBLS,
+	rounds::RoundCalculator,
+	tlock::{BeaconConfig, Error},
+};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::vec::Vec;
+
+/// The chain info of a beacon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconPreset {
+	/// The name of the beacon
+	pub name: &'static str,
+	/// The hex encoded compressed public key
+	pub public_key: &'static str,
+	/// The hex encoded hash of the chain
+	pub chain_hash: &'static str,
+	/// The hex encoded hash of the group running the beacon
+	pub group_hash: &'static str,
+	/// The unix time of round 1
+	pub genesis: u64,
+	/// The number of seconds between rounds
+	pub period: u64,
+	/// The signature scheme of the beacon
+	pub scheme: &'static str,
+	/// The id of the beacon within its network
+	pub beacon_id: &'static str,
+}
+
+/// drand's quicknet: unchained signatures in G1 every 3 seconds, for use with
+/// [`crate::engines::drand::TinyBLS381`]
+pub const QUICKNET: BeaconPreset = BeaconPreset {
+	name: "quicknet",
+	public_key: "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a",
+	chain_hash: "52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971",
+	group_hash: "f477d5c89f21a17c863a7f937c6a6d15859414d2be09cd448d4279af331c5d3e",
+	genesis: 1692803367,
+	period: 3,
+	scheme: "bls-unchained-g1-rfc9380",
+	beacon_id: "quicknet",
+};
+
+/// drand's default network (mainnet): chained signatures in G2 every 30
+/// seconds
+///
+/// Chained signatures also sign the previous signature, so this beacon cannot
+/// be timelocked to.
+pub const MAINNET: BeaconPreset = BeaconPreset {
+	name: "mainnet",
+	public_key: "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31",
+	chain_hash: "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce",
+	group_hash: "176f93498eac9ca337150b46d21dd58673ea4e3581185f869672e59fa4cb390a",
+	genesis: 1595431050,
+	period: 30,
+	scheme: "pedersen-bls-chained",
+	beacon_id: "default",
+};
+
+/// The presets
+pub const ALL: &[BeaconPreset] = &[QUICKNET, MAINNET];
+
+impl BeaconPreset {
+	/// The preset of the given name
+	pub fn by_name(name: &str) -> Option<Self> {
+		ALL.iter().copied().find(|preset| preset.name == name)
+	}
+
+	/// The preset of the given hex encoded chain hash
+	pub fn by_chain_hash(chain_hash: &str) -> Option<Self> {
+		ALL.iter()
+			.copied()
+			.find(|preset| preset.chain_hash.eq_ignore_ascii_case(chain_hash))
+	}
+
+	/// Whether ciphertexts can be timelocked to the rounds of the beacon
+	pub fn is_unchained(&self) -> bool {
+		self.scheme.contains("unchained")
+	}
+
+	/// The compressed public key
+	pub fn public_key_bytes(&self) -> Vec<u8> {
+		array_bytes::hex2bytes(self.public_key).expect("The presets are valid hex.")
+	}
+
+	/// The hash of the chain
+	pub fn chain_hash_bytes(&self) -> Vec<u8> {
+		array_bytes::hex2bytes(self.chain_hash).expect("The presets are valid hex.")
+	}
+
+	/// The round schedule of the beacon
+	pub fn rounds(&self) -> RoundCalculator {
+		RoundCalculator::new(self.genesis, self.period)
+	}
+
+	/// The configuration to encrypt for the beacon with the engine `E`
+	///
+	/// Fails if the beacon is chained or its public key is not in the public
+	/// key group of `E`.
+	pub fn config<E: EngineBLS>(&self) -> Result<BeaconConfig<E>, Error> {
+		if !self.is_unchained() {
+			return Err(Error::InvalidFormat);
+		}
+		let p_pub = E::PublicKeyGroup::deserialize_compressed(&self.public_key_bytes()[..])
+			.map_err(|_| Error::DeserializationErrorG2)?;
+		Ok(BeaconConfig { p_pub, rounds: self.rounds() })
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::engines::drand::TinyBLS381;
+	use sha2::{Digest, Sha256};
+
+	#[test]
+	pub fn presets_match_their_chain_hashes() {
+		for preset in ALL {
+			let mut hasher = Sha256::new();
+			hasher.update((preset.period as u32).to_be_bytes());
+			hasher.update((preset.genesis as i64).to_be_bytes());
+			hasher.update(preset.public_key_bytes());
+			hasher.update(array_bytes::hex2bytes(preset.group_hash).unwrap());
+			if preset.beacon_id != "default" {
+				hasher.update(preset.beacon_id.as_bytes());
+			}
+			assert_eq!(hasher.finalize().to_vec(), preset.chain_hash_bytes(), "{}", preset.name);
+		}
+	}
+
+	#[test]
+	pub fn presets_can_be_found_and_configured() {
+		assert_eq!(BeaconPreset::by_name("quicknet"), Some(QUICKNET));
+		assert_eq!(BeaconPreset::by_chain_hash(&MAINNET.chain_hash.to_uppercase()), Some(MAINNET));
+		assert!(QUICKNET.config::<TinyBLS381>().is_ok());
+		assert!(matches!(MAINNET.config::<TinyBLS381>(), Err(Error::InvalidFormat)));
+	}
+}
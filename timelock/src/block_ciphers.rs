@@ -14,19 +14,24 @@
  * limitations under the License.
  */
 
+use crate::tlock::view::read_bytes;
 use aes_gcm::{
 	aead::{Aead, AeadCore, AeadInPlace, KeyInit},
 	Aes256Gcm, Nonce,
 };
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+	CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
 use ark_std::rand::Rng;
+use chacha20poly1305::ChaCha20Poly1305;
+use zeroize::Zeroize;
 
 use serde::{Deserialize, Serialize};
 
 use ark_std::{rand::CryptoRng, vec::Vec};
 
 /// The output of AES_GCM Encryption
-#[derive(Clone, Serialize, Deserialize, Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Serialize, Deserialize, Debug, CanonicalSerialize)]
 pub struct AESOutput {
 	/// the AES ciphertext
 	pub ciphertext: Vec<u8>,
@@ -34,9 +39,32 @@ pub struct AESOutput {
 	pub nonce: Vec<u8>,
 }
 
+impl Valid for AESOutput {
+	fn check(&self) -> Result<(), SerializationError> {
+		Ok(())
+	}
+}
+
+// Not derived: ciphertext bodies are untrusted input, see
+// [`crate::tlock::view::read_bytes`]
+impl CanonicalDeserialize for AESOutput {
+	fn deserialize_with_mode<R: Read>(
+		mut reader: R,
+		_: Compress,
+		_: Validate,
+	) -> Result<Self, SerializationError> {
+		let ciphertext = read_bytes(&mut reader)?;
+		let nonce = read_bytes(&mut reader)?;
+		Ok(Self { ciphertext, nonce })
+	}
+}
+
 /// The expected length of a nonce used with AES_GCM
 const AES_GCM_NONCE_LEN: usize = 12;
 
+/// The expected length of a nonce used with ChaCha20-Poly1305
+const CHACHA20_POLY1305_NONCE_LEN: usize = 12;
+
 /// Errors that mayb be encountered with using a stream cipher
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -48,6 +76,18 @@ pub enum Error {
 	BadNonce,
 }
 
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str(match self {
+			Error::CiphertextTooLarge => "the ciphertext exceeds the maximum buffer size",
+			Error::InvalidKey => "the key cannot decrypt the ciphertext",
+			Error::BadNonce => "the nonce is invalid",
+		})
+	}
+}
+
+impl core::error::Error for Error {}
+
 /// Something that provides encryption and decryption using a stream cipher
 pub trait BlockCipherProvider<const N: usize> {
 	/// Some identifier to indiciate which ciphersuite was used
@@ -80,10 +120,11 @@ impl BlockCipherProvider<32> for AESGCMBlockCipherProvider {
 	/// * `rng`: A CSPRNG
 	fn encrypt<R: Rng + CryptoRng + Sized>(
 		message: &[u8],
-		key: [u8; 32],
+		mut key: [u8; 32],
 		mut rng: R,
 	) -> Result<Self::Ciphertext, Error> {
 		let cipher = Aes256Gcm::new(generic_array::GenericArray::from_slice(&key));
+		key.zeroize();
 
 		let nonce = Aes256Gcm::generate_nonce(&mut rng); // 96-bits; unique per message
 
@@ -102,8 +143,10 @@ impl BlockCipherProvider<32> for AESGCMBlockCipherProvider {
 	///
 	/// * `ciphertext`: the ciphertext to decrypt
 	/// * `nonce`: the nonce used on encryption
-	fn decrypt(ct: Self::Ciphertext, key: [u8; 32]) -> Result<Vec<u8>, Error> {
-		let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| Error::InvalidKey)?;
+	fn decrypt(ct: Self::Ciphertext, mut key: [u8; 32]) -> Result<Vec<u8>, Error> {
+		let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| Error::InvalidKey);
+		key.zeroize();
+		let cipher = cipher?;
 		if ct.nonce.len() != AES_GCM_NONCE_LEN {
 			return Err(Error::BadNonce);
 		}
@@ -114,6 +157,116 @@ impl BlockCipherProvider<32> for AESGCMBlockCipherProvider {
 	}
 }
 
+/// This provides the ChaCha20-Poly1305 stream cipher, which is faster than
+/// AES_GCM on CPUs without AES instructions
+///
+/// Ciphertexts have the same layout as those of [`AESGCMBlockCipherProvider`].
+pub struct ChaCha20Poly1305BlockCipherProvider;
+impl BlockCipherProvider<32> for ChaCha20Poly1305BlockCipherProvider {
+	const CIPHER_SUITE: &'static [u8] = b"CHACHA20";
+
+	type Ciphertext = AESOutput;
+
+	/// ChaCha20-Poly1305 encryption of the message using an ephemeral keypair
+	///
+	/// * `message`: The message to encrypt
+	/// * `key`: the key used for encryption
+	/// * `rng`: A CSPRNG
+	fn encrypt<R: Rng + CryptoRng + Sized>(
+		message: &[u8],
+		mut key: [u8; 32],
+		mut rng: R,
+	) -> Result<Self::Ciphertext, Error> {
+		let cipher = ChaCha20Poly1305::new(&key.into());
+		key.zeroize();
+		let mut nonce = [0u8; CHACHA20_POLY1305_NONCE_LEN]; // unique per message
+		rng.fill_bytes(&mut nonce);
+
+		let mut buffer: Vec<u8> = Vec::new(); // Note: buffer needs 16-bytes overhead for auth tag
+		buffer.extend_from_slice(message);
+		cipher
+			.encrypt_in_place(&nonce.into(), b"", &mut buffer)
+			.map_err(|_| Error::CiphertextTooLarge)?;
+		Ok(Self::Ciphertext { ciphertext: buffer, nonce: nonce.to_vec() })
+	}
+
+	/// ChaCha20-Poly1305 decryption
+	///
+	/// * `ciphertext`: the ciphertext to decrypt
+	/// * `key`: the key used for encryption
+	fn decrypt(ct: Self::Ciphertext, mut key: [u8; 32]) -> Result<Vec<u8>, Error> {
+		let cipher = ChaCha20Poly1305::new(&key.into());
+		key.zeroize();
+		let nonce: [u8; CHACHA20_POLY1305_NONCE_LEN] =
+			ct.nonce.as_slice().try_into().map_err(|_| Error::BadNonce)?;
+		let plaintext = cipher
+			.decrypt(&nonce.into(), ct.ciphertext.as_ref())
+			.map_err(|_| Error::InvalidKey)?;
+		Ok(plaintext)
+	}
+}
+
+/// The stream ciphers a ciphertext can be encrypted with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CipherSuite {
+	/// [`AESGCMBlockCipherProvider`]
+	AesGcm,
+	/// [`ChaCha20Poly1305BlockCipherProvider`]
+	ChaCha20Poly1305,
+}
+
+impl CipherSuite {
+	/// The fastest cipher on this CPU: AES_GCM if it has AES and carry-less
+	/// multiplication instructions (e.g. AES-NI and PCLMULQDQ), else
+	/// ChaCha20-Poly1305, whose software implementation is faster than AES
+	///
+	/// With the `std` feature the CPU is queried at runtime on x86, x86_64 and
+	/// aarch64; otherwise the target features enabled at compile time decide.
+	pub fn detect() -> Self {
+		if has_aes_instructions() {
+			Self::AesGcm
+		} else {
+			Self::ChaCha20Poly1305
+		}
+	}
+
+	/// The identifier stored in ciphertexts encrypted with this cipher
+	pub fn id(&self) -> &'static [u8] {
+		match self {
+			Self::AesGcm => AESGCMBlockCipherProvider::CIPHER_SUITE,
+			Self::ChaCha20Poly1305 => ChaCha20Poly1305BlockCipherProvider::CIPHER_SUITE,
+		}
+	}
+
+	/// The cipher a ciphertext was encrypted with, from its identifier
+	///
+	/// * `id`: The cipher suite of a ciphertext
+	pub fn from_id(id: &[u8]) -> Option<Self> {
+		[Self::AesGcm, Self::ChaCha20Poly1305]
+			.into_iter()
+			.find(|suite| suite.id() == id)
+	}
+}
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+fn has_aes_instructions() -> bool {
+	std::is_x86_feature_detected!("aes") && std::is_x86_feature_detected!("pclmulqdq")
+}
+
+#[cfg(all(feature = "std", target_arch = "aarch64"))]
+fn has_aes_instructions() -> bool {
+	// the aes feature includes the PMULL instructions GCM needs
+	std::arch::is_aarch64_feature_detected!("aes")
+}
+
+#[cfg(not(all(
+	feature = "std",
+	any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+)))]
+fn has_aes_instructions() -> bool {
+	cfg!(all(target_feature = "aes", any(target_arch = "aarch64", target_feature = "pclmulqdq")))
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -213,4 +366,30 @@ mod test {
 			},
 		}
 	}
+
+	#[test]
+	pub fn chacha20_encrypt_decrypt_works() {
+		let msg = b"test";
+		let esk = [2; 32];
+		let out = ChaCha20Poly1305BlockCipherProvider::encrypt(msg, esk, OsRng).unwrap();
+		assert_eq!(out.nonce.len(), CHACHA20_POLY1305_NONCE_LEN);
+		let bad = AESOutput { ciphertext: out.ciphertext.clone(), nonce: out.nonce.clone() };
+		assert_eq!(
+			ChaCha20Poly1305BlockCipherProvider::decrypt(bad, [4; 32]),
+			Err(Error::InvalidKey)
+		);
+		let bad = AESOutput { ciphertext: out.ciphertext.clone(), nonce: vec![0; 24] };
+		assert_eq!(ChaCha20Poly1305BlockCipherProvider::decrypt(bad, esk), Err(Error::BadNonce));
+		assert_eq!(ChaCha20Poly1305BlockCipherProvider::decrypt(out, esk), Ok(msg.to_vec()));
+	}
+
+	#[test]
+	pub fn cipher_suites_round_trip_their_ids() {
+		for suite in [CipherSuite::AesGcm, CipherSuite::ChaCha20Poly1305] {
+			assert_eq!(CipherSuite::from_id(suite.id()), Some(suite));
+		}
+		assert_eq!(CipherSuite::from_id(b"UNKNOWN_"), None);
+		let detected = CipherSuite::detect();
+		assert_eq!(CipherSuite::from_id(detected.id()), Some(detected));
+	}
 }
@@ -0,0 +1,168 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Identities and schedules for beacons that sign block numbers.
+//!
+//! Substrate chains such as the Ideal Network sign each block's identity,
+//! sha256(block number as u64 BE), so a ciphertext encrypted to
+//! [`block_identity`] unlocks once that block is finalized. Blocks are not
+//! produced on an exact schedule, so [`BlockSchedule`] only estimates when a
+//! block is reached from a known block and the chain's target block time.
+//! [`Eras`] maps eras (or sessions) of a fixed length to their blocks.
+
+use crate::ibe::fullident::Identity;
+
+/// The identity signed in a block, sha256(block number as u64 BE)
+pub fn block_identity(block_number: u64) -> Identity {
+	crate::tlock::round_identity(block_number)
+}
+
+/// Estimates the blocks of a chain at unix times
+///
+/// The block time must be non-zero; the methods panic otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSchedule {
+	/// A known block
+	pub anchor_block: u64,
+	/// The unix time at which the anchor block was produced
+	pub anchor_time: u64,
+	/// The target number of seconds between blocks
+	pub block_time: u64,
+}
+
+impl BlockSchedule {
+	/// A schedule estimating blocks from a known one
+	///
+	/// * `anchor_block`: A known block, e.g. the latest finalized block
+	/// * `anchor_time`: The unix time at which the anchor block was produced
+	/// * `block_time`: The target number of seconds between blocks
+	pub const fn new(anchor_block: u64, anchor_time: u64, block_time: u64) -> Self {
+		assert!(block_time > 0, "The block time of a chain must be non-zero.");
+		Self { anchor_block, anchor_time, block_time }
+	}
+
+	/// The estimated latest block produced at `time`
+	///
+	/// Times before the anchor estimate earlier blocks, down to block 0.
+	pub fn block_at(&self, time: u64) -> u64 {
+		match time.checked_sub(self.anchor_time) {
+			Some(elapsed) => self.anchor_block.saturating_add(elapsed / self.block_time),
+			None => self
+				.anchor_block
+				.saturating_sub((self.anchor_time - time).div_ceil(self.block_time)),
+		}
+	}
+
+	/// The first block estimated to be produced at or after `time`
+	///
+	/// This is the block to encrypt for so that a ciphertext unlocks at about
+	/// `time`.
+	pub fn block_after(&self, time: u64) -> u64 {
+		match time.checked_sub(self.anchor_time) {
+			Some(elapsed) => self.anchor_block.saturating_add(elapsed.div_ceil(self.block_time)),
+			None => self.anchor_block.saturating_sub((self.anchor_time - time) / self.block_time),
+		}
+	}
+
+	/// The estimated unix time at which `block` is produced
+	pub fn time_of(&self, block: u64) -> u64 {
+		if block >= self.anchor_block {
+			self.anchor_time
+				.saturating_add((block - self.anchor_block).saturating_mul(self.block_time))
+		} else {
+			self.anchor_time
+				.saturating_sub((self.anchor_block - block).saturating_mul(self.block_time))
+		}
+	}
+}
+
+/// Eras (or sessions) of a fixed number of blocks
+///
+/// The length must be non-zero; the methods panic otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eras {
+	/// The first block of era 0
+	pub start: u64,
+	/// The number of blocks in an era
+	pub length: u64,
+}
+
+impl Eras {
+	/// Eras of `length` blocks, starting at block `start`
+	///
+	/// * `start`: The first block of era 0
+	/// * `length`: The number of blocks in an era
+	pub const fn new(start: u64, length: u64) -> Self {
+		assert!(length > 0, "The length of an era must be non-zero.");
+		Self { start, length }
+	}
+
+	/// The era of a block, or `None` before the first era
+	pub fn era_of(&self, block_number: u64) -> Option<u64> {
+		block_number.checked_sub(self.start).map(|offset| offset / self.length)
+	}
+
+	/// The first block of an era
+	pub fn first_block(&self, era: u64) -> u64 {
+		self.start.saturating_add(era.saturating_mul(self.length))
+	}
+
+	/// The identity of the first block of an era
+	///
+	/// Encrypting to it unlocks a ciphertext once the era begins.
+	pub fn identity(&self, era: u64) -> Identity {
+		block_identity(self.first_block(era))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	// a 6 second chain whose block 1,000 was produced at unix time 1,700,000,000
+	const CHAIN: BlockSchedule = BlockSchedule::new(1_000, 1_700_000_000, 6);
+
+	#[test]
+	pub fn blocks_and_times_agree() {
+		assert_eq!(CHAIN.block_at(CHAIN.anchor_time), 1_000);
+		assert_eq!(CHAIN.block_at(CHAIN.anchor_time + 5), 1_000);
+		assert_eq!(CHAIN.block_at(CHAIN.anchor_time + 6), 1_001);
+		assert_eq!(CHAIN.block_at(CHAIN.anchor_time - 1), 999);
+		assert_eq!(CHAIN.block_at(0), 0);
+		assert_eq!(CHAIN.time_of(1_000_000), 1_700_000_000 + 999_000 * 6);
+
+		for block in [0, 1, 999, 1_000, 1_000_000] {
+			let time = CHAIN.time_of(block);
+			assert_eq!(CHAIN.block_at(time), block);
+			assert_eq!(CHAIN.block_after(time), block);
+			assert_eq!(CHAIN.block_after(time + 1), block + 1);
+			if block > 0 {
+				assert_eq!(CHAIN.block_after(time - 1), block);
+			}
+		}
+	}
+
+	#[test]
+	pub fn eras_map_to_blocks() {
+		let eras = Eras::new(100, 600);
+		assert_eq!(eras.era_of(99), None);
+		assert_eq!(eras.era_of(100), Some(0));
+		assert_eq!(eras.era_of(699), Some(0));
+		assert_eq!(eras.era_of(700), Some(1));
+		assert_eq!(eras.first_block(3), 1_900);
+		assert_eq!(eras.identity(3).0, block_identity(1_900).0);
+	}
+}
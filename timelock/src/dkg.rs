@@ -0,0 +1,660 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Distributed key generation for beacon committees.
+//!
+//! A committee of `n` participants jointly creates a beacon key so that any
+//! `t` of them can sign a round, while fewer learn nothing of the master
+//! secret. This is Pedersen's joint-Feldman DKG with the complaint phase of
+//! Gennaro, Jarecki, Krawczyk and Rabin:
+//!
+//! 1. Every participant [`deal`]s: it picks a random polynomial of degree `t -
+//!    1`, broadcasts Feldman commitments to its coefficients and sends each
+//!    participant `j` its evaluation at `j` over a private channel.
+//! 2. Every participant checks the shares it received against the commitments
+//!    ([`Participant::receive`]) and broadcasts a complaint for every invalid
+//!    share. An accused dealer answers by revealing the share, which the
+//!    accuser takes if valid ([`Participant::resolve`]).
+//! 3. The qualified dealers are those without unresolved complaints. Every
+//!    participant adds up their shares ([`Participant::finish`]) into its
+//!    [`KeyShare`] of the master secret, the sum of their constant terms.
+//!
+//! Participants sign rounds with their key shares, and any `t` verified
+//! [`PartialSignature`]s of a round combine (by Lagrange interpolation) into
+//! the beacon's signature of the round, which decrypts ciphertexts with
+//! [`crate::tlock::tld`] as any beacon signature does.
+//!
+//! Joint-Feldman lets a dealer who sees the others' commitments first bias the
+//! public key (though not learn the secret), so commitments should be
+//! broadcast simultaneously, e.g. committed to before they are revealed.
+
+use crate::{
+	engines::EngineBLS, ibe::fullident::Identity, rounds::RoundCalculator, tlock::BeaconConfig,
+};
+use ark_ec::PrimeGroup;
+use ark_ff::{Field, UniformRand, Zero};
+use ark_serialize::{
+	CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
+use ark_std::{
+	collections::{BTreeMap, BTreeSet},
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// Errors that may occur during key generation or threshold signing
+#[derive(Debug, PartialEq)]
+pub enum Error {
+	/// The threshold must be non-zero and at most the number of participants
+	InvalidThreshold,
+	/// The index is not that of a participant (they run from 1 to `n`)
+	InvalidIndex(u32),
+	/// The commitments of a dealer do not have one entry per coefficient
+	InvalidCommitments(u32),
+	/// The share sent by a dealer does not match its commitments
+	InvalidShare(u32),
+	/// No valid share was received from a qualified dealer
+	MissingShare(u32),
+	/// There are no qualified dealers
+	NoQualifiedDealers,
+	/// A partial signature is not the signature of its participant
+	InvalidPartialSignature(u32),
+	/// Fewer valid partial signatures than the threshold were given
+	NotEnoughPartialSignatures,
+}
+
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Error::InvalidThreshold =>
+				f.write_str("the threshold must be non-zero and at most the number of participants"),
+			Error::InvalidIndex(index) => write!(f, "{} is not the index of a participant", index),
+			Error::InvalidCommitments(dealer) =>
+				write!(f, "the commitments of dealer {} are malformed", dealer),
+			Error::InvalidShare(dealer) =>
+				write!(f, "the share of dealer {} does not match its commitments", dealer),
+			Error::MissingShare(dealer) =>
+				write!(f, "no valid share was received from dealer {}", dealer),
+			Error::NoQualifiedDealers => f.write_str("there are no qualified dealers"),
+			Error::InvalidPartialSignature(index) =>
+				write!(f, "the partial signature of participant {} is invalid", index),
+			Error::NotEnoughPartialSignatures =>
+				f.write_str("fewer valid partial signatures than the threshold were given"),
+		}
+	}
+}
+
+impl core::error::Error for Error {}
+
+/// The size of a committee and the number of participants needed to sign
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameters {
+	/// The number of participants needed to sign a round
+	pub threshold: u32,
+	/// The number of participants
+	pub participants: u32,
+}
+
+impl Parameters {
+	/// The parameters of a `threshold` of `participants` committee
+	///
+	/// * `threshold`: The number of participants needed to sign a round
+	/// * `participants`: The number of participants
+	pub fn new(threshold: u32, participants: u32) -> Result<Self, Error> {
+		if threshold == 0 || threshold > participants {
+			return Err(Error::InvalidThreshold);
+		}
+		Ok(Self { threshold, participants })
+	}
+
+	/// Check that `index` is the index of a participant
+	fn check(&self, index: u32) -> Result<(), Error> {
+		match index >= 1 && index <= self.participants {
+			true => Ok(()),
+			false => Err(Error::InvalidIndex(index)),
+		}
+	}
+}
+
+/// The polynomial of a dealer: its commitments, to broadcast, and its shares,
+/// to send privately
+pub struct Dealing<E: EngineBLS> {
+	/// The index of the dealer
+	pub dealer: u32,
+	/// The commitments `a_k P` to the coefficients of the polynomial
+	pub commitments: Vec<E::PublicKeyGroup>,
+	shares: Vec<E::Scalar>,
+}
+
+impl<E: EngineBLS> Dealing<E> {
+	/// The share to send to a participant, the polynomial evaluated at its
+	/// index
+	///
+	/// * `index`: The index of the participant
+	pub fn share_for(&self, index: u32) -> Option<E::Scalar> {
+		self.shares.get(index.checked_sub(1)? as usize).copied()
+	}
+}
+
+impl<E: EngineBLS> Drop for Dealing<E> {
+	fn drop(&mut self) {
+		self.shares.zeroize();
+	}
+}
+
+/// Deal a random polynomial of degree `threshold - 1`
+///
+/// * `params`: The parameters of the committee
+/// * `dealer`: The index of the dealer
+/// * `rng`: A CSPRNG
+pub fn deal<E, R>(params: &Parameters, dealer: u32, mut rng: R) -> Result<Dealing<E>, Error>
+where
+	E: EngineBLS,
+	R: Rng + CryptoRng,
+{
+	params.check(dealer)?;
+	let mut coefficients: Vec<E::Scalar> =
+		(0..params.threshold).map(|_| E::Scalar::rand(&mut rng)).collect();
+	let commitments = coefficients.iter().map(|a| E::PublicKeyGroup::generator() * a).collect();
+	let shares = (1..=params.participants)
+		.map(|index| {
+			let x = E::Scalar::from(index as u64);
+			coefficients.iter().rev().fold(E::Scalar::zero(), |acc, a| acc * x + a)
+		})
+		.collect();
+	coefficients.zeroize();
+	Ok(Dealing { dealer, commitments, shares })
+}
+
+/// The commitments evaluated at an index, i.e. the share at the index times
+/// the generator
+fn evaluate<E: EngineBLS>(commitments: &[E::PublicKeyGroup], index: u32) -> E::PublicKeyGroup {
+	let x = E::Scalar::from(index as u64);
+	commitments.iter().rev().fold(E::PublicKeyGroup::zero(), |acc, c| acc * x + c)
+}
+
+/// Check a share against the commitments of its dealer
+///
+/// * `commitments`: The commitments of the dealer
+/// * `index`: The index of the participant the share is for
+/// * `share`: The share
+pub fn verify_share<E: EngineBLS>(
+	commitments: &[E::PublicKeyGroup],
+	index: u32,
+	share: E::Scalar,
+) -> bool {
+	E::PublicKeyGroup::generator() * share == evaluate::<E>(commitments, index)
+}
+
+/// A participant collecting the shares dealt to it
+pub struct Participant<E: EngineBLS> {
+	params: Parameters,
+	index: u32,
+	received: BTreeMap<u32, (Vec<E::PublicKeyGroup>, E::Scalar)>,
+	complaints: BTreeSet<u32>,
+}
+
+impl<E: EngineBLS> Participant<E> {
+	/// A participant of a committee
+	///
+	/// * `params`: The parameters of the committee
+	/// * `index`: The index of the participant
+	pub fn new(params: Parameters, index: u32) -> Result<Self, Error> {
+		params.check(index)?;
+		Ok(Self { params, index, received: BTreeMap::new(), complaints: BTreeSet::new() })
+	}
+
+	/// The index of the participant
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	/// Receive the commitments and the share of a dealer
+	///
+	/// An invalid share is recorded as a complaint against the dealer, to be
+	/// broadcast (see [`Participant::complaints`]).
+	///
+	/// * `dealer`: The index of the dealer
+	/// * `commitments`: The broadcast commitments of the dealer
+	/// * `share`: The share the dealer sent to this participant
+	pub fn receive(
+		&mut self,
+		dealer: u32,
+		commitments: Vec<E::PublicKeyGroup>,
+		share: E::Scalar,
+	) -> Result<(), Error> {
+		self.params.check(dealer)?;
+		if commitments.len() != self.params.threshold as usize {
+			self.complaints.insert(dealer);
+			return Err(Error::InvalidCommitments(dealer));
+		}
+		if !verify_share::<E>(&commitments, self.index, share) {
+			self.complaints.insert(dealer);
+			return Err(Error::InvalidShare(dealer));
+		}
+		self.complaints.remove(&dealer);
+		self.received.insert(dealer, (commitments, share));
+		Ok(())
+	}
+
+	/// The dealers this participant complains about
+	pub fn complaints(&self) -> impl Iterator<Item = u32> + '_ {
+		self.complaints.iter().copied()
+	}
+
+	/// Resolve a complaint with the share the accused dealer revealed
+	///
+	/// Fails, leaving the complaint unresolved (so the dealer is to be
+	/// disqualified), if the share does not match the dealer's commitments.
+	///
+	/// * `dealer`: The accused dealer
+	/// * `commitments`: The broadcast commitments of the dealer
+	/// * `share`: The share the dealer revealed for this participant
+	pub fn resolve(
+		&mut self,
+		dealer: u32,
+		commitments: Vec<E::PublicKeyGroup>,
+		share: E::Scalar,
+	) -> Result<(), Error> {
+		self.receive(dealer, commitments, share)
+	}
+
+	/// Combine the shares of the qualified dealers into a key share
+	///
+	/// Every participant must use the same qualified dealers, those without
+	/// unresolved complaints.
+	///
+	/// * `qualified`: The indices of the qualified dealers
+	pub fn finish(mut self, qualified: &[u32]) -> Result<KeyShare<E>, Error> {
+		let qualified: BTreeSet<u32> = qualified.iter().copied().collect();
+		if qualified.is_empty() {
+			return Err(Error::NoQualifiedDealers);
+		}
+		let mut secret = E::Scalar::zero();
+		let mut commitments =
+			ark_std::vec![E::PublicKeyGroup::zero(); self.params.threshold as usize];
+		for dealer in qualified {
+			let (dealt, share) = self.received.get(&dealer).ok_or(Error::MissingShare(dealer))?;
+			secret += share;
+			for (sum, commitment) in commitments.iter_mut().zip(dealt) {
+				*sum += commitment;
+			}
+		}
+		for (_, share) in self.received.values_mut() {
+			share.zeroize();
+		}
+		Ok(KeyShare {
+			index: self.index,
+			secret,
+			group: GroupKey { threshold: self.params.threshold, commitments },
+		})
+	}
+}
+
+/// The public outcome of key generation: the commitments to the joint
+/// polynomial, which hold the beacon public key and every participant's
+/// public share
+#[derive(CanonicalSerialize)]
+pub struct GroupKey<E: EngineBLS> {
+	/// The number of partial signatures needed to sign a round
+	pub threshold: u32,
+	/// The commitments to the coefficients of the joint polynomial
+	pub commitments: Vec<E::PublicKeyGroup>,
+}
+
+impl<E: EngineBLS> GroupKey<E> {
+	/// Check that there is one commitment per coefficient of a polynomial of
+	/// degree `threshold - 1`
+	fn check_shape(&self) -> Result<(), SerializationError> {
+		if self.threshold == 0 || self.commitments.len() != self.threshold as usize {
+			return Err(SerializationError::InvalidData);
+		}
+		Ok(())
+	}
+}
+
+impl<E: EngineBLS> Valid for GroupKey<E> {
+	fn check(&self) -> Result<(), SerializationError> {
+		self.check_shape()?;
+		self.commitments.check()
+	}
+}
+
+// Not derived: the public key and shares index the commitments, so their
+// number must match a non-zero threshold whether or not points are validated,
+// and is checked before any is read
+impl<E: EngineBLS> CanonicalDeserialize for GroupKey<E> {
+	fn deserialize_with_mode<R: Read>(
+		mut reader: R,
+		compress: Compress,
+		validate: Validate,
+	) -> Result<Self, SerializationError> {
+		let threshold = u32::deserialize_with_mode(&mut reader, compress, validate)?;
+		let len = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+		if threshold == 0 || len != threshold as u64 {
+			return Err(SerializationError::InvalidData);
+		}
+		let commitments = (0..threshold)
+			.map(|_| E::PublicKeyGroup::deserialize_with_mode(&mut reader, compress, validate))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Self { threshold, commitments })
+	}
+}
+
+impl<E: EngineBLS> Clone for GroupKey<E> {
+	fn clone(&self) -> Self {
+		Self { threshold: self.threshold, commitments: self.commitments.clone() }
+	}
+}
+
+impl<E: EngineBLS> PartialEq for GroupKey<E> {
+	fn eq(&self, other: &Self) -> bool {
+		self.threshold == other.threshold && self.commitments == other.commitments
+	}
+}
+
+impl<E: EngineBLS> GroupKey<E> {
+	/// The public key of the beacon
+	pub fn public_key(&self) -> E::PublicKeyGroup {
+		self.commitments[0]
+	}
+
+	/// The public key of a participant's key share
+	///
+	/// * `index`: The index of the participant
+	pub fn public_share(&self, index: u32) -> E::PublicKeyGroup {
+		evaluate::<E>(&self.commitments, index)
+	}
+
+	/// The configuration clients encrypt for the beacon with
+	///
+	/// * `genesis`: The unix time of round 1
+	/// * `period`: The number of seconds between rounds (non-zero)
+	pub fn config(&self, genesis: u64, period: u64) -> BeaconConfig<E> {
+		BeaconConfig { p_pub: self.public_key(), rounds: RoundCalculator::new(genesis, period) }
+	}
+
+	/// Check a partial signature of a round
+	///
+	/// * `round`: The round
+	/// * `partial`: The partial signature
+	pub fn verify(&self, round: u64, partial: &PartialSignature<E>) -> Result<(), Error> {
+		let message = Identity::from_round(round).public::<E>();
+		if partial.index == 0 ||
+			E::pairing(self.public_share(partial.index), message) !=
+				E::pairing(E::PublicKeyGroup::generator(), partial.signature)
+		{
+			return Err(Error::InvalidPartialSignature(partial.index));
+		}
+		Ok(())
+	}
+
+	/// Combine partial signatures of a round into the beacon's signature
+	///
+	/// Every partial signature is verified; invalid ones fail the combination
+	/// rather than being skipped, so their signers can be identified. The first
+	/// `threshold` partial signatures of distinct participants are combined.
+	///
+	/// * `round`: The round
+	/// * `partials`: The partial signatures of the round
+	pub fn combine(
+		&self,
+		round: u64,
+		partials: &[PartialSignature<E>],
+	) -> Result<E::SignatureGroup, Error> {
+		if self.threshold == 0 {
+			return Err(Error::InvalidThreshold);
+		}
+		let mut signers = BTreeMap::new();
+		for partial in partials {
+			self.verify(round, partial)?;
+			if signers.len() < self.threshold as usize {
+				signers.insert(partial.index, partial.signature);
+			}
+		}
+		if signers.len() < self.threshold as usize {
+			return Err(Error::NotEnoughPartialSignatures);
+		}
+
+		// Lagrange interpolation at x = 0
+		let mut signature = E::SignatureGroup::zero();
+		for (&i, partial) in &signers {
+			let x_i = E::Scalar::from(i as u64);
+			let mut lambda = E::Scalar::ONE;
+			for &j in signers.keys().filter(|&&j| j != i) {
+				let x_j = E::Scalar::from(j as u64);
+				lambda *= x_j * (x_j - x_i).inverse().expect("The indices are distinct.");
+			}
+			signature += *partial * lambda;
+		}
+		Ok(signature)
+	}
+}
+
+/// A participant's share of the master secret of the beacon
+///
+/// The share is wiped when dropped. Its serialization holds the secret, so
+/// store it as carefully as the secret of a beacon.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct KeyShare<E: EngineBLS> {
+	/// The index of the participant
+	pub index: u32,
+	secret: E::Scalar,
+	/// The public outcome of key generation
+	pub group: GroupKey<E>,
+}
+
+impl<E: EngineBLS> KeyShare<E> {
+	/// The partial signature of a round
+	///
+	/// * `round`: The round to sign
+	pub fn sign(&self, round: u64) -> PartialSignature<E> {
+		PartialSignature {
+			index: self.index,
+			signature: Identity::from_round(round).extract::<E>(self.secret).0,
+		}
+	}
+}
+
+impl<E: EngineBLS> Drop for KeyShare<E> {
+	fn drop(&mut self) {
+		self.secret.zeroize();
+	}
+}
+
+/// A participant's signature of a round with its key share
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct PartialSignature<E: EngineBLS> {
+	/// The index of the participant
+	pub index: u32,
+	/// The signature with the key share
+	pub signature: E::SignatureGroup,
+}
+
+impl<E: EngineBLS> Clone for PartialSignature<E> {
+	fn clone(&self) -> Self {
+		Self { index: self.index, signature: self.signature }
+	}
+}
+
+impl<E: EngineBLS> PartialEq for PartialSignature<E> {
+	fn eq(&self, other: &Self) -> bool {
+		self.index == other.index && self.signature == other.signature
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{encrypt_at_time, tld, verify_signature},
+	};
+	use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+	type E = TinyBLS381;
+
+	/// Run key generation, with `tamper` changing the shares in flight
+	fn run(
+		params: Parameters,
+		tamper: impl Fn(u32, u32, <E as EngineBLS>::Scalar) -> <E as EngineBLS>::Scalar,
+	) -> (Vec<Dealing<E>>, Vec<Participant<E>>) {
+		let mut rng = StdRng::seed_from_u64(2368);
+		let dealings: Vec<Dealing<E>> =
+			(1..=params.participants).map(|i| deal(&params, i, &mut rng).unwrap()).collect();
+		let participants = (1..=params.participants)
+			.map(|j| {
+				let mut participant = Participant::new(params, j).unwrap();
+				for dealing in &dealings {
+					let share = tamper(dealing.dealer, j, dealing.share_for(j).unwrap());
+					let _ = participant.receive(dealing.dealer, dealing.commitments.clone(), share);
+				}
+				participant
+			})
+			.collect();
+		(dealings, participants)
+	}
+
+	#[test]
+	pub fn committee_signs_rounds_with_any_threshold() {
+		let params = Parameters::new(3, 5).unwrap();
+		let (_, participants) = run(params, |_, _, share| share);
+		let shares: Vec<KeyShare<E>> =
+			participants.into_iter().map(|p| p.finish(&[1, 2, 3, 4, 5]).unwrap()).collect();
+		let group = shares[0].group.clone();
+		assert!(shares.iter().all(|share| share.group == group));
+
+		let mut rng = StdRng::seed_from_u64(3);
+		let config = group.config(1_700_000_000, 3);
+		let (ciphertext, round) = encrypt_at_time::<E, AESGCMBlockCipherProvider, _>(
+			&config,
+			1_700_000_030,
+			b"committee",
+			&mut rng,
+		)
+		.unwrap();
+
+		let partials: Vec<_> = shares.iter().map(|share| share.sign(round)).collect();
+		let signature = group.combine(round, &partials[..3]).unwrap();
+		assert_eq!(group.combine(round, &partials[2..]), Ok(signature));
+		assert_eq!(
+			group.combine(round, &[partials[4].clone(), partials[0].clone(), partials[2].clone()]),
+			Ok(signature)
+		);
+		assert_eq!(verify_signature::<E>(group.public_key(), round, signature), Ok(()));
+		assert_eq!(
+			tld::<E, AESGCMBlockCipherProvider>(ciphertext, signature),
+			Ok(b"committee".to_vec())
+		);
+
+		assert_eq!(group.combine(round, &partials[..2]), Err(Error::NotEnoughPartialSignatures));
+		let mut forged = partials[1].clone();
+		forged.signature = shares[1].sign(round + 1).signature;
+		assert_eq!(
+			group.combine(round, &[partials[0].clone(), forged, partials[2].clone()]),
+			Err(Error::InvalidPartialSignature(2))
+		);
+	}
+
+	#[test]
+	pub fn complaints_are_resolved_or_disqualify() {
+		let params = Parameters::new(2, 4).unwrap();
+		// dealer 2 sends a bad share to participant 3, dealer 4 to participant 1
+		let bad = |dealer, index, share| match (dealer, index) {
+			(2, 3) | (4, 1) => share + <E as EngineBLS>::Scalar::from(1u64),
+			_ => share,
+		};
+		let (dealings, mut participants) = run(params, bad);
+		assert_eq!(participants[2].complaints().collect::<Vec<_>>(), [2]);
+		assert_eq!(participants[0].complaints().collect::<Vec<_>>(), [4]);
+
+		// dealer 2 reveals the right share, dealer 4 a wrong one
+		let reveal = |dealing: &Dealing<E>, index| dealing.share_for(index).unwrap();
+		participants[2]
+			.resolve(2, dealings[1].commitments.clone(), reveal(&dealings[1], 3))
+			.unwrap();
+		assert_eq!(
+			participants[0].resolve(
+				4,
+				dealings[3].commitments.clone(),
+				bad(4, 1, reveal(&dealings[3], 1))
+			),
+			Err(Error::InvalidShare(4))
+		);
+		assert_eq!(participants[2].complaints().count(), 0);
+		assert_eq!(participants[0].complaints().collect::<Vec<_>>(), [4]);
+
+		// dealer 4 is disqualified
+		let shares: Vec<KeyShare<E>> =
+			participants.into_iter().map(|p| p.finish(&[1, 2, 3]).unwrap()).collect();
+		let group = &shares[0].group;
+		let expected: <E as EngineBLS>::PublicKeyGroup =
+			dealings[..3].iter().map(|dealing| dealing.commitments[0]).sum();
+		assert_eq!(group.public_key(), expected);
+
+		let partials = [shares[0].sign(9), shares[3].sign(9)];
+		let signature = group.combine(9, &partials).unwrap();
+		assert_eq!(verify_signature::<E>(group.public_key(), 9, signature), Ok(()));
+	}
+
+	#[test]
+	pub fn invalid_parameters_are_rejected() {
+		assert_eq!(Parameters::new(0, 3), Err(Error::InvalidThreshold));
+		assert_eq!(Parameters::new(4, 3), Err(Error::InvalidThreshold));
+		let params = Parameters::new(2, 3).unwrap();
+		assert!(matches!(
+			deal::<E, _>(&params, 4, StdRng::seed_from_u64(0)),
+			Err(Error::InvalidIndex(4))
+		));
+		assert!(matches!(Participant::<E>::new(params, 0), Err(Error::InvalidIndex(0))));
+		let participant = Participant::<E>::new(params, 1).unwrap();
+		assert!(matches!(participant.finish(&[1]), Err(Error::MissingShare(1))));
+	}
+
+	#[test]
+	pub fn malformed_group_keys_are_rejected() {
+		let params = Parameters::new(2, 3).unwrap();
+		let (_, participants) = run(params, |_, _, share| share);
+		let shares: Vec<KeyShare<E>> =
+			participants.into_iter().map(|p| p.finish(&[1, 2, 3]).unwrap()).collect();
+		let group = shares[0].group.clone();
+
+		let mut bytes = Vec::new();
+		shares[0].serialize_compressed(&mut bytes).unwrap();
+		let share = KeyShare::<E>::deserialize_compressed(&bytes[..]).unwrap();
+		assert!(share.group == group);
+
+		let encode = |group: &GroupKey<E>| {
+			let mut bytes = Vec::new();
+			group.serialize_compressed(&mut bytes).unwrap();
+			bytes
+		};
+		let empty = GroupKey::<E> { threshold: 0, commitments: Vec::new() };
+		let short = GroupKey::<E> { threshold: 3, commitments: group.commitments.clone() };
+		for malformed in [&empty, &short] {
+			let bytes = encode(malformed);
+			assert!(GroupKey::<E>::deserialize_compressed(&bytes[..]).is_err());
+			assert!(GroupKey::<E>::deserialize_compressed_unchecked(&bytes[..]).is_err());
+		}
+		assert!(GroupKey::<E>::deserialize_compressed(&encode(&group)[..]).unwrap() == group);
+
+		// a threshold of zero would combine no partials into the identity
+		let empty = GroupKey::<E> { threshold: 0, commitments: group.commitments };
+		assert_eq!(empty.combine(1, &[]), Err(Error::InvalidThreshold));
+	}
+}
@@ -35,11 +35,19 @@ use core::marker::PhantomData;
 
 pub const QUICKNET_CTX: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
 
+/// The hash to curve domain separation tag for BLS12-377 signatures in G1
+pub const BLS12377_G1_CTX: &[u8] = b"BLS_SIG_BLS12377G1_XMD:SHA-256_SSWU_RO_NUL_";
+
 pub type TinyBLS381 = TinyBLSDrandQuicknet<ark_bls12_381::Bls12_381, ark_bls12_381::Config>;
 
+/// Tiny BLS signatures over BLS12-377, as used by the Ideal Network
+pub type TinyBLS377 = TinyBLSDrandQuicknet<ark_bls12_377::Bls12_377, ark_bls12_377::Config>;
+
 /// Trait to add extra config for a curve which is not in ArkWorks library
 pub trait CurveExtraConfig {
 	const CURVE_NAME: &'static [u8];
+	/// The domain separation tag for hashing to the signature curve
+	const HASH_TO_CURVE_CTX: &'static [u8] = QUICKNET_CTX;
 }
 
 /// Aggregate BLS signature scheme with Signature in G1 for BLS12-381 curve.
@@ -47,6 +55,12 @@ impl CurveExtraConfig for ark_bls12_381::Config {
 	const CURVE_NAME: &'static [u8] = b"BLS12381";
 }
 
+/// Aggregate BLS signature scheme with Signature in G1 for BLS12-377 curve.
+impl CurveExtraConfig for ark_bls12_377::Config {
+	const CURVE_NAME: &'static [u8] = b"BLS12377";
+	const HASH_TO_CURVE_CTX: &'static [u8] = BLS12377_G1_CTX;
+}
+
 /// A BLS variant with tiny 48 byte signatures and 96 byte public keys,
 ///
 /// Specifically, this configuration is used by Drand's QuickNet.
@@ -139,7 +153,7 @@ where
 			Self::SignatureGroup,
 			DefaultFieldHasher<Sha256, 128>,
 			WBMap<P::G1Config>,
-		>::new(QUICKNET_CTX)
+		>::new(P::HASH_TO_CURVE_CTX)
 		.unwrap()
 	}
 }
@@ -39,15 +39,14 @@ use alloc::{vec, vec::Vec};
 use ark_ec::{
 	hashing::{
 		map_to_curve_hasher::{MapToCurve, MapToCurveBasedHasher},
-		HashToCurve,
+		HashToCurve, HashToCurveError,
 	},
 	pairing::{MillerLoopOutput, Pairing, PairingOutput},
 	AffineRepr, CurveGroup,
 };
 use ark_ff::{field_hashers::HashToField, Field, PrimeField, UniformRand};
 use ark_serialize::CanonicalSerialize;
-use ark_std::rand::RngCore;
-use rand::Rng;
+use ark_std::rand::{Rng, RngCore};
 
 use core::fmt::Debug;
 
@@ -143,6 +142,24 @@ pub trait EngineBLS {
 		Self::hash_to_curve_map().hash(message.borrow()).unwrap().into_group()
 	}
 
+	/// Hash one message to the signature curve under another domain separation
+	/// tag, e.g. that of the RFC 9380 test vectors for the engine's suite.
+	///
+	/// * `dst`: The domain separation tag
+	/// * `message`: The message to hash
+	fn hash_to_signature_curve_with_dst<M: Borrow<[u8]>>(
+		dst: &[u8],
+		message: M,
+	) -> Result<Self::SignatureGroup, HashToCurveError> {
+		MapToCurveBasedHasher::<
+			Self::SignatureGroup,
+			Self::HashToSignatureField,
+			Self::MapToSignatureCurve,
+		>::new(dst)?
+		.hash(message.borrow())
+		.map(|point| point.into_group())
+	}
+
 	/// Run the Miller loop from `Engine` but orients its arguments
 	/// to be a `SignatureGroup` and `PublicKeyGroup`.
 	fn miller_loop<'a, I>(i: I) -> MillerLoopOutput<Self::Engine>
@@ -0,0 +1,145 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The errors of the crate.
+//!
+//! Each module reports its own error type, and every one of them implements
+//! [`core::error::Error`] (also without `std`). [`TimelockError`] wraps them
+//! all, so callers using several modules (e.g. the bindings) can handle a
+//! single type and still reach the underlying error through
+//! [`core::error::Error::source`].
+
+use crate::{
+	block_ciphers,
+	ibe::fullident::{IbeError, InputError},
+	tlock,
+};
+#[cfg(feature = "full")]
+use crate::{dkg, vault};
+use core::fmt;
+
+/// Any error reported by the crate
+#[derive(Debug, PartialEq)]
+pub enum TimelockError {
+	/// Timelock encryption or decryption failed
+	Tlock(tlock::Error),
+	/// IBE decryption failed
+	Ibe(IbeError),
+	/// An IBE input is malformed
+	Input(InputError),
+	/// A block cipher failed
+	BlockCipher(block_ciphers::Error),
+	/// A vault operation failed
+	#[cfg(feature = "full")]
+	Vault(vault::Error),
+	/// Key generation or threshold signing failed
+	#[cfg(feature = "full")]
+	Dkg(dkg::Error),
+}
+
+impl fmt::Display for TimelockError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TimelockError::Tlock(e) => fmt::Display::fmt(e, f),
+			TimelockError::Ibe(e) => fmt::Display::fmt(e, f),
+			TimelockError::Input(e) => fmt::Display::fmt(e, f),
+			TimelockError::BlockCipher(e) => fmt::Display::fmt(e, f),
+			#[cfg(feature = "full")]
+			TimelockError::Vault(e) => fmt::Display::fmt(e, f),
+			#[cfg(feature = "full")]
+			TimelockError::Dkg(e) => fmt::Display::fmt(e, f),
+		}
+	}
+}
+
+impl core::error::Error for TimelockError {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		Some(match self {
+			TimelockError::Tlock(e) => e,
+			TimelockError::Ibe(e) => e,
+			TimelockError::Input(e) => e,
+			TimelockError::BlockCipher(e) => e,
+			#[cfg(feature = "full")]
+			TimelockError::Vault(e) => e,
+			#[cfg(feature = "full")]
+			TimelockError::Dkg(e) => e,
+		})
+	}
+}
+
+impl From<tlock::Error> for TimelockError {
+	fn from(e: tlock::Error) -> Self {
+		TimelockError::Tlock(e)
+	}
+}
+
+impl From<IbeError> for TimelockError {
+	fn from(e: IbeError) -> Self {
+		TimelockError::Ibe(e)
+	}
+}
+
+impl From<InputError> for TimelockError {
+	fn from(e: InputError) -> Self {
+		TimelockError::Input(e)
+	}
+}
+
+impl From<block_ciphers::Error> for TimelockError {
+	fn from(e: block_ciphers::Error) -> Self {
+		TimelockError::BlockCipher(e)
+	}
+}
+
+#[cfg(feature = "full")]
+impl From<vault::Error> for TimelockError {
+	fn from(e: vault::Error) -> Self {
+		TimelockError::Vault(e)
+	}
+}
+
+#[cfg(feature = "full")]
+impl From<dkg::Error> for TimelockError {
+	fn from(e: dkg::Error) -> Self {
+		TimelockError::Dkg(e)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use alloc::string::ToString;
+	use core::error::Error as _;
+
+	#[test]
+	fn errors_display_and_chain() {
+		let error = TimelockError::from(vault::Error::Decryption(tlock::Error::InvalidSignature));
+		assert_eq!(
+			error.to_string(),
+			"the entry could not be decrypted: the signature cannot decrypt the ciphertext"
+		);
+		let vault = error.source().unwrap();
+		assert_eq!(
+			vault.source().unwrap().to_string(),
+			"the signature cannot decrypt the ciphertext"
+		);
+		assert_eq!(tlock::Error::from(IbeError::DecryptionFailed), tlock::Error::InvalidSignature);
+		assert_eq!(
+			TimelockError::from(block_ciphers::Error::BadNonce).to_string(),
+			"the nonce is invalid"
+		);
+	}
+}
@@ -0,0 +1,160 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Caching the public keys of identities.
+//!
+//! [`Identity::public`] hashes the identity to the signature curve on every
+//! call, which dominates the cost of encrypting a short message besides the
+//! pairing. A [`HashToCurveCache`] keeps the points of recently used
+//! identities, keyed by the digest of their message, so repeated encryptions
+//! to one round (see [`crate::tlock::tle_cached`]) hash it only once.
+
+use crate::{engines::EngineBLS, ibe::fullident::Identity, MessageDigest};
+use ark_std::collections::{BTreeMap, VecDeque};
+
+/// The public keys of the most recently inserted identities
+pub struct HashToCurveCache<E: EngineBLS> {
+	capacity: usize,
+	points: BTreeMap<MessageDigest, E::SignatureGroup>,
+	/// The cached digests, oldest first
+	order: VecDeque<MessageDigest>,
+}
+
+impl<E: EngineBLS> HashToCurveCache<E> {
+	/// A cache holding at most `capacity` points
+	///
+	/// * `capacity`: The number of identities to remember
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity, points: BTreeMap::new(), order: VecDeque::new() }
+	}
+
+	/// The public key of an identity, hashed to the curve on a miss
+	///
+	/// Once the cache is full, a miss evicts the oldest identity.
+	///
+	/// * `id`: The identity
+	pub fn public(&mut self, id: &Identity) -> E::SignatureGroup {
+		let digest = id.0 .0;
+		if let Some(point) = self.points.get(&digest) {
+			return *point;
+		}
+		let point = id.public::<E>();
+		if self.capacity > 0 {
+			if self.order.len() == self.capacity {
+				if let Some(oldest) = self.order.pop_front() {
+					self.points.remove(&oldest);
+				}
+			}
+			self.points.insert(digest, point);
+			self.order.push_back(digest);
+		}
+		point
+	}
+
+	/// The number of cached identities
+	pub fn len(&self) -> usize {
+		self.points.len()
+	}
+
+	/// Whether no identity is cached
+	pub fn is_empty(&self) -> bool {
+		self.points.is_empty()
+	}
+
+	/// Forget every identity
+	pub fn clear(&mut self) {
+		self.points.clear();
+		self.order.clear();
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle, tle_cached, BeaconConfig},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec::Vec,
+		UniformRand,
+	};
+
+	#[test]
+	fn caches_keep_the_most_recent_identities() {
+		let id = |n: u8| Identity::new(b"", &[n]);
+		let mut cache = HashToCurveCache::<TinyBLS381>::new(2);
+		assert!(cache.is_empty());
+
+		assert_eq!(cache.public(&id(1)), id(1).public::<TinyBLS381>());
+		assert_eq!(cache.public(&id(1)), id(1).public::<TinyBLS381>());
+		assert_eq!(cache.len(), 1);
+		let _ = cache.public(&id(2));
+		let _ = cache.public(&id(3));
+		assert_eq!(cache.len(), 2);
+		assert!(!cache.points.contains_key(&id(1).0 .0));
+		assert_eq!(cache.public(&id(3)), id(3).public::<TinyBLS381>());
+
+		cache.clear();
+		assert!(cache.is_empty());
+		let mut disabled = HashToCurveCache::<TinyBLS381>::new(0);
+		assert_eq!(disabled.public(&id(1)), id(1).public::<TinyBLS381>());
+		assert!(disabled.is_empty());
+	}
+
+	#[test]
+	fn cached_encryption_matches_tle() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let mut cache = HashToCurveCache::new(16);
+		let bytes = |ciphertext: &crate::tlock::TLECiphertext<TinyBLS381>| {
+			let mut bytes = Vec::new();
+			ciphertext.serialize_compressed(&mut bytes).unwrap();
+			bytes
+		};
+
+		let expected = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[4u8; 32],
+			b"cached",
+			id.clone(),
+			StdRng::seed_from_u64(9),
+		)
+		.unwrap();
+		for _ in 0..2 {
+			let ciphertext = tle_cached::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+				p_pub,
+				[4u8; 32],
+				b"cached",
+				&id,
+				&mut cache,
+				StdRng::seed_from_u64(9),
+			)
+			.unwrap();
+			assert_eq!(bytes(&ciphertext), bytes(&expected));
+		}
+		assert_eq!(cache.len(), 1);
+
+		let signature = id.extract::<TinyBLS381>(secret).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(expected, signature).unwrap();
+		assert_eq!(plaintext, b"cached");
+	}
+}
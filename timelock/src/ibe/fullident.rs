@@ -14,12 +14,14 @@
  * limitations under the License.
  */
 
-use super::utils::{cross_product_const, h2, h3, h4};
+use super::utils::{cross_product_const, h2, h3, h4, sha256};
 use alloc::vec;
-use ark_ec::PrimeGroup;
+use ark_ec::{hashing::HashToCurve, pairing::Pairing, AffineRepr, PrimeGroup};
+use ark_ff::{CyclotomicMultSubgroup, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{ops::Mul, rand::Rng, vec::Vec};
 use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{engines::EngineBLS, Hash, Message, HASH_LENGTH};
 
@@ -50,6 +52,27 @@ pub enum InputError {
 	InvalidLength,
 }
 
+impl core::fmt::Display for IbeError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			IbeError::DecryptionFailed =>
+				f.write_str("the IBE ciphertext could not be decrypted with the secret"),
+		}
+	}
+}
+
+impl core::error::Error for IbeError {}
+
+impl core::fmt::Display for InputError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			InputError::InvalidLength => f.write_str("the input has the wrong length"),
+		}
+	}
+}
+
+impl core::error::Error for InputError {}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Input<E: EngineBLS> {
 	data: SerializedFieldElement,
@@ -66,6 +89,14 @@ impl<E: EngineBLS> Input<E> {
 	}
 }
 
+impl<E: EngineBLS> Drop for Input<E> {
+	fn drop(&mut self) {
+		self.data.zeroize();
+	}
+}
+
+impl<E: EngineBLS> ZeroizeOnDrop for Input<E> {}
+
 /// A type to represent an IBE identity (for which we will encrypt message)
 #[derive(Debug, Clone)]
 pub struct Identity(pub Message);
@@ -76,11 +107,26 @@ impl Identity {
 		Self(Message::new(ctx, identity))
 	}
 
+	/// The identity a beacon signs in a round, sha256(round as u64 BE)
+	pub fn from_round(round: u64) -> Self {
+		Self::new(b"", &sha256(&round.to_be_bytes()))
+	}
+
 	/// The IBE extract function on a given secret key
 	pub fn extract<E: EngineBLS>(&self, sk: E::Scalar) -> IBESecret<E> {
 		IBESecret(self.public::<E>() * sk)
 	}
 
+	/// The IBE extract function for many identities under the same secret key
+	///
+	/// See [`extract_many`].
+	pub fn extract_batch<E: EngineBLS>(
+		identities: &[Identity],
+		sk: E::Scalar,
+	) -> Vec<IBESecret<E>> {
+		extract_many::<E>(identities, sk)
+	}
+
 	/// Derive the public key for this identity (hash to G1)
 	pub fn public<E: EngineBLS>(&self) -> E::SignatureGroup {
 		self.0.hash_to_signature_curve::<E>()
@@ -104,54 +150,191 @@ impl Identity {
 		E: EngineBLS,
 		R: Rng + Sized,
 	{
-		let bytes = message.as_bytes();
 		// sigma <- {0, 1}^d
 		let mut sigma = vec![0u8; E::SECRET_KEY_SIZE];
 		rng.fill_bytes(&mut sigma);
-		// r= H3(sigma, message)
-		let r: E::Scalar = h3::<E>(&sigma, bytes);
-		let p = E::PublicKeyGroup::generator();
-		// U = rP \in \mathbb{G}_1
-		let u = p * r;
-		// e(P_pub, Q_id)
-		let g_id = E::pairing(p_pub.mul(r), self.public::<E>());
-		// sigma (+) H2(e(P_pub, Q_id))
-		let v_rhs = h2(g_id);
-		let v = cross_product_const::<HASH_LENGTH>(&sigma, &v_rhs);
-		// message (+) H4(sigma)
-		let w_rhs = h4(&sigma);
-		let w = cross_product_const::<HASH_LENGTH>(bytes, &w_rhs);
-		// (rP, sigma (+) H2(e(Q_id, P_pub)), message (+) H4(sigma))
-		Ciphertext::<E> { u, v, w }
+		let ciphertext = self.encrypt_with_sigma(message, p_pub, &sigma);
+		sigma.zeroize();
+		ciphertext
+	}
+
+	/// BF-IBE encryption using the provided sigma
+	///
+	/// The encryption randomness is derived as r = H_3(sigma, message), so
+	/// callers that need to reason about r (e.g. to prove knowledge of it) can
+	/// sample sigma themselves.
+	pub(crate) fn encrypt_with_sigma<E: EngineBLS>(
+		&self,
+		message: &Input<E>,
+		p_pub: E::PublicKeyGroup,
+		sigma: &[u8],
+	) -> Ciphertext<E> {
+		encrypt_to_point(message, p_pub, self.public::<E>(), sigma)
 	}
 }
 
+/// BF-IBE encryption given the public key Q_id of the identity
+pub(crate) fn encrypt_for_point<E, R>(
+	message: &Input<E>,
+	p_pub: E::PublicKeyGroup,
+	q_id: E::SignatureGroup,
+	mut rng: R,
+) -> Ciphertext<E>
+where
+	E: EngineBLS,
+	R: Rng + Sized,
+{
+	// sigma <- {0, 1}^d
+	let mut sigma = vec![0u8; E::SECRET_KEY_SIZE];
+	rng.fill_bytes(&mut sigma);
+	let ciphertext = encrypt_to_point(message, p_pub, q_id, &sigma);
+	sigma.zeroize();
+	ciphertext
+}
+
+/// BF-IBE encryption using the provided sigma, given the public key Q_id of
+/// the identity
+pub(crate) fn encrypt_to_point<E: EngineBLS>(
+	message: &Input<E>,
+	p_pub: E::PublicKeyGroup,
+	q_id: E::SignatureGroup,
+	sigma: &[u8],
+) -> Ciphertext<E> {
+	// r= H3(sigma, message)
+	let mut r: E::Scalar = h3::<E>(sigma, message.as_bytes());
+	// e(P_pub, Q_id)
+	let g_id = E::pairing(p_pub.mul(r), q_id);
+	let ciphertext = encrypt_with_g_id(message, sigma, r, g_id);
+	r.zeroize();
+	ciphertext
+}
+
+/// BF-IBE encryption using the provided sigma, given e(P_pub, Q_id) of the
+/// identity
+///
+/// Exponentiating the pairing by r in the target group gives the same g_id as
+/// [`Identity::encrypt_with_sigma`] without a pairing or a scalar
+/// multiplication of `p_pub`.
+#[cfg_attr(not(feature = "full"), allow(dead_code))]
+pub(crate) fn encrypt_with_pairing<E: EngineBLS>(
+	message: &Input<E>,
+	pairing: &<E::Engine as Pairing>::TargetField,
+	sigma: &[u8],
+) -> Ciphertext<E> {
+	// r= H3(sigma, message)
+	let mut r: E::Scalar = h3::<E>(sigma, message.as_bytes());
+	// e(P_pub, Q_id)^r = e(rP_pub, Q_id)
+	let g_id = pairing.cyclotomic_exp(r.into_bigint());
+	let ciphertext = encrypt_with_g_id(message, sigma, r, g_id);
+	r.zeroize();
+	ciphertext
+}
+
+/// The ciphertext of a message given its randomness r and g_id = e(rP_pub,
+/// Q_id)
+///
+/// Anyone holding r or g_id can recover sigma and the message, so both are
+/// wiped along with the masks derived from them.
+fn encrypt_with_g_id<E: EngineBLS>(
+	message: &Input<E>,
+	sigma: &[u8],
+	mut r: E::Scalar,
+	mut g_id: <E::Engine as Pairing>::TargetField,
+) -> Ciphertext<E> {
+	let bytes = message.as_bytes();
+	let p = E::PublicKeyGroup::generator();
+	// U = rP \in \mathbb{G}_1
+	let u = p * r;
+	// sigma (+) H2(e(P_pub, Q_id))
+	let mut v_rhs = h2(g_id);
+	let v = cross_product_const::<HASH_LENGTH>(sigma, &v_rhs);
+	// message (+) H4(sigma)
+	let mut w_rhs = h4(sigma);
+	let w = cross_product_const::<HASH_LENGTH>(bytes, &w_rhs);
+	r.zeroize();
+	g_id.zeroize();
+	v_rhs.zeroize();
+	w_rhs.zeroize();
+	// (rP, sigma (+) H2(e(Q_id, P_pub)), message (+) H4(sigma))
+	Ciphertext::<E> { u, v, w }
+}
+
+/// The IBE extract function applied to a batch of identities
+///
+/// This is equivalent to calling [`Identity::extract`] for each identity, with
+/// the same scalar multiplication. Only the hash-to-curve map is built once for
+/// the batch; each identity still costs one hash to curve and one scalar
+/// multiplication. A single scalar multiplied into many different bases has no
+/// cheaper multi-scalar form, so there is no further work to share.
+///
+/// * `identities`: The identities to extract secrets for
+/// * `sk`: The master secret key
+pub fn extract_many<E: EngineBLS>(identities: &[Identity], sk: E::Scalar) -> Vec<IBESecret<E>> {
+	let hasher = E::hash_to_curve_map();
+	identities
+		.iter()
+		.map(|id| {
+			let q_id: E::SignatureGroup = hasher
+				.hash(&id.0 .1[..])
+				.expect("Hashing to the signature curve is infallible for the supported engines.")
+				.into_group();
+			IBESecret(q_id * sk)
+		})
+		.collect()
+}
+
 /// The output of the IBE extract algorithm is a BLS signature
+///
+/// The secret is not wiped on drop, so that the signature can still be moved
+/// out of the public field; call [`Zeroize::zeroize`] once a secret that is
+/// not yet published is no longer needed.
 #[derive(Debug, Clone, CanonicalDeserialize, CanonicalSerialize, Serialize, Deserialize)]
 pub struct IBESecret<E: EngineBLS>(pub E::SignatureGroup);
 
+impl<E: EngineBLS> Zeroize for IBESecret<E> {
+	fn zeroize(&mut self) {
+		self.0.zeroize();
+	}
+}
+
 impl<E: EngineBLS> IBESecret<E> {
 	/// BF-IBE decryption of a
 	/// * `ciphertext`: C = <U, V, W>
 	///
 	/// Attempts to decrypt under the given IBESecret (in G1)
 	pub fn decrypt(&self, ciphertext: &Ciphertext<E>) -> Result<Hash, IbeError> {
-		// sigma = V (+) H2(e(d_id, U))
-		let sigma_rhs = h2(E::pairing(ciphertext.u, self.0));
-		let sigma = cross_product_const::<HASH_LENGTH>(&ciphertext.v, &sigma_rhs);
-		// m = W (+) H4(sigma)
-		let m_rhs = h4(&sigma);
-		let m = cross_product_const::<HASH_LENGTH>(&ciphertext.w, &m_rhs);
-		// check: U == rP
-		let p = E::PublicKeyGroup::generator();
-		let r = h3::<E>(&sigma, &m);
-		let u_check = p * r;
-		if !u_check.eq(&ciphertext.u) {
-			return Err(IbeError::DecryptionFailed);
-		}
+		decrypt_with_pairing(ciphertext, E::pairing(ciphertext.u, self.0))
+	}
+}
 
-		Ok(m)
+/// BF-IBE decryption given the pairing e(d_id, U) of the ciphertext with the
+/// IBE secret of its identity
+pub(crate) fn decrypt_with_pairing<E: EngineBLS>(
+	ciphertext: &Ciphertext<E>,
+	mut pairing: <E::Engine as Pairing>::TargetField,
+) -> Result<Hash, IbeError> {
+	// sigma = V (+) H2(e(d_id, U))
+	let mut sigma_rhs = h2(pairing);
+	let mut sigma = cross_product_const::<HASH_LENGTH>(&ciphertext.v, &sigma_rhs);
+	// m = W (+) H4(sigma)
+	let mut m_rhs = h4(&sigma);
+	let mut m = cross_product_const::<HASH_LENGTH>(&ciphertext.w, &m_rhs);
+	// check: U == rP
+	let p = E::PublicKeyGroup::generator();
+	let mut r = h3::<E>(&sigma, &m);
+	let u_check = p * r;
+
+	pairing.zeroize();
+	sigma_rhs.zeroize();
+	sigma.zeroize();
+	m_rhs.zeroize();
+	r.zeroize();
+	if !u_check.eq(&ciphertext.u) {
+		m.zeroize();
+		return Err(IbeError::DecryptionFailed);
 	}
+
+	Ok(m)
 }
 
 #[cfg(test)]
@@ -215,6 +398,26 @@ mod test {
 		(msk, sk)
 	}
 
+	#[test]
+	fn secrets_are_wiped_on_drop() {
+		fn wiped_on_drop<T: ZeroizeOnDrop>() {}
+		wiped_on_drop::<Input<TinyBLS381>>();
+
+		// the wipes do not change the ciphertext or the decrypted message
+		let mut rng = test_rng();
+		let sk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * sk;
+		let id = Identity::new(b"", b"wiped");
+		let input = Input::<TinyBLS381>::new([7u8; 32]).unwrap();
+		let ciphertext = id.encrypt(&input, p_pub, &mut rng);
+		let mut secret = id.extract::<TinyBLS381>(sk);
+		assert_eq!(secret.decrypt(&ciphertext), Ok([7u8; 32]));
+
+		// the extracted secret is wiped on request
+		secret.zeroize();
+		assert!(secret.decrypt(&ciphertext).is_err());
+	}
+
 	#[test]
 	pub fn fullident_identity_construction_works() {
 		let identity = Identity::new(b"", &[1, 2, 3]);
@@ -222,6 +425,23 @@ mod test {
 		assert_eq!(identity.0, expected_message);
 	}
 
+	#[test]
+	pub fn fullident_extract_many_matches_extract() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut test_rng());
+		let identities: Vec<Identity> =
+			(0u64..8).map(|round| Identity::new(b"", &round.to_be_bytes())).collect();
+
+		let batch = extract_many::<TinyBLS381>(&identities, msk);
+		assert_eq!(batch.len(), identities.len());
+		for (id, secret) in identities.iter().zip(batch.iter()) {
+			assert_eq!(secret.0, id.extract::<TinyBLS381>(msk).0);
+		}
+
+		let batch = Identity::extract_batch::<TinyBLS381>(&identities, msk);
+		assert_eq!(batch[3].0, identities[3].extract::<TinyBLS381>(msk).0);
+		assert!(extract_many::<TinyBLS381>(&[], msk).is_empty());
+	}
+
 	#[test]
 	pub fn fullident_encrypt_and_decrypt() {
 		let identity = Identity::new(b"", &[1, 2, 3]);
@@ -14,5 +14,6 @@
  * limitations under the License.
  */
 
+pub mod cache;
 pub mod fullident;
 pub(crate) mod utils;
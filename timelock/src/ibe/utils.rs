@@ -27,32 +27,41 @@ pub fn sha256(b: &[u8]) -> Vec<u8> {
 	hasher.finalize().to_vec()
 }
 
+/// The width of the lanes [`xor_in_place`] works on: one 128-bit SIMD register
+/// (SSE2, NEON, or wasm simd128)
+const LANE: usize = 16;
+
 #[inline(always)]
 pub fn cross_product_const<const N: usize>(a: &[u8], b: &[u8]) -> [u8; N] {
 	let mut result = [0u8; N];
+	result.copy_from_slice(&a[..N]);
+	xor_in_place(&mut result, &b[..N]);
+	result
+}
 
-	// Process 8 bytes at a time using u64 for better performance
-	let chunks = N / 8;
-	let remainder = N % 8;
-
-	// Process full 8-byte chunks
-	for i in 0..chunks {
-		let start_idx = i * 8;
-		let end_idx = start_idx + 8;
-
-		let a_chunk = u64::from_ne_bytes(a[start_idx..end_idx].try_into().unwrap());
-		let b_chunk = u64::from_ne_bytes(b[start_idx..end_idx].try_into().unwrap());
-		let result_chunk = (a_chunk ^ b_chunk).to_ne_bytes();
-		result[start_idx..end_idx].copy_from_slice(&result_chunk);
+/// XOR `b` into `a`, for buffers of any length
+///
+/// Whole lanes are XORed as fixed-size arrays, which compiles to one SIMD XOR
+/// per lane on x86_64, aarch64 and wasm32 built with `+simd128` (see
+/// `wasm/wasm_build_simd.sh`); the tail is XORed bytewise.
+///
+/// * `a`: The buffer to XOR into
+/// * `b`: The bytes to XOR with, at least as long as `a`
+#[inline]
+pub fn xor_in_place(a: &mut [u8], b: &[u8]) {
+	let b = &b[..a.len()];
+	let mut a_lanes = a.chunks_exact_mut(LANE);
+	let mut b_lanes = b.chunks_exact(LANE);
+	for (x, y) in (&mut a_lanes).zip(&mut b_lanes) {
+		let x: &mut [u8; LANE] = x.try_into().expect("The lane has LANE bytes.");
+		let y: &[u8; LANE] = y.try_into().expect("The lane has LANE bytes.");
+		for (x, y) in x.iter_mut().zip(y) {
+			*x ^= y;
+		}
 	}
-
-	// Handle remaining bytes
-	let remainder_start = chunks * 8;
-	for j in 0..remainder {
-		result[remainder_start + j] = a[remainder_start + j] ^ b[remainder_start + j];
+	for (x, y) in a_lanes.into_remainder().iter_mut().zip(b_lanes.remainder()) {
+		*x ^= y;
 	}
-
-	result
 }
 
 /// a map from G -> {0, 1}^{32}
@@ -95,4 +104,19 @@ mod test {
 		];
 		assert_eq!(actual, expected);
 	}
+
+	#[test]
+	fn utils_can_xor_buffers_of_any_length() {
+		for len in [0, 1, 15, 16, 17, 32, 100] {
+			let a: vec::Vec<u8> = (0..len).map(|i| i as u8).collect();
+			let b: vec::Vec<u8> = (0..len + 3).map(|i| (i * 7 + 1) as u8).collect();
+			let mut actual = a.clone();
+			crate::ibe::utils::xor_in_place(&mut actual, &b);
+			let expected: vec::Vec<u8> = a.iter().zip(&b).map(|(x, y)| x ^ y).collect();
+			assert_eq!(actual, expected, "length {}", len);
+		}
+		let a = [0x5au8; 32];
+		let b = [0xffu8; 32];
+		assert_eq!(crate::ibe::utils::cross_product_const::<32>(&a, &b), [0xa5u8; 32]);
+	}
 }
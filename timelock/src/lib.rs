@@ -19,12 +19,38 @@
 #![deny(unsafe_code)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+#[cfg(feature = "full")]
+pub mod beacon;
+#[cfg(feature = "full")]
+pub mod beacons;
 pub mod block_ciphers;
+#[cfg(feature = "full")]
+pub mod blocks;
+#[cfg(feature = "full")]
+pub mod dkg;
 pub mod engines;
+pub mod error;
 pub mod ibe;
+#[cfg(feature = "full")]
+pub mod puzzles;
+#[cfg(feature = "full")]
+pub mod rounds;
+#[cfg(any(feature = "runtime", test))]
+pub mod runtime;
+#[cfg(feature = "full")]
+pub mod self_test;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
 pub mod tlock;
+#[cfg(feature = "full")]
+pub mod vault;
 use crate::engines::EngineBLS;
+pub use error::TimelockError;
+#[cfg(feature = "full")]
+pub use self_test::{self_test, SelfTestReport};
 
 /// The length of hashes output from sha256
 const HASH_LENGTH: usize = 32;
@@ -0,0 +1,340 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Rivest-Shamir-Wagner time-lock puzzles.
+//!
+//! This module provides a beacon-free timelock: instead of waiting for a
+//! signature to be published, the decryptor must perform `t` sequential
+//! modular squarings `b = a^(2^t) mod N`. The encryptor knows the
+//! factorization of `N` and can compute `b` quickly as `a^(2^t mod phi(N))`.
+//! The message key is masked with a hash of `b` by the [`Puzzle`] witness
+//! scheme, so puzzles share the [`Envelope`] format and
+//! [`BlockCipherProvider`]s used by [`crate::tlock`].
+//!
+//! Unlike beacon-based timelock encryption, the unlock time depends on the
+//! speed of the solver's hardware, so `squarings` must be calibrated against
+//! the fastest expected adversary (see [`squarings_for`]).
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	ibe::utils::sha256,
+	tlock::{open, seal, Envelope, Error, OpaqueSecretKey, WitnessScheme},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use num_bigint::{BigUint, RandBigInt};
+
+/// The smallest modulus accepted when generating or solving a puzzle
+pub const MIN_MODULUS_BITS: u64 = 2048;
+/// The floor applied by this module; the tests lower it, as 2048-bit primes
+/// are slow to generate
+#[cfg(not(test))]
+const MODULUS_FLOOR: u64 = MIN_MODULUS_BITS;
+#[cfg(test)]
+const MODULUS_FLOOR: u64 = 512;
+/// The number of Miller-Rabin rounds used when generating primes
+const MILLER_RABIN_ROUNDS: usize = 40;
+/// Odd primes used to sieve candidates before running Miller-Rabin
+const SMALL_PRIMES: [u32; 24] =
+	[3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+
+/// Parameters of a time-lock puzzle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PuzzleParams {
+	/// The bit length of the RSA modulus N
+	pub modulus_bits: u64,
+	/// The number of sequential squarings needed to solve the puzzle
+	pub squarings: u64,
+}
+
+impl PuzzleParams {
+	/// Parameters with a 2048-bit modulus and the given number of squarings
+	pub fn new(squarings: u64) -> Self {
+		Self { modulus_bits: 2048, squarings }
+	}
+}
+
+/// The header of a message locked by a time-lock puzzle
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PuzzleHeader {
+	/// The big-endian RSA modulus N
+	pub modulus: Vec<u8>,
+	/// The big-endian base a
+	pub base: Vec<u8>,
+	/// The number of squarings t
+	pub squarings: u64,
+	/// The message key masked with H(N, b)
+	pub masked_key: Vec<u8>,
+}
+
+/// A message locked by a time-lock puzzle
+pub type PuzzleCiphertext = Envelope<PuzzleHeader>;
+
+/// Encapsulation to a puzzle, where the witness is the big-endian solution
+/// `b = a^(2^t) mod N` output by [`solve`]
+pub struct Puzzle;
+
+impl WitnessScheme for Puzzle {
+	type PublicParams = ();
+	type Statement = PuzzleParams;
+	type Witness = Vec<u8>;
+	type Header = PuzzleHeader;
+
+	fn encapsulate<R: Rng + CryptoRng>(
+		_: &(),
+		params: &PuzzleParams,
+		key: OpaqueSecretKey,
+		rng: &mut R,
+	) -> Result<PuzzleHeader, Error> {
+		if params.modulus_bits < MODULUS_FLOOR {
+			return Err(Error::InvalidPuzzleParameters);
+		}
+
+		let one = BigUint::from(1u8);
+		let (p, q) = loop {
+			let p = generate_prime(params.modulus_bits / 2, rng);
+			let q = generate_prime(params.modulus_bits - params.modulus_bits / 2, rng);
+			if p != q {
+				break (p, q);
+			}
+		};
+		let n = &p * &q;
+		let phi = (&p - &one) * (&q - &one);
+		// since N has no small factors, any base in [2, N - 1] is a unit
+		let a = rng.gen_biguint_range(&BigUint::from(2u8), &(&n - &one));
+		// the trapdoor: b = a^(2^t mod phi(N)) mod N
+		let e = BigUint::from(2u8).modpow(&BigUint::from(params.squarings), &phi);
+		let b = a.modpow(&e, &n);
+
+		Ok(PuzzleHeader {
+			modulus: n.to_bytes_be(),
+			base: a.to_bytes_be(),
+			squarings: params.squarings,
+			masked_key: xor(&key, &mask(&n, &b)).to_vec(),
+		})
+	}
+
+	fn decapsulate(header: &PuzzleHeader, witness: &Vec<u8>) -> Result<OpaqueSecretKey, Error> {
+		if header.masked_key.len() != 32 {
+			return Err(Error::DeserializationError);
+		}
+		let (n, _) = check(header)?;
+		let b = BigUint::from_bytes_be(witness);
+		Ok(xor(&header.masked_key, &mask(&n, &b)))
+	}
+}
+
+/// Estimate the number of squarings needed to lock a message for `seconds`
+///
+/// * `seconds`: The desired lock duration
+/// * `squarings_per_second`: The squaring speed of the fastest expected solver
+pub fn squarings_for(seconds: u64, squarings_per_second: u64) -> u64 {
+	seconds.saturating_mul(squarings_per_second)
+}
+
+/// Lock a message in a time-lock puzzle
+///
+/// * `params`: The puzzle parameters
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn tle<S, R>(
+	params: PuzzleParams,
+	message: &[u8],
+	mut rng: R,
+) -> Result<PuzzleCiphertext, Error>
+where
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let mut key = [0u8; 32];
+	rng.fill_bytes(&mut key);
+	seal::<Puzzle, S, R>(&(), &params, key, message, rng)
+}
+
+/// Solve a puzzle by sequential squaring, returning `b = a^(2^t) mod N`
+///
+/// Fails with [`Error::InvalidPuzzleParameters`] if the modulus is even or
+/// shorter than [`MIN_MODULUS_BITS`], or the base is not less than it.
+///
+/// * `header`: The header of a puzzle ciphertext
+pub fn solve(header: &PuzzleHeader) -> Result<Vec<u8>, Error> {
+	let (n, mut b) = check(header)?;
+	for _ in 0..header.squarings {
+		b = (&b * &b) % &n;
+	}
+	Ok(b.to_bytes_be())
+}
+
+/// Parse the modulus and base of an untrusted header (internal helper)
+fn check(header: &PuzzleHeader) -> Result<(BigUint, BigUint), Error> {
+	let n = BigUint::from_bytes_be(&header.modulus);
+	let base = BigUint::from_bytes_be(&header.base);
+	// an odd modulus of the full length is also non-zero
+	if !n.bit(0) || n.bits() < MODULUS_FLOOR || base >= n {
+		return Err(Error::InvalidPuzzleParameters);
+	}
+	Ok((n, base))
+}
+
+/// Solve a time-lock puzzle and decrypt the message
+///
+/// * `ciphertext`: A ciphertext output by [`tle`]
+pub fn tld<S>(ciphertext: PuzzleCiphertext) -> Result<Vec<u8>, Error>
+where
+	S: BlockCipherProvider<32>,
+{
+	let solution = solve(&ciphertext.header)?;
+	open::<Puzzle, S>(ciphertext, &solution)
+}
+
+/// H(N, b)
+fn mask(n: &BigUint, b: &BigUint) -> [u8; 32] {
+	let n_bytes = n.to_bytes_be();
+	let mut input = Vec::new();
+	input.extend_from_slice(&(n_bytes.len() as u64).to_le_bytes());
+	input.extend_from_slice(&n_bytes);
+	// left pad b to the length of N so the encoding is canonical
+	let b_bytes = b.to_bytes_be();
+	input.resize(input.len() + n_bytes.len().saturating_sub(b_bytes.len()), 0);
+	input.extend_from_slice(&b_bytes);
+
+	let mut out = [0u8; 32];
+	out.copy_from_slice(&sha256(&input));
+	out
+}
+
+fn xor(a: &[u8], b: &[u8; 32]) -> [u8; 32] {
+	let mut out = [0u8; 32];
+	for (o, (x, y)) in out.iter_mut().zip(a.iter().zip(b.iter())) {
+		*o = x ^ y;
+	}
+	out
+}
+
+/// Sample a random prime with exactly `bits` bits
+fn generate_prime<R: Rng + CryptoRng>(bits: u64, rng: &mut R) -> BigUint {
+	loop {
+		let mut candidate = rng.gen_biguint(bits);
+		// set the two top bits so the product of two primes has the full length
+		candidate.set_bit(bits - 1, true);
+		candidate.set_bit(bits - 2, true);
+		candidate.set_bit(0, true);
+		if is_probable_prime(&candidate, rng) {
+			return candidate;
+		}
+	}
+}
+
+/// Miller-Rabin primality test for odd candidates
+fn is_probable_prime<R: Rng + CryptoRng>(n: &BigUint, rng: &mut R) -> bool {
+	for small in SMALL_PRIMES {
+		if (n % small) == BigUint::ZERO {
+			return *n == BigUint::from(small);
+		}
+	}
+
+	let one = BigUint::from(1u8);
+	let two = BigUint::from(2u8);
+	let n_minus_one = n - &one;
+	let s = n_minus_one.trailing_zeros().expect("n - 1 is non-zero");
+	let d = &n_minus_one >> s;
+
+	'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+		let a = rng.gen_biguint_range(&two, &n_minus_one);
+		let mut x = a.modpow(&d, n);
+		if x == one || x == n_minus_one {
+			continue;
+		}
+		for _ in 1..s {
+			x = x.modpow(&two, n);
+			if x == n_minus_one {
+				continue 'witness;
+			}
+		}
+		return false;
+	}
+	true
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::block_ciphers::AESGCMBlockCipherProvider;
+	use ark_std::{rand::rngs::OsRng, vec};
+
+	const TEST_PARAMS: PuzzleParams = PuzzleParams { modulus_bits: 512, squarings: 1000 };
+
+	#[test]
+	pub fn puzzle_encrypt_decrypt_works() {
+		let ct = tle::<AESGCMBlockCipherProvider, OsRng>(TEST_PARAMS, b"no beacon needed", OsRng)
+			.unwrap();
+		assert_eq!(ct.header.squarings, 1000);
+		assert_eq!(BigUint::from_bytes_be(&ct.header.modulus).bits(), 512);
+
+		let plaintext = tld::<AESGCMBlockCipherProvider>(ct).unwrap();
+		assert_eq!(plaintext, b"no beacon needed".to_vec());
+	}
+
+	#[test]
+	pub fn puzzle_decryption_fails_with_too_few_squarings() {
+		let mut ct =
+			tle::<AESGCMBlockCipherProvider, OsRng>(TEST_PARAMS, b"no beacon needed", OsRng)
+				.unwrap();
+		ct.header.squarings -= 1;
+		assert_eq!(tld::<AESGCMBlockCipherProvider>(ct), Err(Error::DecryptionError));
+	}
+
+	#[test]
+	pub fn puzzle_rejects_small_modulus() {
+		let params = PuzzleParams { modulus_bits: 256, squarings: 10 };
+		let result = tle::<AESGCMBlockCipherProvider, OsRng>(params, b"", OsRng);
+		assert_eq!(result, Err(Error::InvalidPuzzleParameters));
+		assert_eq!(MIN_MODULUS_BITS, PuzzleParams::new(1).modulus_bits);
+	}
+
+	#[test]
+	pub fn puzzle_rejects_malformed_headers() {
+		let ct = tle::<AESGCMBlockCipherProvider, OsRng>(TEST_PARAMS, b"no beacon needed", OsRng)
+			.unwrap();
+		let n = BigUint::from_bytes_be(&ct.header.modulus);
+
+		let malformed = [
+			PuzzleHeader { modulus: Vec::new(), ..ct.header.clone() },
+			PuzzleHeader { modulus: vec![0; 64], ..ct.header.clone() },
+			PuzzleHeader { modulus: (&n + 1u8).to_bytes_be(), ..ct.header.clone() },
+			PuzzleHeader { modulus: vec![0xff; 32], ..ct.header.clone() },
+			PuzzleHeader { base: n.to_bytes_be(), ..ct.header.clone() },
+		];
+		for header in malformed {
+			assert_eq!(solve(&header), Err(Error::InvalidPuzzleParameters));
+			let ct =
+				Envelope { header, body: ct.body.clone(), cipher_suite: ct.cipher_suite.clone() };
+			assert_eq!(tld::<AESGCMBlockCipherProvider>(ct), Err(Error::InvalidPuzzleParameters));
+		}
+	}
+
+	#[test]
+	pub fn puzzle_primality_test_works() {
+		let prime = BigUint::parse_bytes(b"170141183460469231731687303715884105727", 10).unwrap();
+		let composite = &prime * BigUint::from(101u8);
+		assert!(is_probable_prime(&prime, &mut OsRng));
+		assert!(!is_probable_prime(&composite, &mut OsRng));
+		assert_eq!(squarings_for(60, 1_000_000), 60_000_000);
+	}
+}
@@ -0,0 +1,106 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Conversions between unix times and beacon rounds.
+//!
+//! Round 1 of a drand style beacon is published at the genesis time and round
+//! `r` at `genesis + (r - 1) * period`. Times are unix seconds provided by the
+//! caller, so this works without a clock (e.g. in no_std or on-chain).
+
+/// Converts between unix times and the rounds of a beacon
+///
+/// The period must be non-zero; the methods panic otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundCalculator {
+	/// The unix time of round 1
+	pub genesis: u64,
+	/// The number of seconds between rounds
+	pub period: u64,
+}
+
+impl RoundCalculator {
+	/// A calculator for a beacon
+	///
+	/// * `genesis`: The unix time of round 1
+	/// * `period`: The number of seconds between rounds
+	pub const fn new(genesis: u64, period: u64) -> Self {
+		assert!(period > 0, "The period of a beacon must be non-zero.");
+		Self { genesis, period }
+	}
+
+	/// The latest round published at `time`, or 0 before genesis
+	pub fn round_at(&self, time: u64) -> u64 {
+		match time.checked_sub(self.genesis) {
+			Some(elapsed) => elapsed / self.period + 1,
+			None => 0,
+		}
+	}
+
+	/// The first round published at or after `time`
+	///
+	/// This is the round to encrypt for so that a ciphertext unlocks at `time`.
+	pub fn round_after(&self, time: u64) -> u64 {
+		match time.checked_sub(self.genesis) {
+			Some(elapsed) => elapsed.div_ceil(self.period) + 1,
+			None => 1,
+		}
+	}
+
+	/// The unix time at which `round` is published (genesis for round 0)
+	pub fn time_of(&self, round: u64) -> u64 {
+		self.genesis.saturating_add(round.saturating_sub(1).saturating_mul(self.period))
+	}
+
+	/// The number of rounds published after `start`, up to and including `end`
+	pub fn rounds_between(&self, start: u64, end: u64) -> u64 {
+		self.round_at(end).saturating_sub(self.round_at(start))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	// drand quicknet
+	const QUICKNET: RoundCalculator = RoundCalculator::new(1692803367, 3);
+
+	#[test]
+	pub fn rounds_and_times_agree() {
+		assert_eq!(QUICKNET.round_at(QUICKNET.genesis - 1), 0);
+		assert_eq!(QUICKNET.round_at(QUICKNET.genesis), 1);
+		assert_eq!(QUICKNET.round_at(QUICKNET.genesis + 2), 1);
+		assert_eq!(QUICKNET.round_at(QUICKNET.genesis + 3), 2);
+		assert_eq!(QUICKNET.time_of(1), QUICKNET.genesis);
+		assert_eq!(QUICKNET.time_of(1000), 1692806364);
+
+		for round in [1, 2, 1000, 12_345_678] {
+			let time = QUICKNET.time_of(round);
+			assert_eq!(QUICKNET.round_at(time), round);
+			assert_eq!(QUICKNET.round_after(time), round);
+			assert_eq!(QUICKNET.round_after(time - 1), round);
+			assert_eq!(QUICKNET.round_after(time + 1), round + 1);
+		}
+	}
+
+	#[test]
+	pub fn rounds_between_counts_published_rounds() {
+		let start = QUICKNET.time_of(10);
+		assert_eq!(QUICKNET.rounds_between(start, start + 2), 0);
+		assert_eq!(QUICKNET.rounds_between(start, start + 3), 1);
+		assert_eq!(QUICKNET.rounds_between(start, start + 3600), 1200);
+		assert_eq!(QUICKNET.rounds_between(start + 3600, start), 0);
+	}
+}
@@ -0,0 +1,136 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Decryption for Substrate runtimes.
+//!
+//! Pallets decrypt on-chain but never encrypt. Built with
+//! `default-features = false, features = ["runtime"]`, the crate compiles
+//! only its core, without the `full` modules and without any RNG
+//! or entropy dependency of its own (arkworks links `rand`, but no generator
+//! is ever run).
+//!
+//! The functions here fix the engine to [`TinyBLS381`] (drand's quicknet) and
+//! the cipher to AES-GCM, parse every input strictly (see [`strict`]), and
+//! decrypt in place into the caller's buffer: besides that buffer, a
+//! decryption uses a constant amount of memory, whatever the ciphertext.
+
+use crate::{
+	engines::drand::TinyBLS381,
+	tlock::{
+		buffer::{tld_into, tld_len},
+		strict, verify_signature, Error,
+	},
+};
+
+/// The engine of runtime ciphertexts
+pub type Engine = TinyBLS381;
+
+/// The length of the message of a serialized ciphertext, i.e. the size of the
+/// buffer [`tld`] needs
+///
+/// * `ciphertext`: The compressed ciphertext
+pub fn plaintext_len(ciphertext: &[u8]) -> Result<usize, Error> {
+	tld_len::<Engine>(ciphertext)
+}
+
+/// Decrypt a serialized ciphertext into `out`, returning the length of the
+/// message
+///
+/// * `ciphertext`: The compressed ciphertext
+/// * `signature`: The compressed signature of the round it is locked to
+/// * `out`: Where to write the message, at least [`plaintext_len`] bytes
+pub fn tld(ciphertext: &[u8], signature: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+	let signature = strict::signature::<Engine>(signature)?;
+	tld_into::<Engine>(ciphertext, signature, out)
+}
+
+/// Check that a signature is the beacon's signature of a round, then decrypt
+/// a serialized ciphertext into `out`, returning the length of the message
+///
+/// * `ciphertext`: The compressed ciphertext
+/// * `p_pub`: The compressed public key of the beacon
+/// * `round`: The round the ciphertext is locked to
+/// * `signature`: The compressed signature of the round
+/// * `out`: Where to write the message, at least [`plaintext_len`] bytes
+pub fn tld_verified(
+	ciphertext: &[u8],
+	p_pub: &[u8],
+	round: u64,
+	signature: &[u8],
+	out: &mut [u8],
+) -> Result<usize, Error> {
+	let p_pub = strict::public_key::<Engine>(p_pub)?;
+	let signature = strict::signature::<Engine>(signature)?;
+	verify_signature::<Engine>(p_pub, round, signature)?;
+	tld_into::<Engine>(ciphertext, signature, out)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::EngineBLS,
+		tlock::{round_identity, tle},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec,
+		vec::Vec,
+		UniformRand,
+	};
+
+	fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		value.serialize_compressed(&mut bytes).unwrap();
+		bytes
+	}
+
+	#[test]
+	fn runtime_decrypts_into_the_callers_buffer() {
+		let mut rng = StdRng::seed_from_u64(6);
+		let secret = <Engine as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <Engine as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let locked = tle::<Engine, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[6; 32],
+			b"on-chain",
+			round_identity(7),
+			rng,
+		)
+		.unwrap();
+		let ciphertext = bytes(&locked);
+		let signature = bytes(&round_identity(7).extract::<Engine>(secret).0);
+		let other = bytes(&round_identity(8).extract::<Engine>(secret).0);
+
+		assert_eq!(plaintext_len(&ciphertext), Ok(8));
+		let mut out = vec![0u8; 8];
+		assert_eq!(tld(&ciphertext, &signature, &mut out), Ok(8));
+		assert_eq!(&out, b"on-chain");
+		assert_eq!(tld(&ciphertext, &signature, &mut out[..7]), Err(Error::BufferTooSmall));
+		assert_eq!(tld(&ciphertext, &other, &mut out), Err(Error::InvalidSignature));
+		assert_eq!(tld(&ciphertext, &signature[1..], &mut out), Err(Error::DeserializationError));
+
+		let p_pub = bytes(&p_pub);
+		assert_eq!(tld_verified(&ciphertext, &p_pub, 7, &signature, &mut out), Ok(8));
+		assert_eq!(
+			tld_verified(&ciphertext, &p_pub, 8, &signature, &mut out),
+			Err(Error::InvalidSignature)
+		);
+	}
+}
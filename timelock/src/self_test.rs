@@ -0,0 +1,212 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A power-on self test.
+//!
+//! [`self_test`] runs one known-answer test for each compiled-in engine and
+//! cipher. A fixed seed drives a ChaCha20 RNG from which the beacon's master
+//! secret, the ephemeral secret key and all of the randomness of [`tle`] are
+//! drawn, in the same order as the `test-vectors` feature's vectors. The
+//! ciphertext must hash to a known digest, and must decrypt to the message
+//! with the signature extracted from the master secret.
+
+use crate::{
+	block_ciphers::{
+		AESGCMBlockCipherProvider, BlockCipherProvider, ChaCha20Poly1305BlockCipherProvider,
+	},
+	engines::{
+		drand::{TinyBLS377, TinyBLS381},
+		EngineBLS,
+	},
+	ibe::{fullident::Identity, utils::sha256},
+	tlock::{tld, tle},
+};
+use alloc::{string::String, vec, vec::Vec};
+use ark_ec::PrimeGroup;
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use core::fmt;
+use rand_chacha::{
+	rand_core::{RngCore, SeedableRng},
+	ChaCha20Rng,
+};
+use serde::Serialize;
+
+/// The seed of every known-answer test
+const SEED: [u8; 32] = [0x5e; 32];
+/// The message encrypted by every known-answer test
+const MESSAGE: &[u8] = b"timelock self test";
+/// The round the message is encrypted for
+const ROUND: u64 = 1000;
+
+/// The hex sha256 digests of the known-answer ciphertexts
+const BLS12_381_AES_GCM: &str = "0360ac8b2f7fbfe974845b5ee72abb09e7ea0245d81f29e7bf25428f2504ec7f";
+const BLS12_381_CHACHA20_POLY1305: &str =
+	"addbd94b7399af13d4643bbd5d25fb81ff79bc7f922cb60f81ae14218eab9d70";
+const BLS12_377_AES_GCM: &str = "ba4efc409326e7d0286154218e63a1b658bd609a769f67b888c5bea68ccf2422";
+const BLS12_377_CHACHA20_POLY1305: &str =
+	"21510b2e8843e72a922937815ed11d167b5613b9b9a762d3b7c0f01fedaa5070";
+
+/// Why a known-answer test failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Failure {
+	/// The message could not be encrypted
+	Encryption,
+	/// The ciphertext differs from the known answer
+	CiphertextMismatch,
+	/// The ciphertext could not be decrypted
+	Decryption,
+	/// The ciphertext decrypted to another message
+	PlaintextMismatch,
+}
+
+impl fmt::Display for Failure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Failure::Encryption => write!(f, "encryption failed"),
+			Failure::CiphertextMismatch => write!(f, "ciphertext mismatch"),
+			Failure::Decryption => write!(f, "decryption failed"),
+			Failure::PlaintextMismatch => write!(f, "plaintext mismatch"),
+		}
+	}
+}
+
+/// The outcome of the known-answer test of one engine and cipher
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KnownAnswer {
+	/// The curve of the engine, e.g. `BLS12381`
+	pub engine: String,
+	/// The cipher suite of the body
+	pub cipher_suite: String,
+	/// Why the test failed, if it did
+	pub failure: Option<Failure>,
+}
+
+/// The outcomes of every known-answer test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelfTestReport {
+	/// One outcome per engine and cipher
+	pub checks: Vec<KnownAnswer>,
+}
+
+impl SelfTestReport {
+	/// Whether every known-answer test passed
+	pub fn passed(&self) -> bool {
+		self.checks.iter().all(|check| check.failure.is_none())
+	}
+
+	/// The known-answer tests which failed
+	pub fn failures(&self) -> impl Iterator<Item = &KnownAnswer> {
+		self.checks.iter().filter(|check| check.failure.is_some())
+	}
+}
+
+/// Run the known-answer tests of every compiled-in engine and cipher
+pub fn self_test() -> SelfTestReport {
+	SelfTestReport {
+		checks: vec![
+			known_answer::<TinyBLS381, AESGCMBlockCipherProvider>(BLS12_381_AES_GCM),
+			known_answer::<TinyBLS381, ChaCha20Poly1305BlockCipherProvider>(
+				BLS12_381_CHACHA20_POLY1305,
+			),
+			known_answer::<TinyBLS377, AESGCMBlockCipherProvider>(BLS12_377_AES_GCM),
+			known_answer::<TinyBLS377, ChaCha20Poly1305BlockCipherProvider>(
+				BLS12_377_CHACHA20_POLY1305,
+			),
+		],
+	}
+}
+
+fn known_answer<E, S>(digest: &str) -> KnownAnswer
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	KnownAnswer {
+		engine: String::from_utf8_lossy(E::CURVE_NAME).into(),
+		cipher_suite: String::from_utf8_lossy(S::CIPHER_SUITE).into(),
+		failure: run::<E, S>(digest).err(),
+	}
+}
+
+fn run<E, S>(digest: &str) -> Result<(), Failure>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let mut rng = ChaCha20Rng::from_seed(SEED);
+	let msk = E::Scalar::rand(&mut rng);
+	let p_pub = E::PublicKeyGroup::generator() * msk;
+	let mut secret_key = [0u8; 32];
+	rng.fill_bytes(&mut secret_key);
+
+	let id = Identity::new(b"", &ROUND.to_be_bytes());
+	let ciphertext = tle::<E, S, _>(p_pub, secret_key, MESSAGE, id.clone(), &mut rng)
+		.map_err(|_| Failure::Encryption)?;
+	let mut bytes = Vec::new();
+	ciphertext.serialize_compressed(&mut bytes).map_err(|_| Failure::Encryption)?;
+	if array_bytes::bytes2hex("", sha256(&bytes)) != digest {
+		return Err(Failure::CiphertextMismatch);
+	}
+
+	let signature = id.extract::<E>(msk).0;
+	let plaintext = tld::<E, S>(ciphertext, signature).map_err(|_| Failure::Decryption)?;
+	if plaintext != MESSAGE {
+		return Err(Failure::PlaintextMismatch);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn known_answers_pass() {
+		let report = self_test();
+		assert_eq!(report.checks.len(), 4);
+		assert!(report.passed(), "{:?}", report);
+		assert_eq!(report.failures().count(), 0);
+	}
+
+	#[test]
+	fn wrong_answers_are_reported() {
+		let check = known_answer::<TinyBLS381, AESGCMBlockCipherProvider>(BLS12_377_AES_GCM);
+		assert_eq!(check.engine, "BLS12381");
+		assert_eq!(check.cipher_suite, "AES_GCM_");
+		assert_eq!(check.failure, Some(Failure::CiphertextMismatch));
+
+		let report = SelfTestReport { checks: vec![check] };
+		assert!(!report.passed());
+		assert_eq!(report.failures().count(), 1);
+	}
+
+	#[cfg(feature = "test-vectors")]
+	#[test]
+	fn known_answers_match_the_test_vectors() {
+		let vector = crate::test_vectors::generate::<TinyBLS377, ChaCha20Poly1305BlockCipherProvider>(
+			"self test",
+			"BLS12-377",
+			SEED,
+			&ROUND.to_be_bytes(),
+			MESSAGE,
+		);
+		assert_eq!(
+			array_bytes::bytes2hex("", sha256(&vector.ciphertext)),
+			BLS12_377_CHACHA20_POLY1305
+		);
+	}
+}
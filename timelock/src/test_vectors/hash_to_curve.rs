@@ -0,0 +1,194 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! RFC 9380 hash to curve conformance vectors.
+//!
+//! The vectors of each suite are those published in RFC 9380, appendix J,
+//! and are hashed under the suite's test DST rather than the engine's own.
+//! An engine's suite is `CURVE_NAME || SIG_GROUP_NAME ||
+//! CIPHER_SUIT_DOMAIN_SEPARATION`; RFC 9380 publishes no BLS12-377 suite,
+//! so [`suite_for`] has no vectors for [`TinyBLS377`].
+//!
+//! [`TinyBLS377`]: crate::engines::drand::TinyBLS377
+
+use crate::engines::EngineBLS;
+use alloc::string::String;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+use core::fmt;
+use serde::Deserialize;
+
+/// The field of the coordinates of signatures
+type BaseField<E> = <<E as EngineBLS>::SignatureGroupAffine as AffineRepr>::BaseField;
+
+/// The published suites, in the format of the RFC's reference implementation
+const SUITES: &[&str] = &[include_str!("BLS12381G1_XMD-SHA-256_SSWU_RO_.json")];
+
+/// The test vectors of one hash to curve suite
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Suite {
+	/// The suite identifier, e.g. `BLS12381G1_XMD:SHA-256_SSWU_RO_`
+	pub ciphersuite: String,
+	/// The domain separation tag the vectors are hashed under
+	pub dst: String,
+	/// The vectors
+	pub vectors: Vec<Vector>,
+}
+
+/// A message and the point it hashes to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Vector {
+	/// The message
+	pub msg: String,
+	/// The point, as big-endian hex coordinates
+	#[serde(rename = "P")]
+	pub point: Point,
+}
+
+/// The affine coordinates of a point
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Point {
+	/// The x coordinate
+	pub x: String,
+	/// The y coordinate
+	pub y: String,
+}
+
+/// The errors of checking an engine against the vectors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// RFC 9380 publishes no vectors for the engine's suite
+	NoVectors,
+	/// A message could not be hashed to the curve
+	HashToCurve,
+	/// A coordinate of a vector is not valid hex
+	InvalidVector,
+	/// A message hashed to another point than the published one
+	Mismatch(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::NoVectors => write!(f, "no published hash to curve vectors for the suite"),
+			Error::HashToCurve => write!(f, "hashing to the curve failed"),
+			Error::InvalidVector => write!(f, "invalid hash to curve vector"),
+			Error::Mismatch(msg) => write!(f, "hash to curve mismatch for message {:?}", msg),
+		}
+	}
+}
+
+impl core::error::Error for Error {}
+
+/// The published suites
+pub fn suites() -> Vec<Suite> {
+	SUITES
+		.iter()
+		.map(|json| serde_json::from_str(json).expect("The compiled in vectors must parse."))
+		.collect()
+}
+
+/// The suite identifier of an engine
+pub fn suite_id<E: EngineBLS>() -> Vec<u8> {
+	[E::CURVE_NAME, E::SIG_GROUP_NAME, E::CIPHER_SUIT_DOMAIN_SEPARATION].concat()
+}
+
+/// The published suite of an engine, if any
+pub fn suite_for<E: EngineBLS>() -> Option<Suite> {
+	let id = suite_id::<E>();
+	suites().into_iter().find(|suite| suite.ciphersuite.as_bytes() == id)
+}
+
+/// Check an engine against the vectors of a suite
+///
+/// * `suite`: The suite of the engine `E`
+pub fn verify<E>(suite: &Suite) -> Result<(), Error>
+where
+	E: EngineBLS,
+	BaseField<E>: PrimeField,
+{
+	for vector in &suite.vectors {
+		let point =
+			E::hash_to_signature_curve_with_dst(suite.dst.as_bytes(), vector.msg.as_bytes())
+				.map_err(|_| Error::HashToCurve)?;
+		let expected = (coordinate::<E>(&vector.point.x)?, coordinate::<E>(&vector.point.y)?);
+		if point.into_affine().xy() != Some(expected) {
+			return Err(Error::Mismatch(vector.msg.clone()));
+		}
+	}
+	Ok(())
+}
+
+/// Check an engine against the vectors of its suite
+pub fn verify_engine<E>() -> Result<(), Error>
+where
+	E: EngineBLS,
+	BaseField<E>: PrimeField,
+{
+	verify::<E>(&suite_for::<E>().ok_or(Error::NoVectors)?)
+}
+
+fn coordinate<E>(hex: &str) -> Result<BaseField<E>, Error>
+where
+	E: EngineBLS,
+	BaseField<E>: PrimeField,
+{
+	let bytes = array_bytes::hex2bytes(hex).map_err(|_| Error::InvalidVector)?;
+	Ok(BaseField::<E>::from_be_bytes_mod_order(&bytes))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		engines::drand::{TinyBLS377, TinyBLS381, QUICKNET_CTX},
+		Message,
+	};
+
+	#[test]
+	fn every_engine_passes_its_vectors() {
+		assert_eq!(suite_id::<TinyBLS381>(), b"BLS12381G1_XMD:SHA-256_SSWU_RO_");
+		assert_eq!(suite_for::<TinyBLS381>().map(|s| s.vectors.len()), Some(5));
+		assert_eq!(verify_engine::<TinyBLS381>(), Ok(()));
+		// RFC 9380 has no BLS12-377 suite; add its vectors here if one is published
+		assert_eq!(verify_engine::<TinyBLS377>(), Err(Error::NoVectors));
+	}
+
+	#[test]
+	fn engine_hashes_under_its_own_dst() {
+		let message = Message::new(b"", b"round");
+		assert_eq!(
+			message.hash_to_signature_curve::<TinyBLS381>(),
+			TinyBLS381::hash_to_signature_curve_with_dst(QUICKNET_CTX, &message.1[..]).unwrap()
+		);
+	}
+
+	#[test]
+	fn mismatches_are_reported() {
+		let mut suite = suite_for::<TinyBLS381>().unwrap();
+		suite.vectors[1].msg.push('!');
+		assert_eq!(verify::<TinyBLS381>(&suite), Err(Error::Mismatch("abc!".into())));
+
+		let mut suite = suite_for::<TinyBLS381>().unwrap();
+		suite.dst.push('!');
+		assert_eq!(verify::<TinyBLS381>(&suite), Err(Error::Mismatch("".into())));
+
+		let mut suite = suite_for::<TinyBLS381>().unwrap();
+		suite.vectors[0].point.x = "0xzz".into();
+		assert_eq!(verify::<TinyBLS381>(&suite), Err(Error::InvalidVector));
+	}
+}
@@ -0,0 +1,396 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Solidity ABI encodings of ciphertexts and curve points.
+//!
+//! Contracts that verify beacon signatures or decrypt on-chain (e.g. against
+//! drand's evmnet) work with uncompressed points in 32 byte words. A point is
+//! encoded as in the EIP-2537 precompiles: its affine coordinates `x` then `y`,
+//! every base prime field element of a coordinate (`c0` before `c1` in an
+//! extension field) big-endian and left-padded to a whole number of words, and
+//! the point at infinity as zeros. A BLS12-381 G1 point takes 128 bytes and a
+//! G2 point 256 bytes.
+//!
+//! A [`TLECiphertext`] is encoded as `abi.encode(u, v, w, body, cipherSuite)`
+//! for the Solidity types
+//!
+//! ```text
+//! (bytes32[K] u, bytes32 v, bytes32 w, bytes body, bytes cipherSuite)
+//! ```
+//!
+//! where `K` is the number of words of a public key group point (8 for the
+//! drand engines), so the IBE header sits in fixed slots and only the body and
+//! the cipher suite are dynamic. [`pairing_check_input`] builds the input of a
+//! pairing check precompile that verifies the signature of a round.
+
+use crate::{
+	engines::EngineBLS,
+	ibe::fullident::Ciphertext as IBECiphertext,
+	tlock::{round_identity, strict, Error, TLECiphertext},
+};
+use ark_ec::{
+	short_weierstrass::{Affine, SWCurveConfig},
+	AffineRepr, CurveGroup, PrimeGroup,
+};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_std::{vec, vec::Vec};
+
+/// The size of an ABI word
+pub const WORD_LEN: usize = 32;
+
+type BasePrimeField<P> = <<P as ark_ec::CurveConfig>::BaseField as Field>::BasePrimeField;
+
+/// The number of bytes of a base prime field element, padded to whole words
+fn element_len<P: SWCurveConfig>() -> usize {
+	let bytes = (BasePrimeField::<P>::MODULUS_BIT_SIZE as usize).div_ceil(8);
+	bytes.div_ceil(WORD_LEN) * WORD_LEN
+}
+
+/// The number of bytes of an encoded point of the curve
+pub fn point_len<P: SWCurveConfig>() -> usize {
+	2 * P::BaseField::extension_degree() as usize * element_len::<P>()
+}
+
+/// Encode a point in the EIP-2537 layout
+///
+/// * `point`: The point to encode
+pub fn point_to_abi<P: SWCurveConfig>(point: &Affine<P>) -> Vec<u8> {
+	let len = element_len::<P>();
+	let mut out = vec![0u8; point_len::<P>()];
+	if let Some((x, y)) = point.xy() {
+		let elements = x.to_base_prime_field_elements().chain(y.to_base_prime_field_elements());
+		for (slot, element) in out.chunks_exact_mut(len).zip(elements) {
+			let bytes = element.into_bigint().to_bytes_be();
+			// the big integer may have more leading zero bytes than the slot
+			let bytes = &bytes[bytes.len().saturating_sub(len)..];
+			slot[len - bytes.len()..].copy_from_slice(bytes);
+		}
+	}
+	out
+}
+
+/// Decode a point from the EIP-2537 layout
+///
+/// Fails unless every field element is canonical and the point is on the
+/// curve and in the prime order subgroup. Zeros decode to the point at
+/// infinity.
+///
+/// * `bytes`: The output of [`point_to_abi`]
+pub fn point_from_abi<P: SWCurveConfig>(bytes: &[u8]) -> Result<Affine<P>, Error> {
+	if bytes.len() != point_len::<P>() {
+		return Err(Error::InvalidFormat);
+	}
+	if bytes.iter().all(|byte| *byte == 0) {
+		return Ok(Affine::identity());
+	}
+	let mut elements = bytes.chunks_exact(element_len::<P>()).map(|slot| {
+		let element = BasePrimeField::<P>::from_be_bytes_mod_order(slot);
+		let canonical = element.into_bigint().to_bytes_be();
+		// the slot must hold the element unreduced, with zero padding
+		let padding = slot.len().saturating_sub(canonical.len());
+		let unpadded = canonical.len().saturating_sub(slot.len());
+		match slot[..padding].iter().all(|byte| *byte == 0) &&
+			canonical[..unpadded].iter().all(|byte| *byte == 0) &&
+			slot[padding..] == canonical[unpadded..]
+		{
+			true => Ok(element),
+			false => Err(Error::InvalidFormat),
+		}
+	});
+	let degree = P::BaseField::extension_degree() as usize;
+	let x = (&mut elements).take(degree).collect::<Result<Vec<_>, _>>()?;
+	let y = elements.collect::<Result<Vec<_>, _>>()?;
+	let x = P::BaseField::from_base_prime_field_elems(x).ok_or(Error::InvalidFormat)?;
+	let y = P::BaseField::from_base_prime_field_elems(y).ok_or(Error::InvalidFormat)?;
+
+	let point = Affine::new_unchecked(x, y);
+	if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+		return Err(Error::InvalidPoint);
+	}
+	Ok(point)
+}
+
+/// Encode a ciphertext as `abi.encode(u, v, w, body, cipherSuite)`
+///
+/// * `ciphertext`: The ciphertext to encode
+pub fn ciphertext_to_abi<E, P>(ciphertext: &TLECiphertext<E>) -> Vec<u8>
+where
+	E: EngineBLS<PublicKeyGroupAffine = Affine<P>>,
+	P: SWCurveConfig,
+{
+	let mut out = point_to_abi(&ciphertext.header.u.into_affine());
+	out.extend_from_slice(&ciphertext.header.v);
+	out.extend_from_slice(&ciphertext.header.w);
+
+	// the offsets of the dynamic parts, from the start of the encoding
+	let body_offset = out.len() + 2 * WORD_LEN;
+	let suite_offset = body_offset + WORD_LEN + padded_len(ciphertext.body.len());
+	write_word(&mut out, body_offset as u64);
+	write_word(&mut out, suite_offset as u64);
+	write_bytes(&mut out, &ciphertext.body);
+	write_bytes(&mut out, &ciphertext.cipher_suite);
+	out
+}
+
+/// Decode a ciphertext from `abi.encode(u, v, w, body, cipherSuite)`
+///
+/// Only the canonical encoding is accepted: the dynamic parts must follow
+/// the header in order, zero padded, with nothing after them.
+///
+/// * `bytes`: The output of [`ciphertext_to_abi`]
+pub fn ciphertext_from_abi<E, P>(bytes: &[u8]) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS<PublicKeyGroupAffine = Affine<P>>,
+	P: SWCurveConfig,
+{
+	let u_len = point_len::<P>();
+	let head_len = u_len + 4 * WORD_LEN;
+	let head = bytes.get(..head_len).ok_or(Error::InvalidFormat)?;
+	let u = strict::validate(E::PublicKeyGroup::from(point_from_abi::<P>(&head[..u_len])?))?;
+	let v = head[u_len..u_len + WORD_LEN].try_into().map_err(|_| Error::InvalidFormat)?;
+	let w = head[u_len + WORD_LEN..u_len + 2 * WORD_LEN]
+		.try_into()
+		.map_err(|_| Error::InvalidFormat)?;
+
+	let body_offset = read_word(&head[u_len + 2 * WORD_LEN..])?;
+	let suite_offset = read_word(&head[u_len + 3 * WORD_LEN..])?;
+	if body_offset != head_len {
+		return Err(Error::InvalidFormat);
+	}
+	let (body, end) = read_bytes(bytes, body_offset)?;
+	if suite_offset != end {
+		return Err(Error::InvalidFormat);
+	}
+	let (cipher_suite, end) = read_bytes(bytes, suite_offset)?;
+	if end != bytes.len() {
+		return Err(Error::InvalidFormat);
+	}
+
+	Ok(TLECiphertext {
+		header: IBECiphertext { u, v, w },
+		body: body.to_vec(),
+		cipher_suite: cipher_suite.to_vec(),
+	})
+}
+
+/// The input of a pairing check precompile (e.g. EIP-2537's) that verifies
+/// the beacon's signature of a round
+///
+/// The input holds the pairs `(signature, -g)` and `(H(round), p_pub)`, each
+/// a signature group point followed by a public key group point, so the
+/// check passes exactly when `e(H(round), p_pub) = e(signature, g)`. The
+/// precompiles take G1 points first, so this fits engines with signatures in
+/// G1, such as the drand engines.
+///
+/// * `p_pub`: The public key of the beacon
+/// * `round`: The round of the signature
+/// * `signature`: The signature
+pub fn pairing_check_input<E, P1, P2>(
+	p_pub: E::PublicKeyGroup,
+	round: u64,
+	signature: E::SignatureGroup,
+) -> Vec<u8>
+where
+	E: EngineBLS<SignatureGroupAffine = Affine<P1>, PublicKeyGroupAffine = Affine<P2>>,
+	P1: SWCurveConfig,
+	P2: SWCurveConfig,
+{
+	let message = round_identity(round).public::<E>();
+	let mut out = point_to_abi(&signature.into_affine());
+	out.extend(point_to_abi(&(-E::PublicKeyGroup::generator()).into_affine()));
+	out.extend(point_to_abi(&message.into_affine()));
+	out.extend(point_to_abi(&p_pub.into_affine()));
+	out
+}
+
+/// The length of `len` bytes padded to whole words
+fn padded_len(len: usize) -> usize {
+	len.div_ceil(WORD_LEN) * WORD_LEN
+}
+
+/// Write an unsigned integer as a big-endian word
+fn write_word(out: &mut Vec<u8>, value: u64) {
+	out.extend_from_slice(&[0u8; WORD_LEN - 8]);
+	out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Write a length word followed by the bytes, zero padded to whole words
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+	write_word(out, bytes.len() as u64);
+	out.extend_from_slice(bytes);
+	out.resize(out.len() + padded_len(bytes.len()) - bytes.len(), 0);
+}
+
+/// Read a word holding an integer that fits a usize
+fn read_word(bytes: &[u8]) -> Result<usize, Error> {
+	let word = bytes.get(..WORD_LEN).ok_or(Error::InvalidFormat)?;
+	if word[..WORD_LEN - 8].iter().any(|byte| *byte != 0) {
+		return Err(Error::InvalidFormat);
+	}
+	let value = u64::from_be_bytes(word[WORD_LEN - 8..].try_into().expect("8 bytes"));
+	usize::try_from(value).map_err(|_| Error::InvalidFormat)
+}
+
+/// Read the bytes at `offset`, returning them and the offset after their
+/// padding
+fn read_bytes(bytes: &[u8], offset: usize) -> Result<(&[u8], usize), Error> {
+	let len = read_word(bytes.get(offset..).ok_or(Error::InvalidFormat)?)?;
+	let start = offset + WORD_LEN;
+	let end = start.checked_add(padded_len(len)).ok_or(Error::InvalidFormat)?;
+	let padded = bytes.get(start..end).ok_or(Error::InvalidFormat)?;
+	if padded[len..].iter().any(|byte| *byte != 0) {
+		return Err(Error::InvalidFormat);
+	}
+	Ok((&padded[..len], end))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::{TinyBLS377, TinyBLS381},
+		tlock::{tld, tle},
+	};
+	use ark_bls12_381::{g1, g2, Bls12_381, Fq, G1Affine, G2Affine};
+	use ark_ec::pairing::Pairing;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		UniformRand, Zero,
+	};
+
+	fn ciphertext<E: EngineBLS>(secret: E::Scalar) -> TLECiphertext<E> {
+		tle::<E, AESGCMBlockCipherProvider, _>(
+			E::PublicKeyGroup::generator() * secret,
+			[3; 32],
+			b"abi encoded",
+			round_identity(10),
+			StdRng::seed_from_u64(3),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	pub fn points_use_the_eip_2537_layout() {
+		assert_eq!(point_len::<g1::Config>(), 128);
+		assert_eq!(point_len::<g2::Config>(), 256);
+		assert_eq!(point_to_abi(&G1Affine::identity()), vec![0u8; 128]);
+
+		let generator = point_to_abi(&G1Affine::generator());
+		let x = Fq::from_be_bytes_mod_order(&generator[16..64]);
+		assert_eq!(generator[..16], [0u8; 16]);
+		assert_eq!(x, G1Affine::generator().x);
+
+		let mut rng = StdRng::seed_from_u64(25);
+		let point = G2Affine::rand(&mut rng);
+		assert_eq!(point_from_abi::<g2::Config>(&point_to_abi(&point)), Ok(point));
+		assert_eq!(point_from_abi::<g1::Config>(&[0u8; 128]), Ok(G1Affine::identity()));
+	}
+
+	#[test]
+	pub fn non_canonical_points_are_rejected() {
+		let point = point_to_abi(&G1Affine::generator());
+
+		// non-zero padding
+		let mut padded = point.clone();
+		padded[0] = 1;
+		assert_eq!(point_from_abi::<g1::Config>(&padded), Err(Error::InvalidFormat));
+
+		// a coordinate plus the modulus
+		let mut unreduced = point.clone();
+		let x = <Fq as PrimeField>::MODULUS.to_bytes_be();
+		let mut carry = 0u16;
+		for (byte, m) in unreduced[16..64].iter_mut().zip(x.iter()).rev() {
+			let sum = *byte as u16 + *m as u16 + carry;
+			*byte = sum as u8;
+			carry = sum >> 8;
+		}
+		unreduced[15] = carry as u8;
+		assert_eq!(point_from_abi::<g1::Config>(&unreduced), Err(Error::InvalidFormat));
+
+		// off the curve
+		let mut off_curve = point.clone();
+		off_curve[127] ^= 1;
+		assert_eq!(point_from_abi::<g1::Config>(&off_curve), Err(Error::InvalidPoint));
+		assert_eq!(point_from_abi::<g1::Config>(&point[1..]), Err(Error::InvalidFormat));
+	}
+
+	#[test]
+	pub fn ciphertexts_round_trip() {
+		let mut rng = StdRng::seed_from_u64(7);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let bytes = ciphertext_to_abi(&ciphertext::<TinyBLS381>(secret));
+
+		// u, v, w and the offsets, then the body and the cipher suite
+		assert_eq!(read_word(&bytes[256 + 64..]), Ok(384));
+		let decoded = ciphertext_from_abi::<TinyBLS381, _>(&bytes).unwrap();
+		assert_eq!(ciphertext_to_abi(&decoded), bytes);
+		let signature = round_identity(10).extract::<TinyBLS381>(secret).0;
+		assert_eq!(
+			tld::<TinyBLS381, AESGCMBlockCipherProvider>(decoded, signature),
+			Ok(b"abi encoded".to_vec())
+		);
+
+		let secret = <TinyBLS377 as EngineBLS>::Scalar::rand(&mut rng);
+		let bytes = ciphertext_to_abi(&ciphertext::<TinyBLS377>(secret));
+		let decoded = ciphertext_from_abi::<TinyBLS377, _>(&bytes).unwrap();
+		assert_eq!(ciphertext_to_abi(&decoded), bytes);
+	}
+
+	#[test]
+	pub fn non_canonical_ciphertexts_are_rejected() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::from(7u64);
+		let bytes = ciphertext_to_abi(&ciphertext::<TinyBLS381>(secret));
+		let decode = |bytes: &[u8]| ciphertext_from_abi::<TinyBLS381, _>(bytes).err();
+
+		let mut trailing = bytes.clone();
+		trailing.extend_from_slice(&[0; 32]);
+		assert_eq!(decode(&trailing), Some(Error::InvalidFormat));
+		assert_eq!(decode(&bytes[..bytes.len() - 1]), Some(Error::InvalidFormat));
+
+		let mut offset = bytes.clone();
+		offset[256 + 95] += 32;
+		assert_eq!(decode(&offset), Some(Error::InvalidFormat));
+
+		// the point at infinity is not a valid header
+		let mut infinity = bytes.clone();
+		infinity[..256].fill(0);
+		assert_eq!(decode(&infinity), Some(Error::InvalidPoint));
+	}
+
+	#[test]
+	pub fn pairing_check_input_verifies_the_signature() {
+		let mut rng = StdRng::seed_from_u64(11);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let check = |signature| {
+			let input = pairing_check_input::<TinyBLS381, _, _>(p_pub, 10, signature);
+			assert_eq!(input.len(), 2 * (128 + 256));
+			let (g1s, g2s): (Vec<_>, Vec<_>) = input
+				.chunks_exact(384)
+				.map(|pair| {
+					(
+						point_from_abi::<g1::Config>(&pair[..128]).unwrap(),
+						point_from_abi::<g2::Config>(&pair[128..]).unwrap(),
+					)
+				})
+				.unzip();
+			Bls12_381::multi_pairing(g1s, g2s).is_zero()
+		};
+		assert!(check(round_identity(10).extract::<TinyBLS381>(secret).0));
+		assert!(!check(round_identity(11).extract::<TinyBLS381>(secret).0));
+	}
+}
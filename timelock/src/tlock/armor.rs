@@ -0,0 +1,257 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! ASCII armor for timelock ciphertexts.
+//!
+//! An armored message is a `-----BEGIN TIMELOCK MESSAGE-----` line, optional
+//! `Key: value` headers followed by an empty line, the compressed bytes in
+//! base64 wrapped at 64 columns, and a `-----END TIMELOCK MESSAGE-----` line:
+//!
+//! ```text
+//! -----BEGIN TIMELOCK MESSAGE-----
+//! Round: 1000
+//! Chain-Hash: 52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971
+//!
+//! kYaHtL6bJ2lmIuvlbNg...
+//! -----END TIMELOCK MESSAGE-----
+//! ```
+//!
+//! Headers are informational and are not authenticated. Decoding tolerates
+//! CRLF line endings and surrounding whitespace, so messages survive being
+//! pasted into email or a JSON string.
+
+use crate::tlock::Error;
+use alloc::string::String;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+/// The first line of an armored message
+pub const BEGIN_LINE: &str = "-----BEGIN TIMELOCK MESSAGE-----";
+/// The last line of an armored message
+pub const END_LINE: &str = "-----END TIMELOCK MESSAGE-----";
+/// The header naming the round a ciphertext is locked to
+pub const ROUND_HEADER: &str = "Round";
+/// The header naming the chain of the beacon
+pub const CHAIN_HASH_HEADER: &str = "Chain-Hash";
+/// The number of base64 characters per line
+const COLUMNS_PER_LINE: usize = 64;
+
+/// Armored data and its headers
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Armored {
+	/// The headers, in the order they appear
+	pub headers: Vec<(String, String)>,
+	/// The armored bytes
+	pub data: Vec<u8>,
+}
+
+impl Armored {
+	/// Armor raw bytes without headers
+	pub fn new(data: Vec<u8>) -> Self {
+		Self { headers: Vec::new(), data }
+	}
+
+	/// Armor the compressed serialization of a value (e.g. a
+	/// [`crate::tlock::TLECiphertext`])
+	pub fn from_value<T: CanonicalSerialize>(value: &T) -> Self {
+		let mut data = Vec::new();
+		value.serialize_compressed(&mut data).expect("The value must be serializable.");
+		Self::new(data)
+	}
+
+	/// Deserialize the armored bytes
+	pub fn to_value<T: CanonicalDeserialize>(&self) -> Result<T, Error> {
+		T::deserialize_compressed(&self.data[..]).map_err(|_| Error::DeserializationError)
+	}
+
+	/// Add a header
+	///
+	/// Fails with [`Error::InvalidFormat`] if the key is empty or contains a
+	/// colon or whitespace, or if the value spans several lines.
+	///
+	/// * `key`: The name of the header
+	/// * `value`: The value of the header
+	pub fn with_header(mut self, key: &str, value: &str) -> Result<Self, Error> {
+		if key.is_empty() ||
+			key.contains(|c: char| c == ':' || c.is_whitespace() || c.is_control()) ||
+			value.contains(['\r', '\n'])
+		{
+			return Err(Error::InvalidFormat);
+		}
+		self.headers.push((key.into(), value.trim().into()));
+		Ok(self)
+	}
+
+	/// Add the round and chain hash headers
+	///
+	/// * `round`: The round the data is locked to
+	/// * `chain_hash`: The hash of the beacon's chain
+	pub fn with_round(self, round: u64, chain_hash: &[u8]) -> Self {
+		self.with_header(ROUND_HEADER, &alloc::format!("{}", round))
+			.and_then(|armored| {
+				armored.with_header(CHAIN_HASH_HEADER, &array_bytes::bytes2hex("", chain_hash))
+			})
+			.expect("The round headers are well-formed.")
+	}
+
+	/// The value of the first header named `key`, ignoring case
+	pub fn header(&self, key: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(k, _)| k.eq_ignore_ascii_case(key))
+			.map(|(_, v)| v.as_str())
+	}
+
+	/// The value of the round header, if present and numeric
+	pub fn round(&self) -> Option<u64> {
+		self.header(ROUND_HEADER)?.parse().ok()
+	}
+
+	/// The value of the chain hash header, if present and hex encoded
+	pub fn chain_hash(&self) -> Option<Vec<u8>> {
+		array_bytes::hex2bytes(self.header(CHAIN_HASH_HEADER)?).ok()
+	}
+
+	/// Encode as an armored string
+	pub fn encode(&self) -> String {
+		let encoded = STANDARD.encode(&self.data);
+		let mut out = String::from(BEGIN_LINE);
+		out.push('\n');
+		for (key, value) in &self.headers {
+			out.push_str(key);
+			out.push_str(": ");
+			out.push_str(value);
+			out.push('\n');
+		}
+		if !self.headers.is_empty() {
+			out.push('\n');
+		}
+		for line in encoded.as_bytes().chunks(COLUMNS_PER_LINE) {
+			out.push_str(core::str::from_utf8(line).expect("base64 is ascii."));
+			out.push('\n');
+		}
+		out.push_str(END_LINE);
+		out.push('\n');
+		out
+	}
+
+	/// Decode an armored string
+	///
+	/// * `armored`: A string output by [`Armored::encode`]
+	pub fn decode(armored: &str) -> Result<Self, Error> {
+		let mut lines = armored.trim().lines().map(str::trim);
+		if lines.next() != Some(BEGIN_LINE) {
+			return Err(Error::InvalidFormat);
+		}
+
+		let mut headers = Vec::new();
+		let mut encoded = String::new();
+		let mut in_headers = true;
+		let mut ended = false;
+		for line in lines.by_ref() {
+			if line == END_LINE {
+				ended = true;
+				break;
+			}
+			if in_headers {
+				if line.is_empty() {
+					in_headers = false;
+					continue;
+				}
+				if let Some((key, value)) = line.split_once(':') {
+					headers.push((key.trim().into(), value.trim().into()));
+					continue;
+				}
+				// no headers, the first line is already data
+				in_headers = false;
+			}
+			encoded.push_str(line);
+		}
+		if !ended || lines.next().is_some() {
+			return Err(Error::InvalidFormat);
+		}
+
+		let data = STANDARD.decode(encoded).map_err(|_| Error::InvalidFormat)?;
+		Ok(Self { headers, data })
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::{drand::TinyBLS381, EngineBLS},
+		ibe::fullident::Identity,
+		tlock::{tld, tle, TLECiphertext},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	pub fn armored_ciphertext_round_trips() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[1; 32],
+			b"through the mail",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		let text = Armored::from_value(&ct).with_round(1000, &[0xab; 32]).encode();
+		assert!(text.starts_with("-----BEGIN TIMELOCK MESSAGE-----\nRound: 1000\n"));
+		let mut data_lines = text.lines().skip(4).take_while(|line| *line != END_LINE);
+		assert!(data_lines.all(|line| line.len() <= COLUMNS_PER_LINE));
+
+		// as if it had been pasted from an email client
+		let pasted = alloc::format!("\r\n  {}  ", text.replace('\n', "\r\n"));
+		let armored = Armored::decode(&pasted).unwrap();
+		assert_eq!(armored.round(), Some(1000));
+		assert_eq!(armored.chain_hash(), Some([0xab; 32].to_vec()));
+
+		let ct: TLECiphertext<TinyBLS381> = armored.to_value().unwrap();
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig).unwrap();
+		assert_eq!(plaintext, b"through the mail".to_vec());
+	}
+
+	#[test]
+	pub fn armor_without_headers_round_trips() {
+		for len in [0, 1, 47, 48, 49, 200] {
+			let armored = Armored::new(ark_std::vec![9u8; len]);
+			assert_eq!(Armored::decode(&armored.encode()), Ok(armored));
+		}
+	}
+
+	#[test]
+	pub fn armor_rejects_malformed_input() {
+		let text = Armored::new(b"data".to_vec()).encode();
+		assert_eq!(Armored::decode(&text.replace("BEGIN", "START")), Err(Error::InvalidFormat));
+		assert_eq!(Armored::decode(&text.replace("-----END", "END")), Err(Error::InvalidFormat));
+		assert_eq!(Armored::decode(&text.replace("ZGF0YQ", "ZGF0Y!")), Err(Error::InvalidFormat));
+		assert_eq!(
+			Armored::new(Vec::new()).with_header("Bad Key", "value"),
+			Err(Error::InvalidFormat)
+		);
+		assert_eq!(Armored::new(Vec::new()).with_header("Key", "a\nb"), Err(Error::InvalidFormat));
+	}
+}
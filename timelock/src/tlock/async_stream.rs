@@ -0,0 +1,370 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Streaming encryption with tokio.
+//!
+//! [`AsyncTleWriter`] and [`AsyncTldReader`] mirror the adapters of
+//! [`super::stream`] as [`AsyncWrite`] and [`AsyncRead`], with the same wire
+//! format, so services can encrypt or decrypt request and response bodies as
+//! they stream instead of buffering them. As with the blocking writer, the
+//! last chunk is only written once the writer is shut down.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	tlock::{
+		stream::{
+			invalid_data, lock_payload_key, open_chunk, seal_chunk, unlock_payload_key, CHUNK_SIZE,
+			SEALED_CHUNK_SIZE,
+		},
+		TLECiphertext,
+	},
+};
+use aes_gcm::{Aes256Gcm, KeyInit};
+use ark_ec::PrimeGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng};
+use std::{
+	io,
+	pin::Pin,
+	task::{ready, Context, Poll},
+	vec,
+	vec::Vec,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+
+/// The largest length prefixed field accepted in a header; the fields of a
+/// header only hold an encrypted payload key and a cipher suite
+const MAX_HEADER_FIELD: u64 = 1024;
+
+/// Encrypts everything written to it into a chunked payload
+///
+/// The last chunk is written on [`AsyncWrite::poll_shutdown`], so the writer
+/// must be shut down to complete the payload.
+pub struct AsyncTleWriter<W> {
+	inner: W,
+	cipher: Aes256Gcm,
+	counter: u64,
+	buffer: Vec<u8>,
+	/// Bytes not yet written to `inner`
+	pending: Vec<u8>,
+	written: usize,
+	finished: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncTleWriter<W> {
+	/// A writer that timelock encrypts a fresh payload key for an identity and
+	/// writes it ahead of the payload
+	///
+	/// * `p_pub`: The public key of the beacon
+	/// * `id`: The identity (e.g. round) to encrypt for
+	/// * `inner`: Where to write the ciphertext
+	/// * `rng`: A CSPRNG
+	pub fn new<E, S, R>(
+		p_pub: E::PublicKeyGroup,
+		id: Identity,
+		inner: W,
+		rng: R,
+	) -> io::Result<Self>
+	where
+		E: EngineBLS,
+		S: BlockCipherProvider<32>,
+		R: Rng + CryptoRng,
+	{
+		let (payload_key, header) = lock_payload_key::<E, S, R>(p_pub, id, rng)?;
+		let mut writer = Self::with_key(&payload_key, inner);
+		writer.pending = header;
+		Ok(writer)
+	}
+
+	/// A writer that encrypts with a payload key managed by the caller
+	///
+	/// * `key`: The payload key
+	/// * `inner`: Where to write the encrypted chunks
+	pub fn with_key(key: &[u8; 32], inner: W) -> Self {
+		Self {
+			inner,
+			cipher: Aes256Gcm::new(key.into()),
+			counter: 0,
+			buffer: Vec::with_capacity(CHUNK_SIZE),
+			pending: Vec::new(),
+			written: 0,
+			finished: false,
+		}
+	}
+
+	/// The inner writer
+	pub fn into_inner(self) -> W {
+		self.inner
+	}
+
+	/// Encrypt the buffered chunk
+	fn seal(&mut self, last: bool) {
+		self.pending = seal_chunk(&self.cipher, self.counter, &self.buffer, last);
+		self.written = 0;
+		self.buffer.clear();
+		self.counter += 1;
+	}
+
+	/// Write the pending bytes to `inner`
+	fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		while self.written < self.pending.len() {
+			let n =
+				ready!(Pin::new(&mut self.inner).poll_write(cx, &self.pending[self.written..]))?;
+			if n == 0 {
+				return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+			}
+			self.written += n;
+		}
+		self.pending.clear();
+		self.written = 0;
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncTleWriter<W> {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		let this = self.get_mut();
+		if this.finished {
+			return Poll::Ready(Err(io::Error::new(
+				io::ErrorKind::BrokenPipe,
+				"the payload is already finished",
+			)));
+		}
+		if buf.is_empty() {
+			return Poll::Ready(Ok(0));
+		}
+		ready!(this.poll_drain(cx))?;
+		// a full chunk is only sealed once more data shows it is not the last
+		if this.buffer.len() == CHUNK_SIZE {
+			this.seal(false);
+			ready!(this.poll_drain(cx))?;
+		}
+		let n = buf.len().min(CHUNK_SIZE - this.buffer.len());
+		this.buffer.extend_from_slice(&buf[..n]);
+		Poll::Ready(Ok(n))
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_drain(cx))?;
+		Pin::new(&mut this.inner).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		ready!(this.poll_drain(cx))?;
+		if !this.finished {
+			this.seal(true);
+			this.finished = true;
+			ready!(this.poll_drain(cx))?;
+		}
+		Pin::new(&mut this.inner).poll_shutdown(cx)
+	}
+}
+
+/// Decrypts a chunked payload as it is read
+///
+/// Chunks are returned as soon as they are authenticated, so a failure part
+/// way through follows a prefix of the message. Authentication failures are
+/// reported as [`io::ErrorKind::InvalidData`].
+pub struct AsyncTldReader<R> {
+	inner: R,
+	cipher: Aes256Gcm,
+	counter: u64,
+	/// Sealed bytes read ahead, up to a chunk and one byte
+	sealed: Vec<u8>,
+	filled: usize,
+	/// The plaintext of the current chunk
+	plaintext: Vec<u8>,
+	position: usize,
+	done: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncTldReader<R> {
+	/// A reader that decrypts the payload key written by
+	/// [`AsyncTleWriter::new`] with the signature of its round
+	///
+	/// * `inner`: The ciphertext
+	/// * `signature`: The signature of the round the ciphertext is locked to
+	pub async fn new<E, S>(mut inner: R, signature: E::SignatureGroup) -> io::Result<Self>
+	where
+		E: EngineBLS,
+		S: BlockCipherProvider<32>,
+	{
+		let header = read_header::<E, R>(&mut inner).await?;
+		let payload_key = unlock_payload_key::<E, S>(header, signature)?;
+		Ok(Self::with_key(&payload_key, inner))
+	}
+
+	/// A reader that decrypts with a payload key managed by the caller
+	///
+	/// * `key`: The payload key
+	/// * `inner`: The encrypted chunks
+	pub fn with_key(key: &[u8; 32], inner: R) -> Self {
+		Self {
+			inner,
+			cipher: Aes256Gcm::new(key.into()),
+			counter: 0,
+			sealed: vec![0u8; SEALED_CHUNK_SIZE + 1],
+			filled: 0,
+			plaintext: Vec::new(),
+			position: 0,
+			done: false,
+		}
+	}
+
+	/// The inner reader, positioned after the payload once it is fully read
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncTldReader<R> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		while this.position == this.plaintext.len() {
+			if this.done {
+				return Poll::Ready(Ok(()));
+			}
+			// look one byte past a chunk to find the last
+			while this.filled < this.sealed.len() {
+				let mut read = ReadBuf::new(&mut this.sealed[this.filled..]);
+				ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read))?;
+				let n = read.filled().len();
+				if n == 0 {
+					break;
+				}
+				this.filled += n;
+			}
+			let last = this.filled <= SEALED_CHUNK_SIZE;
+			let len = this.filled.min(SEALED_CHUNK_SIZE);
+			this.plaintext = open_chunk(&this.cipher, this.counter, &this.sealed[..len], last)?;
+			this.sealed.copy_within(len..this.filled, 0);
+			this.filled -= len;
+			this.position = 0;
+			this.counter += 1;
+			this.done = last;
+		}
+		let n = buf.remaining().min(this.plaintext.len() - this.position);
+		buf.put_slice(&this.plaintext[this.position..this.position + n]);
+		this.position += n;
+		Poll::Ready(Ok(()))
+	}
+}
+
+/// Read the header written by [`AsyncTleWriter::new`]
+///
+/// The IBE ciphertext of the header has a fixed size and its other fields are
+/// length prefixed, so the header is read field by field.
+async fn read_header<E: EngineBLS, R: AsyncRead + Unpin>(
+	inner: &mut R,
+) -> io::Result<TLECiphertext<E>> {
+	let mut bytes = vec![0u8; E::PublicKeyGroup::generator().compressed_size() + 64];
+	inner.read_exact(&mut bytes).await?;
+	for _ in 0..2 {
+		let len = inner.read_u64_le().await?;
+		if len > MAX_HEADER_FIELD {
+			return Err(invalid_data("the header is malformed"));
+		}
+		bytes.extend_from_slice(&len.to_le_bytes());
+		let start = bytes.len();
+		bytes.resize(start + len as usize, 0);
+		inner.read_exact(&mut bytes[start..]).await?;
+	}
+	TLECiphertext::<E>::deserialize_compressed(&bytes[..])
+		.map_err(|_| invalid_data("the header is malformed"))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS377,
+		tlock::{stream::TleWriter, BeaconConfig},
+	};
+	use ark_std::{rand::rngs::OsRng, UniformRand};
+	use std::io::Write;
+	use tokio::io::AsyncWriteExt;
+
+	#[tokio::test]
+	async fn async_streams_match_the_blocking_format() {
+		let secret = <TinyBLS377 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS377 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS377>::identity(10);
+		let signature = id.extract::<TinyBLS377>(secret).0;
+		let message: Vec<u8> = (0..2 * CHUNK_SIZE + 7).map(|i| i as u8).collect();
+
+		let mut writer = AsyncTleWriter::new::<TinyBLS377, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			id.clone(),
+			Vec::new(),
+			OsRng,
+		)
+		.unwrap();
+		for piece in message.chunks(1000) {
+			writer.write_all(piece).await.unwrap();
+		}
+		writer.shutdown().await.unwrap();
+		let ciphertext = writer.into_inner();
+
+		let mut reader = AsyncTldReader::new::<TinyBLS377, AESGCMBlockCipherProvider>(
+			&ciphertext[..],
+			signature,
+		)
+		.await
+		.unwrap();
+		let mut plaintext = Vec::new();
+		reader.read_to_end(&mut plaintext).await.unwrap();
+		assert_eq!(plaintext, message);
+
+		// payloads of the blocking writer decrypt, and truncation is detected
+		let mut writer = TleWriter::new::<TinyBLS377, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			id,
+			Vec::new(),
+			OsRng,
+		)
+		.unwrap();
+		writer.write_all(&message).unwrap();
+		let ciphertext = writer.finish().unwrap();
+		let read = |ciphertext: &[u8]| {
+			let ciphertext = ciphertext.to_vec();
+			async move {
+				let mut reader = AsyncTldReader::new::<TinyBLS377, AESGCMBlockCipherProvider>(
+					&ciphertext[..],
+					signature,
+				)
+				.await?;
+				let mut plaintext = Vec::new();
+				reader.read_to_end(&mut plaintext).await.map(|_| plaintext)
+			}
+		};
+		assert_eq!(read(&ciphertext).await.unwrap(), message);
+		let error = read(&ciphertext[..ciphertext.len() - 1]).await.unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+	}
+}
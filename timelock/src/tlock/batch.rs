@@ -0,0 +1,220 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Encrypting and decrypting batches of messages.
+//!
+//! Backends that timelock thousands of records per round spend most of their
+//! time in pairings. With the `parallel` feature, `tle_batch_par` computes the
+//! pairing of each distinct identity of a batch once (see
+//! [`super::EncryptionContext`]) and encrypts the messages across rayon's
+//! thread pool.
+//!
+//! Once the signature of a round is known, [`tld_batch`] (or `tld_batch_par`)
+//! decrypts the ciphertexts locked to it, preparing the signature for the
+//! pairing once for the whole batch. The pairings themselves cannot share a
+//! Miller loop: each ciphertext needs its own e(d_id, U), while a combined
+//! Miller loop and final exponentiation only yield their product.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::decrypt_with_pairing,
+	tlock::{witness::open_body, Error, TLECiphertext},
+};
+use ark_std::vec::Vec;
+use zeroize::Zeroize;
+#[cfg(feature = "parallel")]
+use {
+	crate::{ibe::fullident::Identity, tlock::EncryptionContext},
+	ark_std::rand::{CryptoRng, Rng, SeedableRng},
+	rand_chacha::ChaCha20Rng,
+	rayon::prelude::*,
+};
+
+/// Decrypt a batch of ciphertexts locked to one round
+///
+/// Every ciphertext is decrypted independently, so one that cannot be
+/// decrypted does not stop the batch; its result is the error instead. The
+/// results are in the order of `ciphertexts`.
+///
+/// * `ciphertexts`: The ciphertexts
+/// * `signature`: The signature of the round the ciphertexts are locked to
+pub fn tld_batch<E, S>(
+	ciphertexts: &[TLECiphertext<E>],
+	signature: E::SignatureGroup,
+) -> Vec<Result<Vec<u8>, Error>>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let signature = E::prepare_signature(signature);
+	ciphertexts
+		.iter()
+		.map(|ciphertext| decrypt_prepared::<E, S>(ciphertext, &signature))
+		.collect()
+}
+
+/// Decrypt a ciphertext with the prepared signature of its round
+fn decrypt_prepared<E, S>(
+	ciphertext: &TLECiphertext<E>,
+	signature: &E::SignaturePrepared,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let u = E::prepare_public_key(ciphertext.header.u);
+	// e(d_id, U)
+	let pairing = E::final_exponentiation(E::miller_loop(&[(u, signature.clone())]))
+		.ok_or(Error::InvalidSignature)?
+		.0;
+	let mut secret_key = decrypt_with_pairing(&ciphertext.header, pairing)?;
+	let plaintext = open_body::<S>(&ciphertext.body, secret_key);
+	secret_key.zeroize();
+	plaintext
+}
+
+/// Encrypt a batch of messages in parallel
+///
+/// Each message is encrypted with its own ChaCha20 RNG, seeded from `rng`, so
+/// the batch is reproducible given the seed of `rng`. The results are in the
+/// order of `items`.
+///
+/// * `p_pub`: The public key of the beacon
+/// * `items`: The identity (e.g. round) to encrypt each message for, and the
+///   message
+/// * `rng`: A CSPRNG
+#[cfg(feature = "parallel")]
+pub fn tle_batch_par<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	items: &[(Identity, &[u8])],
+	mut rng: R,
+) -> Vec<Result<TLECiphertext<E>, Error>>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let mut context = EncryptionContext::<E>::new(p_pub);
+	let mut ids: Vec<&Identity> = items.iter().map(|(id, _)| id).collect();
+	ids.sort_by_key(|id| id.0 .0);
+	ids.dedup_by_key(|id| id.0 .0);
+	let pairings: Vec<_> = ids.par_iter().map(|id| context.pairing(id)).collect();
+	for (id, pairing) in ids.into_iter().zip(pairings) {
+		context.insert(id, pairing);
+	}
+
+	let seeds: Vec<[u8; 32]> = items.iter().map(|_| rng.gen()).collect();
+	items
+		.par_iter()
+		.zip(seeds)
+		.map(|((id, message), seed)| {
+			let mut rng = ChaCha20Rng::from_seed(seed);
+			let secret_key = rng.gen();
+			context.encrypt::<S, _>(id, secret_key, message, rng)
+		})
+		.collect()
+}
+
+/// Decrypt a batch of ciphertexts locked to one round in parallel
+///
+/// See [`tld_batch`].
+///
+/// * `ciphertexts`: The ciphertexts
+/// * `signature`: The signature of the round the ciphertexts are locked to
+#[cfg(feature = "parallel")]
+pub fn tld_batch_par<E, S>(
+	ciphertexts: &[TLECiphertext<E>],
+	signature: E::SignatureGroup,
+) -> Vec<Result<Vec<u8>, Error>>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let signature = E::prepare_signature(signature);
+	ciphertexts
+		.par_iter()
+		.map(|ciphertext| decrypt_prepared::<E, S>(ciphertext, &signature))
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle, BeaconConfig},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_std::{rand::rngs::OsRng, UniformRand};
+
+	#[test]
+	#[cfg(feature = "parallel")]
+	fn batches_encrypt_to_each_identity() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let rounds = [10, 11, 10, 12, 10];
+		let messages: Vec<Vec<u8>> = (0..rounds.len()).map(|i| [i as u8; 40].to_vec()).collect();
+		let items: Vec<(Identity, &[u8])> = rounds
+			.iter()
+			.zip(&messages)
+			.map(|(round, message)| (BeaconConfig::<TinyBLS381>::identity(*round), &message[..]))
+			.collect();
+
+		let ciphertexts =
+			tle_batch_par::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, &items, OsRng);
+		assert_eq!(ciphertexts.len(), items.len());
+		for ((ciphertext, (id, message)), round) in ciphertexts.into_iter().zip(&items).zip(rounds)
+		{
+			let signature = id.extract::<TinyBLS381>(secret).0;
+			let plaintext =
+				tld::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext.unwrap(), signature);
+			assert_eq!(plaintext.as_deref(), Ok(*message), "round {}", round);
+		}
+	}
+
+	#[test]
+	fn batches_decrypt_like_tld() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let lock = |round: u64, message: &[u8]| {
+			let id = BeaconConfig::<TinyBLS381>::identity(round);
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, [6u8; 32], message, id, OsRng)
+				.unwrap()
+		};
+		let mut corrupted = lock(20, b"corrupted");
+		corrupted.body[0] ^= 1;
+		let ciphertexts = [lock(20, b"first"), lock(21, b"other round"), corrupted, lock(20, b"")];
+
+		let signature = BeaconConfig::<TinyBLS381>::identity(20).extract::<TinyBLS381>(secret).0;
+		let results = tld_batch::<TinyBLS381, AESGCMBlockCipherProvider>(&ciphertexts, signature);
+		#[cfg(feature = "parallel")]
+		assert_eq!(
+			tld_batch_par::<TinyBLS381, AESGCMBlockCipherProvider>(&ciphertexts, signature),
+			results
+		);
+		let expected: Vec<_> = ciphertexts
+			.into_iter()
+			.map(|ciphertext| tld::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, signature))
+			.collect();
+		assert_eq!(results, expected);
+		assert_eq!(results[0], Ok(b"first".to_vec()));
+		assert_eq!(results[1], Err(Error::InvalidSignature));
+		assert!(results[2].is_err());
+		assert_eq!(results[3], Ok(Vec::new()));
+	}
+}
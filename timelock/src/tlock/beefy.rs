@@ -0,0 +1,205 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Verification of BEEFY signed commitments.
+//!
+//! The validators of the Ideal Network sign the BEEFY [`Commitment`] of each
+//! finalized block (its payload, block number and validator set id, SCALE
+//! encoded) and aggregate their signatures. A ciphertext encrypted to
+//! [`Commitment::identity`] is decrypted by that aggregate signature, but the
+//! commitment reaches consumers through relayers that could forge it.
+//! [`SignedCommitment::verify`] checks the aggregate signature against the
+//! public key of the [`ValidatorSet`], and [`tld_commitment`] only decrypts
+//! with a verified commitment.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	tlock::{strict, tld, Error, TLECiphertext},
+};
+use ark_ec::PrimeGroup;
+use ark_std::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// The id of a BEEFY payload, e.g. `*b"mh"` for the MMR root
+pub type BeefyPayloadId = [u8; 2];
+
+/// A BEEFY commitment, as signed by the validators
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Commitment {
+	/// The payloads of the commitment, sorted by id
+	pub payload: Vec<(BeefyPayloadId, Vec<u8>)>,
+	/// The number of the finalized block
+	pub block_number: u32,
+	/// The id of the validator set that signed the commitment
+	pub validator_set_id: u64,
+}
+
+impl Commitment {
+	/// The identity signed by the validators, the SCALE encoded commitment
+	pub fn identity(&self) -> Identity {
+		Identity::new(b"", &self.encode())
+	}
+}
+
+/// A set of validators and their aggregate public key
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorSet<E: EngineBLS> {
+	/// The id of the validator set
+	pub id: u64,
+	/// The aggregate public key of the validators
+	pub public_key: E::PublicKeyGroup,
+}
+
+/// A commitment and the aggregate signature of the validators on it
+pub struct SignedCommitment<E: EngineBLS> {
+	/// The signed commitment
+	pub commitment: Commitment,
+	/// The aggregate signature on the commitment
+	pub signature: E::SignatureGroup,
+}
+
+impl<E: EngineBLS> SignedCommitment<E> {
+	/// Decode a signed commitment, rejecting signatures that are not valid
+	/// group elements
+	///
+	/// * `commitment`: The SCALE encoded commitment
+	/// * `signature`: The compressed aggregate signature
+	pub fn decode(mut commitment: &[u8], signature: &[u8]) -> Result<Self, Error> {
+		let commitment =
+			Commitment::decode(&mut commitment).map_err(|_| Error::DeserializationError)?;
+		let signature = strict::signature::<E>(signature)?;
+		Ok(Self { commitment, signature })
+	}
+
+	/// Check that the commitment is signed by a validator set
+	///
+	/// * `validators`: The validator set expected to sign the commitment
+	pub fn verify(&self, validators: &ValidatorSet<E>) -> Result<(), Error> {
+		if self.commitment.validator_set_id != validators.id {
+			return Err(Error::ValidatorSetMismatch);
+		}
+		let message = self.commitment.identity().public::<E>();
+		if E::pairing(validators.public_key, message) !=
+			E::pairing(E::PublicKeyGroup::generator(), self.signature)
+		{
+			return Err(Error::InvalidSignature);
+		}
+		Ok(())
+	}
+}
+
+/// Verify a signed commitment, then decrypt a ciphertext with its signature
+///
+/// * `ciphertext`: A ciphertext encrypted to the identity of the commitment
+/// * `signed`: The signed commitment
+/// * `validators`: The validator set expected to sign the commitment
+pub fn tld_commitment<E, S>(
+	ciphertext: TLECiphertext<E>,
+	signed: &SignedCommitment<E>,
+	validators: &ValidatorSet<E>,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	signed.verify(validators)?;
+	tld::<E, S>(ciphertext, signed.signature)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381, tlock::tle};
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec, UniformRand,
+	};
+
+	type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+
+	fn commitment(block_number: u32) -> Commitment {
+		Commitment { payload: vec![(*b"mh", vec![7; 32])], block_number, validator_set_id: 3 }
+	}
+
+	fn setup() -> (Vec<Scalar>, ValidatorSet<TinyBLS381>) {
+		let mut rng = StdRng::seed_from_u64(64);
+		let validators: Vec<Scalar> = (0..3).map(|_| Scalar::rand(&mut rng)).collect();
+		let public_key = validators
+			.iter()
+			.map(|sk| <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * sk)
+			.sum();
+		(validators, ValidatorSet { id: 3, public_key })
+	}
+
+	fn sign(validators: &[Scalar], commitment: Commitment) -> SignedCommitment<TinyBLS381> {
+		let identity = commitment.identity();
+		let signature = validators.iter().map(|sk| identity.extract::<TinyBLS381>(*sk).0).sum();
+		SignedCommitment { commitment, signature }
+	}
+
+	#[test]
+	fn verified_commitments_decrypt() {
+		let (validators, set) = setup();
+		let signed = sign(&validators, commitment(1_000));
+		let ciphertext = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			set.public_key,
+			[9; 32],
+			b"beefy",
+			signed.commitment.identity(),
+			StdRng::seed_from_u64(9),
+		)
+		.unwrap();
+
+		let mut signature = Vec::new();
+		signed.signature.serialize_compressed(&mut signature).unwrap();
+		let decoded =
+			SignedCommitment::<TinyBLS381>::decode(&signed.commitment.encode(), &signature)
+				.unwrap();
+		assert_eq!(decoded.commitment, signed.commitment);
+		assert_eq!(
+			tld_commitment::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, &decoded, &set),
+			Ok(b"beefy".to_vec())
+		);
+	}
+
+	#[test]
+	fn forged_commitments_are_rejected() {
+		let (validators, set) = setup();
+
+		// a signature on another commitment
+		let mut forged = sign(&validators, commitment(1_000));
+		forged.commitment.block_number = 1_001;
+		assert_eq!(forged.verify(&set), Err(Error::InvalidSignature));
+
+		// a signature missing a validator
+		let partial = sign(&validators[1..], commitment(1_000));
+		assert_eq!(partial.verify(&set), Err(Error::InvalidSignature));
+
+		// a commitment of another validator set
+		let mut other = commitment(1_000);
+		other.validator_set_id = 4;
+		assert_eq!(sign(&validators, other).verify(&set), Err(Error::ValidatorSetMismatch));
+
+		assert_eq!(
+			SignedCommitment::<TinyBLS381>::decode(&[1, 2, 3], &[0; 48]).err(),
+			Some(Error::DeserializationError)
+		);
+	}
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Bounded ciphertexts for on-chain storage.
+//!
+//! Runtime storage needs a [`MaxEncodedLen`] for every stored type, which an
+//! unbounded [`TLECiphertext`] cannot give. A [`BoundedTLECiphertext`] holds
+//! the same parts as compressed bytes in [`BoundedVec`]s, with the message
+//! bounded by `MaxLen`, and is SCALE encoded with [`TypeInfo`] metadata.
+
+use crate::{
+	engines::EngineBLS,
+	ibe::fullident::Ciphertext as IBECiphertext,
+	tlock::{Error, TLECiphertext},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{marker::PhantomData, vec::Vec};
+use bounded_collections::{BoundedVec, ConstU32, Get};
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// The bound of the compressed header, which fits the header of every engine
+pub const MAX_HEADER_LEN: u32 = 160;
+
+/// The bound of the cipher suite
+pub const MAX_CIPHER_SUITE_LEN: u32 = 32;
+
+/// The size of the body beyond the message: the length prefixes of the
+/// sealed message and the nonce, the tag and the nonce of either cipher
+pub const BODY_OVERHEAD: u32 = 8 + 16 + 8 + 12;
+
+/// The bound of the body of a message of at most `MaxLen` bytes
+pub struct BodyBound<MaxLen>(PhantomData<MaxLen>);
+
+impl<MaxLen: Get<u32>> Get<u32> for BodyBound<MaxLen> {
+	fn get() -> u32 {
+		MaxLen::get().saturating_add(BODY_OVERHEAD)
+	}
+}
+
+/// A [`TLECiphertext`] of a message of at most `MaxLen` bytes
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[scale_info(skip_type_params(E, MaxLen))]
+pub struct BoundedTLECiphertext<E: EngineBLS, MaxLen: Get<u32>> {
+	/// The compressed IBE header
+	pub header: BoundedVec<u8, ConstU32<MAX_HEADER_LEN>>,
+	/// The body holds the message encrypted with a stream cipher
+	pub body: BoundedVec<u8, BodyBound<MaxLen>>,
+	/// The cipher suite used (symmetric encryption scheme)
+	pub cipher_suite: BoundedVec<u8, ConstU32<MAX_CIPHER_SUITE_LEN>>,
+	#[codec(skip)]
+	_engine: PhantomData<E>,
+}
+
+impl<E: EngineBLS, MaxLen: Get<u32>> BoundedTLECiphertext<E, MaxLen> {
+	/// Bound a ciphertext, failing with [`Error::ExceedsBound`] if its message
+	/// is longer than `MaxLen`
+	///
+	/// * `ciphertext`: The ciphertext
+	pub fn new(ciphertext: TLECiphertext<E>) -> Result<Self, Error> {
+		let mut header = Vec::new();
+		ciphertext
+			.header
+			.serialize_compressed(&mut header)
+			.map_err(|_| Error::DeserializationError)?;
+		Ok(Self {
+			header: header.try_into().map_err(|_| Error::ExceedsBound)?,
+			body: ciphertext.body.try_into().map_err(|_| Error::ExceedsBound)?,
+			cipher_suite: ciphertext.cipher_suite.try_into().map_err(|_| Error::ExceedsBound)?,
+			_engine: PhantomData,
+		})
+	}
+
+	/// The unbounded ciphertext, e.g. to decrypt it with [`super::tld`]
+	pub fn into_ciphertext(self) -> Result<TLECiphertext<E>, Error> {
+		let header = IBECiphertext::<E>::deserialize_compressed(&self.header[..])
+			.map_err(|_| Error::DeserializationError)?;
+		Ok(TLECiphertext {
+			header,
+			body: self.body.into_inner(),
+			cipher_suite: self.cipher_suite.into_inner(),
+		})
+	}
+}
+
+impl<E: EngineBLS, MaxLen: Get<u32>> Clone for BoundedTLECiphertext<E, MaxLen> {
+	fn clone(&self) -> Self {
+		Self {
+			header: self.header.clone(),
+			body: self.body.clone(),
+			cipher_suite: self.cipher_suite.clone(),
+			_engine: PhantomData,
+		}
+	}
+}
+
+impl<E: EngineBLS, MaxLen: Get<u32>> PartialEq for BoundedTLECiphertext<E, MaxLen> {
+	fn eq(&self, other: &Self) -> bool {
+		self.header == other.header &&
+			self.body == other.body &&
+			self.cipher_suite == other.cipher_suite
+	}
+}
+
+impl<E: EngineBLS, MaxLen: Get<u32>> Eq for BoundedTLECiphertext<E, MaxLen> {}
+
+impl<E: EngineBLS, MaxLen: Get<u32>> core::fmt::Debug for BoundedTLECiphertext<E, MaxLen> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("BoundedTLECiphertext")
+			.field("header", &self.header)
+			.field("body", &self.body)
+			.field("cipher_suite", &self.cipher_suite)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::ChaCha20Poly1305BlockCipherProvider,
+		engines::drand::{TinyBLS377, TinyBLS381},
+		tlock::{round_identity, tld, tle},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		UniformRand,
+	};
+
+	type Bounded<E> = BoundedTLECiphertext<E, ConstU32<32>>;
+
+	fn lock<E: EngineBLS>(message: &[u8]) -> (TLECiphertext<E>, E::SignatureGroup) {
+		let mut rng = StdRng::seed_from_u64(7);
+		let secret = E::Scalar::rand(&mut rng);
+		let p_pub = E::PublicKeyGroup::generator() * secret;
+		let ciphertext = tle::<E, ChaCha20Poly1305BlockCipherProvider, _>(
+			p_pub,
+			[7; 32],
+			message,
+			round_identity(7),
+			rng,
+		)
+		.unwrap();
+		(ciphertext, round_identity(7).extract::<E>(secret).0)
+	}
+
+	fn round_trips<E: EngineBLS>() {
+		let (ciphertext, signature) = lock::<E>(&[1; 32]);
+		let bounded = Bounded::<E>::new(ciphertext).unwrap();
+		let encoded = bounded.encode();
+		assert!(encoded.len() <= Bounded::<E>::max_encoded_len());
+		let decoded = Bounded::<E>::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded, bounded);
+		let plaintext = tld::<E, ChaCha20Poly1305BlockCipherProvider>(
+			decoded.into_ciphertext().unwrap(),
+			signature,
+		);
+		assert_eq!(plaintext, Ok([1; 32].to_vec()));
+	}
+
+	#[test]
+	fn bounded_ciphertexts_round_trip() {
+		round_trips::<TinyBLS381>();
+		round_trips::<TinyBLS377>();
+		assert_eq!(<BodyBound<ConstU32<32>> as Get<u32>>::get(), 32 + BODY_OVERHEAD);
+		// compact length prefixes, then the header, body and cipher suite
+		assert_eq!(Bounded::<TinyBLS381>::max_encoded_len(), 2 + 160 + 2 + 76 + 1 + 32);
+		let _ = scale_info::meta_type::<Bounded<TinyBLS381>>();
+	}
+
+	#[test]
+	fn long_messages_exceed_the_bound() {
+		let (ciphertext, _) = lock::<TinyBLS381>(&[1; 33]);
+		assert_eq!(Bounded::<TinyBLS381>::new(ciphertext).err(), Some(Error::ExceedsBound));
+
+		// nor does the encoding of a longer message decode
+		let (ciphertext, _) = lock::<TinyBLS381>(&[1; 33]);
+		let encoded = BoundedTLECiphertext::<TinyBLS381, ConstU32<33>>::new(ciphertext)
+			.unwrap()
+			.encode();
+		assert!(Bounded::<TinyBLS381>::decode(&mut &encoded[..]).is_err());
+	}
+}
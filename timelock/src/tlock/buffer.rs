@@ -0,0 +1,430 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Encryption and decryption into caller buffers.
+//!
+//! [`super::tle`] copies the message into the AES-GCM buffer, serializes the
+//! body into another buffer and leaves the caller to serialize the whole
+//! ciphertext into a third; [`super::tld`] does the same in reverse.
+//! [`tle_into`] writes the serialized ciphertext straight into a caller
+//! provided buffer and encrypts the message in place there, and [`tld_into`]
+//! decrypts a serialized ciphertext in place in the caller's output buffer,
+//! so neither allocates in proportion to the message. This suits embedded
+//! targets and FFI callers that manage their own memory. [`tld_early_into`]
+//! decrypts the same way with the ephemeral secret key instead of a signature.
+//!
+//! The output is the compressed serialization of a [`TLECiphertext`] with
+//! the AES-GCM cipher suite, exactly as [`super::tle`] with
+//! [`AESGCMBlockCipherProvider`] produces it. Ciphertexts of either
+//! [`CipherSuite`] are decrypted, as their bodies have the same layout.
+
+use crate::{
+	block_ciphers::{AESGCMBlockCipherProvider, BlockCipherProvider, CipherSuite},
+	engines::EngineBLS,
+	ibe::fullident::{encrypt_for_point, Ciphertext as IBECiphertext, IBESecret, Identity, Input},
+	tlock::{
+		strict,
+		view::{header_len, take, take_len, TLECiphertextRef, LEN_SIZE},
+		Error, OpaqueSecretKey,
+	},
+};
+use aes_gcm::{
+	aead::{AeadCore, AeadInPlace, KeyInit},
+	Aes256Gcm, Nonce, Tag,
+};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{CryptoRng, Rng};
+use chacha20poly1305::ChaCha20Poly1305;
+use zeroize::Zeroize;
+
+/// The size of the authentication tag of either cipher suite
+const TAG_SIZE: usize = 16;
+
+/// The size of the nonce of either cipher suite
+const NONCE_SIZE: usize = 12;
+
+/// The size of the serialized ciphertext of a message
+///
+/// * `message_len`: The length of the message
+pub fn tle_len<E: EngineBLS>(message_len: usize) -> usize {
+	header_len::<E>() +
+		LEN_SIZE + // body
+		LEN_SIZE + message_len + TAG_SIZE +
+		LEN_SIZE + NONCE_SIZE +
+		LEN_SIZE + cipher_suite().len()
+}
+
+/// Write `bytes` at `*at`, advancing it
+fn put(out: &mut [u8], at: &mut usize, bytes: &[u8]) {
+	out[*at..*at + bytes.len()].copy_from_slice(bytes);
+	*at += bytes.len();
+}
+
+/// The cipher suite of AES-GCM ciphertexts
+fn cipher_suite() -> &'static [u8] {
+	<AESGCMBlockCipherProvider as BlockCipherProvider<32>>::CIPHER_SUITE
+}
+
+/// Encrypt a message for an identity into `out`, returning the length of the
+/// serialized ciphertext
+///
+/// `out` must hold at least [`tle_len`] bytes. Given the same randomness the
+/// ciphertext is the serialization of the output of [`super::tle`].
+///
+/// * `p_pub`: The public key of the beacon
+/// * `secret_key`: The ephemeral secret key
+/// * `message`: The message to encrypt
+/// * `id`: The identity (e.g. round) to encrypt for
+/// * `rng`: A CSPRNG
+/// * `out`: Where to write the serialized ciphertext
+pub fn tle_into<E, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: &Identity,
+	rng: R,
+	out: &mut [u8],
+) -> Result<usize, Error>
+where
+	E: EngineBLS,
+	R: Rng + CryptoRng,
+{
+	tle_into_point::<E, R>(p_pub, secret_key, message, id.public::<E>(), rng, out)
+}
+
+/// Encrypt a message for the identity with public key `q_id` into `out`,
+/// returning the length of the serialized ciphertext
+pub(crate) fn tle_into_point<E, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	q_id: E::SignatureGroup,
+	mut rng: R,
+	out: &mut [u8],
+) -> Result<usize, Error>
+where
+	E: EngineBLS,
+	R: Rng + CryptoRng,
+{
+	let len = tle_len::<E>(message.len());
+	if out.len() < len {
+		return Err(Error::BufferTooSmall);
+	}
+	let input = Input::<E>::new(secret_key).map_err(|_| Error::InvalidSecretKey)?;
+	let header = encrypt_for_point(&input, p_pub, q_id, &mut rng);
+	let cipher = Aes256Gcm::new(&secret_key.into());
+	let nonce = Aes256Gcm::generate_nonce(&mut rng);
+
+	let mut at = header_len::<E>();
+	header
+		.serialize_compressed(&mut out[..at])
+		.map_err(|_| Error::MessageEncryptionError)?;
+	let sealed_len = message.len() + TAG_SIZE;
+	let body_len = LEN_SIZE + sealed_len + LEN_SIZE + NONCE_SIZE;
+	put(out, &mut at, &(body_len as u64).to_le_bytes());
+	put(out, &mut at, &(sealed_len as u64).to_le_bytes());
+	let start = at;
+	put(out, &mut at, message);
+	let tag = cipher
+		.encrypt_in_place_detached(&nonce, b"", &mut out[start..at])
+		.map_err(|_| Error::MessageEncryptionError)?;
+	put(out, &mut at, &tag);
+	put(out, &mut at, &(NONCE_SIZE as u64).to_le_bytes());
+	put(out, &mut at, &nonce);
+	put(out, &mut at, &(cipher_suite().len() as u64).to_le_bytes());
+	put(out, &mut at, cipher_suite());
+	Ok(at)
+}
+
+/// The parts of a serialized ciphertext
+struct Parts<'a, E: EngineBLS> {
+	header: IBECiphertext<E>,
+	suite: CipherSuite,
+	sealed: &'a [u8],
+	tag: &'a [u8],
+	nonce: &'a [u8],
+}
+
+/// Split a serialized ciphertext into its parts, without copying the body
+fn parse<E: EngineBLS>(ciphertext: &[u8]) -> Result<Parts<'_, E>, Error> {
+	let TLECiphertextRef { header, body, cipher_suite: suite } =
+		strict::ciphertext_ref::<E>(ciphertext)?;
+	let suite = CipherSuite::from_id(suite).ok_or(Error::InvalidFormat)?;
+	let mut at = 0;
+	let sealed_len = take_len(body, &mut at)?;
+	let sealed = take(body, &mut at, sealed_len)?;
+	if sealed.len() < TAG_SIZE || take_len(body, &mut at)? != NONCE_SIZE {
+		return Err(Error::DeserializationError);
+	}
+	let nonce = take(body, &mut at, NONCE_SIZE)?;
+	if at != body.len() {
+		return Err(Error::DeserializationError);
+	}
+	let (sealed, tag) = sealed.split_at(sealed.len() - TAG_SIZE);
+	Ok(Parts { header, suite, sealed, tag, nonce })
+}
+
+/// The length of the message of a serialized ciphertext
+///
+/// * `ciphertext`: A ciphertext serialized by [`tle_into`] or [`super::tle`]
+pub fn tld_len<E: EngineBLS>(ciphertext: &[u8]) -> Result<usize, Error> {
+	Ok(parse::<E>(ciphertext)?.sealed.len())
+}
+
+/// Decrypt a serialized ciphertext into `out`, returning the length of the
+/// message
+///
+/// `out` must hold at least [`tld_len`] bytes. The ciphertext is parsed
+/// strictly (see [`super::strict`]).
+///
+/// * `ciphertext`: A ciphertext serialized by [`tle_into`] or [`super::tle`]
+/// * `signature`: The signature of the round the ciphertext is locked to
+/// * `out`: Where to write the message
+pub fn tld_into<E: EngineBLS>(
+	ciphertext: &[u8],
+	signature: E::SignatureGroup,
+	out: &mut [u8],
+) -> Result<usize, Error> {
+	let parts = parse::<E>(ciphertext)?;
+	if out.len() < parts.sealed.len() {
+		return Err(Error::BufferTooSmall);
+	}
+	let mut secret_key = IBESecret(signature).decrypt(&parts.header)?;
+	let result = open_into(&parts, &secret_key, out);
+	secret_key.zeroize();
+	result
+}
+
+/// Decrypt a serialized ciphertext into `out` with the ephemeral secret key it
+/// was encrypted with, returning the length of the message
+///
+/// This bypasses the timelock: the message can be read before the round, by
+/// whoever kept the key. `out` must hold at least [`tld_len`] bytes.
+///
+/// * `ciphertext`: A ciphertext serialized by [`tle_into`] or [`super::tle`]
+/// * `secret_key`: The ephemeral secret key of the encryption
+/// * `out`: Where to write the message
+pub fn tld_early_into<E: EngineBLS>(
+	ciphertext: &[u8],
+	secret_key: &OpaqueSecretKey,
+	out: &mut [u8],
+) -> Result<usize, Error> {
+	let parts = parse::<E>(ciphertext)?;
+	if out.len() < parts.sealed.len() {
+		return Err(Error::BufferTooSmall);
+	}
+	open_into(&parts, secret_key, out)
+}
+
+/// Decrypt the body of a ciphertext in place in `out`, which holds at least
+/// the sealed message
+fn open_into<E: EngineBLS>(
+	parts: &Parts<'_, E>,
+	secret_key: &OpaqueSecretKey,
+	out: &mut [u8],
+) -> Result<usize, Error> {
+	let len = parts.sealed.len();
+	let sealed = &mut out[..len];
+	sealed.copy_from_slice(parts.sealed);
+	// both ciphers take a 96-bit nonce and a 128-bit tag
+	let nonce = Nonce::from_slice(parts.nonce);
+	let tag = Tag::from_slice(parts.tag);
+	match parts.suite {
+		CipherSuite::AesGcm =>
+			Aes256Gcm::new(secret_key.into()).decrypt_in_place_detached(nonce, b"", sealed, tag),
+		CipherSuite::ChaCha20Poly1305 => ChaCha20Poly1305::new(secret_key.into())
+			.decrypt_in_place_detached(nonce, b"", sealed, tag),
+	}
+	.map_err(|_| Error::DecryptionError)?;
+	Ok(len)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle, BeaconConfig, TLECiphertext},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalDeserialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec,
+		vec::Vec,
+		UniformRand,
+	};
+
+	#[test]
+	fn buffers_hold_the_serialized_ciphertext() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let signature = id.extract::<TinyBLS381>(secret).0;
+
+		for message in [&b""[..], b"into a buffer"] {
+			let mut out = [0u8; 512];
+			let len = tle_into::<TinyBLS381, _>(
+				p_pub,
+				[3u8; 32],
+				message,
+				&id,
+				StdRng::seed_from_u64(5),
+				&mut out,
+			)
+			.unwrap();
+			assert_eq!(len, tle_len::<TinyBLS381>(message.len()));
+
+			let expected = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+				p_pub,
+				[3u8; 32],
+				message,
+				id.clone(),
+				StdRng::seed_from_u64(5),
+			)
+			.unwrap();
+			let mut bytes = Vec::new();
+			expected.serialize_compressed(&mut bytes).unwrap();
+			assert_eq!(out[..len], bytes[..]);
+
+			let mut plaintext = [0u8; 64];
+			let n = tld_into::<TinyBLS381>(&out[..len], signature, &mut plaintext).unwrap();
+			assert_eq!(&plaintext[..n], message);
+			assert_eq!(tld_len::<TinyBLS381>(&bytes), Ok(message.len()));
+			let parsed = TLECiphertext::<TinyBLS381>::deserialize_compressed(&bytes[..]).unwrap();
+			let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(parsed, signature);
+			assert_eq!(plaintext.as_deref(), Ok(message));
+		}
+	}
+
+	#[test]
+	fn buffers_that_are_too_small_or_tampered_are_rejected() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let signature = id.extract::<TinyBLS381>(secret).0;
+		let message = [9u8; 40];
+
+		let mut out = vec![0u8; tle_len::<TinyBLS381>(message.len()) - 1];
+		let result = tle_into::<TinyBLS381, _>(
+			p_pub,
+			[3u8; 32],
+			&message,
+			&id,
+			StdRng::seed_from_u64(5),
+			&mut out,
+		);
+		assert_eq!(result, Err(Error::BufferTooSmall));
+		out.push(0);
+		let len = tle_into::<TinyBLS381, _>(
+			p_pub,
+			[3u8; 32],
+			&message,
+			&id,
+			StdRng::seed_from_u64(5),
+			&mut out,
+		)
+		.unwrap();
+
+		let mut plaintext = [0u8; 40];
+		assert_eq!(
+			tld_into::<TinyBLS381>(&out, signature, &mut plaintext[..39]),
+			Err(Error::BufferTooSmall)
+		);
+		assert_eq!(
+			tld_into::<TinyBLS381>(&out[..len - 1], signature, &mut plaintext),
+			Err(Error::DeserializationError)
+		);
+		let other = BeaconConfig::<TinyBLS381>::identity(1001).extract::<TinyBLS381>(secret).0;
+		assert_eq!(
+			tld_into::<TinyBLS381>(&out, other, &mut plaintext),
+			Err(Error::InvalidSignature)
+		);
+		out[200] ^= 1;
+		assert_eq!(
+			tld_into::<TinyBLS381>(&out, signature, &mut plaintext),
+			Err(Error::DecryptionError)
+		);
+	}
+
+	#[test]
+	fn buffers_are_decrypted_early_with_the_secret_key() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let message = b"before the round";
+
+		let mut out = [0u8; 512];
+		let len = tle_into::<TinyBLS381, _>(
+			p_pub,
+			[3u8; 32],
+			message,
+			&id,
+			StdRng::seed_from_u64(5),
+			&mut out,
+		)
+		.unwrap();
+
+		let mut plaintext = [0u8; 64];
+		let n = tld_early_into::<TinyBLS381>(&out[..len], &[3u8; 32], &mut plaintext).unwrap();
+		assert_eq!(&plaintext[..n], message);
+		assert_eq!(
+			tld_early_into::<TinyBLS381>(&out[..len], &[3u8; 32], &mut plaintext[..8]),
+			Err(Error::BufferTooSmall)
+		);
+		assert_eq!(
+			tld_early_into::<TinyBLS381>(&out[..len], &[4u8; 32], &mut plaintext),
+			Err(Error::DecryptionError)
+		);
+	}
+
+	#[test]
+	fn chacha_ciphertexts_are_decrypted_into_buffers() {
+		use crate::block_ciphers::ChaCha20Poly1305BlockCipherProvider;
+
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let signature = id.extract::<TinyBLS381>(secret).0;
+		let message = b"without AES instructions";
+
+		let ciphertext = tle::<TinyBLS381, ChaCha20Poly1305BlockCipherProvider, _>(
+			p_pub,
+			[3u8; 32],
+			message,
+			id,
+			StdRng::seed_from_u64(5),
+		)
+		.unwrap();
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+		assert_eq!(tld_len::<TinyBLS381>(&bytes), Ok(message.len()));
+
+		let mut plaintext = [0u8; 64];
+		let n = tld_into::<TinyBLS381>(&bytes, signature, &mut plaintext).unwrap();
+		assert_eq!(&plaintext[..n], message);
+		let n = tld_early_into::<TinyBLS381>(&bytes, &[3u8; 32], &mut plaintext).unwrap();
+		assert_eq!(&plaintext[..n], message);
+
+		// the body does not open under the other cipher
+		let suite = bytes.len() - ChaCha20Poly1305BlockCipherProvider::CIPHER_SUITE.len();
+		bytes[suite..].copy_from_slice(cipher_suite());
+		assert_eq!(
+			tld_into::<TinyBLS381>(&bytes, signature, &mut plaintext),
+			Err(Error::DecryptionError)
+		);
+	}
+}
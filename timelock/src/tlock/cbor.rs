@@ -0,0 +1,295 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Deterministic CBOR encodings of ciphertexts and identities.
+//!
+//! Both encodings are a tagged map with small unsigned integer keys in
+//! ascending order, using the shortest form of every length and integer
+//! (RFC 8949 §4.2.1 core deterministic encoding), so that equal values always
+//! encode to equal bytes. Decoding only accepts this form.
+//!
+//! A [`TLECiphertext`] is encoded as
+//!
+//! ```text
+//! 5524549({0: 1, 1: u, 2: v, 3: w, 4: body, 5: cipher_suite})
+//! ```
+//!
+//! where key 0 is the schema version, `u` is the compressed group element and
+//! every other value is a byte string. An [`Identity`] is encoded as
+//!
+//! ```text
+//! 5524553({0: 1, 1: digest, 2: bytes})
+//! ```
+//!
+//! holding the message digest and the (context and identity) bytes it was
+//! derived from. The tags are in the first-come-first-served range and spell
+//! `TLE` and `TLI`.
+
+use crate::{
+	engines::EngineBLS,
+	ibe::fullident::{Ciphertext as IBECiphertext, Identity},
+	tlock::{Error, TLECiphertext},
+	Message,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+/// The CBOR tag of a timelock ciphertext
+pub const CIPHERTEXT_TAG: u64 = 0x544c45;
+/// The CBOR tag of an identity
+pub const IDENTITY_TAG: u64 = 0x544c49;
+/// The version of the schemas
+pub const SCHEMA_VERSION: u64 = 1;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+
+/// Encode a ciphertext as deterministic CBOR
+///
+/// * `ciphertext`: The ciphertext to encode
+pub fn ciphertext_to_cbor<E: EngineBLS>(ciphertext: &TLECiphertext<E>) -> Vec<u8> {
+	let mut u = Vec::new();
+	ciphertext
+		.header
+		.u
+		.serialize_compressed(&mut u)
+		.expect("The group element must be serializable.");
+
+	let mut out = Vec::new();
+	write_head(&mut out, MAJOR_TAG, CIPHERTEXT_TAG);
+	write_head(&mut out, MAJOR_MAP, 6);
+	write_head(&mut out, MAJOR_UNSIGNED, 0);
+	write_head(&mut out, MAJOR_UNSIGNED, SCHEMA_VERSION);
+	for (key, value) in [
+		&u[..],
+		&ciphertext.header.v,
+		&ciphertext.header.w,
+		&ciphertext.body,
+		&ciphertext.cipher_suite,
+	]
+	.into_iter()
+	.enumerate()
+	{
+		write_head(&mut out, MAJOR_UNSIGNED, key as u64 + 1);
+		write_bytes(&mut out, value);
+	}
+	out
+}
+
+/// Decode a ciphertext from deterministic CBOR
+///
+/// * `bytes`: The output of [`ciphertext_to_cbor`]
+pub fn ciphertext_from_cbor<E: EngineBLS>(bytes: &[u8]) -> Result<TLECiphertext<E>, Error> {
+	let mut reader = Reader { bytes, pos: 0 };
+	let fields = reader.tagged_map(CIPHERTEXT_TAG, 6)?;
+	let u = E::PublicKeyGroup::deserialize_compressed(fields[0])
+		.map_err(|_| Error::DeserializationErrorG2)?;
+	let v = fields[1].try_into().map_err(|_| Error::InvalidFormat)?;
+	let w = fields[2].try_into().map_err(|_| Error::InvalidFormat)?;
+
+	Ok(TLECiphertext {
+		header: IBECiphertext { u, v, w },
+		body: fields[3].to_vec(),
+		cipher_suite: fields[4].to_vec(),
+	})
+}
+
+/// Encode an identity as deterministic CBOR
+///
+/// * `identity`: The identity to encode
+pub fn identity_to_cbor(identity: &Identity) -> Vec<u8> {
+	let mut out = Vec::new();
+	write_head(&mut out, MAJOR_TAG, IDENTITY_TAG);
+	write_head(&mut out, MAJOR_MAP, 3);
+	write_head(&mut out, MAJOR_UNSIGNED, 0);
+	write_head(&mut out, MAJOR_UNSIGNED, SCHEMA_VERSION);
+	write_head(&mut out, MAJOR_UNSIGNED, 1);
+	write_bytes(&mut out, &identity.0 .0);
+	write_head(&mut out, MAJOR_UNSIGNED, 2);
+	write_bytes(&mut out, &identity.0 .1);
+	out
+}
+
+/// Decode an identity from deterministic CBOR
+///
+/// * `bytes`: The output of [`identity_to_cbor`]
+pub fn identity_from_cbor(bytes: &[u8]) -> Result<Identity, Error> {
+	let mut reader = Reader { bytes, pos: 0 };
+	let fields = reader.tagged_map(IDENTITY_TAG, 3)?;
+	let digest = fields[0].try_into().map_err(|_| Error::InvalidFormat)?;
+	Ok(Identity(Message(digest, fields[1].to_vec())))
+}
+
+/// Write the head of a data item with the shortest argument encoding
+fn write_head(out: &mut Vec<u8>, major: u8, argument: u64) {
+	let major = major << 5;
+	match argument {
+		0..=23 => out.push(major | argument as u8),
+		24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+		0x100..=0xffff => {
+			out.push(major | 25);
+			out.extend_from_slice(&(argument as u16).to_be_bytes());
+		},
+		0x10000..=0xffff_ffff => {
+			out.push(major | 26);
+			out.extend_from_slice(&(argument as u32).to_be_bytes());
+		},
+		_ => {
+			out.push(major | 27);
+			out.extend_from_slice(&argument.to_be_bytes());
+		},
+	}
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+	write_head(out, MAJOR_BYTES, bytes.len() as u64);
+	out.extend_from_slice(bytes);
+}
+
+/// A strict reader for the deterministic subset of CBOR used here
+struct Reader<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+		let end = self.pos.checked_add(n).ok_or(Error::InvalidFormat)?;
+		let slice = self.bytes.get(self.pos..end).ok_or(Error::InvalidFormat)?;
+		self.pos = end;
+		Ok(slice)
+	}
+
+	/// Read the head of a data item, rejecting non-shortest arguments
+	fn head(&mut self, major: u8) -> Result<u64, Error> {
+		let initial = self.take(1)?[0];
+		if initial >> 5 != major {
+			return Err(Error::InvalidFormat);
+		}
+		let (argument, min) = match initial & 0x1f {
+			info @ 0..=23 => return Ok(info as u64),
+			24 => (self.take(1)?[0] as u64, 24),
+			25 => (u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes")) as u64, 0x100),
+			26 => (u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")) as u64, 0x10000),
+			27 => (u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")), 0x1_0000_0000),
+			_ => return Err(Error::InvalidFormat),
+		};
+		if argument < min {
+			return Err(Error::InvalidFormat);
+		}
+		Ok(argument)
+	}
+
+	fn expect(&mut self, major: u8, argument: u64) -> Result<(), Error> {
+		if self.head(major)? != argument {
+			return Err(Error::InvalidFormat);
+		}
+		Ok(())
+	}
+
+	fn bytes(&mut self) -> Result<&'a [u8], Error> {
+		let len = self.head(MAJOR_BYTES)?;
+		self.take(usize::try_from(len).map_err(|_| Error::InvalidFormat)?)
+	}
+
+	/// Read `tag({0: version, 1: bytes, ..., entries - 1: bytes})` and nothing
+	/// else, returning the byte strings in key order
+	fn tagged_map(&mut self, tag: u64, entries: u64) -> Result<Vec<&'a [u8]>, Error> {
+		self.expect(MAJOR_TAG, tag)?;
+		self.expect(MAJOR_MAP, entries)?;
+		self.expect(MAJOR_UNSIGNED, 0)?;
+		self.expect(MAJOR_UNSIGNED, SCHEMA_VERSION)?;
+		let mut fields = Vec::with_capacity(entries as usize - 1);
+		for key in 1..entries {
+			self.expect(MAJOR_UNSIGNED, key)?;
+			fields.push(self.bytes()?);
+		}
+		if self.pos != self.bytes.len() {
+			return Err(Error::InvalidFormat);
+		}
+		Ok(fields)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	pub fn cbor_ciphertext_round_trips() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[3; 32],
+			b"constrained device",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		let bytes = ciphertext_to_cbor(&ct);
+		// tag 5524549, map(6), 0: 1, 1: bytes(96)
+		assert_eq!(bytes[..11], [0xda, 0x00, 0x54, 0x4c, 0x45, 0xa6, 0x00, 0x01, 0x01, 0x58, 96]);
+
+		let decoded = ciphertext_from_cbor::<TinyBLS381>(&bytes).unwrap();
+		assert_eq!(ciphertext_to_cbor(&decoded), bytes);
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(decoded, sig).unwrap();
+		assert_eq!(plaintext, b"constrained device".to_vec());
+	}
+
+	#[test]
+	pub fn cbor_identity_round_trips() {
+		let id = Identity::new(b"ctx", &1000u64.to_be_bytes());
+		let bytes = identity_to_cbor(&id);
+		let decoded = identity_from_cbor(&bytes).unwrap();
+		assert_eq!(decoded.0, id.0);
+		assert_eq!(identity_to_cbor(&decoded), bytes);
+	}
+
+	#[test]
+	pub fn cbor_rejects_non_canonical_input() {
+		let id = Identity::new(b"", b"round");
+		let bytes = identity_to_cbor(&id);
+
+		// trailing data
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert!(matches!(identity_from_cbor(&trailing), Err(Error::InvalidFormat)));
+
+		// the version encoded with a one byte argument instead of inline
+		let mut long = bytes[..7].to_vec();
+		long.extend_from_slice(&[0x18, 0x01]);
+		long.extend_from_slice(&bytes[8..]);
+		assert!(matches!(identity_from_cbor(&long), Err(Error::InvalidFormat)));
+
+		// the wrong tag
+		assert!(matches!(ciphertext_from_cbor::<TinyBLS381>(&bytes), Err(Error::InvalidFormat)));
+		assert!(matches!(identity_from_cbor(&bytes[..bytes.len() - 1]), Err(Error::InvalidFormat)));
+	}
+}
@@ -0,0 +1,218 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Onion timelock chains.
+//!
+//! A chain encrypts a message through a sequence of identities (e.g.
+//! consecutive rounds): the ciphertext for the first identity wraps the
+//! ciphertext for the second, and so on, so each published signature peels
+//! exactly one layer. Every layer uses a fresh ephemeral key. Inner layers are
+//! stored as just their header and body, since the cipher suite is shared by
+//! the whole chain, which keeps the overhead per layer to one IBE header and
+//! the block cipher's own expansion.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::{Ciphertext as IBECiphertext, Identity},
+	tlock::{tld, tle, Error, TLECiphertext},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+
+/// A message encrypted through a chain of identities
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ChainCiphertext<E: EngineBLS> {
+	/// The number of layers left, including the outermost one
+	pub layers: u32,
+	/// The outermost layer, decryptable with a signature on the next identity
+	pub ciphertext: TLECiphertext<E>,
+}
+
+/// The result of removing one layer from a chain
+pub enum Unwrapped<E: EngineBLS> {
+	/// The chain for the remaining identities
+	Next(ChainCiphertext<E>),
+	/// The message, once the innermost layer has been removed
+	Plaintext(Vec<u8>),
+}
+
+/// Encrypt a message through a chain of identities
+///
+/// The ciphertext must be unwrapped with signatures on `rounds` in order.
+///
+/// * `p_pub`: the public key commitment for the IBE system
+/// * `message`: The message to encrypt
+/// * `rounds`: The identities to encrypt for, outermost first
+/// * `rng`: A CSPRNG
+pub fn tle_chain<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	message: &[u8],
+	rounds: &[Identity],
+	mut rng: R,
+) -> Result<ChainCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let layers = u32::try_from(rounds.len()).map_err(|_| Error::InvalidChainLength)?;
+	if layers == 0 {
+		return Err(Error::InvalidChainLength);
+	}
+
+	let mut ciphertext: Option<TLECiphertext<E>> = None;
+	for id in rounds.iter().rev() {
+		let payload = match ciphertext {
+			None => message.to_vec(),
+			Some(inner) => {
+				let mut bytes = Vec::new();
+				(inner.header, inner.body)
+					.serialize_compressed(&mut bytes)
+					.expect("The ciphertext must be serializable.");
+				bytes
+			},
+		};
+		let mut secret_key = [0u8; 32];
+		rng.fill_bytes(&mut secret_key);
+		ciphertext = Some(tle::<E, S, _>(p_pub, secret_key, &payload, id.clone(), &mut rng)?);
+	}
+
+	Ok(ChainCiphertext { layers, ciphertext: ciphertext.expect("There is at least one round.") })
+}
+
+/// Remove the outermost layer of a chain
+///
+/// * `chain`: A ciphertext output by [`tle_chain`] or a previous unwrap
+/// * `signature`: A signature on the identity of the outermost layer
+pub fn unwrap_layer<E, S>(
+	chain: ChainCiphertext<E>,
+	signature: E::SignatureGroup,
+) -> Result<Unwrapped<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let ChainCiphertext { layers, ciphertext } = chain;
+	let cipher_suite = ciphertext.cipher_suite.clone();
+	let payload = tld::<E, S>(ciphertext, signature)?;
+	if layers <= 1 {
+		return Ok(Unwrapped::Plaintext(payload));
+	}
+
+	let (header, body) = <(IBECiphertext<E>, Vec<u8>)>::deserialize_compressed(&payload[..])
+		.map_err(|_| Error::DeserializationError)?;
+	Ok(Unwrapped::Next(ChainCiphertext {
+		layers: layers - 1,
+		ciphertext: TLECiphertext { header, body, cipher_suite },
+	}))
+}
+
+/// Decrypt a chain given signatures on every identity, outermost first
+///
+/// * `chain`: A ciphertext output by [`tle_chain`]
+/// * `signatures`: Signatures on the identities of the remaining layers
+pub fn tld_chain<E, S>(
+	chain: ChainCiphertext<E>,
+	signatures: &[E::SignatureGroup],
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	if signatures.len() != chain.layers as usize {
+		return Err(Error::InvalidChainLength);
+	}
+
+	let mut chain = chain;
+	for signature in signatures {
+		match unwrap_layer::<E, S>(chain, *signature)? {
+			Unwrapped::Next(next) => chain = next,
+			Unwrapped::Plaintext(message) => return Ok(message),
+		}
+	}
+	Err(Error::InvalidChainLength)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+	type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+
+	fn encrypt(n: u64) -> (ChainCiphertext<TinyBLS381>, Vec<Signature>) {
+		let msk = Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let rounds: Vec<Identity> =
+			(1000..1000 + n).map(|r| Identity::new(b"", &r.to_be_bytes())).collect();
+		let signatures = rounds.iter().map(|id| id.extract::<TinyBLS381>(msk).0).collect();
+		let chain = tle_chain::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			b"one step at a time",
+			&rounds,
+			OsRng,
+		)
+		.unwrap();
+		(chain, signatures)
+	}
+
+	#[test]
+	pub fn chain_unwraps_one_layer_per_round() {
+		let (mut chain, signatures) = encrypt(3);
+		assert_eq!(chain.layers, 3);
+
+		for (i, signature) in signatures.iter().enumerate() {
+			match unwrap_layer::<TinyBLS381, AESGCMBlockCipherProvider>(chain, *signature) {
+				Ok(Unwrapped::Next(next)) => {
+					assert_eq!(next.layers as usize, 2 - i);
+					chain = next;
+				},
+				Ok(Unwrapped::Plaintext(message)) => {
+					assert_eq!(i, 2);
+					assert_eq!(message, b"one step at a time".to_vec());
+					return;
+				},
+				Err(_) => panic!("layer {} should unwrap", i),
+			}
+		}
+		panic!("the chain should end in the plaintext");
+	}
+
+	#[test]
+	pub fn chain_requires_signatures_in_order() {
+		let (chain, mut signatures) = encrypt(2);
+		signatures.swap(0, 1);
+		let result = tld_chain::<TinyBLS381, AESGCMBlockCipherProvider>(chain, &signatures);
+		assert_eq!(result, Err(Error::InvalidSignature));
+	}
+
+	#[test]
+	pub fn chain_overhead_is_linear_in_layers() {
+		let (one, _) = encrypt(1);
+		let (two, _) = encrypt(2);
+		let (three, _) = encrypt(3);
+		let layer = two.compressed_size() - one.compressed_size();
+		assert_eq!(three.compressed_size() - two.compressed_size(), layer);
+	}
+}
@@ -0,0 +1,347 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! An R1CS circuit proving that a ciphertext encrypts a committed value.
+//!
+//! Sealed-bid protocols need to know that a ciphertext will open to the value
+//! a bidder committed to, before its round is reached. [`EncryptionCircuit`]
+//! proves that the IBE header `(U, V, W)` of a [`TLECiphertext`] is the
+//! encryption of a 32 byte value `k` to a round, exactly as [`super::tle`]
+//! computes it, where `k` opens the salted commitment
+//! [`PlaintextCommitment::new(k, salt)`](PlaintextCommitment::new):
+//!
+//! ```text
+//! r = H_3(sigma, k), U = rP, V = sigma (+) H_2(e(P_pub, Q_id)^r), W = k (+) H_4(sigma)
+//! ```
+//!
+//! The block cipher of the body is not part of the statement; encapsulate the
+//! committed value itself (e.g. a bid) as the key of the ciphertext.
+//!
+//! The circuit is over the base field of BLS12-377, so it only proves
+//! [`TinyBLS377`] ciphertexts, with a SNARK over a curve whose scalar field is
+//! that field (e.g. Groth16 over BW6-761). The public inputs are `e(P_pub,
+//! Q_id)`, which binds the beacon and the round and is computed by the
+//! verifier, the header and the commitment; see [`Statement::public_inputs`].
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::{drand::TinyBLS377, EngineBLS},
+	ibe::fullident::Input,
+	tlock::{
+		commitment::{PlaintextCommitment, COMMITMENT_DST, SALT_LENGTH},
+		round_identity,
+		witness::envelope,
+		Error, OpaqueSecretKey, TLECiphertext,
+	},
+};
+use ark_bls12_377::{
+	constraints::{Fq12Var, Fq2Var, G2Var},
+	Fq, Fq12, G2Affine, G2Projective,
+};
+use ark_crypto_primitives::crh::sha256::constraints::Sha256Gadget;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::{One, ToConstraintField};
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The field the circuit is defined over, the base field of BLS12-377
+pub type ConstraintF = Fq;
+
+/// The public inputs of an [`EncryptionCircuit`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+	/// e(P_pub, Q_id) for the beacon public key and the identity of the round
+	pub pairing: Fq12,
+	/// U of the header of the ciphertext
+	pub u: G2Affine,
+	/// V of the header of the ciphertext
+	pub v: [u8; 32],
+	/// W of the header of the ciphertext
+	pub w: [u8; 32],
+	/// The commitment to the encrypted value
+	pub commitment: PlaintextCommitment,
+}
+
+impl Statement {
+	/// The statement that a ciphertext encrypts a committed value to a round
+	///
+	/// * `p_pub`: The public key of the beacon
+	/// * `round`: The round the ciphertext is encrypted to
+	/// * `ciphertext`: The ciphertext
+	/// * `commitment`: The commitment to the encrypted value
+	pub fn new(
+		p_pub: G2Projective,
+		round: u64,
+		ciphertext: &TLECiphertext<TinyBLS377>,
+		commitment: PlaintextCommitment,
+	) -> Self {
+		let q_id = round_identity(round).public::<TinyBLS377>();
+		Self {
+			pairing: TinyBLS377::pairing(p_pub, q_id),
+			u: ciphertext.header.u.into_affine(),
+			v: ciphertext.header.v,
+			w: ciphertext.header.w,
+			commitment,
+		}
+	}
+
+	/// The public inputs of the circuit, in the order it allocates them: the
+	/// pairing, the affine coordinates of U, then V, W and the commitment
+	/// packed into field elements
+	pub fn public_inputs(&self) -> Vec<ConstraintF> {
+		let (x, y) = self.u.xy().unwrap_or_default();
+		let bytes: &[u8] = &self.bytes();
+		[
+			self.pairing.to_field_elements(),
+			x.to_field_elements(),
+			y.to_field_elements(),
+			bytes.to_field_elements(),
+		]
+		.into_iter()
+		.flat_map(|elements| elements.expect("Field elements and bytes are packable."))
+		.collect()
+	}
+
+	/// V, W and the commitment
+	fn bytes(&self) -> Vec<u8> {
+		[&self.v[..], &self.w[..], &self.commitment.0[..]].concat()
+	}
+}
+
+/// The secret inputs of an [`EncryptionCircuit`]
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct Witness {
+	sigma: [u8; 32],
+	value: OpaqueSecretKey,
+	salt: [u8; SALT_LENGTH],
+}
+
+/// A circuit proving that a ciphertext encrypts a committed value to a round
+#[derive(Clone)]
+pub struct EncryptionCircuit {
+	/// The public inputs
+	pub statement: Statement,
+	witness: Witness,
+}
+
+impl EncryptionCircuit {
+	/// A circuit of the same shape as every other, for generating keys
+	pub fn blank() -> Self {
+		Self {
+			statement: Statement {
+				pairing: Fq12::one(),
+				u: G2Affine::generator(),
+				v: [0; 32],
+				w: [0; 32],
+				commitment: PlaintextCommitment([0; 32]),
+			},
+			witness: Witness { sigma: [0; 32], value: [0; 32], salt: [0; SALT_LENGTH] },
+		}
+	}
+}
+
+impl ConstraintSynthesizer<ConstraintF> for EncryptionCircuit {
+	fn generate_constraints(
+		self,
+		cs: ConstraintSystemRef<ConstraintF>,
+	) -> Result<(), SynthesisError> {
+		let Self { statement, witness } = self;
+		let (x, y) = statement.u.xy().unwrap_or_default();
+
+		// the public inputs, in the order of `Statement::public_inputs`
+		let pairing = Fq12Var::new_input(cs.clone(), || Ok(statement.pairing))?;
+		let u_x = Fq2Var::new_input(cs.clone(), || Ok(x))?;
+		let u_y = Fq2Var::new_input(cs.clone(), || Ok(y))?;
+		let bytes = UInt8::new_input_vec(cs.clone(), &statement.bytes())?;
+		let (v, rest) = bytes.split_at(32);
+		let (w, commitment) = rest.split_at(32);
+
+		let sigma = UInt8::new_witness_vec(cs.clone(), &witness.sigma)?;
+		let value = UInt8::new_witness_vec(cs.clone(), &witness.value)?;
+		let salt = UInt8::new_witness_vec(cs.clone(), &witness.salt)?;
+
+		// r = H_3(sigma, k), as the little-endian bits of the big-endian digest;
+		// both groups have prime order, so r need not be reduced
+		let r = Sha256Gadget::digest(&[&sigma[..], &value[..]].concat())?.to_bytes_le()?;
+		let r_bits: Vec<Boolean<ConstraintF>> = r
+			.iter()
+			.rev()
+			.map(|byte| byte.to_bits_le())
+			.collect::<Result<Vec<_>, _>>()?
+			.concat();
+
+		// U = rP
+		let u = G2Var::constant(G2Projective::generator()).scalar_mul_le(r_bits.iter())?;
+		let u = u.to_affine()?;
+		u.x.enforce_equal(&u_x)?;
+		u.y.enforce_equal(&u_y)?;
+		u.infinity.enforce_equal(&Boolean::FALSE)?;
+
+		// V = sigma (+) H_2(e(P_pub, Q_id)^r)
+		let g_id = pairing.pow_le(&r_bits)?;
+		let mask = Sha256Gadget::digest(&g_id.to_bytes_le()?)?.to_bytes_le()?;
+		xor(&sigma, &mask).enforce_equal(&v.to_vec())?;
+
+		// W = k (+) H_4(sigma)
+		let mask = Sha256Gadget::digest(&sigma)?.to_bytes_le()?;
+		xor(&value, &mask).enforce_equal(&w.to_vec())?;
+
+		// the commitment H(dst, salt, k)
+		let dst = UInt8::constant_vec(COMMITMENT_DST);
+		let digest = Sha256Gadget::digest(&[&dst[..], &salt[..], &value[..]].concat())?;
+		digest.to_bytes_le()?.enforce_equal(&commitment.to_vec())?;
+		Ok(())
+	}
+}
+
+/// The bytewise XOR of two byte strings of equal length
+fn xor(a: &[UInt8<ConstraintF>], b: &[UInt8<ConstraintF>]) -> Vec<UInt8<ConstraintF>> {
+	a.iter().zip(b).map(|(a, b)| a ^ b).collect()
+}
+
+/// Timelock encrypt a value and build the circuit proving that the ciphertext
+/// encrypts it under a commitment
+///
+/// This behaves like [`crate::tlock::tle`] with `value` as the key, but keeps
+/// the randomness of the header as the witness of the circuit. The statement
+/// of the circuit holds the commitment `PlaintextCommitment::new(value,
+/// salt)`.
+///
+/// * `p_pub`: The public key of the beacon
+/// * `value`: The committed value, encapsulated as the key of the ciphertext
+/// * `salt`: The salt of the commitment
+/// * `message`: The message to encrypt under `value`
+/// * `round`: The round to encrypt to
+/// * `rng`: A CSPRNG
+pub fn tle_provable<S, R>(
+	p_pub: G2Projective,
+	value: OpaqueSecretKey,
+	salt: [u8; SALT_LENGTH],
+	message: &[u8],
+	round: u64,
+	mut rng: R,
+) -> Result<(TLECiphertext<TinyBLS377>, EncryptionCircuit), Error>
+where
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let input = Input::<TinyBLS377>::new(value).expect("The value has 32 bytes.");
+	let mut sigma = [0u8; 32];
+	rng.fill_bytes(&mut sigma);
+	let header = round_identity(round).encrypt_with_sigma(&input, p_pub, &sigma);
+	let ciphertext = envelope::<_, S, R>(header, value, message, rng)?;
+
+	let commitment = PlaintextCommitment::new(&value, &salt);
+	let circuit = EncryptionCircuit {
+		statement: Statement::new(p_pub, round, &ciphertext, commitment),
+		witness: Witness { sigma, value, salt },
+	};
+	sigma.zeroize();
+	Ok((ciphertext, circuit))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, tlock::tld};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		UniformRand,
+	};
+
+	type Scalar = <TinyBLS377 as EngineBLS>::Scalar;
+
+	fn setup() -> (Scalar, G2Projective, TLECiphertext<TinyBLS377>, EncryptionCircuit) {
+		let mut rng = StdRng::seed_from_u64(2366);
+		let secret = Scalar::rand(&mut rng);
+		let p_pub = G2Projective::generator() * secret;
+		let (ciphertext, circuit) = tle_provable::<AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[42; 32],
+			[7; SALT_LENGTH],
+			b"sealed bid",
+			1_000,
+			rng,
+		)
+		.unwrap();
+		(secret, p_pub, ciphertext, circuit)
+	}
+
+	fn is_satisfied(circuit: EncryptionCircuit) -> bool {
+		let cs = ConstraintSystem::<ConstraintF>::new_ref();
+		let inputs = circuit.statement.public_inputs();
+		circuit.generate_constraints(cs.clone()).unwrap();
+		// the allocated inputs follow the constant one
+		assert_eq!(cs.borrow().unwrap().instance_assignment[1..], inputs[..]);
+		cs.is_satisfied().unwrap()
+	}
+
+	#[test]
+	pub fn circuit_is_satisfied_by_native_encryptions() {
+		let (secret, p_pub, ciphertext, circuit) = setup();
+		assert_eq!(
+			circuit.statement,
+			Statement::new(
+				p_pub,
+				1_000,
+				&ciphertext,
+				PlaintextCommitment::new(&[42; 32], &[7; SALT_LENGTH])
+			)
+		);
+		assert!(is_satisfied(circuit));
+
+		let signature = round_identity(1_000).extract::<TinyBLS377>(secret).0;
+		let plaintext = tld::<TinyBLS377, AESGCMBlockCipherProvider>(ciphertext, signature);
+		assert_eq!(plaintext.unwrap(), b"sealed bid".to_vec());
+	}
+
+	#[test]
+	pub fn circuit_rejects_other_statements() {
+		let (_, p_pub, ciphertext, circuit) = setup();
+
+		// another round
+		let mut other_round = circuit.clone();
+		other_round.statement =
+			Statement::new(p_pub, 1_001, &ciphertext, circuit.statement.commitment);
+		assert!(!is_satisfied(other_round));
+
+		// another committed value
+		let mut other_value = circuit.clone();
+		other_value.statement.commitment = PlaintextCommitment::new(&[43; 32], &[7; SALT_LENGTH]);
+		assert!(!is_satisfied(other_value));
+
+		// a mauled header
+		let mut mauled = circuit.clone();
+		mauled.statement.w[0] ^= 1;
+		assert!(!is_satisfied(mauled));
+	}
+
+	#[test]
+	pub fn blank_circuit_has_the_same_shape() {
+		let count = |circuit: EncryptionCircuit| {
+			let cs = ConstraintSystem::<ConstraintF>::new_ref();
+			circuit.generate_constraints(cs.clone()).unwrap();
+			(cs.num_instance_variables(), cs.num_witness_variables(), cs.num_constraints())
+		};
+		assert_eq!(count(EncryptionCircuit::blank()), count(setup().3));
+	}
+}
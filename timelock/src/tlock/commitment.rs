@@ -0,0 +1,204 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Timelock ciphertexts with a public commitment to the plaintext.
+//!
+//! The header carries `H(dst, salt, message)` next to the IBE ciphertext, and
+//! the salt is encrypted along with the message. Whoever decrypts learns the
+//! salt, and can publish the (message, salt) opening so that anyone can check
+//! it against the commitment without trusting the decryptor. The salt keeps
+//! low-entropy plaintexts from being guessed from the commitment before the
+//! round is reached.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::{
+		fullident::{Ciphertext as IBECiphertext, Identity},
+		utils::sha256,
+	},
+	tlock::{tld, tle, Envelope, Error, OpaqueSecretKey, TLECiphertext},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+
+/// Domain separation tag for plaintext commitments
+pub(crate) const COMMITMENT_DST: &[u8] = b"TIMELOCK_PLAINTEXT_COMMITMENT_V1";
+/// The length of the commitment salt
+pub const SALT_LENGTH: usize = 32;
+
+/// A salted hash commitment to a plaintext
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlaintextCommitment(pub [u8; 32]);
+
+impl PlaintextCommitment {
+	/// Commit to a message with a salt
+	pub fn new(message: &[u8], salt: &[u8; SALT_LENGTH]) -> Self {
+		let mut input = Vec::with_capacity(COMMITMENT_DST.len() + SALT_LENGTH + message.len());
+		input.extend_from_slice(COMMITMENT_DST);
+		input.extend_from_slice(salt);
+		input.extend_from_slice(message);
+		let mut digest = [0u8; 32];
+		digest.copy_from_slice(&sha256(&input));
+		Self(digest)
+	}
+
+	/// Check that a revealed message and salt open this commitment
+	pub fn verify(&self, message: &[u8], salt: &[u8; SALT_LENGTH]) -> bool {
+		Self::new(message, salt) == *self
+	}
+}
+
+/// The header of a committed ciphertext
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommittedHeader<E: EngineBLS> {
+	/// The IBE encrypted key
+	pub header: IBECiphertext<E>,
+	/// The commitment to the plaintext
+	pub commitment: PlaintextCommitment,
+}
+
+/// A timelock ciphertext with a commitment to its plaintext
+pub type CommittedCiphertext<E> = Envelope<CommittedHeader<E>>;
+
+/// Timelock encrypt a message and commit to it in the header
+///
+/// * `p_pub`: the public key commitment for the IBE system
+/// * `secret_key`: the ephemeral key used to encrypt the message
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `rng`: A CSPRNG
+pub fn tle_committed<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	mut rng: R,
+) -> Result<CommittedCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let mut salt = [0u8; SALT_LENGTH];
+	rng.fill_bytes(&mut salt);
+	let commitment = PlaintextCommitment::new(message, &salt);
+
+	let mut payload = Vec::with_capacity(SALT_LENGTH + message.len());
+	payload.extend_from_slice(&salt);
+	payload.extend_from_slice(message);
+	let ct = tle::<E, S, R>(p_pub, secret_key, &payload, id, rng)?;
+
+	Ok(Envelope {
+		header: CommittedHeader { header: ct.header, commitment },
+		body: ct.body,
+		cipher_suite: ct.cipher_suite,
+	})
+}
+
+/// Decrypt a committed ciphertext, returning the message and the salt that
+/// opens the commitment
+///
+/// Fails with [`Error::InvalidCommitment`] if the decrypted message does not
+/// match the commitment in the header.
+///
+/// * `ciphertext`: A ciphertext output by [`tle_committed`]
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+pub fn tld_committed<E, S>(
+	ciphertext: CommittedCiphertext<E>,
+	signature: E::SignatureGroup,
+) -> Result<(Vec<u8>, [u8; SALT_LENGTH]), Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let commitment = ciphertext.header.commitment;
+	let ct = TLECiphertext {
+		header: ciphertext.header.header,
+		body: ciphertext.body,
+		cipher_suite: ciphertext.cipher_suite,
+	};
+	let mut payload = tld::<E, S>(ct, signature)?;
+	if payload.len() < SALT_LENGTH {
+		return Err(Error::InvalidCommitment);
+	}
+
+	let message = payload.split_off(SALT_LENGTH);
+	let mut salt = [0u8; SALT_LENGTH];
+	salt.copy_from_slice(&payload);
+	if !commitment.verify(&message, &salt) {
+		return Err(Error::InvalidCommitment);
+	}
+
+	Ok((message, salt))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	pub fn committed_reveal_can_be_verified_by_anyone() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+
+		let ct = tle_committed::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[2; 32],
+			b"yes",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+		let commitment = ct.header.commitment;
+
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let (message, salt) =
+			tld_committed::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig).unwrap();
+		assert_eq!(message, b"yes".to_vec());
+		assert!(commitment.verify(b"yes", &salt));
+		assert!(!commitment.verify(b"no", &salt));
+	}
+
+	#[test]
+	pub fn committed_decryption_rejects_swapped_commitment() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+
+		let mut ct = tle_committed::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[2; 32],
+			b"yes",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+		ct.header.commitment = PlaintextCommitment::new(b"no", &[0; SALT_LENGTH]);
+
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let result = tld_committed::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig);
+		assert_eq!(result, Err(Error::InvalidCommitment));
+	}
+}
@@ -0,0 +1,287 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Decoding of ciphertexts produced by the legacy etf-sdk/etf.js stack.
+//!
+//! The etf-sdk client split an AES-256-GCM key into Shamir shares over the
+//! BLS12-381 scalar field and IBE encrypted one share per identity. Its
+//! ciphertexts were passed around as the serde JSON form of
+//!
+//! ```text
+//! { "aes_ct": { "ciphertext": [..], "nonce": [..], "key": [..] }, "etf_ct": [[..], ..] }
+//! ```
+//!
+//! where every entry of `etf_ct` is a compressed `(U, V, W)` triple of its IBE
+//! variant (unprefixed SHA-256 for H_2, H_3 and H_4, and a generic G2 public
+//! parameter `P`). Those headers cannot be translated to the current layout
+//! without the identity secrets, so [`upgrade`] decrypts with the secrets and
+//! re-encrypts the message as a [`TLECiphertext`].
+//!
+//! The legacy `key` field held the AES key itself. It is ignored here, and
+//! anyone holding such a ciphertext could read it without any secrets.
+
+use crate::{
+	block_ciphers::{AESGCMBlockCipherProvider, AESOutput, BlockCipherProvider},
+	engines::{drand::TinyBLS381, EngineBLS},
+	ibe::{fullident::Identity, utils::sha256},
+	tlock::{tle, Error, TLECiphertext},
+};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+
+type PublicKey = <TinyBLS381 as EngineBLS>::PublicKeyGroup;
+type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+
+/// The AES-GCM output of the legacy client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacyAesOutput {
+	/// The AES ciphertext
+	pub ciphertext: Vec<u8>,
+	/// The AES nonce
+	pub nonce: Vec<u8>,
+	/// The AES key, which the legacy client leaked into the ciphertext
+	#[serde(default, skip_serializing)]
+	pub key: Vec<u8>,
+}
+
+/// A ciphertext produced by the legacy etf-sdk client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacyCiphertext {
+	/// The message encrypted with AES-GCM
+	pub aes_ct: LegacyAesOutput,
+	/// The IBE encrypted shares of the AES key, one per identity
+	pub etf_ct: Vec<Vec<u8>>,
+}
+
+/// A ciphertext in either the current or the legacy layout
+pub enum AnyCiphertext<E: EngineBLS> {
+	/// A ciphertext in the current layout
+	Current(TLECiphertext<E>),
+	/// A ciphertext produced by the legacy etf-sdk client
+	Legacy(LegacyCiphertext),
+}
+
+/// Decode a ciphertext, detecting the legacy JSON layout
+///
+/// Legacy ciphertexts are JSON objects, while current ones are the compressed
+/// serialization of a [`TLECiphertext`].
+///
+/// * `bytes`: The encoded ciphertext
+pub fn decode<E: EngineBLS>(bytes: &[u8]) -> Result<AnyCiphertext<E>, Error> {
+	if bytes.trim_ascii_start().starts_with(b"{") {
+		let legacy = serde_json::from_slice(bytes).map_err(|_| Error::DeserializationError)?;
+		return Ok(AnyCiphertext::Legacy(legacy));
+	}
+	TLECiphertext::<E>::deserialize_compressed(bytes)
+		.map(AnyCiphertext::Current)
+		.map_err(|_| Error::DeserializationError)
+}
+
+/// Decrypt a legacy ciphertext
+///
+/// * `ciphertext`: A ciphertext produced by the legacy client
+/// * `ibe_pp`: The IBE public parameter `P` it was encrypted under
+/// * `secrets`: The identity secrets, in the order of the identities used to
+///   encrypt (one per entry of `etf_ct`)
+pub fn tld_legacy(
+	ciphertext: &LegacyCiphertext,
+	ibe_pp: PublicKey,
+	secrets: &[Signature],
+) -> Result<Vec<u8>, Error> {
+	if secrets.len() != ciphertext.etf_ct.len() || secrets.is_empty() {
+		return Err(Error::NotEnoughShares);
+	}
+
+	let mut points = Vec::with_capacity(secrets.len());
+	for (i, (capsule, secret)) in ciphertext.etf_ct.iter().zip(secrets).enumerate() {
+		let share = legacy_ibe_decrypt(capsule, ibe_pp, *secret)?;
+		let share = Scalar::deserialize_compressed(&share[..])
+			.map_err(|_| Error::DeserializationErrorFr)?;
+		points.push((Scalar::from(i as u64 + 1), share));
+	}
+
+	let mut key = [0u8; 32];
+	interpolate(&points)
+		.serialize_compressed(&mut key[..])
+		.expect("A scalar is 32 bytes.");
+	let aes_ct = AESOutput {
+		ciphertext: ciphertext.aes_ct.ciphertext.clone(),
+		nonce: ciphertext.aes_ct.nonce.clone(),
+	};
+	AESGCMBlockCipherProvider::decrypt(aes_ct, key).map_err(|_| Error::DecryptionError)
+}
+
+/// Decrypt a legacy ciphertext and re-encrypt the message in the current
+/// layout
+///
+/// * `ciphertext`: A ciphertext produced by the legacy client
+/// * `ibe_pp`: The IBE public parameter `P` it was encrypted under
+/// * `secrets`: The identity secrets, see [`tld_legacy`]
+/// * `p_pub`: The public key to encrypt the upgraded ciphertext for
+/// * `id`: The identity to encrypt the upgraded ciphertext for
+/// * `rng`: A CSPRNG
+pub fn upgrade<S, R>(
+	ciphertext: &LegacyCiphertext,
+	ibe_pp: PublicKey,
+	secrets: &[Signature],
+	p_pub: PublicKey,
+	id: Identity,
+	mut rng: R,
+) -> Result<TLECiphertext<TinyBLS381>, Error>
+where
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let message = tld_legacy(ciphertext, ibe_pp, secrets)?;
+	let mut secret_key = [0u8; 32];
+	rng.fill_bytes(&mut secret_key);
+	tle::<TinyBLS381, S, R>(p_pub, secret_key, &message, id, rng)
+}
+
+/// The legacy BF-IBE decryption of one 32 byte share
+fn legacy_ibe_decrypt(
+	capsule: &[u8],
+	ibe_pp: PublicKey,
+	secret: Signature,
+) -> Result<[u8; 32], Error> {
+	let (u, v, w) = <(PublicKey, Vec<u8>, Vec<u8>)>::deserialize_compressed(capsule)
+		.map_err(|_| Error::DeserializationError)?;
+	if v.len() != 32 || w.len() != 32 {
+		return Err(Error::DeserializationError);
+	}
+
+	// sigma = V (+) H_2(e(d_id, U))
+	let mut g = Vec::new();
+	TinyBLS381::pairing(u, secret)
+		.serialize_compressed(&mut g)
+		.expect("Enough space has been allocated in the buffer");
+	let sigma = xor(&v, &sha256(&g));
+	// m = W (+) H_4(sigma)
+	let message = xor(&w, &sha256(&sigma));
+	// U = rP, with r = H_3(sigma, m)
+	let r = Scalar::from_be_bytes_mod_order(&sha256(&[sigma, message].concat()));
+	if ibe_pp * r != u {
+		return Err(Error::InvalidSignature);
+	}
+	Ok(message)
+}
+
+/// Lagrange interpolation at x = 0
+fn interpolate(points: &[(Scalar, Scalar)]) -> Scalar {
+	let mut secret = Scalar::zero();
+	for (i, (xi, yi)) in points.iter().enumerate() {
+		let mut basis = Scalar::one();
+		for (j, (xj, _)) in points.iter().enumerate() {
+			if i != j {
+				basis *= *xj * (*xj - xi).inverse().expect("The points are distinct.");
+			}
+		}
+		secret += basis * yi;
+	}
+	secret
+}
+
+fn xor(a: &[u8], b: &[u8]) -> [u8; 32] {
+	let mut out = [0u8; 32];
+	for (o, (x, y)) in out.iter_mut().zip(a.iter().zip(b.iter())) {
+		*o = x ^ y;
+	}
+	out
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::tlock::tld;
+	use ark_ec::PrimeGroup;
+	use ark_std::rand::rngs::OsRng;
+
+	// produced by etf-crypto-primitives 0.2.3 with P the generator of G2, a master
+	// secret of 42, the identities "id1", "id2" and "id3", and a threshold of 1
+	const LEGACY_CIPHERTEXT: &str = r#"{"aes_ct":{"ciphertext":[31,126,174,51,254,238,167,52,227,30,174,200,160,58,170,230,22,8,197,253,228,50,80,5,201,1,242,14,119,225,80,33,15,86],"nonce":[221,104,204,2,167,148,182,23,203,136,61,25],"key":[109,147,162,135,32,120,95,194,27,158,85,11,147,17,93,34,71,222,127,87,252,54,197,116,116,179,204,0,191,221,43,67]},"etf_ct":[[135,219,128,45,157,193,179,174,218,181,128,205,45,203,153,70,160,33,193,50,78,217,80,28,130,245,174,93,35,168,20,166,167,19,213,58,34,159,29,174,123,87,156,124,148,197,29,255,10,230,205,118,15,189,67,150,28,138,27,208,63,41,149,26,171,217,244,154,180,52,63,118,242,201,107,68,82,38,111,195,196,222,131,250,162,194,216,52,199,167,10,91,47,182,210,199,32,0,0,0,0,0,0,0,27,35,170,115,243,154,83,235,94,183,153,174,191,50,244,89,2,239,97,124,135,100,75,130,73,84,210,158,213,167,185,175,32,0,0,0,0,0,0,0,214,17,37,2,118,205,248,3,6,199,249,98,160,168,144,144,251,50,255,67,210,186,136,179,173,185,221,108,50,148,78,21],[165,189,245,210,34,229,198,111,142,53,134,93,121,249,202,90,111,119,247,53,230,29,190,4,77,147,251,248,202,17,39,102,135,137,20,103,27,127,45,122,230,202,138,254,80,193,9,20,13,121,206,27,37,91,83,8,203,138,6,228,204,15,119,223,164,29,163,51,86,115,231,84,129,121,147,170,156,187,186,15,210,95,125,73,108,228,25,156,206,107,208,220,32,160,86,134,32,0,0,0,0,0,0,0,2,59,26,147,128,206,225,175,128,200,218,159,222,222,232,59,39,41,102,102,182,154,30,246,106,127,91,85,166,34,196,53,32,0,0,0,0,0,0,0,117,232,146,100,163,208,193,187,20,99,16,39,124,49,130,62,129,168,77,46,139,151,213,124,6,177,190,22,72,196,50,236],[147,152,53,243,8,77,165,249,148,103,153,206,173,234,85,236,201,77,154,165,174,148,24,7,63,190,147,223,184,54,160,49,160,204,3,153,168,83,43,56,134,105,86,92,6,239,186,211,2,214,85,166,108,203,200,52,11,99,201,9,155,132,29,66,148,20,70,210,186,238,22,170,144,132,180,85,106,156,72,89,98,156,158,240,240,72,106,112,222,239,119,51,42,6,97,219,32,0,0,0,0,0,0,0,247,61,11,103,140,134,140,8,7,117,149,31,215,92,72,229,223,232,225,212,31,138,52,161,108,126,63,201,90,193,107,15,32,0,0,0,0,0,0,0,163,163,173,162,4,154,172,166,133,19,167,206,158,17,27,1,204,19,39,21,59,223,208,127,163,247,190,166,218,114,137,253]]}"#;
+	const LEGACY_SECRETS: [&str; 3] = [
+		"8def6e1a86526b9cc783a8e520328220a7ea81dc8999add646aa405625a7c4ab9f8b33cc4558ae1ac808fb5ae07790c4",
+		"91380a481941db3583a8a9a4e8e2037d257cbf7df94e29c9aad152bf2ee9f5ee4bec2f8d57cbb8849f1e532207112c21",
+		"a72ab1ccd476411e287e32088f9603a1376eeb653e91ef1e9e2c520dedc8228cd2a7599d9cd89533532b07d80a943e0e",
+	];
+
+	fn secrets() -> Vec<Signature> {
+		LEGACY_SECRETS
+			.iter()
+			.map(|hex| {
+				Signature::deserialize_compressed(&array_bytes::hex2bytes(hex).unwrap()[..])
+					.unwrap()
+			})
+			.collect()
+	}
+
+	fn legacy() -> LegacyCiphertext {
+		match decode::<TinyBLS381>(LEGACY_CIPHERTEXT.as_bytes()).unwrap() {
+			AnyCiphertext::Legacy(ct) => ct,
+			AnyCiphertext::Current(_) => panic!("the legacy layout should be detected"),
+		}
+	}
+
+	#[test]
+	pub fn legacy_ciphertext_decrypts() {
+		let plaintext = tld_legacy(&legacy(), PublicKey::generator(), &secrets()).unwrap();
+		assert_eq!(plaintext, b"legacy etf message".to_vec());
+	}
+
+	#[test]
+	pub fn legacy_ciphertext_upgrades() {
+		let msk = Scalar::from(7u64);
+		let p_pub = PublicKey::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let upgraded = upgrade::<AESGCMBlockCipherProvider, OsRng>(
+			&legacy(),
+			PublicKey::generator(),
+			&secrets(),
+			p_pub,
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		// the current layout is detected as such
+		let mut bytes = Vec::new();
+		upgraded.serialize_compressed(&mut bytes).unwrap();
+		let upgraded = match decode::<TinyBLS381>(&bytes).unwrap() {
+			AnyCiphertext::Current(ct) => ct,
+			AnyCiphertext::Legacy(_) => panic!("the current layout should be detected"),
+		};
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(upgraded, sig).unwrap();
+		assert_eq!(plaintext, b"legacy etf message".to_vec());
+	}
+
+	#[test]
+	pub fn legacy_decryption_rejects_wrong_secrets() {
+		let mut secrets = secrets();
+		secrets.swap(0, 1);
+		let result = tld_legacy(&legacy(), PublicKey::generator(), &secrets);
+		assert_eq!(result, Err(Error::InvalidSignature));
+
+		let result = tld_legacy(&legacy(), PublicKey::generator(), &secrets[..2]);
+		assert_eq!(result, Err(Error::NotEnoughShares));
+	}
+}
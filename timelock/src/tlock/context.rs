@@ -0,0 +1,180 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Repeated encryption to one beacon.
+//!
+//! Each call to [`super::tle`] multiplies the beacon's public key by the
+//! encryption randomness r and computes a full pairing e(rP_pub, Q_id). An
+//! [`EncryptionContext`] prepares the public key once, and for an identity
+//! computes the pairing e(P_pub, Q_id) once; every encryption then only raises
+//! it to r in the target group, which is the same value. Ciphertexts are
+//! identical to those of [`super::tle`] given the same randomness.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::{encrypt_with_pairing, Identity, Input},
+	tlock::{witness::envelope, Error, OpaqueSecretKey, TLECiphertext},
+	MessageDigest,
+};
+use ark_ec::pairing::Pairing;
+use ark_std::{
+	collections::BTreeMap,
+	rand::{CryptoRng, Rng},
+	vec,
+};
+
+pub(crate) type TargetField<E> = <<E as EngineBLS>::Engine as Pairing>::TargetField;
+
+/// A beacon public key prepared for encrypting many messages
+pub struct EncryptionContext<E: EngineBLS> {
+	/// The prepared public key of the beacon
+	p_pub: E::PublicKeyPrepared,
+	/// e(P_pub, Q_id) of the prepared identities
+	pairings: BTreeMap<MessageDigest, TargetField<E>>,
+}
+
+impl<E: EngineBLS> EncryptionContext<E> {
+	/// A context for encrypting to a beacon
+	///
+	/// * `p_pub`: The public key of the beacon
+	pub fn new(p_pub: E::PublicKeyGroup) -> Self {
+		Self { p_pub: E::prepare_public_key(p_pub), pairings: BTreeMap::new() }
+	}
+
+	/// Cache the pairing of an identity, so encryptions to it skip the pairing
+	///
+	/// * `id`: The identity (e.g. round) that will be encrypted to
+	pub fn prepare(&mut self, id: &Identity) {
+		let pairing = self.pairing(id);
+		self.insert(id, pairing);
+	}
+
+	/// Cache a pairing computed with [`Self::pairing`]
+	pub(crate) fn insert(&mut self, id: &Identity, pairing: TargetField<E>) {
+		self.pairings.insert(id.0 .0, pairing);
+	}
+
+	/// Whether the pairing of an identity is cached
+	///
+	/// * `id`: The identity
+	pub fn is_prepared(&self, id: &Identity) -> bool {
+		self.pairings.contains_key(&id.0 .0)
+	}
+
+	/// e(P_pub, Q_id), computed with the prepared public key
+	pub(crate) fn pairing(&self, id: &Identity) -> TargetField<E> {
+		let q_id = E::prepare_signature(id.public::<E>());
+		E::final_exponentiation(E::miller_loop(&[(self.p_pub.clone(), q_id)]))
+			.expect("The pairing of points of prime order is non-zero.")
+			.0
+	}
+
+	/// Timelock encrypt a message for an identity
+	///
+	/// The pairing of an identity that was not [prepared](Self::prepare) is
+	/// computed for this encryption only.
+	///
+	/// * `id`: The identity (e.g. round) to encrypt for
+	/// * `secret_key`: The ephemeral secret key
+	/// * `message`: The message to encrypt
+	/// * `rng`: A CSPRNG
+	pub fn encrypt<S, R>(
+		&self,
+		id: &Identity,
+		secret_key: OpaqueSecretKey,
+		message: &[u8],
+		mut rng: R,
+	) -> Result<TLECiphertext<E>, Error>
+	where
+		S: BlockCipherProvider<32>,
+		R: Rng + CryptoRng,
+	{
+		let input = Input::<E>::new(secret_key).map_err(|_| Error::InvalidSecretKey)?;
+		let mut sigma = vec![0u8; E::SECRET_KEY_SIZE];
+		rng.fill_bytes(&mut sigma);
+		let header = match self.pairings.get(&id.0 .0) {
+			Some(pairing) => encrypt_with_pairing(&input, pairing, &sigma),
+			None => encrypt_with_pairing(&input, &self.pairing(id), &sigma),
+		};
+		envelope::<_, S, R>(header, secret_key, message, rng)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle, BeaconConfig},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec::Vec,
+		UniformRand,
+	};
+
+	fn bytes(ciphertext: &TLECiphertext<TinyBLS381>) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+		bytes
+	}
+
+	#[test]
+	fn contexts_encrypt_like_tle() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let mut context = EncryptionContext::<TinyBLS381>::new(p_pub);
+
+		let expected = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[2u8; 32],
+			b"bulk",
+			id.clone(),
+			StdRng::seed_from_u64(7),
+		)
+		.unwrap();
+		let unprepared = context
+			.encrypt::<AESGCMBlockCipherProvider, _>(
+				&id,
+				[2u8; 32],
+				b"bulk",
+				StdRng::seed_from_u64(7),
+			)
+			.unwrap();
+		assert!(!context.is_prepared(&id));
+		context.prepare(&id);
+		assert!(context.is_prepared(&id));
+		let prepared = context
+			.encrypt::<AESGCMBlockCipherProvider, _>(
+				&id,
+				[2u8; 32],
+				b"bulk",
+				StdRng::seed_from_u64(7),
+			)
+			.unwrap();
+		assert_eq!(bytes(&unprepared), bytes(&expected));
+		assert_eq!(bytes(&prepared), bytes(&expected));
+
+		let signature = id.extract::<TinyBLS381>(secret).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(prepared, signature).unwrap();
+		assert_eq!(plaintext, b"bulk");
+	}
+}
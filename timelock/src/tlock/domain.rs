@@ -0,0 +1,253 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Application domain separation.
+//!
+//! Every application encrypting to the same beacon encrypts to the same
+//! identities, so any of them can decrypt the others' ciphertexts once a
+//! round is signed. An [`AppContext`] scopes ciphertexts to one application:
+//! the key encapsulated to the identity is not the key of the body, which is
+//! derived from it and the context (under a versioned domain separation tag).
+//! The context is recorded in the header, so opening a ciphertext in another
+//! context fails early with [`Error::ContextMismatch`]; relabelling the header
+//! or opening the ciphertext with [`super::tld`] derives the wrong key and
+//! fails to authenticate the body.
+//!
+//! The context is public, so this separates applications that use the library
+//! honestly rather than hiding ciphertexts from one another. The identity
+//! itself is unchanged, since the beacon signs it without any application
+//! context.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::{
+		fullident::{Ciphertext as IBECiphertext, Identity},
+		utils::sha256,
+	},
+	tlock::{
+		witness::{envelope, open_body},
+		BeaconSignature, Envelope, Error, OpaqueSecretKey, WitnessScheme,
+	},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// The version of the domain separation tags of new contexts
+pub const APP_CONTEXT_VERSION: u8 = 1;
+
+/// The domain separation tag of each version, indexed by version - 1
+const DOMAIN_SEPARATION_TAGS: &[&[u8]] = &[b"TIMELOCK_APP_CONTEXT_V1"];
+
+/// The context of an application encrypting to a shared beacon
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AppContext {
+	/// The version of the domain separation tag
+	pub version: u8,
+	/// The application's tag (e.g. its name)
+	pub tag: Vec<u8>,
+}
+
+impl AppContext {
+	/// A context with the current version
+	///
+	/// * `tag`: The application's tag
+	pub fn new(tag: &[u8]) -> Self {
+		Self { version: APP_CONTEXT_VERSION, tag: tag.to_vec() }
+	}
+
+	/// k' = H(dst, len(tag), tag, k), the key of the body for the encapsulated
+	/// key k
+	fn derive_key(&self, key: &OpaqueSecretKey) -> Result<OpaqueSecretKey, Error> {
+		let dst = usize::from(self.version)
+			.checked_sub(1)
+			.and_then(|i| DOMAIN_SEPARATION_TAGS.get(i))
+			.ok_or(Error::InvalidFormat)?;
+		let mut input = Vec::new();
+		input.extend_from_slice(dst);
+		input.extend_from_slice(&(self.tag.len() as u64).to_le_bytes());
+		input.extend_from_slice(&self.tag);
+		input.extend_from_slice(key);
+		let mut derived = [0u8; 32];
+		derived.copy_from_slice(&sha256(&input));
+		input.zeroize();
+		Ok(derived)
+	}
+}
+
+/// An identity scoped to an application
+#[derive(Debug, Clone)]
+pub struct ScopedIdentity {
+	/// The identity (e.g. round) the beacon signs
+	pub identity: Identity,
+	/// The application encrypting to it
+	pub context: AppContext,
+}
+
+impl Identity {
+	/// Scope the identity to an application
+	///
+	/// * `context`: The application's context
+	pub fn in_context(self, context: AppContext) -> ScopedIdentity {
+		ScopedIdentity { identity: self, context }
+	}
+}
+
+/// The header of a ciphertext scoped to an application
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, Clone, PartialEq)]
+pub struct ScopedHeader<E: EngineBLS> {
+	/// The context the body key was derived in
+	pub context: AppContext,
+	/// The key, IBE encrypted for the identity
+	pub header: IBECiphertext<E>,
+}
+
+/// A ciphertext scoped to an application
+pub type ScopedCiphertext<E> = Envelope<ScopedHeader<E>>;
+
+/// Encrypt a message for an identity, scoped to an application
+///
+/// * `p_pub`: The public key of the beacon
+/// * `secret_key`: The ephemeral secret key
+/// * `message`: The message to encrypt
+/// * `id`: The identity (e.g. round) and the application's context
+/// * `rng`: A CSPRNG
+pub fn tle_scoped<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: ScopedIdentity,
+	mut rng: R,
+) -> Result<ScopedCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let ScopedIdentity { identity, context } = id;
+	let key = context.derive_key(&secret_key)?;
+	let header = BeaconSignature::<E>::encapsulate(&p_pub, &identity, secret_key, &mut rng)?;
+	envelope::<_, S, _>(ScopedHeader { context, header }, key, message, &mut rng)
+}
+
+/// Decrypt a ciphertext scoped to an application
+///
+/// * `ciphertext`: A ciphertext output by [`tle_scoped`]
+/// * `signature`: The signature of the round the ciphertext is locked to
+/// * `context`: The context of the application decrypting it
+pub fn tld_scoped<E, S>(
+	ciphertext: ScopedCiphertext<E>,
+	signature: E::SignatureGroup,
+	context: AppContext,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	if ciphertext.header.context != context {
+		return Err(Error::ContextMismatch);
+	}
+	let mut secret_bytes =
+		BeaconSignature::<E>::decapsulate(&ciphertext.header.header, &signature)?;
+	let key = context.derive_key(&secret_bytes);
+	secret_bytes.zeroize();
+	open_body::<S>(&ciphertext.body, key?)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, BeaconConfig},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		UniformRand,
+	};
+
+	#[test]
+	fn scoped_ciphertexts_open_only_in_their_context() {
+		let mut rng = StdRng::seed_from_u64(3);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let sig = id.extract::<TinyBLS381>(secret).0;
+		let lock = |context: &[u8]| {
+			let id = id.clone().in_context(AppContext::new(context));
+			tle_scoped::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+				p_pub,
+				[8; 32],
+				b"scoped",
+				id,
+				StdRng::seed_from_u64(4),
+			)
+			.unwrap()
+		};
+
+		let scoped = lock(b"app-a");
+		assert_eq!(scoped.header.context, AppContext::new(b"app-a"));
+		let mut bytes = Vec::new();
+		scoped.serialize_compressed(&mut bytes).unwrap();
+		let parse = || ScopedCiphertext::<TinyBLS381>::deserialize_compressed(&bytes[..]).unwrap();
+		let plaintext = tld_scoped::<TinyBLS381, AESGCMBlockCipherProvider>(
+			parse(),
+			sig,
+			AppContext::new(b"app-a"),
+		);
+		assert_eq!(plaintext.as_deref(), Ok(&b"scoped"[..]));
+
+		let other = tld_scoped::<TinyBLS381, AESGCMBlockCipherProvider>(
+			parse(),
+			sig,
+			AppContext::new(b"app-b"),
+		);
+		assert_eq!(other, Err(Error::ContextMismatch));
+
+		// Relabelling the header does not help: the body key is derived in the
+		// context it was encrypted in
+		let mut relabelled = lock(b"app-b");
+		relabelled.header.context = AppContext::new(b"app-a");
+		let relabelled = tld_scoped::<TinyBLS381, AESGCMBlockCipherProvider>(
+			relabelled,
+			sig,
+			AppContext::new(b"app-a"),
+		);
+		assert_eq!(relabelled, Err(Error::DecryptionError));
+
+		// Nor does stripping the context and opening with tld
+		let scoped = parse();
+		let unscoped = Envelope {
+			header: scoped.header.header,
+			body: scoped.body,
+			cipher_suite: scoped.cipher_suite,
+		};
+		let unscoped = tld::<TinyBLS381, AESGCMBlockCipherProvider>(unscoped, sig);
+		assert_eq!(unscoped, Err(Error::DecryptionError));
+
+		let mut unknown = lock(b"app-a");
+		unknown.header.context.version = 0;
+		let context = unknown.header.context.clone();
+		let unknown = tld_scoped::<TinyBLS381, AESGCMBlockCipherProvider>(unknown, sig, context);
+		assert_eq!(unknown, Err(Error::InvalidFormat));
+	}
+}
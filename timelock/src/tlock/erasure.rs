@@ -0,0 +1,215 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Erasure coding of serialized ciphertexts.
+//!
+//! [`split`] cuts a ciphertext into `threshold` stripes and encodes them with
+//! a Reed–Solomon code over GF(2^8) into `total` shares, any `threshold` of
+//! which [`reassemble`] the ciphertext. The code is systematic: the stripes
+//! are the values of a polynomial at x = 1..=threshold, so the first
+//! `threshold` shares hold the ciphertext itself and the others its
+//! evaluations at the remaining points. This lets a ciphertext be spread
+//! across storage providers, some of which may lose their share.
+//!
+//! Every share carries a [`ShareHeader`] with the round and chain the
+//! ciphertext is locked to and the SHA-256 hash of the ciphertext, so shares
+//! can be collected and checked without any other metadata. Erasure coding
+//! only adds availability: a single share of a systematic code reveals part
+//! of the ciphertext, which is fine as the ciphertext is public anyway.
+
+use crate::{
+	ibe::utils::sha256,
+	tlock::{
+		sharded::{gf_inv, gf_mul},
+		Error,
+	},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{vec, vec::Vec};
+
+/// The metadata of an erasure coded share
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShareHeader {
+	/// The round the ciphertext is locked to
+	pub round: u64,
+	/// The hash of the chain of the round
+	pub chain_hash: Vec<u8>,
+	/// The number of shares needed to reassemble the ciphertext
+	pub threshold: u8,
+	/// The number of shares the ciphertext was split into
+	pub total: u8,
+	/// The (non-zero) evaluation point of the share
+	pub index: u8,
+	/// The length of the ciphertext
+	pub length: u64,
+	/// The SHA-256 hash of the ciphertext
+	pub digest: Vec<u8>,
+}
+
+/// An erasure coded share of a ciphertext
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ErasureShare {
+	/// The metadata of the share
+	pub header: ShareHeader,
+	/// The encoded stripe
+	pub data: Vec<u8>,
+}
+
+/// Split a serialized ciphertext into shares, any `threshold` of which
+/// reassemble it
+///
+/// * `ciphertext`: The serialized ciphertext
+/// * `round`: The round the ciphertext is locked to
+/// * `chain_hash`: The hash of the chain of the round
+/// * `threshold`: The number of shares needed to reassemble the ciphertext
+/// * `total`: The number of shares to produce
+pub fn split(
+	ciphertext: &[u8],
+	round: u64,
+	chain_hash: &[u8],
+	threshold: u8,
+	total: u8,
+) -> Result<Vec<ErasureShare>, Error> {
+	if threshold == 0 || threshold > total {
+		return Err(Error::InvalidThreshold);
+	}
+	let k = threshold as usize;
+	let width = ciphertext.len().div_ceil(k);
+	let mut padded = ciphertext.to_vec();
+	padded.resize(width * k, 0);
+	let stripes: Vec<(u8, &[u8])> =
+		padded.chunks(width.max(1)).enumerate().map(|(i, s)| (i as u8 + 1, s)).collect();
+
+	let header = ShareHeader {
+		round,
+		chain_hash: chain_hash.to_vec(),
+		threshold,
+		total,
+		index: 0,
+		length: ciphertext.len() as u64,
+		digest: sha256(ciphertext),
+	};
+	Ok((1..=total)
+		.map(|index| {
+			let data = match stripes.get(index as usize - 1) {
+				Some((_, stripe)) => stripe.to_vec(),
+				None => evaluate(&stripes, index, width),
+			};
+			ErasureShare { header: ShareHeader { index, ..header.clone() }, data }
+		})
+		.collect())
+}
+
+/// Reassemble a ciphertext from at least `threshold` of its shares
+///
+/// Shares that belong to another ciphertext, or repeat an index, are
+/// rejected. The reassembled ciphertext is checked against the digest of the
+/// headers.
+///
+/// * `shares`: The shares
+pub fn reassemble(shares: &[ErasureShare]) -> Result<Vec<u8>, Error> {
+	let first = &shares.first().ok_or(Error::NotEnoughShares)?.header;
+	let k = first.threshold as usize;
+	let length = usize::try_from(first.length).map_err(|_| Error::InvalidFormat)?;
+	let width = length.div_ceil(k.max(1));
+	let mut points: Vec<(u8, &[u8])> = Vec::with_capacity(k);
+	for share in shares {
+		let header = &share.header;
+		let consistent = ShareHeader { index: first.index, ..header.clone() } == *first;
+		if !consistent ||
+			header.index == 0 ||
+			header.index > header.total ||
+			share.data.len() != width
+		{
+			return Err(Error::InvalidFormat);
+		}
+		if points.iter().any(|(x, _)| *x == header.index) {
+			return Err(Error::InvalidFormat);
+		}
+		if points.len() < k {
+			points.push((header.index, &share.data));
+		}
+	}
+	if k == 0 || points.len() < k {
+		return Err(Error::NotEnoughShares);
+	}
+
+	let mut ciphertext = Vec::with_capacity(width * k);
+	for index in 1..=first.threshold {
+		match points.iter().find(|(x, _)| *x == index) {
+			Some((_, stripe)) => ciphertext.extend_from_slice(stripe),
+			None => ciphertext.extend_from_slice(&evaluate(&points, index, width)),
+		}
+	}
+	ciphertext.truncate(length);
+	if sha256(&ciphertext) != first.digest {
+		return Err(Error::InvalidFormat);
+	}
+	Ok(ciphertext)
+}
+
+/// The stripe at `x` of the polynomial through `points`, by Lagrange
+/// interpolation in GF(2^8)
+fn evaluate(points: &[(u8, &[u8])], x: u8, width: usize) -> Vec<u8> {
+	let mut stripe = vec![0u8; width];
+	for (i, (xi, yi)) in points.iter().enumerate() {
+		let mut basis = 1u8;
+		for (j, (xj, _)) in points.iter().enumerate() {
+			if i != j {
+				// (x - xj) / (xi - xj), where subtraction is xor
+				basis = gf_mul(basis, gf_mul(x ^ xj, gf_inv(xi ^ xj)));
+			}
+		}
+		for (s, y) in stripe.iter_mut().zip(yi.iter()) {
+			*s ^= gf_mul(basis, *y);
+		}
+	}
+	stripe
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn any_threshold_of_shares_reassemble_the_ciphertext() {
+		let ciphertext: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 3) as u8).collect();
+		let shares = split(&ciphertext, 1000, &[9u8; 32], 3, 5).unwrap();
+		assert_eq!(shares.len(), 5);
+		assert!(shares.iter().all(|share| share.header.round == 1000));
+		assert_eq!(shares[0].data, ciphertext[..334]);
+
+		for chosen in [[0, 1, 2], [2, 3, 4], [4, 0, 3], [1, 4, 2]] {
+			let subset: Vec<ErasureShare> = chosen.iter().map(|&i| shares[i].clone()).collect();
+			assert_eq!(reassemble(&subset).unwrap(), ciphertext);
+		}
+		assert_eq!(reassemble(&shares[..2]), Err(Error::NotEnoughShares));
+
+		let mut corrupted = shares[..3].to_vec();
+		corrupted[2].data[0] ^= 1;
+		assert_eq!(reassemble(&corrupted), Err(Error::InvalidFormat));
+		let mut repeated = shares[..3].to_vec();
+		repeated[2] = repeated[1].clone();
+		assert_eq!(reassemble(&repeated), Err(Error::InvalidFormat));
+		let other = split(b"another ciphertext", 1000, &[9u8; 32], 3, 5).unwrap();
+		let mixed = [shares[0].clone(), shares[1].clone(), other[2].clone()];
+		assert_eq!(reassemble(&mixed), Err(Error::InvalidFormat));
+
+		assert_eq!(split(&ciphertext, 1000, &[], 6, 5), Err(Error::InvalidThreshold));
+		let empty = split(&[], 1, &[], 2, 3).unwrap();
+		assert_eq!(reassemble(&empty[1..]).unwrap(), Vec::<u8>::new());
+	}
+}
@@ -0,0 +1,213 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Fixed-capacity timelock encryption for constrained targets.
+//!
+//! Identities, ciphertexts and plaintexts are held in const-generic stack
+//! buffers instead of `Vec`s: [`BoundedIdentity`] bounds the identity and
+//! [`BoundedBuffer`] bounds the serialized ciphertext or the message, and
+//! [`tle_bounded`]/[`tld_bounded`] go through [`super::tle_into`] and
+//! [`super::tld_into`]. A message or identity that does not fit its bound is
+//! rejected with [`Error::ExceedsBound`].
+//!
+//! None of the buffers this module hands to or takes from the caller live on
+//! the heap, but the crate still needs a global allocator: the arkworks curve
+//! backend allocates internally (e.g. hashing to the curve and preparing
+//! points for the pairing), in amounts that do not depend on the message.
+
+use crate::{
+	engines::EngineBLS,
+	tlock::{
+		buffer::{tle_into_point, tle_len},
+		tld_into, Error, OpaqueSecretKey,
+	},
+};
+use ark_std::rand::{CryptoRng, Rng};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+/// An identity of at most `N` bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedIdentity<const N: usize> {
+	bytes: [u8; N],
+	len: usize,
+}
+
+impl<const N: usize> BoundedIdentity<N> {
+	/// An identity with an empty context, as built by
+	/// [`crate::ibe::fullident::Identity::new`] with `ctx = b""`
+	///
+	/// * `identity`: The identity, of at most `N` bytes
+	pub fn new(identity: &[u8]) -> Result<Self, Error> {
+		let mut bytes = [0u8; N];
+		bytes.get_mut(..identity.len()).ok_or(Error::ExceedsBound)?.copy_from_slice(identity);
+		Ok(Self { bytes, len: identity.len() })
+	}
+
+	/// The bytes of the identity
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes[..self.len]
+	}
+
+	/// Derive the public key for this identity (hash to G1)
+	pub fn public<E: EngineBLS>(&self) -> E::SignatureGroup {
+		E::hash_to_signature_curve(self.as_bytes())
+	}
+}
+
+impl BoundedIdentity<32> {
+	/// The identity a beacon signs in a round, sha256(round as u64 BE)
+	pub fn from_round(round: u64) -> Self {
+		Self { bytes: Sha256::digest(round.to_be_bytes()).into(), len: 32 }
+	}
+}
+
+/// A buffer of at most `N` bytes, zeroized on drop
+pub struct BoundedBuffer<const N: usize> {
+	bytes: [u8; N],
+	len: usize,
+}
+
+impl<const N: usize> BoundedBuffer<N> {
+	/// The bytes written to the buffer
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes[..self.len]
+	}
+}
+
+impl<const N: usize> Drop for BoundedBuffer<N> {
+	fn drop(&mut self) {
+		self.bytes.zeroize();
+	}
+}
+
+/// Encrypt a message for an identity into a buffer of at most `N` bytes
+///
+/// `N` must be at least [`super::buffer::tle_len`] of the message length. The
+/// buffer holds the same serialized ciphertext as [`super::tle_into`] writes.
+///
+/// * `p_pub`: The public key of the beacon
+/// * `secret_key`: The ephemeral secret key
+/// * `message`: The message to encrypt
+/// * `id`: The identity (e.g. round) to encrypt for
+/// * `rng`: A CSPRNG
+pub fn tle_bounded<E, R, const I: usize, const N: usize>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: &BoundedIdentity<I>,
+	rng: R,
+) -> Result<BoundedBuffer<N>, Error>
+where
+	E: EngineBLS,
+	R: Rng + CryptoRng,
+{
+	if tle_len::<E>(message.len()) > N {
+		return Err(Error::ExceedsBound);
+	}
+	let mut out = BoundedBuffer { bytes: [0u8; N], len: 0 };
+	out.len =
+		tle_into_point::<E, R>(p_pub, secret_key, message, id.public::<E>(), rng, &mut out.bytes)?;
+	Ok(out)
+}
+
+/// Decrypt a serialized ciphertext into a buffer of at most `N` bytes
+///
+/// * `ciphertext`: A ciphertext output by [`tle_bounded`] or [`super::tle_into`]
+/// * `signature`: The signature of the round the ciphertext is locked to
+pub fn tld_bounded<E: EngineBLS, const N: usize>(
+	ciphertext: &[u8],
+	signature: E::SignatureGroup,
+) -> Result<BoundedBuffer<N>, Error> {
+	let mut out = BoundedBuffer { bytes: [0u8; N], len: 0 };
+	out.len = tld_into::<E>(ciphertext, signature, &mut out.bytes).map_err(|e| match e {
+		Error::BufferTooSmall => Error::ExceedsBound,
+		e => e,
+	})?;
+	Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{engines::drand::TinyBLS381, ibe::fullident::Identity, tlock::tle_into};
+	use ark_ec::PrimeGroup;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		UniformRand,
+	};
+
+	#[test]
+	fn bounded_buffers_round_trip() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BoundedIdentity::from_round(1000);
+		let signature = Identity::from_round(1000).extract::<TinyBLS381>(secret).0;
+		assert_eq!(id.public::<TinyBLS381>(), Identity::from_round(1000).public::<TinyBLS381>());
+
+		let ciphertext = tle_bounded::<TinyBLS381, _, 32, 256>(
+			p_pub,
+			[3u8; 32],
+			b"on the stack",
+			&id,
+			StdRng::seed_from_u64(5),
+		)
+		.unwrap();
+		let mut expected = [0u8; 256];
+		let len = tle_into::<TinyBLS381, _>(
+			p_pub,
+			[3u8; 32],
+			b"on the stack",
+			&Identity::from_round(1000),
+			StdRng::seed_from_u64(5),
+			&mut expected,
+		)
+		.unwrap();
+		assert_eq!(ciphertext.as_bytes(), &expected[..len]);
+
+		let plaintext = tld_bounded::<TinyBLS381, 16>(ciphertext.as_bytes(), signature).unwrap();
+		assert_eq!(plaintext.as_bytes(), b"on the stack");
+	}
+
+	#[test]
+	fn bounded_buffers_reject_oversized_inputs() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut StdRng::seed_from_u64(1));
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BoundedIdentity::from_round(1000);
+		let signature = Identity::from_round(1000).extract::<TinyBLS381>(secret).0;
+
+		assert_eq!(BoundedIdentity::<4>::new(b"too long"), Err(Error::ExceedsBound));
+		let result = tle_bounded::<TinyBLS381, _, 32, 64>(
+			p_pub,
+			[3u8; 32],
+			b"on the stack",
+			&id,
+			StdRng::seed_from_u64(5),
+		);
+		assert!(matches!(result, Err(Error::ExceedsBound)));
+
+		let ciphertext = tle_bounded::<TinyBLS381, _, 32, 256>(
+			p_pub,
+			[3u8; 32],
+			b"on the stack",
+			&id,
+			StdRng::seed_from_u64(5),
+		)
+		.unwrap();
+		let result = tld_bounded::<TinyBLS381, 8>(ciphertext.as_bytes(), signature);
+		assert!(matches!(result, Err(Error::ExceedsBound)));
+	}
+}
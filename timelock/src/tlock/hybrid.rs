@@ -0,0 +1,188 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Post-quantum hybrid timelock encryption.
+//!
+//! The ephemeral key is IBE encrypted for the identity as usual, and a second
+//! shared secret is encapsulated to an ML-KEM-768 recipient key. The body is
+//! encrypted under a key derived from both, so reading it requires the beacon
+//! signature *and* the recipient's ML-KEM decapsulation key. An adversary who
+//! records ciphertexts today and later breaks the pairing assumptions still
+//! learns nothing about the body without breaking ML-KEM as well.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::{
+		fullident::{Ciphertext as IBECiphertext, Identity},
+		utils::sha256,
+	},
+	tlock::{
+		witness::{envelope, open_body, BeaconSignature, WitnessScheme},
+		Envelope, Error, OpaqueSecretKey,
+	},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use ml_kem::{
+	kem::{Decapsulate, Encapsulate},
+	KemCore, MlKem768,
+};
+
+/// Domain separation tag for the hybrid key derivation
+const HYBRID_KDF_DST: &[u8] = b"TIMELOCK_HYBRID_MLKEM768_KDF_V1";
+
+/// An ML-KEM-768 encapsulation (public) key
+pub type HybridEncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+/// An ML-KEM-768 decapsulation (secret) key
+pub type HybridDecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+
+/// The header of a hybrid ciphertext
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HybridHeader<E: EngineBLS> {
+	/// The IBE encrypted key
+	pub header: IBECiphertext<E>,
+	/// The ML-KEM-768 ciphertext
+	pub kem_ciphertext: Vec<u8>,
+}
+
+/// A timelock ciphertext additionally locked to an ML-KEM-768 key
+pub type HybridCiphertext<E> = Envelope<HybridHeader<E>>;
+
+/// Generate an ML-KEM-768 key pair for a hybrid recipient
+pub fn hybrid_keypair<R: Rng + CryptoRng>(
+	rng: &mut R,
+) -> (HybridDecapsulationKey, HybridEncapsulationKey) {
+	MlKem768::generate(rng)
+}
+
+/// Encrypt a message for an identity and an ML-KEM-768 recipient
+///
+/// * `p_pub`: the public key commitment for the IBE system
+/// * `secret_key`: the ephemeral key that is timelock encrypted
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `recipient`: The recipient's ML-KEM-768 encapsulation key
+/// * `rng`: A CSPRNG
+pub fn tle_hybrid<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	recipient: &HybridEncapsulationKey,
+	mut rng: R,
+) -> Result<HybridCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let (kem_ciphertext, shared) =
+		recipient.encapsulate(&mut rng).map_err(|_| Error::MessageEncryptionError)?;
+	let key = derive_key(&secret_key, &shared, &kem_ciphertext);
+
+	let header = BeaconSignature::<E>::encapsulate(&p_pub, &id, secret_key, &mut rng)?;
+	envelope::<_, S, _>(
+		HybridHeader { header, kem_ciphertext: kem_ciphertext.to_vec() },
+		key,
+		message,
+		&mut rng,
+	)
+}
+
+/// Decrypt a hybrid ciphertext
+///
+/// * `ciphertext`: A ciphertext output by [`tle_hybrid`]
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+/// * `recipient`: The recipient's ML-KEM-768 decapsulation key
+pub fn tld_hybrid<E, S>(
+	ciphertext: HybridCiphertext<E>,
+	signature: E::SignatureGroup,
+	recipient: &HybridDecapsulationKey,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let secret_bytes = BeaconSignature::<E>::decapsulate(&ciphertext.header.header, &signature)?;
+	let kem_ciphertext =
+		ml_kem::Ciphertext::<MlKem768>::try_from(&ciphertext.header.kem_ciphertext[..])
+			.map_err(|_| Error::DeserializationError)?;
+	let shared = recipient.decapsulate(&kem_ciphertext).map_err(|_| Error::DecryptionError)?;
+	let key = derive_key(&secret_bytes, &shared, &kem_ciphertext);
+
+	open_body::<S>(&ciphertext.body, key)
+}
+
+/// k = H(dst, secret, shared, kem_ciphertext)
+fn derive_key(secret: &OpaqueSecretKey, shared: &[u8], kem_ciphertext: &[u8]) -> OpaqueSecretKey {
+	let mut input = Vec::new();
+	input.extend_from_slice(HYBRID_KDF_DST);
+	input.extend_from_slice(secret);
+	input.extend_from_slice(shared);
+	input.extend_from_slice(kem_ciphertext);
+	let mut key = [0u8; 32];
+	key.copy_from_slice(&sha256(&input));
+	key
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381, tlock::tld};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	pub fn hybrid_requires_signature_and_kem_key() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let (dk, ek) = hybrid_keypair(&mut OsRng);
+		let (other_dk, _) = hybrid_keypair(&mut OsRng);
+
+		let encrypt = || {
+			tle_hybrid::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+				p_pub,
+				[6; 32],
+				b"quantum safe",
+				id.clone(),
+				&ek,
+				OsRng,
+			)
+			.unwrap()
+		};
+
+		let plaintext =
+			tld_hybrid::<TinyBLS381, AESGCMBlockCipherProvider>(encrypt(), sig, &dk).unwrap();
+		assert_eq!(plaintext, b"quantum safe".to_vec());
+
+		let result = tld_hybrid::<TinyBLS381, AESGCMBlockCipherProvider>(encrypt(), sig, &other_dk);
+		assert_eq!(result, Err(Error::DecryptionError));
+
+		// the beacon signature alone does not open the body
+		let ct = encrypt();
+		let classic =
+			Envelope { header: ct.header.header, body: ct.body, cipher_suite: ct.cipher_suite };
+		let result = tld::<TinyBLS381, AESGCMBlockCipherProvider>(classic, sig);
+		assert_eq!(result, Err(Error::DecryptionError));
+	}
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! [`age`] recipients and identities for timelock encryption.
+//!
+//! [`TlockRecipient`] wraps the age file key for a beacon round and
+//! [`TlockIdentity`] unwraps it with the round's signature, so files can be
+//! timelocked with the `age` crate's encryptor and decryptor (e.g. in rage
+//! pipelines). The file key is carried in a `-> tlock <round> <chain hash>`
+//! stanza in the format of [`super::drand`], so the files are interoperable
+//! with drand's `tlock` tools.
+
+use crate::{
+	engines::{drand::TinyBLS381, EngineBLS},
+	tlock::interop::drand::{ibe_decrypt, ibe_encrypt, STANZA_TAG},
+};
+use age::{secrecy::ExposeSecret, Identity, Recipient};
+use age_core::format::{FileKey, Stanza};
+use rand::rngs::OsRng;
+use std::{
+	boxed::Box,
+	collections::HashSet,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+
+type PublicKey = <TinyBLS381 as EngineBLS>::PublicKeyGroup;
+type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+
+/// An age recipient that timelocks the file key to a beacon round
+pub struct TlockRecipient {
+	/// The public key of the beacon
+	pub p_pub: PublicKey,
+	/// The hash of the beacon's chain
+	pub chain_hash: Vec<u8>,
+	/// The round to encrypt for
+	pub round: u64,
+}
+
+impl Recipient for TlockRecipient {
+	fn wrap_file_key(
+		&self,
+		file_key: &FileKey,
+	) -> Result<(Vec<Stanza>, HashSet<String>), age::EncryptError> {
+		let body = ibe_encrypt(self.p_pub, self.round, file_key.expose_secret(), &mut OsRng);
+		let stanza = Stanza {
+			tag: STANZA_TAG.into(),
+			args: vec![self.round.to_string(), array_bytes::bytes2hex("", &self.chain_hash)],
+			body,
+		};
+		Ok((vec![stanza], HashSet::new()))
+	}
+}
+
+/// An age identity that unwraps file keys timelocked to a round once its
+/// signature is known
+pub struct TlockIdentity {
+	/// The hash of the beacon's chain
+	pub chain_hash: Vec<u8>,
+	/// The round of the signature
+	pub round: u64,
+	/// The beacon's signature for the round
+	pub signature: Signature,
+}
+
+impl Identity for TlockIdentity {
+	fn unwrap_stanza(&self, stanza: &Stanza) -> Option<Result<FileKey, age::DecryptError>> {
+		// only stanzas for this round and chain are ours to open
+		if stanza.tag != STANZA_TAG ||
+			stanza.args.len() != 2 ||
+			stanza.args[0].parse::<u64>().ok()? != self.round ||
+			array_bytes::hex2bytes(&stanza.args[1]).ok()? != self.chain_hash
+		{
+			return None;
+		}
+		Some(
+			ibe_decrypt(&stanza.body, self.signature)
+				.map(|key| FileKey::new(Box::new(key)))
+				.map_err(|_| age::DecryptError::KeyDecryptionFailed),
+		)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{beacons::QUICKNET, tlock::interop::drand::dearmor};
+	use ark_serialize::CanonicalDeserialize;
+	use std::{
+		io::{Read, Write},
+		vec::Vec,
+	};
+
+	const QUICKNET_SIG_1000: &str = "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39";
+
+	fn identity(round: u64) -> TlockIdentity {
+		TlockIdentity {
+			chain_hash: QUICKNET.chain_hash_bytes(),
+			round,
+			signature: Signature::deserialize_compressed(
+				&array_bytes::hex2bytes(QUICKNET_SIG_1000).unwrap()[..],
+			)
+			.unwrap(),
+		}
+	}
+
+	fn encrypt(message: &[u8]) -> Vec<u8> {
+		let recipient = TlockRecipient {
+			p_pub: QUICKNET.config::<TinyBLS381>().unwrap().p_pub,
+			chain_hash: QUICKNET.chain_hash_bytes(),
+			round: 1000,
+		};
+		let encryptor =
+			age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn Recipient)).unwrap();
+		let mut out = Vec::new();
+		let mut writer = encryptor.wrap_output(&mut out).unwrap();
+		writer.write_all(message).unwrap();
+		writer.finish().unwrap();
+		out
+	}
+
+	fn decrypt(ciphertext: &[u8], identity: &TlockIdentity) -> Result<Vec<u8>, age::DecryptError> {
+		let decryptor = age::Decryptor::new(ciphertext)?;
+		let mut reader = decryptor.decrypt(std::iter::once(identity as &dyn Identity))?;
+		let mut plaintext = Vec::new();
+		reader.read_to_end(&mut plaintext)?;
+		Ok(plaintext)
+	}
+
+	#[test]
+	pub fn age_encrypt_decrypt_works() {
+		let ciphertext = encrypt(b"timelocked with age");
+		assert!(ciphertext.windows(11).any(|w| w == b"-> tlock 10"));
+		assert_eq!(decrypt(&ciphertext, &identity(1000)).unwrap(), b"timelocked with age");
+		// an identity for another round does not match the stanza
+		assert!(matches!(
+			decrypt(&ciphertext, &identity(1001)),
+			Err(age::DecryptError::NoMatchingKeys)
+		));
+	}
+
+	#[test]
+	pub fn age_output_is_readable_by_drand_format() {
+		let ciphertext = encrypt(b"interoperable");
+		let plaintext =
+			crate::tlock::interop::drand::decrypt(&ciphertext, identity(1000).signature).unwrap();
+		assert_eq!(plaintext, b"interoperable".to_vec());
+
+		let ciphertext = crate::tlock::interop::drand::armor(&ciphertext);
+		let plaintext = decrypt(&dearmor(ciphertext.as_bytes()).unwrap(), &identity(1000));
+		assert_eq!(plaintext.unwrap(), b"interoperable".to_vec());
+	}
+}
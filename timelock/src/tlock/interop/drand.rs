@@ -0,0 +1,532 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The age-based format of drand's `tlock` and `tlock-js`.
+//!
+//! drand's implementations wrap a 16 byte age file key with BF-IBE and store
+//! it in an age stanza `-> tlock <round> <chain hash>` whose body is
+//! `U || V || W`. Their IBE differs from [`crate::ibe::fullident`] in the
+//! message length (16 bytes), the tagged hash functions (`IBE-H2`, `IBE-H3`,
+//! `IBE-H4`), the byte order of target group elements, and the rejection
+//! sampling used to derive the encryption randomness, so it is implemented
+//! here for the BLS12-381 networks whose signatures are in G1 (e.g.
+//! quicknet). The payload is encrypted with age's ChaCha20-Poly1305 STREAM
+//! construction.
+//!
+//! Both binary and armored (`-----BEGIN AGE ENCRYPTED FILE-----`) inputs are
+//! accepted when decrypting.
+
+use crate::{
+	engines::{drand::TinyBLS381, EngineBLS},
+	ibe::{fullident::Identity, utils::sha256},
+	tlock::Error,
+};
+use alloc::string::{String, ToString};
+use ark_ec::PrimeGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec,
+	vec::Vec,
+};
+use base64::{
+	engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+	Engine as _,
+};
+use chacha20poly1305::{
+	aead::{Aead, KeyInit},
+	ChaCha20Poly1305,
+};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use num_bigint::BigUint;
+use sha2::Sha256;
+
+type PublicKey = <TinyBLS381 as EngineBLS>::PublicKeyGroup;
+type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+
+/// The tag of the age stanza holding the IBE ciphertext
+pub const STANZA_TAG: &str = "tlock";
+/// The first line of every age header
+const VERSION_LINE: &[u8] = b"age-encryption.org/v1";
+/// The size of an age file key
+pub(crate) const FILE_KEY_SIZE: usize = 16;
+/// The size of a compressed G2 element
+const G2_SIZE: usize = 96;
+/// The size of the nonce that starts the payload
+const PAYLOAD_NONCE_SIZE: usize = 16;
+/// The size of a STREAM plaintext chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+/// The size of a Poly1305 tag
+const TAG_SIZE: usize = 16;
+/// The number of base64 characters per line in headers and armor
+const COLUMNS_PER_LINE: usize = 64;
+/// The first line of an armored file
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+/// The last line of an armored file
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+
+/// The round and chain a drand tlock ciphertext is encrypted for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlockHeader {
+	/// The round whose signature decrypts the ciphertext
+	pub round: u64,
+	/// The hash of the chain the round belongs to
+	pub chain_hash: Vec<u8>,
+}
+
+/// A stanza of an age header
+struct Stanza {
+	tag: String,
+	args: Vec<String>,
+	body: Vec<u8>,
+}
+
+/// A parsed age header
+struct AgeHeader {
+	stanzas: Vec<Stanza>,
+	mac: Vec<u8>,
+	/// The length of the header up to and including `---`
+	mac_input_len: usize,
+	/// The offset of the payload in the file
+	payload_offset: usize,
+}
+
+/// Encrypt a message in the format of drand's `tlock`
+///
+/// * `p_pub`: The public key of the beacon
+/// * `chain_hash`: The hash of the beacon's chain
+/// * `round`: The round to encrypt for
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn encrypt<R: Rng + CryptoRng>(
+	p_pub: PublicKey,
+	chain_hash: &[u8],
+	round: u64,
+	message: &[u8],
+	mut rng: R,
+) -> Result<Vec<u8>, Error> {
+	let mut file_key = [0u8; FILE_KEY_SIZE];
+	rng.fill_bytes(&mut file_key);
+
+	let mut out = Vec::new();
+	out.extend_from_slice(VERSION_LINE);
+	out.push(b'\n');
+	let body = ibe_encrypt(p_pub, round, &file_key, &mut rng);
+	write_stanza(
+		&mut out,
+		STANZA_TAG,
+		&[round.to_string(), array_bytes::bytes2hex("", chain_hash)],
+		&body,
+	);
+	out.extend_from_slice(b"---");
+	let mac = header_mac(&file_key, &out);
+	out.push(b' ');
+	out.extend_from_slice(STANDARD_NO_PAD.encode(mac).as_bytes());
+	out.push(b'\n');
+
+	let mut nonce = [0u8; PAYLOAD_NONCE_SIZE];
+	rng.fill_bytes(&mut nonce);
+	out.extend_from_slice(&nonce);
+	out.extend_from_slice(&stream_encrypt(&payload_key(&file_key, &nonce), message));
+	Ok(out)
+}
+
+/// Decrypt a ciphertext in the format of drand's `tlock`
+///
+/// * `ciphertext`: A binary or armored ciphertext
+/// * `signature`: The beacon signature for the round in the header
+pub fn decrypt(ciphertext: &[u8], signature: Signature) -> Result<Vec<u8>, Error> {
+	let dearmored;
+	let bytes = if is_armored(ciphertext) {
+		dearmored = dearmor(ciphertext)?;
+		&dearmored[..]
+	} else {
+		ciphertext
+	};
+
+	let header = parse_header(bytes)?;
+	// other stanzas (e.g. grease added by some age implementations) are ignored
+	let file_key = header
+		.stanzas
+		.iter()
+		.filter(|stanza| stanza.tag == STANZA_TAG)
+		.find_map(|stanza| ibe_decrypt(&stanza.body, signature).ok())
+		.ok_or(Error::InvalidSignature)?;
+
+	let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&hkdf(&file_key, &[], b"header"))
+		.expect("HMAC accepts keys of any length.");
+	mac.update(&bytes[..header.mac_input_len]);
+	mac.verify_slice(&header.mac).map_err(|_| Error::DecryptionError)?;
+
+	let payload = &bytes[header.payload_offset..];
+	if payload.len() < PAYLOAD_NONCE_SIZE {
+		return Err(Error::InvalidFormat);
+	}
+	let (nonce, chunks) = payload.split_at(PAYLOAD_NONCE_SIZE);
+	stream_decrypt(&payload_key(&file_key, nonce), chunks)
+}
+
+/// Read the round and chain hash from the header of a drand `tlock` ciphertext
+///
+/// * `ciphertext`: A binary or armored ciphertext
+pub fn decrypt_header(ciphertext: &[u8]) -> Result<TlockHeader, Error> {
+	let dearmored;
+	let bytes = if is_armored(ciphertext) {
+		dearmored = dearmor(ciphertext)?;
+		&dearmored[..]
+	} else {
+		ciphertext
+	};
+
+	let header = parse_header(bytes)?;
+	let stanza = header
+		.stanzas
+		.iter()
+		.find(|stanza| stanza.tag == STANZA_TAG && stanza.args.len() == 2)
+		.ok_or(Error::InvalidFormat)?;
+	Ok(TlockHeader {
+		round: stanza.args[0].parse().map_err(|_| Error::InvalidFormat)?,
+		chain_hash: array_bytes::hex2bytes(&stanza.args[1]).map_err(|_| Error::InvalidFormat)?,
+	})
+}
+
+/// Armor a binary age file
+pub fn armor(bytes: &[u8]) -> String {
+	let encoded = STANDARD.encode(bytes);
+	let mut out = String::from(ARMOR_BEGIN);
+	out.push('\n');
+	for line in encoded.as_bytes().chunks(COLUMNS_PER_LINE) {
+		out.push_str(core::str::from_utf8(line).expect("base64 is ascii."));
+		out.push('\n');
+	}
+	out.push_str(ARMOR_END);
+	out.push('\n');
+	out
+}
+
+/// Remove the armor from an age file
+pub fn dearmor(armored: &[u8]) -> Result<Vec<u8>, Error> {
+	let text = core::str::from_utf8(armored).map_err(|_| Error::InvalidFormat)?.trim();
+	let body = text
+		.strip_prefix(ARMOR_BEGIN)
+		.and_then(|rest| rest.strip_suffix(ARMOR_END))
+		.ok_or(Error::InvalidFormat)?;
+	let joined: String = body.split_whitespace().collect();
+	STANDARD.decode(joined).map_err(|_| Error::InvalidFormat)
+}
+
+fn is_armored(bytes: &[u8]) -> bool {
+	bytes.trim_ascii_start().starts_with(ARMOR_BEGIN.as_bytes())
+}
+
+/// BF-IBE encryption of the file key as done by drand's `tlock`
+pub(crate) fn ibe_encrypt<R: Rng + CryptoRng>(
+	p_pub: PublicKey,
+	round: u64,
+	file_key: &[u8; FILE_KEY_SIZE],
+	rng: &mut R,
+) -> Vec<u8> {
+	let mut sigma = [0u8; FILE_KEY_SIZE];
+	rng.fill_bytes(&mut sigma);
+	let r = h3(&sigma, file_key);
+	let u = PublicKey::generator() * r;
+	let id = Identity::new(b"", &sha256(&round.to_be_bytes()));
+	let r_gid = TinyBLS381::pairing(p_pub * r, id.public::<TinyBLS381>());
+	let v = xor(&sigma, &h2(r_gid));
+	let w = xor(file_key, &h4(&sigma));
+
+	let mut body = Vec::with_capacity(G2_SIZE + 2 * FILE_KEY_SIZE);
+	u.serialize_compressed(&mut body)
+		.expect("Enough space has been allocated in the buffer");
+	body.extend_from_slice(&v);
+	body.extend_from_slice(&w);
+	body
+}
+
+/// BF-IBE decryption of the file key as done by drand's `tlock`
+pub(crate) fn ibe_decrypt(body: &[u8], signature: Signature) -> Result<[u8; FILE_KEY_SIZE], Error> {
+	if body.len() != G2_SIZE + 2 * FILE_KEY_SIZE {
+		return Err(Error::InvalidFormat);
+	}
+	let u = PublicKey::deserialize_compressed(&body[..G2_SIZE])
+		.map_err(|_| Error::DeserializationErrorG2)?;
+	let r_gid = TinyBLS381::pairing(u, signature);
+	let sigma = xor(&body[G2_SIZE..G2_SIZE + FILE_KEY_SIZE], &h2(r_gid));
+	let file_key = xor(&body[G2_SIZE + FILE_KEY_SIZE..], &h4(&sigma));
+	// U = rP
+	if PublicKey::generator() * h3(&sigma, &file_key) != u {
+		return Err(Error::InvalidSignature);
+	}
+	Ok(file_key)
+}
+
+/// H_2: the target group element is hashed in big-endian coefficient order
+fn h2<G: CanonicalSerialize>(g: G) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	g.serialize_compressed(&mut bytes)
+		.expect("Enough space has been allocated in the buffer");
+	bytes.reverse();
+	tagged_hash(b"IBE-H2", &[&bytes])
+}
+
+/// H_3: hash-and-try until the 255 bit big-endian output is a valid scalar
+fn h3(sigma: &[u8], message: &[u8]) -> Scalar {
+	let seed = tagged_hash(b"IBE-H3", &[sigma, message]);
+	let modulus: BigUint = Scalar::MODULUS.into();
+	(1..u16::MAX)
+		.find_map(|i| {
+			let mut hash = tagged_hash(&i.to_le_bytes(), &[&seed]);
+			hash[0] >>= 1;
+			(BigUint::from_bytes_be(&hash) < modulus)
+				.then(|| Scalar::from_be_bytes_mod_order(&hash))
+		})
+		.expect("A valid scalar is found with overwhelming probability.")
+}
+
+/// H_4: {0, 1}^n -> {0, 1}^n
+fn h4(sigma: &[u8]) -> Vec<u8> {
+	tagged_hash(b"IBE-H4", &[sigma])
+}
+
+fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+	let mut input = tag.to_vec();
+	for part in parts {
+		input.extend_from_slice(part);
+	}
+	sha256(&input)
+}
+
+fn xor(a: &[u8], b: &[u8]) -> [u8; FILE_KEY_SIZE] {
+	let mut out = [0u8; FILE_KEY_SIZE];
+	for (o, (x, y)) in out.iter_mut().zip(a.iter().zip(b.iter())) {
+		*o = x ^ y;
+	}
+	out
+}
+
+fn hkdf(ikm: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+	let mut okm = [0u8; 32];
+	Hkdf::<Sha256>::new(Some(salt), ikm)
+		.expand(info, &mut okm)
+		.expect("32 bytes is a valid output length.");
+	okm
+}
+
+fn header_mac(file_key: &[u8], header: &[u8]) -> Vec<u8> {
+	let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&hkdf(file_key, &[], b"header"))
+		.expect("HMAC accepts keys of any length.");
+	mac.update(header);
+	mac.finalize().into_bytes().to_vec()
+}
+
+fn payload_key(file_key: &[u8], nonce: &[u8]) -> [u8; 32] {
+	hkdf(file_key, nonce, b"payload")
+}
+
+/// The STREAM nonce: an 11 byte big-endian counter and a last chunk flag
+fn stream_nonce(counter: usize, last: bool) -> [u8; 12] {
+	let mut nonce = [0u8; 12];
+	nonce[3..11].copy_from_slice(&(counter as u64).to_be_bytes());
+	nonce[11] = last as u8;
+	nonce
+}
+
+fn stream_encrypt(key: &[u8; 32], plaintext: &[u8]) -> Vec<u8> {
+	let cipher = ChaCha20Poly1305::new(key.into());
+	// an empty plaintext is encrypted as a single empty last chunk
+	let chunks: Vec<&[u8]> =
+		if plaintext.is_empty() { vec![&[][..]] } else { plaintext.chunks(CHUNK_SIZE).collect() };
+	let last = chunks.len() - 1;
+
+	let mut out = Vec::with_capacity(plaintext.len() + chunks.len() * TAG_SIZE);
+	for (i, chunk) in chunks.into_iter().enumerate() {
+		let ct = cipher
+			.encrypt(&stream_nonce(i, i == last).into(), chunk)
+			.expect("Encryption of a chunk is infallible.");
+		out.extend_from_slice(&ct);
+	}
+	out
+}
+
+fn stream_decrypt(key: &[u8; 32], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+	let cipher = ChaCha20Poly1305::new(key.into());
+	let chunks: Vec<&[u8]> = ciphertext.chunks(CHUNK_SIZE + TAG_SIZE).collect();
+	if chunks.is_empty() {
+		return Err(Error::InvalidFormat);
+	}
+	let last = chunks.len() - 1;
+
+	let mut out = Vec::with_capacity(ciphertext.len());
+	for (i, chunk) in chunks.into_iter().enumerate() {
+		let pt = cipher
+			.decrypt(&stream_nonce(i, i == last).into(), chunk)
+			.map_err(|_| Error::DecryptionError)?;
+		// only the payload of an empty message may end with an empty chunk
+		if pt.is_empty() && i > 0 {
+			return Err(Error::InvalidFormat);
+		}
+		out.extend_from_slice(&pt);
+	}
+	Ok(out)
+}
+
+fn write_stanza(out: &mut Vec<u8>, tag: &str, args: &[String], body: &[u8]) {
+	out.extend_from_slice(b"-> ");
+	out.extend_from_slice(tag.as_bytes());
+	for arg in args {
+		out.push(b' ');
+		out.extend_from_slice(arg.as_bytes());
+	}
+	out.push(b'\n');
+	// the body is wrapped, and always ends with a line shorter than a full one
+	let encoded = STANDARD_NO_PAD.encode(body);
+	let mut lines = encoded.as_bytes().chunks(COLUMNS_PER_LINE).peekable();
+	while let Some(line) = lines.next() {
+		out.extend_from_slice(line);
+		out.push(b'\n');
+		if lines.peek().is_none() && line.len() == COLUMNS_PER_LINE {
+			out.push(b'\n');
+		}
+	}
+	if encoded.is_empty() {
+		out.push(b'\n');
+	}
+}
+
+fn next_line<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a [u8], Error> {
+	let rest = &bytes[*pos..];
+	let end = rest.iter().position(|b| *b == b'\n').ok_or(Error::InvalidFormat)?;
+	*pos += end + 1;
+	Ok(&rest[..end])
+}
+
+fn parse_header(bytes: &[u8]) -> Result<AgeHeader, Error> {
+	let mut pos = 0;
+	if next_line(bytes, &mut pos)? != VERSION_LINE {
+		return Err(Error::InvalidFormat);
+	}
+
+	let mut stanzas = Vec::new();
+	loop {
+		let start = pos;
+		let line = next_line(bytes, &mut pos)?;
+		if let Some(rest) = line.strip_prefix(b"-> ") {
+			let text = core::str::from_utf8(rest).map_err(|_| Error::InvalidFormat)?;
+			let mut parts = text.split(' ').map(String::from);
+			let tag = parts.next().ok_or(Error::InvalidFormat)?;
+			let args = parts.collect();
+			let mut body = Vec::new();
+			loop {
+				let line = next_line(bytes, &mut pos)?;
+				if line.len() > COLUMNS_PER_LINE {
+					return Err(Error::InvalidFormat);
+				}
+				body.extend(STANDARD_NO_PAD.decode(line).map_err(|_| Error::InvalidFormat)?);
+				if line.len() < COLUMNS_PER_LINE {
+					break;
+				}
+			}
+			stanzas.push(Stanza { tag, args, body });
+		} else if let Some(mac) = line.strip_prefix(b"--- ") {
+			return Ok(AgeHeader {
+				stanzas,
+				mac: STANDARD_NO_PAD.decode(mac).map_err(|_| Error::InvalidFormat)?,
+				mac_input_len: start + 3,
+				payload_offset: pos,
+			});
+		} else {
+			return Err(Error::InvalidFormat);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::beacons::QUICKNET;
+	use ark_std::rand::rngs::OsRng;
+
+	const QUICKNET_SIG_1000: &str = "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39";
+
+	// an encryption of 100 zero bytes for round 1000 of drand's fastnet, produced
+	// by another implementation; it includes an extra grease stanza
+	const FASTNET_SIG_1000: &str = "b09eacd45767c4d58306b98901ad0d6086e2663766f3a4ec71d00cf26f0f49eaf248abc7151c60cf419c4e8b37e80412";
+	const FASTNET_CHAIN_HASH: &str =
+		"dbd506d6ef76e5f386f41c651dcb808c5bcbd75471cc4eafa3f4df7ad4e4c493";
+	const FASTNET_CIPHERTEXT: &str = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IHRsb2NrIDEwMDAgZGJkNTA2ZDZlZjc2
+ZTVmMzg2ZjQxYzY1MWRjYjgwOGM1YmNiZDc1NDcxY2M0ZWFmYTNmNGRmN2FkNGU0
+YzQ5MwppZ09hY2VnNC9hNUNrYzJXVTlhWkpkRHpFUkZOWWNaSDZVdy9tc2tiWXlK
+SWx3Rnc2QWU4TDMzUXpZQ2NiY0dSCkRPZXFYeGl1dWI1SUVkRUVlV1doWkFTNmxk
+TG02TDhZTDFCTE1HZ1ZPVHF2b2xQVDhYQVlSMGk4aUJNTFJyTGMKYStBbFQvZEtI
+eWZIa0VrYW56ZFBNQ0l4WDFmOGlkU2N6RHpVZk92WGYrYwotPiAlVl8tZ3JlYXNl
+CldreTFpeWxFdGd5MlZXRjJzZDdiRHRXc3dkc0VRQmVuUTA0MUdoY3R2QjQKLS0t
+IEJyTDlWVEIvem5mbjFxYzlzYTUyTGFITm5iOGVQTFIwMzg5bUJQQU92a2cKB1ca
+67u0wjEVoC/sRHsi+F7E2SJ6Z7bQQAva+dS1YYpXt07AdXOmqllqVGy8YGhi3wSf
+ZlxKpBAQw+Lz+nvn5vCJPPNQcp264wTd6om5h9QnuHBkxkbr7JBqBeRxNucdl6El
+2af/n+e6s7cuhvvZp/TPTtV000+2Crq8kmAQIpzTdVIe
+-----END AGE ENCRYPTED FILE-----";
+
+	fn signature(hex: &str) -> Signature {
+		Signature::deserialize_compressed(&array_bytes::hex2bytes(hex).unwrap()[..]).unwrap()
+	}
+
+	#[test]
+	pub fn drand_decrypts_external_ciphertext() {
+		let header = decrypt_header(FASTNET_CIPHERTEXT.as_bytes()).unwrap();
+		assert_eq!(header.round, 1000);
+		assert_eq!(header.chain_hash, array_bytes::hex2bytes(FASTNET_CHAIN_HASH).unwrap());
+
+		let plaintext =
+			decrypt(FASTNET_CIPHERTEXT.as_bytes(), signature(FASTNET_SIG_1000)).unwrap();
+		assert_eq!(plaintext, vec![0u8; 100]);
+	}
+
+	#[test]
+	pub fn drand_encrypt_decrypt_quicknet_works() {
+		let p_pub = QUICKNET.config::<TinyBLS381>().unwrap().p_pub;
+		let chain_hash = QUICKNET.chain_hash_bytes();
+
+		// cover the empty message, partial, full and multiple chunks
+		for len in [0, 13, CHUNK_SIZE, CHUNK_SIZE + 1] {
+			let message = vec![7u8; len];
+			let ct = encrypt(p_pub, &chain_hash, 1000, &message, OsRng).unwrap();
+			assert_eq!(decrypt(&ct, signature(QUICKNET_SIG_1000)).unwrap(), message);
+			assert_eq!(
+				decrypt(armor(&ct).as_bytes(), signature(QUICKNET_SIG_1000)).unwrap(),
+				message
+			);
+		}
+
+		let ct = encrypt(p_pub, &chain_hash, 1001, b"too early", OsRng).unwrap();
+		assert_eq!(decrypt(&ct, signature(QUICKNET_SIG_1000)), Err(Error::InvalidSignature));
+	}
+
+	#[test]
+	pub fn drand_rejects_tampered_header() {
+		let p_pub = QUICKNET.config::<TinyBLS381>().unwrap().p_pub;
+		let chain_hash = QUICKNET.chain_hash_bytes();
+		let mut ct = encrypt(p_pub, &chain_hash, 1000, b"hello", OsRng).unwrap();
+		// flip a character of the chain hash argument
+		let offset = VERSION_LINE.len() + 1 + "-> tlock 1000 ".len();
+		ct[offset] = if ct[offset] == b'0' { b'1' } else { b'0' };
+
+		assert_eq!(decrypt(&ct, signature(QUICKNET_SIG_1000)), Err(Error::DecryptionError));
+	}
+}
@@ -0,0 +1,191 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The canonical JSON representation of a timelock ciphertext.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "scheme": "AES_GCM_",
+//!   "round": 1000,
+//!   "u": "<base64>",
+//!   "v": "<base64>",
+//!   "w": "<base64>",
+//!   "body": "<base64>"
+//! }
+//! ```
+//!
+//! Byte fields use standard, padded base64. `scheme` is the cipher suite of
+//! the body and `round` is optional, informational and not authenticated.
+//! The schema version is named `version` rather than `v`, which is already the
+//! IBE ciphertext component.
+
+use crate::{
+	engines::EngineBLS,
+	ibe::fullident::Ciphertext as IBECiphertext,
+	tlock::{Error, TLECiphertext},
+};
+use alloc::string::String;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// The version of the JSON schema
+pub const JSON_VERSION: u8 = 1;
+
+/// The JSON form of a [`TLECiphertext`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonCiphertext {
+	/// The schema version
+	pub version: u8,
+	/// The cipher suite of the body
+	pub scheme: String,
+	/// The round the ciphertext is locked to, if known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub round: Option<u64>,
+	/// U = rP, compressed
+	pub u: String,
+	/// V = sigma (+) H_2(g_id^r)
+	pub v: String,
+	/// W = message (+) H_4(sigma)
+	pub w: String,
+	/// The encrypted message
+	pub body: String,
+}
+
+impl<E: EngineBLS> TLECiphertext<E> {
+	/// The JSON form of the ciphertext
+	///
+	/// * `round`: The round the ciphertext is locked to, if it should be
+	///   included
+	pub fn to_json_value(&self, round: Option<u64>) -> JsonCiphertext {
+		let mut u = Vec::new();
+		self.header
+			.u
+			.serialize_compressed(&mut u)
+			.expect("The group element must be serializable.");
+		JsonCiphertext {
+			version: JSON_VERSION,
+			scheme: String::from_utf8_lossy(&self.cipher_suite).into(),
+			round,
+			u: STANDARD.encode(u),
+			v: STANDARD.encode(self.header.v),
+			w: STANDARD.encode(self.header.w),
+			body: STANDARD.encode(&self.body),
+		}
+	}
+
+	/// Encode the ciphertext as a JSON string
+	///
+	/// * `round`: The round the ciphertext is locked to, if it should be
+	///   included
+	pub fn to_json(&self, round: Option<u64>) -> String {
+		serde_json::to_string(&self.to_json_value(round))
+			.expect("The JSON form only contains strings and integers.")
+	}
+
+	/// Read a ciphertext from its JSON form
+	///
+	/// * `json`: The output of [`TLECiphertext::to_json_value`]
+	pub fn from_json_value(json: &JsonCiphertext) -> Result<Self, Error> {
+		if json.version != JSON_VERSION {
+			return Err(Error::InvalidFormat);
+		}
+		let decode = |field: &str| STANDARD.decode(field).map_err(|_| Error::InvalidFormat);
+		let u = E::PublicKeyGroup::deserialize_compressed(&decode(&json.u)?[..])
+			.map_err(|_| Error::DeserializationErrorG2)?;
+		let v = decode(&json.v)?.try_into().map_err(|_| Error::InvalidFormat)?;
+		let w = decode(&json.w)?.try_into().map_err(|_| Error::InvalidFormat)?;
+
+		Ok(Self {
+			header: IBECiphertext { u, v, w },
+			body: decode(&json.body)?,
+			cipher_suite: json.scheme.as_bytes().to_vec(),
+		})
+	}
+
+	/// Decode a ciphertext from a JSON string
+	///
+	/// * `json`: The output of [`TLECiphertext::to_json`]
+	pub fn from_json(json: &str) -> Result<Self, Error> {
+		let json: JsonCiphertext = serde_json::from_str(json).map_err(|_| Error::InvalidFormat)?;
+		Self::from_json_value(&json)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		ibe::fullident::Identity,
+		tlock::{tld, tle},
+	};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	pub fn json_round_trips() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[5; 32],
+			b"web backend",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		let json = ct.to_json(Some(1000));
+		let value: JsonCiphertext = serde_json::from_str(&json).unwrap();
+		assert_eq!(value.version, JSON_VERSION);
+		assert_eq!(value.scheme, "AES_GCM_");
+		assert_eq!(value.round, Some(1000));
+		assert!(!ct.to_json(None).contains("round"));
+
+		let decoded = TLECiphertext::<TinyBLS381>::from_json(&json).unwrap();
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(decoded, sig).unwrap();
+		assert_eq!(plaintext, b"web backend".to_vec());
+	}
+
+	#[test]
+	pub fn json_rejects_malformed_fields() {
+		let msk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let ct =
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(p_pub, [5; 32], b"", id, OsRng)
+				.unwrap();
+		let value = ct.to_json_value(None);
+
+		let short_v = JsonCiphertext { v: STANDARD.encode([0u8; 31]), ..value.clone() };
+		let bad_base64 = JsonCiphertext { body: "not base64!".into(), ..value.clone() };
+		let future = JsonCiphertext { version: 2, ..value };
+		for json in [short_v, bad_base64, future] {
+			let result = TLECiphertext::<TinyBLS381>::from_json_value(&json);
+			assert!(matches!(result, Err(Error::InvalidFormat)));
+		}
+		let result = TLECiphertext::<TinyBLS381>::from_json("{\"version\": 1}");
+		assert!(matches!(result, Err(Error::InvalidFormat)));
+	}
+}
@@ -0,0 +1,201 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The public keys of a beacon that rotated its key.
+//!
+//! A beacon that rotates its key signs the rounds before the rotation with
+//! the old key and the rounds after it with the new one. A [`BeaconKeyRing`]
+//! holds every key with the window of rounds it signs, so encryption,
+//! verification and decryption use the right key for a round without the
+//! caller tracking rotations.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	tlock::{tld, tle, verify_signature, BeaconConfig, Error, OpaqueSecretKey, TLECiphertext},
+};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+
+/// A public key and the rounds it signs
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyWindow<E: EngineBLS> {
+	/// The public key
+	pub p_pub: E::PublicKeyGroup,
+	/// The first round signed with the key
+	pub from_round: u64,
+	/// The first round no longer signed with the key, if it was rotated
+	pub until_round: Option<u64>,
+}
+
+impl<E: EngineBLS> KeyWindow<E> {
+	/// Whether the key signs a round
+	pub fn contains(&self, round: u64) -> bool {
+		round >= self.from_round && self.until_round.is_none_or(|until| round < until)
+	}
+}
+
+/// The public keys of a beacon, by the rounds they sign
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeaconKeyRing<E: EngineBLS> {
+	/// The windows, ordered by their first round and disjoint
+	windows: Vec<KeyWindow<E>>,
+}
+
+impl<E: EngineBLS> Default for BeaconKeyRing<E> {
+	fn default() -> Self {
+		Self { windows: Vec::new() }
+	}
+}
+
+impl<E: EngineBLS> BeaconKeyRing<E> {
+	/// A key ring with no keys
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a key, which must not sign any round another key signs
+	///
+	/// * `p_pub`: The public key
+	/// * `from_round`: The first round signed with the key
+	/// * `until_round`: The first round no longer signed with the key, if it
+	///   was rotated
+	pub fn insert(
+		&mut self,
+		p_pub: E::PublicKeyGroup,
+		from_round: u64,
+		until_round: Option<u64>,
+	) -> Result<(), Error> {
+		if until_round.is_some_and(|until| until <= from_round) {
+			return Err(Error::OverlappingKeyWindows);
+		}
+		let window = KeyWindow { p_pub, from_round, until_round };
+		let overlaps =
+			|other: &KeyWindow<E>| other.contains(from_round) || window.contains(other.from_round);
+		if self.windows.iter().any(overlaps) {
+			return Err(Error::OverlappingKeyWindows);
+		}
+		let position = self.windows.partition_point(|other| other.from_round < from_round);
+		self.windows.insert(position, window);
+		Ok(())
+	}
+
+	/// The windows of the keys, ordered by their first round
+	pub fn windows(&self) -> &[KeyWindow<E>] {
+		&self.windows
+	}
+
+	/// The public key that signs a round
+	///
+	/// * `round`: The round
+	pub fn key_for(&self, round: u64) -> Result<E::PublicKeyGroup, Error> {
+		let position = self.windows.partition_point(|window| window.from_round <= round);
+		position
+			.checked_sub(1)
+			.map(|i| &self.windows[i])
+			.filter(|window| window.contains(round))
+			.map(|window| window.p_pub)
+			.ok_or(Error::NoKeyForRound)
+	}
+
+	/// Encrypt a message for a round, with the key that signs the round
+	///
+	/// * `round`: The round to encrypt for
+	/// * `secret_key`: The ephemeral secret key
+	/// * `message`: The message to encrypt
+	/// * `rng`: A CSPRNG
+	pub fn encrypt<S, R>(
+		&self,
+		round: u64,
+		secret_key: OpaqueSecretKey,
+		message: &[u8],
+		rng: R,
+	) -> Result<TLECiphertext<E>, Error>
+	where
+		S: BlockCipherProvider<32>,
+		R: Rng + CryptoRng,
+	{
+		let p_pub = self.key_for(round)?;
+		tle::<E, S, R>(p_pub, secret_key, message, BeaconConfig::<E>::identity(round), rng)
+	}
+
+	/// Check a signature of a round against the key that signs the round
+	///
+	/// * `round`: The round of the signature
+	/// * `signature`: The signature
+	pub fn verify(&self, round: u64, signature: E::SignatureGroup) -> Result<(), Error> {
+		verify_signature::<E>(self.key_for(round)?, round, signature)
+	}
+
+	/// Verify the signature of a round, then decrypt a ciphertext with it
+	///
+	/// * `round`: The round the ciphertext is locked to
+	/// * `ciphertext`: The ciphertext
+	/// * `signature`: The signature of the round
+	pub fn decrypt<S: BlockCipherProvider<32>>(
+		&self,
+		round: u64,
+		ciphertext: TLECiphertext<E>,
+		signature: E::SignatureGroup,
+	) -> Result<Vec<u8>, Error> {
+		self.verify(round, signature)?;
+		tld::<E, S>(ciphertext, signature)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS377};
+	use ark_ec::PrimeGroup;
+	use ark_std::{rand::rngs::OsRng, UniformRand};
+
+	type Scalar = <TinyBLS377 as EngineBLS>::Scalar;
+
+	fn sign(secret: Scalar, round: u64) -> <TinyBLS377 as EngineBLS>::SignatureGroup {
+		BeaconConfig::<TinyBLS377>::identity(round).extract::<TinyBLS377>(secret).0
+	}
+
+	#[test]
+	fn key_rings_pick_the_key_of_each_round() {
+		let (old, new) = (Scalar::rand(&mut OsRng), Scalar::rand(&mut OsRng));
+		let public = |secret| <TinyBLS377 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let mut ring = BeaconKeyRing::<TinyBLS377>::new();
+		ring.insert(public(new), 100, None).unwrap();
+		ring.insert(public(old), 1, Some(100)).unwrap();
+		assert_eq!(ring.windows()[0].from_round, 1);
+		assert_eq!(ring.insert(public(new), 50, Some(60)), Err(Error::OverlappingKeyWindows));
+		assert_eq!(ring.insert(public(new), 5, Some(5)), Err(Error::OverlappingKeyWindows));
+
+		assert_eq!(ring.key_for(99), Ok(public(old)));
+		assert_eq!(ring.key_for(100), Ok(public(new)));
+		assert_eq!(ring.key_for(0), Err(Error::NoKeyForRound));
+
+		for (round, secret) in [(99, old), (100, new)] {
+			let ciphertext = ring
+				.encrypt::<AESGCMBlockCipherProvider, _>(round, [3u8; 32], b"rotated", OsRng)
+				.unwrap();
+			let plaintext = ring
+				.decrypt::<AESGCMBlockCipherProvider>(round, ciphertext, sign(secret, round))
+				.unwrap();
+			assert_eq!(plaintext, b"rotated");
+		}
+		// a round signed with the key of another window is rejected
+		assert_eq!(ring.verify(100, sign(old, 100)), Err(Error::InvalidSignature));
+	}
+}
@@ -0,0 +1,664 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[cfg(feature = "abi")]
+pub mod abi;
+#[cfg(feature = "full")]
+pub mod armor;
+#[cfg(feature = "tokio")]
+pub mod async_stream;
+#[cfg(feature = "full")]
+pub mod batch;
+#[cfg(feature = "substrate")]
+pub mod beefy;
+#[cfg(feature = "substrate")]
+pub mod bounded;
+pub mod buffer;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+#[cfg(feature = "full")]
+pub mod chain;
+#[cfg(feature = "r1cs")]
+pub mod circuit;
+#[cfg(feature = "full")]
+pub mod commitment;
+#[cfg(feature = "full")]
+pub mod compat;
+#[cfg(feature = "full")]
+pub mod context;
+#[cfg(feature = "full")]
+pub mod domain;
+#[cfg(feature = "full")]
+pub mod erasure;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "full")]
+pub mod hybrid;
+#[cfg(feature = "full")]
+pub mod interop;
+#[cfg(feature = "full")]
+pub mod json;
+#[cfg(feature = "full")]
+pub mod keyring;
+#[cfg(test)]
+mod proptests;
+#[cfg(feature = "full")]
+pub mod randomness;
+#[cfg(feature = "full")]
+pub mod recipient;
+#[cfg(feature = "full")]
+pub mod relock;
+#[cfg(feature = "full")]
+pub mod scheduled;
+#[cfg(feature = "full")]
+pub mod sharded;
+#[cfg(feature = "full")]
+pub mod signed;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod strict;
+pub mod view;
+pub mod witness;
+
+#[cfg(feature = "full")]
+pub use armor::Armored;
+#[cfg(feature = "tokio")]
+pub use async_stream::{AsyncTldReader, AsyncTleWriter};
+#[cfg(feature = "full")]
+pub use batch::tld_batch;
+#[cfg(feature = "parallel")]
+pub use batch::{tld_batch_par, tle_batch_par};
+#[cfg(feature = "substrate")]
+pub use beefy::{tld_commitment, Commitment, SignedCommitment, ValidatorSet};
+#[cfg(feature = "substrate")]
+pub use bounded::BoundedTLECiphertext;
+pub use buffer::{tld_early_into, tld_into, tle_into};
+#[cfg(feature = "full")]
+pub use chain::{tld_chain, tle_chain, unwrap_layer, ChainCiphertext, Unwrapped};
+#[cfg(feature = "r1cs")]
+pub use circuit::{tle_provable, EncryptionCircuit};
+#[cfg(feature = "full")]
+pub use commitment::{tld_committed, tle_committed, PlaintextCommitment};
+#[cfg(feature = "full")]
+pub use context::EncryptionContext;
+#[cfg(feature = "full")]
+pub use domain::{tld_scoped, tle_scoped, AppContext, ScopedCiphertext};
+#[cfg(feature = "full")]
+pub use erasure::{ErasureShare, ShareHeader};
+#[cfg(feature = "heapless")]
+pub use heapless::{tld_bounded, tle_bounded, BoundedBuffer, BoundedIdentity};
+#[cfg(feature = "full")]
+pub use hybrid::{hybrid_keypair, tld_hybrid, tle_hybrid};
+#[cfg(feature = "full")]
+pub use keyring::{BeaconKeyRing, KeyWindow};
+#[cfg(feature = "full")]
+pub use randomness::Randomness;
+#[cfg(feature = "full")]
+pub use recipient::{tld_with_recipient, tle_with_recipient};
+#[cfg(feature = "full")]
+pub use relock::{relock, Relockable};
+#[cfg(feature = "full")]
+pub use scheduled::{encrypt_at_block, encrypt_at_time, BeaconConfig};
+#[cfg(feature = "full")]
+pub use sharded::{tld_sharded, tle_sharded, ShareDescriptor, ShareTarget};
+#[cfg(feature = "full")]
+pub use signed::{tld_signed, tle_signed};
+#[cfg(feature = "std")]
+pub use stream::{TldReader, TldWriter, TleWriter};
+pub use view::TLECiphertextRef;
+pub use witness::{open, seal, BeaconSignature, WitnessScheme};
+
+use crate::{
+	block_ciphers::{
+		AESGCMBlockCipherProvider, BlockCipherProvider, ChaCha20Poly1305BlockCipherProvider,
+		CipherSuite,
+	},
+	engines::EngineBLS,
+	ibe::{
+		cache::HashToCurveCache,
+		fullident::{encrypt_to_point, Ciphertext as IBECiphertext, IbeError, Identity, Input},
+	},
+	tlock::view::read_bytes,
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::{
+	CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec,
+	vec::Vec,
+};
+
+/// A secret key used for encryption/decryption
+pub type OpaqueSecretKey = [u8; 32];
+
+/// A ciphertext whose key is encapsulated by some [`WitnessScheme`]
+#[derive(CanonicalSerialize, Debug, Clone, PartialEq)]
+pub struct Envelope<H: CanonicalSerialize + CanonicalDeserialize> {
+	/// The header holds the encapsulated key
+	pub header: H,
+	/// The body holds the message encrypted with a stream cipher
+	pub body: Vec<u8>,
+	/// The cipher suite used (symmetric encryption scheme)
+	pub cipher_suite: Vec<u8>,
+}
+
+impl<H: CanonicalSerialize + CanonicalDeserialize> Valid for Envelope<H> {
+	fn check(&self) -> Result<(), SerializationError> {
+		self.header.check()
+	}
+}
+
+// Not derived: envelopes are untrusted input, see [`view::read_bytes`]
+impl<H: CanonicalSerialize + CanonicalDeserialize> CanonicalDeserialize for Envelope<H> {
+	fn deserialize_with_mode<R: Read>(
+		mut reader: R,
+		compress: Compress,
+		validate: Validate,
+	) -> Result<Self, SerializationError> {
+		let header = H::deserialize_with_mode(&mut reader, compress, validate)?;
+		let body = read_bytes(&mut reader)?;
+		let cipher_suite = read_bytes(&mut reader)?;
+		Ok(Self { header, body, cipher_suite })
+	}
+}
+
+/// A ciphertext whose key is IBE encrypted for a beacon round
+pub type TLECiphertext<E> = Envelope<IBECiphertext<E>>;
+
+/// Errors that may occur while execute timelock encryption/decryption
+#[derive(Debug, PartialEq)]
+pub enum Error {
+	/// The message could not be encrypted with the provided cipher
+	MessageEncryptionError,
+	/// The type could not be deserialized
+	DeserializationError,
+	/// The type could not be deserialized to an element of G1
+	DeserializationErrorG1,
+	/// The type could not be deserialized to an element of G2
+	DeserializationErrorG2,
+	/// The type could not be deserialized to a field element
+	DeserializationErrorFr,
+	/// The ciphertext could not be decrypted
+	DecryptionError,
+	/// The signature is invalid
+	InvalidSignature,
+	/// The secret key is not well-formed (must be 32 bytes)
+	InvalidSecretKey,
+	/// The sender's signature over the plaintext is invalid
+	InvalidSenderSignature,
+	/// The time-lock puzzle parameters are invalid
+	InvalidPuzzleParameters,
+	/// The threshold must be non-zero and at most the number of shares
+	InvalidThreshold,
+	/// Fewer shares than the threshold could be decrypted
+	NotEnoughShares,
+	/// A chain must have at least one layer and one signature per layer
+	InvalidChainLength,
+	/// The plaintext does not match its commitment
+	InvalidCommitment,
+	/// The input is not in the expected wire format
+	InvalidFormat,
+	/// No key of the key ring signs the round
+	NoKeyForRound,
+	/// A key would sign rounds another key of the key ring already signs
+	OverlappingKeyWindows,
+	/// The output buffer is too small
+	BufferTooSmall,
+	/// A point is the identity or not in the prime order subgroup
+	InvalidPoint,
+	/// The ciphertext is scoped to another application context
+	ContextMismatch,
+	/// A part of the ciphertext is longer than its bound
+	ExceedsBound,
+	/// The commitment is signed by another validator set
+	ValidatorSetMismatch,
+}
+
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str(match self {
+			Error::MessageEncryptionError => "the message could not be encrypted",
+			Error::DeserializationError => "the input could not be deserialized",
+			Error::DeserializationErrorG1 => "the input is not an element of G1",
+			Error::DeserializationErrorG2 => "the input is not an element of G2",
+			Error::DeserializationErrorFr => "the input is not a field element",
+			Error::DecryptionError => "the ciphertext could not be decrypted",
+			Error::InvalidSignature => "the signature cannot decrypt the ciphertext",
+			Error::InvalidSecretKey => "the secret key must be 32 bytes",
+			Error::InvalidSenderSignature => "the sender's signature is invalid",
+			Error::InvalidPuzzleParameters => "the time-lock puzzle parameters are invalid",
+			Error::InvalidThreshold =>
+				"the threshold must be non-zero and at most the number of shares",
+			Error::NotEnoughShares => "fewer shares than the threshold could be decrypted",
+			Error::InvalidChainLength =>
+				"a chain needs at least one layer and one signature per layer",
+			Error::InvalidCommitment => "the plaintext does not match its commitment",
+			Error::InvalidFormat => "the input is not in the expected wire format",
+			Error::NoKeyForRound => "no key of the key ring signs the round",
+			Error::OverlappingKeyWindows =>
+				"the key would sign rounds another key of the key ring already signs",
+			Error::BufferTooSmall => "the output buffer is too small",
+			Error::InvalidPoint => "a point is the identity or not in the prime order subgroup",
+			Error::ContextMismatch => "the ciphertext is scoped to another application context",
+			Error::ExceedsBound => "a part of the ciphertext is longer than its bound",
+			Error::ValidatorSetMismatch => "the commitment is signed by another validator set",
+		})
+	}
+}
+
+impl core::error::Error for Error {}
+
+impl From<IbeError> for Error {
+	fn from(_: IbeError) -> Self {
+		// IBE decryption only fails when the secret is not for the identity
+		Error::InvalidSignature
+	}
+}
+
+/// Encrypt a message for an identity
+///
+/// * `p_pub`: the public key commitment for the IBE system (i.e. the setup
+///   phase)
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `rng`: A CSPRNG
+pub fn tle<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	rng: R,
+) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	seal::<BeaconSignature<E>, S, R>(&p_pub, &id, secret_key, message, rng)
+}
+
+/// Encrypt a message for an identity with the fastest cipher on this CPU
+///
+/// See [`CipherSuite::detect`]. Decrypt with [`tld_auto`], which picks the
+/// cipher from the ciphertext.
+///
+/// * `p_pub`: the public key commitment for the IBE system (i.e. the setup
+///   phase)
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `rng`: A CSPRNG
+pub fn tle_auto<E, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	rng: R,
+) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS,
+	R: Rng + CryptoRng,
+{
+	match CipherSuite::detect() {
+		CipherSuite::AesGcm =>
+			tle::<E, AESGCMBlockCipherProvider, R>(p_pub, secret_key, message, id, rng),
+		CipherSuite::ChaCha20Poly1305 =>
+			tle::<E, ChaCha20Poly1305BlockCipherProvider, R>(p_pub, secret_key, message, id, rng),
+	}
+}
+
+/// Encrypt a message for an identity, looking up its public key in a cache
+///
+/// The ciphertext is the same as that of [`tle`] given the same randomness.
+///
+/// * `p_pub`: the public key commitment for the IBE system (i.e. the setup
+///   phase)
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `cache`: The public keys of recently used identities
+/// * `rng`: A CSPRNG
+pub fn tle_cached<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: &Identity,
+	cache: &mut HashToCurveCache<E>,
+	mut rng: R,
+) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let input = Input::<E>::new(secret_key).map_err(|_| Error::InvalidSecretKey)?;
+	let mut sigma = vec![0u8; E::SECRET_KEY_SIZE];
+	rng.fill_bytes(&mut sigma);
+	let header = encrypt_to_point(&input, p_pub, cache.public(id), &sigma);
+	witness::envelope::<_, S, R>(header, secret_key, message, rng)
+}
+
+/// Decrypt a ciphertext created as a result of timelock encryption
+/// the signature should be equivalent to the output of IBE.Extract(ID)
+/// where ID is the identity for which the message was created
+///
+/// * `ciphertext`: A TLECiphertext encrypted under some supported protocol
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+pub fn tld<E, S>(
+	ciphertext: TLECiphertext<E>,
+	signature: E::SignatureGroup,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	open::<BeaconSignature<E>, S>(ciphertext, &signature)
+}
+
+/// Decrypt a ciphertext with the cipher named by its cipher suite
+///
+/// * `ciphertext`: A TLECiphertext encrypted with any [`CipherSuite`]
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+pub fn tld_auto<E: EngineBLS>(
+	ciphertext: TLECiphertext<E>,
+	signature: E::SignatureGroup,
+) -> Result<Vec<u8>, Error> {
+	match CipherSuite::from_id(&ciphertext.cipher_suite).ok_or(Error::InvalidFormat)? {
+		CipherSuite::AesGcm => tld::<E, AESGCMBlockCipherProvider>(ciphertext, signature),
+		CipherSuite::ChaCha20Poly1305 =>
+			tld::<E, ChaCha20Poly1305BlockCipherProvider>(ciphertext, signature),
+	}
+}
+
+/// The identity signed by a beacon in a round, sha256(round as u64 BE)
+pub(crate) fn round_identity(round: u64) -> Identity {
+	Identity::from_round(round)
+}
+
+/// Check that a signature is the beacon's signature of a round
+///
+/// * `p_pub`: The public key of the beacon
+/// * `round`: The round of the signature
+/// * `signature`: The signature
+pub fn verify_signature<E: EngineBLS>(
+	p_pub: E::PublicKeyGroup,
+	round: u64,
+	signature: E::SignatureGroup,
+) -> Result<(), Error> {
+	let message = round_identity(round).public::<E>();
+	if E::pairing(p_pub, message) != E::pairing(E::PublicKeyGroup::generator(), signature) {
+		return Err(Error::InvalidSignature);
+	}
+	Ok(())
+}
+
+/// Verify the signature of a round, then decrypt a ciphertext with it
+///
+/// * `ciphertext`: A ciphertext locked to `round`
+/// * `p_pub`: The public key of the beacon
+/// * `round`: The round the ciphertext is locked to
+/// * `signature`: The beacon's signature of the round
+pub fn tld_verified<E, S>(
+	ciphertext: TLECiphertext<E>,
+	p_pub: E::PublicKeyGroup,
+	round: u64,
+	signature: E::SignatureGroup,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	verify_signature::<E>(p_pub, round, signature)?;
+	tld::<E, S>(ciphertext, signature)
+}
+
+/// Like [`tld_verified`], also returning the randomness of the round
+///
+/// * `ciphertext`: A ciphertext locked to `round`
+/// * `p_pub`: The public key of the beacon
+/// * `round`: The round the ciphertext is locked to
+/// * `signature`: The beacon's signature of the round
+#[cfg(feature = "full")]
+pub fn tld_verified_with_randomness<E, S>(
+	ciphertext: TLECiphertext<E>,
+	p_pub: E::PublicKeyGroup,
+	round: u64,
+	signature: E::SignatureGroup,
+) -> Result<(Vec<u8>, Randomness), Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let plaintext = tld_verified::<E, S>(ciphertext, p_pub, round, signature)?;
+	Ok((plaintext, Randomness::from_signature::<E>(&signature)))
+}
+
+#[cfg(test)]
+mod test {
+
+	use super::*;
+	use crate::{
+		block_ciphers::AESOutput,
+		engines::drand::{TinyBLS377, TinyBLS381},
+	};
+	use alloc::vec;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+	use sha2::Digest;
+
+	// specific conditions that we want to test/verify
+	enum TestStatusReport {
+		DecryptSuccess { actual: Vec<u8>, expected: Vec<u8> },
+		DecryptionFailed { error: Error },
+	}
+
+	fn tlock_test_aes_gcm<E: EngineBLS, R: Rng + Sized + CryptoRng>(
+		inject_bad_ct: bool,
+		inject_bad_nonce: bool,
+		handler: &dyn Fn(TestStatusReport) -> (),
+	) {
+		let message = b"this is a test message".to_vec();
+		let id = Identity::new(b"", &message);
+		let sk = E::Scalar::rand(&mut OsRng);
+		let p_pub = E::PublicKeyGroup::generator() * sk;
+
+		// key used for aes encryption
+		let msk = [1; 32];
+
+		let sig: E::SignatureGroup = id.extract::<E>(sk).0;
+
+		match tle::<E, AESGCMBlockCipherProvider, OsRng>(p_pub, msk, &message, id, OsRng) {
+			Ok(mut ct) => {
+				// create error scenarios here
+				if inject_bad_ct {
+					let mut output = AESOutput::deserialize_compressed(&mut &ct.body[..]).unwrap();
+					output.ciphertext = vec![];
+					let mut corrupted = Vec::new();
+					output.serialize_compressed(&mut corrupted).unwrap();
+					ct.body = corrupted;
+				}
+
+				if inject_bad_nonce {
+					let mut output = AESOutput::deserialize_compressed(&mut &ct.body[..]).unwrap();
+					output.nonce = vec![];
+					let mut corrupted = Vec::new();
+					output.serialize_compressed(&mut corrupted).unwrap();
+					ct.body = corrupted;
+				}
+
+				match tld::<E, AESGCMBlockCipherProvider>(ct, sig) {
+					Ok(output) => {
+						handler(TestStatusReport::DecryptSuccess {
+							actual: output,
+							expected: message,
+						});
+					},
+					Err(e) => {
+						handler(TestStatusReport::DecryptionFailed { error: e });
+					},
+				}
+			},
+			Err(_) => {
+				panic!("The test should pass but failed to run tlock encrypt");
+			},
+		}
+	}
+
+	#[test]
+	pub fn tlock_can_encrypt_decrypt_with_single_sig() {
+		tlock_test_aes_gcm::<TinyBLS381, OsRng>(false, false, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptSuccess { actual, expected } => {
+					assert_eq!(actual, expected);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_can_encrypt_decrypt_with_full_sigs_present() {
+		tlock_test_aes_gcm::<TinyBLS381, OsRng>(false, false, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptSuccess { actual, expected } => {
+					assert_eq!(actual, expected);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_can_encrypt_decrypt_with_many_identities_at_threshold() {
+		tlock_test_aes_gcm::<TinyBLS381, OsRng>(false, false, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptSuccess { actual, expected } => {
+					assert_eq!(actual, expected);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_can_encrypt_decrypt_with_bls12_377() {
+		tlock_test_aes_gcm::<TinyBLS377, OsRng>(false, false, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptSuccess { actual, expected } => {
+					assert_eq!(actual, expected);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_decryption_fails_with_bad_ciphertext() {
+		tlock_test_aes_gcm::<TinyBLS381, OsRng>(true, false, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptionFailed { error } => {
+					assert_eq!(error, Error::DecryptionError);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_decryption_fails_with_bad_nonce() {
+		tlock_test_aes_gcm::<TinyBLS381, OsRng>(false, true, &|status: TestStatusReport| {
+			match status {
+				TestStatusReport::DecryptionFailed { error } => {
+					assert_eq!(error, Error::DecryptionError);
+				},
+				_ => panic!("all other conditions invalid"),
+			}
+		});
+	}
+
+	#[test]
+	pub fn tlock_encrypt_decrypt_drand_quicknet_works() {
+		// using a pulse from drand's QuickNet
+		// https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/public/1000
+		// the beacon public key
+		let pk_bytes =
+	b"83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a"
+	; // a round number that we know a signature for
+		let round: u64 = 1000;
+		// the signature produced in that round
+		let signature =
+	b"b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39"
+	;
+
+		// Convert hex string to bytes
+		let pub_key_bytes = hex::decode(pk_bytes).expect("Decoding failed");
+		// Deserialize to G1Affine
+		let pub_key =
+			<TinyBLS381 as EngineBLS>::PublicKeyGroup::deserialize_compressed(&*pub_key_bytes)
+				.unwrap();
+
+		// then we tlock a message for the pubkey
+		let plaintext = b"this is a test".as_slice();
+		let esk = [2; 32];
+
+		let sig_bytes = hex::decode(signature).expect("The signature should be well formatted");
+		let sig =
+			<TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(&*sig_bytes).unwrap();
+
+		let message = {
+			let mut hasher = sha2::Sha256::new();
+			hasher.update(round.to_be_bytes());
+			hasher.finalize().to_vec()
+		};
+
+		let identity = Identity::new(b"", &message);
+
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			pub_key, esk, plaintext, identity, OsRng,
+		)
+		.unwrap();
+
+		// then we can decrypt the ciphertext using the signature
+		let result = tld::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig).unwrap();
+		assert!(result == plaintext);
+	}
+
+	#[test]
+	fn tld_auto_decrypts_every_cipher_suite() {
+		let sk = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * sk;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let signature = id.extract::<TinyBLS381>(sk).0;
+
+		let detected =
+			tle_auto::<TinyBLS381, OsRng>(p_pub, [2; 32], b"auto", id.clone(), OsRng).unwrap();
+		assert_eq!(detected.cipher_suite, CipherSuite::detect().id());
+		let chacha = tle::<TinyBLS381, ChaCha20Poly1305BlockCipherProvider, OsRng>(
+			p_pub, [2; 32], b"chacha", id, OsRng,
+		)
+		.unwrap();
+		assert_eq!(chacha.cipher_suite, b"CHACHA20");
+
+		assert_eq!(tld_auto::<TinyBLS381>(detected, signature), Ok(b"auto".to_vec()));
+		let unknown = TLECiphertext {
+			header: IBECiphertext { u: chacha.header.u, v: chacha.header.v, w: chacha.header.w },
+			body: chacha.body.clone(),
+			cipher_suite: b"UNKNOWN_".to_vec(),
+		};
+		assert_eq!(tld_auto::<TinyBLS381>(chacha, signature), Ok(b"chacha".to_vec()));
+		assert_eq!(tld_auto::<TinyBLS381>(unknown, signature), Err(Error::InvalidFormat));
+	}
+}
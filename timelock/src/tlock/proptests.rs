@@ -0,0 +1,124 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Property-based round-trip tests.
+//!
+//! Each property runs for every engine and [`BlockCipherProvider`]; a new
+//! curve or cipher only needs a line in the `engines_and_ciphers!`
+//! invocation at the bottom of this file.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	tlock::{tld, tle, TLECiphertext},
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{rngs::StdRng, SeedableRng},
+	vec::Vec,
+	UniformRand,
+};
+use proptest::prelude::*;
+
+/// A beacon secret and the serialized ciphertext locked to `identity`, with
+/// the length of its header and body (the cipher suite is chosen by the caller
+/// of [`tld`], so only those bytes are authenticated)
+fn lock<E, S>(message: &[u8], identity: &[u8], seed: u64) -> (E::Scalar, Vec<u8>, usize)
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let mut rng = StdRng::seed_from_u64(seed);
+	let secret = E::Scalar::rand(&mut rng);
+	let p_pub = E::PublicKeyGroup::generator() * secret;
+	let mut key = [0u8; 32];
+	ark_std::rand::RngCore::fill_bytes(&mut rng, &mut key);
+	let id = Identity::new(b"", identity);
+	let ciphertext = tle::<E, S, _>(p_pub, key, message, id, rng).unwrap();
+	let authenticated = ciphertext.header.compressed_size() + ciphertext.body.compressed_size();
+	let mut bytes = Vec::new();
+	ciphertext.serialize_compressed(&mut bytes).unwrap();
+	(secret, bytes, authenticated)
+}
+
+/// Decrypt serialized ciphertext bytes with the signature on `identity`
+fn unlock<E, S>(bytes: &[u8], identity: &[u8], secret: E::Scalar) -> Option<Vec<u8>>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let signature = Identity::new(b"", identity).extract::<E>(secret).0;
+	let ciphertext = TLECiphertext::<E>::deserialize_compressed(bytes).ok()?;
+	tld::<E, S>(ciphertext, signature).ok()
+}
+
+macro_rules! engines_and_ciphers {
+	($($name:ident => $engine:ty, $cipher:ty;)*) => {$(
+		mod $name {
+			use super::*;
+
+			proptest! {
+				#![proptest_config(ProptestConfig::with_cases(32))]
+
+				#[test]
+				fn round_trips(
+					message in prop::collection::vec(any::<u8>(), 0..256),
+					identity in prop::collection::vec(any::<u8>(), 0..64),
+					seed in any::<u64>(),
+				) {
+					let (secret, bytes, _) = lock::<$engine, $cipher>(&message, &identity, seed);
+					let plaintext = unlock::<$engine, $cipher>(&bytes, &identity, secret);
+					prop_assert_eq!(plaintext, Some(message));
+				}
+
+				#[test]
+				fn mutated_ciphertexts_fail(
+					message in prop::collection::vec(any::<u8>(), 0..256),
+					identity in prop::collection::vec(any::<u8>(), 0..64),
+					seed in any::<u64>(),
+					index in any::<prop::sample::Index>(),
+					bit in 0..8u8,
+				) {
+					let (secret, mut bytes, authenticated) = lock::<$engine, $cipher>(&message, &identity, seed);
+					let index = index.index(authenticated);
+					bytes[index] ^= 1 << bit;
+					prop_assert_eq!(unlock::<$engine, $cipher>(&bytes, &identity, secret), None);
+				}
+
+				#[test]
+				fn other_identities_fail(
+					message in prop::collection::vec(any::<u8>(), 0..256),
+					identity in prop::collection::vec(any::<u8>(), 0..64),
+					other in prop::collection::vec(any::<u8>(), 0..64),
+					seed in any::<u64>(),
+				) {
+					prop_assume!(identity != other);
+					let (secret, bytes, _) = lock::<$engine, $cipher>(&message, &identity, seed);
+					prop_assert_eq!(unlock::<$engine, $cipher>(&bytes, &other, secret), None);
+				}
+			}
+		}
+	)*};
+}
+
+engines_and_ciphers! {
+	bls381_aes_gcm => crate::engines::drand::TinyBLS381, crate::block_ciphers::AESGCMBlockCipherProvider;
+	bls381_chacha20 => crate::engines::drand::TinyBLS381, crate::block_ciphers::ChaCha20Poly1305BlockCipherProvider;
+	bls377_aes_gcm => crate::engines::drand::TinyBLS377, crate::block_ciphers::AESGCMBlockCipherProvider;
+	bls377_chacha20 => crate::engines::drand::TinyBLS377, crate::block_ciphers::ChaCha20Poly1305BlockCipherProvider;
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The randomness of a beacon round.
+//!
+//! drand publishes the SHA-256 hash of a round's compressed signature as the
+//! randomness of the round. The signature that unlocks a ciphertext thus also
+//! yields unbiasable randomness, which lotteries and games can use alongside
+//! the revealed messages (see [`super::tld_verified_with_randomness`]).
+//! Randomness should only be derived from a verified signature.
+
+use crate::{engines::EngineBLS, ibe::utils::sha256};
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+
+/// The randomness of a beacon round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Randomness(pub [u8; 32]);
+
+impl Randomness {
+	/// The randomness of the round with a signature, sha256(signature)
+	///
+	/// * `signature`: The beacon's signature of the round
+	pub fn from_signature<E: EngineBLS>(signature: &E::SignatureGroup) -> Self {
+		let mut bytes = Vec::new();
+		signature
+			.serialize_compressed(&mut bytes)
+			.expect("The signature must be serializable.");
+		Self(sha256(&bytes).try_into().expect("SHA-256 outputs 32 bytes."))
+	}
+
+	/// The randomness as bytes
+	pub fn as_bytes(&self) -> &[u8; 32] {
+		&self.0
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacons::QUICKNET,
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld_verified, tld_verified_with_randomness, tle, BeaconConfig, Error},
+	};
+	use ark_serialize::CanonicalDeserialize;
+	use ark_std::rand::rngs::OsRng;
+
+	#[test]
+	fn randomness_is_derived_alongside_verified_decryption() {
+		let hex = "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39";
+		let bytes = array_bytes::hex2bytes(hex).unwrap();
+		// quicknet's signature of round 1000
+		let signature =
+			<TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(&bytes[..]).unwrap();
+		let p_pub = QUICKNET.config::<TinyBLS381>().unwrap().p_pub;
+		let lock = |round| {
+			let id = BeaconConfig::<TinyBLS381>::identity(round);
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, [5u8; 32], b"winner", id, OsRng)
+				.unwrap()
+		};
+
+		let (plaintext, randomness) = tld_verified_with_randomness::<
+			TinyBLS381,
+			AESGCMBlockCipherProvider,
+		>(lock(1000), p_pub, 1000, signature)
+		.unwrap();
+		assert_eq!(plaintext, b"winner");
+		assert_eq!(randomness, Randomness::from_signature::<TinyBLS381>(&signature));
+		assert_eq!(randomness.as_bytes()[..], sha256(&bytes)[..]);
+
+		// the signature of another round is rejected before decrypting
+		let result = tld_verified::<TinyBLS381, AESGCMBlockCipherProvider>(
+			lock(1001),
+			p_pub,
+			1001,
+			signature,
+		);
+		assert_eq!(result, Err(Error::InvalidSignature));
+	}
+}
@@ -0,0 +1,256 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Designated-recipient timelock encryption.
+//!
+//! In this mode the ciphertext can only be opened by a specific recipient once
+//! the round has been reached: decryption requires both the beacon signature
+//! for the identity and the recipient's static secret key. The IBE header
+//! encapsulates the ephemeral key as usual, and an additional recipient header
+//! carries an ephemeral Diffie-Hellman key in the public key group of the
+//! engine. The message is encrypted under a key derived from both secrets, so
+//! publication of the beacon signature alone does not reveal the message.
+//!
+//! Recipient keys are scalars of the engine with public keys in its public key
+//! group; X25519 recipient keys are not supported.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::{fullident::Identity, utils::sha256},
+	tlock::{
+		witness::{envelope, open_body, BeaconSignature, WitnessScheme},
+		Error, OpaqueSecretKey, TLECiphertext,
+	},
+};
+use ark_ec::PrimeGroup;
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// Domain separation tag for the recipient key derivation
+const RECIPIENT_KDF_DST: &[u8] = b"TIMELOCK_RECIPIENT_KDF_V1";
+
+/// The additional header for designated-recipient ciphertexts
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RecipientHeader<E: EngineBLS> {
+	/// The sender's ephemeral Diffie-Hellman public key eP
+	pub ephemeral_key: E::PublicKeyGroup,
+}
+
+/// A timelock ciphertext that is additionally locked to a recipient
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RecipientCiphertext<E: EngineBLS> {
+	/// The timelock ciphertext, whose body is encrypted under the derived key
+	pub ciphertext: TLECiphertext<E>,
+	/// The recipient header
+	pub recipient: RecipientHeader<E>,
+}
+
+/// Derive the public key of a recipient from their static secret key
+pub fn recipient_public_key<E: EngineBLS>(secret: E::Scalar) -> E::PublicKeyGroup {
+	E::PublicKeyGroup::generator() * secret
+}
+
+/// Encrypt a message for an identity and a designated recipient
+///
+/// Fails with [`Error::InvalidPoint`] if the recipient key is the identity.
+///
+/// * `p_pub`: the public key commitment for the IBE system
+/// * `secret_key`: the ephemeral key that is timelock encrypted
+/// * `message`: The message to encrypt
+/// * `id`: The identity to encrypt for
+/// * `recipient`: The recipient's static public key
+/// * `rng`: A CSPRNG
+pub fn tle_with_recipient<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	recipient: E::PublicKeyGroup,
+	mut rng: R,
+) -> Result<RecipientCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	if recipient.is_zero() {
+		return Err(Error::InvalidPoint);
+	}
+	let mut e = E::Scalar::rand(&mut rng);
+	let ephemeral_key = E::PublicKeyGroup::generator() * e;
+	let mut shared = recipient * e;
+	let mut key = derive_key::<E>(&secret_key, &shared, &ephemeral_key, &recipient);
+	e.zeroize();
+	shared.zeroize();
+	// the IBE header carries the timelocked secret, while the body is encrypted
+	// under the key derived from both the secret and the recipient secret
+	let ciphertext = BeaconSignature::<E>::encapsulate(&p_pub, &id, secret_key, &mut rng)
+		.and_then(|header| envelope::<_, S, _>(header, key, message, &mut rng));
+	key.zeroize();
+	let ciphertext: TLECiphertext<E> = ciphertext?;
+
+	Ok(RecipientCiphertext { ciphertext, recipient: RecipientHeader { ephemeral_key } })
+}
+
+/// Decrypt a designated-recipient ciphertext
+///
+/// Fails with [`Error::InvalidSecretKey`] if the recipient secret is zero and
+/// with [`Error::InvalidPoint`] if the ephemeral key is the identity.
+///
+/// * `ciphertext`: A ciphertext output by [`tle_with_recipient`]
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+/// * `recipient_secret`: The recipient's static secret key
+pub fn tld_with_recipient<E, S>(
+	ciphertext: RecipientCiphertext<E>,
+	signature: E::SignatureGroup,
+	recipient_secret: E::Scalar,
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let RecipientCiphertext { ciphertext, recipient } = ciphertext;
+	if recipient_secret.is_zero() {
+		return Err(Error::InvalidSecretKey);
+	}
+	if recipient.ephemeral_key.is_zero() {
+		return Err(Error::InvalidPoint);
+	}
+	let mut secret_bytes = BeaconSignature::<E>::decapsulate(&ciphertext.header, &signature)?;
+	let mut shared = recipient.ephemeral_key * recipient_secret;
+	let mut key = derive_key::<E>(
+		&secret_bytes,
+		&shared,
+		&recipient.ephemeral_key,
+		&recipient_public_key::<E>(recipient_secret),
+	);
+	secret_bytes.zeroize();
+	shared.zeroize();
+
+	let plaintext = open_body::<S>(&ciphertext.body, key);
+	key.zeroize();
+	plaintext
+}
+
+/// k = H(dst, secret, shared, ephemeral, recipient)
+fn derive_key<E: EngineBLS>(
+	secret: &OpaqueSecretKey,
+	shared: &E::PublicKeyGroup,
+	ephemeral: &E::PublicKeyGroup,
+	recipient: &E::PublicKeyGroup,
+) -> OpaqueSecretKey {
+	let mut input = Vec::new();
+	input.extend_from_slice(RECIPIENT_KDF_DST);
+	input.extend_from_slice(secret);
+	for point in [shared, ephemeral, recipient] {
+		point
+			.serialize_compressed(&mut input)
+			.expect("Enough space has been allocated in the buffer");
+	}
+	let mut key = [0u8; 32];
+	key.copy_from_slice(&sha256(&input));
+	// the input holds the timelocked secret and the shared point
+	input.zeroize();
+	key
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381, tlock::tld};
+	use ark_std::rand::rngs::OsRng;
+
+	type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+
+	fn encrypt(
+		message: &[u8],
+	) -> (RecipientCiphertext<TinyBLS381>, <TinyBLS381 as EngineBLS>::SignatureGroup, Scalar) {
+		let msk = Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let recipient_sk = Scalar::rand(&mut OsRng);
+
+		let ct = tle_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[7; 32],
+			message,
+			id.clone(),
+			recipient_public_key::<TinyBLS381>(recipient_sk),
+			OsRng,
+		)
+		.unwrap();
+		(ct, id.extract::<TinyBLS381>(msk).0, recipient_sk)
+	}
+
+	#[test]
+	pub fn recipient_can_decrypt_with_signature() {
+		let (ct, sig, recipient_sk) = encrypt(b"for your eyes only");
+		let plaintext =
+			tld_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig, recipient_sk)
+				.unwrap();
+		assert_eq!(plaintext, b"for your eyes only".to_vec());
+	}
+
+	#[test]
+	pub fn recipient_decryption_fails_with_wrong_recipient_key() {
+		let (ct, sig, _) = encrypt(b"for your eyes only");
+		let result = tld_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider>(
+			ct,
+			sig,
+			Scalar::rand(&mut OsRng),
+		);
+		assert_eq!(result, Err(Error::DecryptionError));
+	}
+
+	#[test]
+	pub fn recipient_keys_must_not_be_zero() {
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator();
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let result = tle_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[7; 32],
+			b"for nobody",
+			id,
+			<TinyBLS381 as EngineBLS>::PublicKeyGroup::zero(),
+			OsRng,
+		);
+		assert!(matches!(result, Err(Error::InvalidPoint)));
+
+		let (ct, sig, _) = encrypt(b"for your eyes only");
+		let result =
+			tld_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig, Scalar::zero());
+		assert_eq!(result, Err(Error::InvalidSecretKey));
+
+		let (mut ct, sig, recipient_sk) = encrypt(b"for your eyes only");
+		ct.recipient.ephemeral_key = <TinyBLS381 as EngineBLS>::PublicKeyGroup::zero();
+		let result =
+			tld_with_recipient::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig, recipient_sk);
+		assert_eq!(result, Err(Error::InvalidPoint));
+	}
+
+	#[test]
+	pub fn recipient_ciphertext_is_not_opened_by_signature_alone() {
+		let (ct, sig, _) = encrypt(b"for your eyes only");
+		let result = tld::<TinyBLS381, AESGCMBlockCipherProvider>(ct.ciphertext, sig);
+		assert_eq!(result, Err(Error::DecryptionError));
+	}
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Moving ciphertexts from one beacon to another.
+//!
+//! When a beacon is deprecated (as drand's fastnet was), ciphertexts locked
+//! to it must be relocked to a round of a beacon that will keep publishing.
+//! [`relock`] opens each ciphertext with the old beacon's signature of its
+//! round, or takes the original plaintext when the caller still has it, and
+//! encrypts the message again for the new round. The old and new beacons may
+//! use different engines.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	tlock::{tld, tle, BeaconConfig, Error, TLECiphertext},
+};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+
+/// A message to relock
+pub enum Relockable<E: EngineBLS> {
+	/// A ciphertext of the old beacon and the signature of its round
+	Ciphertext(TLECiphertext<E>, E::SignatureGroup),
+	/// The original plaintext of a ciphertext
+	Plaintext(Vec<u8>),
+}
+
+/// Relock a batch of messages to a round of a new beacon
+///
+/// Every message is relocked independently, so a ciphertext that cannot be
+/// opened does not stop the batch; its result is the error instead. The
+/// results are in the order of `items`.
+///
+/// * `items`: The messages to relock
+/// * `config`: The new beacon
+/// * `round`: The round of the new beacon to lock to
+/// * `rng`: A CSPRNG
+/// * `progress`: Called with the number of messages relocked so far and the
+///   total after each message
+pub fn relock<Old, New, S, R>(
+	items: Vec<Relockable<Old>>,
+	config: &BeaconConfig<New>,
+	round: u64,
+	mut rng: R,
+	mut progress: impl FnMut(usize, usize),
+) -> Vec<Result<TLECiphertext<New>, Error>>
+where
+	Old: EngineBLS,
+	New: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let total = items.len();
+	let id = BeaconConfig::<New>::identity(round);
+	let mut results = Vec::with_capacity(total);
+	for item in items {
+		let message = match item {
+			Relockable::Ciphertext(ciphertext, signature) => tld::<Old, S>(ciphertext, signature),
+			Relockable::Plaintext(message) => Ok(message),
+		};
+		results.push(message.and_then(|message| {
+			let mut secret_key = [0u8; 32];
+			rng.fill_bytes(&mut secret_key);
+			tle::<New, S, &mut R>(config.p_pub, secret_key, &message, id.clone(), &mut rng)
+		}));
+		progress(results.len(), total);
+	}
+	results
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::{TinyBLS377, TinyBLS381},
+		ibe::fullident::Identity,
+		rounds::RoundCalculator,
+	};
+	use ark_ec::PrimeGroup;
+	use ark_std::{rand::rngs::OsRng, vec, UniformRand};
+
+	/// A beacon with a random master secret
+	fn beacon<E: EngineBLS>() -> (BeaconConfig<E>, E::Scalar) {
+		let secret = E::Scalar::rand(&mut OsRng);
+		let config = BeaconConfig {
+			p_pub: E::PublicKeyGroup::generator() * secret,
+			rounds: RoundCalculator::new(0, 3),
+		};
+		(config, secret)
+	}
+
+	fn sign<E: EngineBLS>(secret: E::Scalar, round: u64) -> E::SignatureGroup {
+		let id: Identity = BeaconConfig::<E>::identity(round);
+		id.extract::<E>(secret).0
+	}
+
+	#[test]
+	fn relock_moves_ciphertexts_between_beacons() {
+		let (old, old_secret) = beacon::<TinyBLS377>();
+		let (new, new_secret) = beacon::<TinyBLS381>();
+		let lock = |round: u64, message: &[u8]| {
+			tle::<TinyBLS377, AESGCMBlockCipherProvider, OsRng>(
+				old.p_pub,
+				[1u8; 32],
+				message,
+				BeaconConfig::<TinyBLS377>::identity(round),
+				OsRng,
+			)
+			.unwrap()
+		};
+
+		let items = vec![
+			Relockable::Ciphertext(lock(10, b"first"), sign::<TinyBLS377>(old_secret, 10)),
+			Relockable::Plaintext(b"second".to_vec()),
+			// signed for the wrong round
+			Relockable::Ciphertext(lock(11, b"third"), sign::<TinyBLS377>(old_secret, 10)),
+		];
+		let mut reported = Vec::new();
+		let results =
+			relock::<_, _, AESGCMBlockCipherProvider, _>(items, &new, 500, OsRng, |done, total| {
+				reported.push((done, total))
+			});
+		assert_eq!(reported, [(1, 3), (2, 3), (3, 3)]);
+
+		let signature = sign::<TinyBLS381>(new_secret, 500);
+		let opened: Vec<_> = results
+			.into_iter()
+			.map(|result| tld::<TinyBLS381, AESGCMBlockCipherProvider>(result?, signature))
+			.collect();
+		assert_eq!(opened[0], Ok(b"first".to_vec()));
+		assert_eq!(opened[1], Ok(b"second".to_vec()));
+		assert!(opened[2].is_err());
+	}
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Timelock encryption for a point in time rather than a round.
+//!
+//! A [`BeaconConfig`] holds everything needed to encrypt for a beacon: its
+//! public key and its round schedule. [`encrypt_at_time`] picks the first round
+//! published at or after the unlock time and encrypts for it, returning the
+//! round so that it can be stored next to the ciphertext.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	blocks::block_identity,
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	rounds::RoundCalculator,
+	tlock::{tle, Error, TLECiphertext},
+};
+use ark_std::rand::{CryptoRng, Rng};
+
+/// The parameters of a drand style beacon
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeaconConfig<E: EngineBLS> {
+	/// The public key of the beacon
+	pub p_pub: E::PublicKeyGroup,
+	/// The round schedule of the beacon
+	pub rounds: RoundCalculator,
+}
+
+impl<E: EngineBLS> BeaconConfig<E> {
+	/// The identity signed by the beacon in a round, sha256(round as u64 BE)
+	pub fn identity(round: u64) -> Identity {
+		super::round_identity(round)
+	}
+}
+
+/// Encrypt a message so that it unlocks at a unix time
+///
+/// Returns the ciphertext and the round whose signature decrypts it (with
+/// [`super::tld`]), which is the first round published at or after
+/// `unlock_time`.
+///
+/// * `config`: The beacon to encrypt for
+/// * `unlock_time`: The unix time (in seconds) at which to unlock the message
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn encrypt_at_time<E, S, R>(
+	config: &BeaconConfig<E>,
+	unlock_time: u64,
+	message: &[u8],
+	mut rng: R,
+) -> Result<(TLECiphertext<E>, u64), Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let round = config.rounds.round_after(unlock_time);
+	let mut secret_key = [0u8; 32];
+	rng.fill_bytes(&mut secret_key);
+	let ciphertext =
+		tle::<E, S, R>(config.p_pub, secret_key, message, BeaconConfig::<E>::identity(round), rng)?;
+	Ok((ciphertext, round))
+}
+
+/// Encrypt a message so that it unlocks once a block is finalized
+///
+/// For beacons that sign block numbers (e.g. the Ideal Network), the
+/// ciphertext is decrypted (with [`super::tld`]) by the signature of
+/// `block_number`. See [`crate::blocks`] for estimating blocks from times.
+///
+/// * `p_pub`: The public key of the beacon
+/// * `block_number`: The block at which to unlock the message
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn encrypt_at_block<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	block_number: u64,
+	message: &[u8],
+	mut rng: R,
+) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let mut secret_key = [0u8; 32];
+	rng.fill_bytes(&mut secret_key);
+	tle::<E, S, R>(p_pub, secret_key, message, block_identity(block_number), rng)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacons::QUICKNET, block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381,
+		tlock::tld,
+	};
+	use ark_ec::PrimeGroup;
+	use ark_serialize::CanonicalDeserialize;
+	use ark_std::rand::rngs::OsRng;
+
+	type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+
+	const QUICKNET_SIG_1000: &str = "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39";
+
+	#[test]
+	pub fn encrypt_at_time_targets_the_next_round() {
+		let config = QUICKNET.config::<TinyBLS381>().unwrap();
+		// one second after round 999, so round 1000 is the first one after it
+		let unlock_time = config.rounds.time_of(999) + 1;
+		let (ciphertext, round) = encrypt_at_time::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			&config,
+			unlock_time,
+			b"unlock me later",
+			OsRng,
+		)
+		.unwrap();
+		assert_eq!(round, 1000);
+
+		let signature = Signature::deserialize_compressed(
+			&array_bytes::hex2bytes(QUICKNET_SIG_1000).unwrap()[..],
+		)
+		.unwrap();
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, signature);
+		assert_eq!(plaintext.unwrap(), b"unlock me later".to_vec());
+	}
+
+	#[test]
+	pub fn encrypt_at_block_unlocks_with_the_block_signature() {
+		let sk = <TinyBLS381 as EngineBLS>::Scalar::from(42u64);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * sk;
+		let encrypt = || {
+			encrypt_at_block::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+				p_pub,
+				1_000_000,
+				b"unlock at block 1,000,000",
+				OsRng,
+			)
+			.unwrap()
+		};
+
+		let signature = block_identity(1_000_000).extract::<TinyBLS381>(sk).0;
+		let plaintext = tld::<TinyBLS381, AESGCMBlockCipherProvider>(encrypt(), signature);
+		assert_eq!(plaintext.unwrap(), b"unlock at block 1,000,000".to_vec());
+
+		let early = block_identity(999_999).extract::<TinyBLS381>(sk).0;
+		assert!(tld::<TinyBLS381, AESGCMBlockCipherProvider>(encrypt(), early).is_err());
+	}
+}
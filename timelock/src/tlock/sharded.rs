@@ -0,0 +1,341 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Threshold timelock encryption across several beacons or rounds.
+//!
+//! The ephemeral key is split with Shamir secret sharing over GF(2^8), one
+//! byte at a time, and each share is IBE encrypted for a different target
+//! (a beacon public key and an identity). Any `threshold` of the targets'
+//! signatures recover the key, so a message can be made to depend on k-of-n
+//! independent beacons having revealed.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::{Ciphertext as IBECiphertext, Identity},
+	tlock::{open, seal, BeaconSignature, Envelope, Error, OpaqueSecretKey, WitnessScheme},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+/// A beacon and identity that a share of the key is encrypted for
+#[derive(Debug)]
+pub struct ShareTarget<E: EngineBLS> {
+	/// The public key of the beacon
+	pub p_pub: E::PublicKeyGroup,
+	/// The identity (e.g. round) to encrypt the share for
+	pub id: Identity,
+}
+
+impl<E: EngineBLS> Clone for ShareTarget<E> {
+	fn clone(&self) -> Self {
+		Self { p_pub: self.p_pub, id: self.id.clone() }
+	}
+}
+
+/// A share of the key, IBE encrypted for one target
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShareDescriptor<E: EngineBLS> {
+	/// The (non-zero) evaluation point of the share
+	pub index: u8,
+	/// The IBE encrypted share
+	pub header: IBECiphertext<E>,
+}
+
+/// The header of a sharded ciphertext
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ShardedHeader<E: EngineBLS> {
+	/// The number of shares needed to recover the key
+	pub threshold: u8,
+	/// The encrypted shares, in the order of the targets
+	pub shares: Vec<ShareDescriptor<E>>,
+}
+
+/// A ciphertext whose key is shared across several targets
+pub type ShardedCiphertext<E> = Envelope<ShardedHeader<E>>;
+
+/// Encapsulation of a key to k-of-n beacon targets
+struct Sharded<E: EngineBLS>(PhantomData<E>);
+
+impl<E: EngineBLS> WitnessScheme for Sharded<E> {
+	type PublicParams = ();
+	type Statement = (Vec<ShareTarget<E>>, u8);
+	type Witness = Vec<(u8, E::SignatureGroup)>;
+	type Header = ShardedHeader<E>;
+
+	fn encapsulate<R: Rng + CryptoRng>(
+		_: &(),
+		statement: &Self::Statement,
+		key: OpaqueSecretKey,
+		rng: &mut R,
+	) -> Result<Self::Header, Error> {
+		let (targets, threshold) = statement;
+		if *threshold == 0 ||
+			targets.len() > u8::MAX as usize ||
+			*threshold as usize > targets.len()
+		{
+			return Err(Error::InvalidThreshold);
+		}
+
+		// one random polynomial of degree threshold - 1 per byte of the key
+		let mut coefficients = Zeroizing::new(Vec::with_capacity(*threshold as usize));
+		coefficients.push(key);
+		for _ in 1..*threshold {
+			let mut c = [0u8; 32];
+			rng.fill_bytes(&mut c);
+			coefficients.push(c);
+		}
+
+		let mut shares = Vec::with_capacity(targets.len());
+		for (i, target) in targets.iter().enumerate() {
+			let index = i as u8 + 1;
+			let mut share = [0u8; 32];
+			for (b, byte) in share.iter_mut().enumerate() {
+				// Horner evaluation at x = index
+				*byte = coefficients.iter().rev().fold(0, |acc, c| gf_mul(acc, index) ^ c[b]);
+			}
+			let header = BeaconSignature::<E>::encapsulate(&target.p_pub, &target.id, share, rng);
+			share.zeroize();
+			shares.push(ShareDescriptor { index, header: header? });
+		}
+
+		Ok(ShardedHeader { threshold: *threshold, shares })
+	}
+
+	fn decapsulate(
+		header: &Self::Header,
+		witness: &Self::Witness,
+	) -> Result<OpaqueSecretKey, Error> {
+		let mut points: Vec<(u8, OpaqueSecretKey)> = Vec::new();
+		for (index, signature) in witness {
+			if points.len() == header.threshold as usize {
+				break;
+			}
+			if *index == 0 || points.iter().any(|(x, _)| x == index) {
+				continue;
+			}
+			// a signature for an unknown share or one that does not decrypt its
+			// share is skipped, so that other valid shares can still reach the
+			// threshold
+			let share = header
+				.shares
+				.iter()
+				.find(|d| d.index == *index)
+				.and_then(|d| BeaconSignature::<E>::decapsulate(&d.header, signature).ok());
+			if let Some(share) = share {
+				points.push((*index, share));
+			}
+		}
+		if header.threshold == 0 || points.len() < header.threshold as usize {
+			for (_, share) in points.iter_mut() {
+				share.zeroize();
+			}
+			return Err(Error::NotEnoughShares);
+		}
+
+		// Lagrange interpolation at x = 0
+		let mut key = [0u8; 32];
+		for (i, (xi, yi)) in points.iter().enumerate() {
+			let mut basis = 1u8;
+			for (j, (xj, _)) in points.iter().enumerate() {
+				if i != j {
+					basis = gf_mul(basis, gf_mul(*xj, gf_inv(xi ^ xj)));
+				}
+			}
+			for (k, y) in key.iter_mut().zip(yi.iter()) {
+				*k ^= gf_mul(basis, *y);
+			}
+		}
+		for (_, share) in points.iter_mut() {
+			share.zeroize();
+		}
+		Ok(key)
+	}
+}
+
+/// Encrypt a message so that any `threshold` of the targets can decrypt it
+///
+/// * `targets`: The beacons and identities to encrypt shares of the key for
+/// * `threshold`: The number of targets needed to decrypt
+/// * `secret_key`: the ephemeral key used to encrypt the message
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn tle_sharded<E, S, R>(
+	targets: &[ShareTarget<E>],
+	threshold: u8,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	rng: R,
+) -> Result<ShardedCiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	seal::<Sharded<E>, S, R>(&(), &(targets.to_vec(), threshold), secret_key, message, rng)
+}
+
+/// Decrypt a sharded ciphertext
+///
+/// Signatures that do not decrypt their share are skipped, and decryption only
+/// fails when fewer than `threshold` shares can be decrypted.
+///
+/// * `ciphertext`: A ciphertext output by [`tle_sharded`]
+/// * `signatures`: Signatures for at least `threshold` shares, each paired with
+///   the index of the share it decrypts
+pub fn tld_sharded<E, S>(
+	ciphertext: ShardedCiphertext<E>,
+	signatures: &[(u8, E::SignatureGroup)],
+) -> Result<Vec<u8>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	open::<Sharded<E>, S>(ciphertext, &signatures.to_vec())
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1
+///
+/// Runs in constant time: every bit of `b` costs the same masked operations.
+pub(crate) fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+	let mut product = 0u8;
+	for _ in 0..8 {
+		product ^= a & (b & 1).wrapping_neg();
+		let carry = (a >> 7).wrapping_neg();
+		a = (a << 1) ^ (0x1b & carry);
+		b >>= 1;
+	}
+	product
+}
+
+/// Inversion in GF(2^8) as a^254, in constant time
+pub(crate) fn gf_inv(a: u8) -> u8 {
+	let mut result = 1u8;
+	for _ in 0..254 {
+		result = gf_mul(result, a);
+	}
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS381};
+	use ark_ec::PrimeGroup;
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+	type Signature = <TinyBLS381 as EngineBLS>::SignatureGroup;
+
+	fn setup(n: u64) -> (Vec<ShareTarget<TinyBLS381>>, Vec<(u8, Signature)>) {
+		let mut targets = Vec::new();
+		let mut signatures = Vec::new();
+		for i in 0..n {
+			// a distinct beacon and round per share
+			let msk = Scalar::rand(&mut OsRng);
+			let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * msk;
+			let id = Identity::new(b"", &(1000 + i).to_be_bytes());
+			signatures.push((i as u8 + 1, id.extract::<TinyBLS381>(msk).0));
+			targets.push(ShareTarget { p_pub, id });
+		}
+		(targets, signatures)
+	}
+
+	#[test]
+	pub fn sharded_decrypts_with_any_threshold_subset() {
+		let (targets, signatures) = setup(5);
+		let ct = tle_sharded::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			&targets,
+			3,
+			[4; 32],
+			b"three of five",
+			OsRng,
+		)
+		.unwrap();
+		let mut bytes = Vec::new();
+		ct.serialize_compressed(&mut bytes).unwrap();
+
+		for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+			let ct = ShardedCiphertext::<TinyBLS381>::deserialize_compressed(&bytes[..]).unwrap();
+			let sigs: Vec<_> = subset.iter().map(|i| signatures[*i]).collect();
+			let plaintext =
+				tld_sharded::<TinyBLS381, AESGCMBlockCipherProvider>(ct, &sigs).unwrap();
+			assert_eq!(plaintext, b"three of five".to_vec());
+		}
+	}
+
+	#[test]
+	pub fn sharded_fails_below_threshold() {
+		let (targets, signatures) = setup(5);
+		let ct = tle_sharded::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			&targets,
+			3,
+			[4; 32],
+			b"three of five",
+			OsRng,
+		)
+		.unwrap();
+
+		let result = tld_sharded::<TinyBLS381, AESGCMBlockCipherProvider>(ct, &signatures[..2]);
+		assert_eq!(result, Err(Error::NotEnoughShares));
+	}
+
+	#[test]
+	pub fn sharded_skips_invalid_shares() {
+		let (targets, mut signatures) = setup(5);
+		let ct = tle_sharded::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			&targets,
+			3,
+			[4; 32],
+			b"three of five",
+			OsRng,
+		)
+		.unwrap();
+
+		// a wrong signature for share 1 and a signature for an unknown share
+		signatures[0].1 = signatures[1].1;
+		signatures.insert(0, (9, signatures[2].1));
+		let plaintext =
+			tld_sharded::<TinyBLS381, AESGCMBlockCipherProvider>(ct, &signatures).unwrap();
+		assert_eq!(plaintext, b"three of five".to_vec());
+	}
+
+	#[test]
+	pub fn sharded_rejects_invalid_threshold() {
+		let (targets, _) = setup(2);
+		for threshold in [0, 3] {
+			let result = tle_sharded::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+				&targets, threshold, [4; 32], b"", OsRng,
+			);
+			assert!(matches!(result, Err(Error::InvalidThreshold)));
+		}
+	}
+
+	#[test]
+	pub fn gf_inverse_works() {
+		for a in 1..=255u8 {
+			assert_eq!(gf_mul(a, gf_inv(a)), 1);
+		}
+		// {57} * {83} = {c1} (FIPS 197, 4.2)
+		assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+	}
+}
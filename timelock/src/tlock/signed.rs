@@ -0,0 +1,251 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Sender-authenticated timelock encryption.
+//!
+//! The sender signs the plaintext with a BLS key over the same engine used for
+//! the beacon, and the signature and the sender's public key are placed inside
+//! the encrypted (and authenticated) body. After decryption the signature is
+//! verified and the sender key is returned along with the plaintext.
+//!
+//! The signature covers the IBE header as well as the plaintext. The header
+//! depends on the beacon public key and the identity, so a recipient who
+//! decrypts the payload cannot lock it to another round or beacon and still
+//! have it verify as from the sender. Sender keys are BLS keys of the engine;
+//! Ed25519 sender keys are not supported.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::{Ciphertext as IBECiphertext, Identity},
+	tlock::{
+		witness::{envelope, open_body, BeaconSignature, WitnessScheme},
+		Error, OpaqueSecretKey, TLECiphertext,
+	},
+	Message,
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// The context used when hashing the plaintext to the signature curve
+const SENDER_SIGNATURE_CONTEXT: &[u8] = b"TIMELOCK_SENDER_SIGNATURE_V2";
+
+/// The payload encrypted in the body of a signed ciphertext
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+struct SignedPayload<E: EngineBLS> {
+	/// The sender's public key
+	sender: E::PublicKeyGroup,
+	/// A BLS signature over the IBE header and the plaintext
+	signature: E::SignatureGroup,
+	/// The plaintext
+	message: Vec<u8>,
+}
+
+/// Sign a message with the sender's secret key and timelock encrypt it
+///
+/// * `p_pub`: the public key commitment for the IBE system
+/// * `secret_key`: the ephemeral key used to encrypt the message
+/// * `message`: The message to sign and encrypt
+/// * `id`: The identity to encrypt for
+/// * `sender_secret`: The sender's BLS secret key
+/// * `rng`: A CSPRNG
+pub fn tle_signed<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	id: Identity,
+	sender_secret: E::Scalar,
+	mut rng: R,
+) -> Result<TLECiphertext<E>, Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	// the header is made first, so that the signature can cover it
+	let header = BeaconSignature::<E>::encapsulate(&p_pub, &id, secret_key, &mut rng)?;
+	let payload = SignedPayload::<E> {
+		sender: E::PublicKeyGroup::generator() * sender_secret,
+		signature: signed_message::<E>(&header, message)
+			.map_err(|_| Error::MessageEncryptionError)?
+			.hash_to_signature_curve::<E>() *
+			sender_secret,
+		message: message.to_vec(),
+	};
+	let mut payload_bytes = Vec::new();
+	payload
+		.serialize_compressed(&mut payload_bytes)
+		.map_err(|_| Error::MessageEncryptionError)?;
+
+	envelope::<_, S, _>(header, secret_key, &payload_bytes, rng)
+}
+
+/// Decrypt a signed ciphertext and verify the sender's signature
+///
+/// Returns the plaintext and the public key of the sender that signed it.
+///
+/// * `ciphertext`: A ciphertext output by [`tle_signed`]
+/// * `signature`: A BLS signature that allows decryption of the ciphertext
+pub fn tld_signed<E, S>(
+	ciphertext: TLECiphertext<E>,
+	signature: E::SignatureGroup,
+) -> Result<(Vec<u8>, E::PublicKeyGroup), Error>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	// as tld, keeping the header to verify the signature against
+	let mut secret_bytes = BeaconSignature::<E>::decapsulate(&ciphertext.header, &signature)?;
+	let payload_bytes = open_body::<S>(&ciphertext.body, secret_bytes);
+	secret_bytes.zeroize();
+	let payload_bytes = payload_bytes?;
+	let payload = SignedPayload::<E>::deserialize_compressed(&payload_bytes[..])
+		.map_err(|_| Error::DeserializationError)?;
+
+	// e(sender, H(header, m)) == e(P, signature)
+	let hashed = signed_message::<E>(&ciphertext.header, &payload.message)
+		.map_err(|_| Error::DeserializationError)?
+		.hash_to_signature_curve::<E>();
+	if E::pairing(payload.sender, hashed) !=
+		E::pairing(E::PublicKeyGroup::generator(), payload.signature)
+	{
+		return Err(Error::InvalidSenderSignature);
+	}
+
+	Ok((payload.message, payload.sender))
+}
+
+/// The message the sender signs: the serialized IBE header followed by the
+/// plaintext
+///
+/// The compressed header has a fixed length, so the two cannot be confused.
+fn signed_message<E: EngineBLS>(
+	header: &IBECiphertext<E>,
+	message: &[u8],
+) -> Result<Message, SerializationError> {
+	let mut bytes = Vec::new();
+	header.serialize_compressed(&mut bytes)?;
+	bytes.extend_from_slice(message);
+	Ok(Message::new(SENDER_SIGNATURE_CONTEXT, &bytes))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tld, tle},
+	};
+	use ark_ff::UniformRand;
+	use ark_std::rand::rngs::OsRng;
+
+	type Scalar = <TinyBLS381 as EngineBLS>::Scalar;
+	type PublicKey = <TinyBLS381 as EngineBLS>::PublicKeyGroup;
+
+	#[test]
+	pub fn signed_tlock_returns_verified_sender() {
+		let msk = Scalar::rand(&mut OsRng);
+		let p_pub = PublicKey::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let sender_sk = Scalar::rand(&mut OsRng);
+
+		let ct = tle_signed::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[5; 32],
+			b"signed, sealed, delivered",
+			id.clone(),
+			sender_sk,
+			OsRng,
+		)
+		.unwrap();
+
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let (plaintext, sender) =
+			tld_signed::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig).unwrap();
+		assert_eq!(plaintext, b"signed, sealed, delivered".to_vec());
+		assert_eq!(sender, PublicKey::generator() * sender_sk);
+	}
+
+	#[test]
+	pub fn signed_tlock_rejects_forged_signature() {
+		let msk = Scalar::rand(&mut OsRng);
+		let p_pub = PublicKey::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+
+		// claim to be someone else by pairing their key with our own signature
+		let payload = SignedPayload::<TinyBLS381> {
+			sender: PublicKey::generator() * Scalar::rand(&mut OsRng),
+			signature: Message::new(SENDER_SIGNATURE_CONTEXT, b"forged")
+				.hash_to_signature_curve::<TinyBLS381>() *
+				Scalar::rand(&mut OsRng),
+			message: b"forged".to_vec(),
+		};
+		let mut payload_bytes = Vec::new();
+		payload.serialize_compressed(&mut payload_bytes).unwrap();
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[5; 32],
+			&payload_bytes,
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		let sig = id.extract::<TinyBLS381>(msk).0;
+		let result = tld_signed::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig);
+		assert_eq!(result, Err(Error::InvalidSenderSignature));
+	}
+
+	#[test]
+	pub fn signed_tlock_rejects_payload_relocked_to_another_round() {
+		let msk = Scalar::rand(&mut OsRng);
+		let p_pub = PublicKey::generator() * msk;
+		let id = Identity::new(b"", &1000u64.to_be_bytes());
+		let ct = tle_signed::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[5; 32],
+			b"only for round 1000",
+			id.clone(),
+			Scalar::rand(&mut OsRng),
+			OsRng,
+		)
+		.unwrap();
+
+		// a recipient of round 1000 locks the signed payload to round 2000
+		let payload_bytes =
+			tld::<TinyBLS381, AESGCMBlockCipherProvider>(ct, id.extract::<TinyBLS381>(msk).0)
+				.unwrap();
+		let other = Identity::new(b"", &2000u64.to_be_bytes());
+		let ct = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			p_pub,
+			[6; 32],
+			&payload_bytes,
+			other.clone(),
+			OsRng,
+		)
+		.unwrap();
+
+		let sig = other.extract::<TinyBLS381>(msk).0;
+		let result = tld_signed::<TinyBLS381, AESGCMBlockCipherProvider>(ct, sig);
+		assert_eq!(result, Err(Error::InvalidSenderSignature));
+	}
+}
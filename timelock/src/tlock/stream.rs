@@ -0,0 +1,516 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Streaming encryption with `std::io`.
+//!
+//! In the chunked mode a message is split into chunks of [`CHUNK_SIZE`]
+//! bytes, each encrypted with AES-256-GCM under a payload key. As in age's
+//! STREAM, the nonce of a chunk is an 11 byte big-endian counter followed by
+//! a flag marking the last chunk, so chunks cannot be reordered, dropped or
+//! truncated unnoticed.
+//!
+//! [`TleWriter`] encrypts everything written to it and [`TldReader`] decrypts
+//! everything read through it, so both compose with [`std::io::copy`],
+//! archive builders and compression streams while holding at most two chunks
+//! in memory. [`TldWriter`] decrypts everything written to it, for callers
+//! that are handed the ciphertext piece by piece. [`TleWriter::new`] starts the
+//! stream with the payload key timelock encrypted for a round, and
+//! [`TldReader::new`] recovers it with the round's signature; the `with_key`
+//! constructors leave the key to the caller.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::Identity,
+	tlock::{tld, tle, TLECiphertext},
+};
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::{CryptoRng, Rng};
+use std::{
+	io::{self, Read, Write},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// The size of a plaintext chunk
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The size of the authentication tag of a chunk
+pub const TAG_SIZE: usize = 16;
+
+/// The size of an encrypted chunk
+pub(crate) const SEALED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_SIZE;
+
+/// The length of the message of a chunked payload, if `len` is a valid payload
+/// length
+///
+/// * `len`: The length of the payload
+pub fn plaintext_len(len: u64) -> Option<u64> {
+	let chunks = len.div_ceil(SEALED_CHUNK_SIZE as u64);
+	let last = len.checked_sub(chunks.checked_sub(1)? * SEALED_CHUNK_SIZE as u64)?;
+	(last >= TAG_SIZE as u64).then(|| len - chunks * TAG_SIZE as u64)
+}
+
+/// The nonce of a chunk: an 11 byte big-endian counter and a last chunk flag
+fn nonce(counter: u64, last: bool) -> [u8; 12] {
+	let mut nonce = [0u8; 12];
+	nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+	nonce[11] = last as u8;
+	nonce
+}
+
+pub(crate) fn invalid_data(message: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Encrypt a chunk
+pub(crate) fn seal_chunk(cipher: &Aes256Gcm, counter: u64, chunk: &[u8], last: bool) -> Vec<u8> {
+	cipher
+		.encrypt(&nonce(counter, last).into(), chunk)
+		.expect("Encryption of a chunk is infallible.")
+}
+
+/// Decrypt a chunk
+pub(crate) fn open_chunk(
+	cipher: &Aes256Gcm,
+	counter: u64,
+	sealed: &[u8],
+	last: bool,
+) -> io::Result<Vec<u8>> {
+	let plaintext = cipher
+		.decrypt(&nonce(counter, last).into(), sealed)
+		.map_err(|_| invalid_data("a chunk failed to authenticate"))?;
+	// only the payload of an empty message may end with an empty chunk
+	if plaintext.is_empty() && counter > 0 {
+		return Err(invalid_data("the payload ends with an empty chunk"));
+	}
+	Ok(plaintext)
+}
+
+/// A fresh payload key and its timelock encryption for an identity
+pub(crate) fn lock_payload_key<E, S, R>(
+	p_pub: E::PublicKeyGroup,
+	id: Identity,
+	mut rng: R,
+) -> io::Result<([u8; 32], Vec<u8>)>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let mut secret_key = [0u8; 32];
+	let mut payload_key = [0u8; 32];
+	rng.fill_bytes(&mut secret_key);
+	rng.fill_bytes(&mut payload_key);
+	let header = tle::<E, S, &mut R>(p_pub, secret_key, &payload_key, id, &mut rng);
+	secret_key.zeroize();
+	let header = header.map_err(|_| invalid_data("the payload key could not be encrypted"))?;
+	let mut bytes = Vec::new();
+	header
+		.serialize_compressed(&mut bytes)
+		.expect("The header must be serializable.");
+	Ok((payload_key, bytes))
+}
+
+/// Decrypt the payload key of a stream with the signature of its round
+pub(crate) fn unlock_payload_key<E, S>(
+	header: TLECiphertext<E>,
+	signature: E::SignatureGroup,
+) -> io::Result<[u8; 32]>
+where
+	E: EngineBLS,
+	S: BlockCipherProvider<32>,
+{
+	let mut key = tld::<E, S>(header, signature)
+		.map_err(|_| invalid_data("the payload key could not be decrypted"))?;
+	let payload_key = key
+		.as_slice()
+		.try_into()
+		.map_err(|_| invalid_data("the payload key is malformed"));
+	key.zeroize();
+	payload_key
+}
+
+/// Encrypts everything written to it into a chunked payload
+///
+/// The last chunk is only known once writing ends, so [`TleWriter::finish`]
+/// must be called to complete the payload; a dropped writer leaves a payload
+/// that fails to decrypt.
+pub struct TleWriter<W: Write> {
+	inner: W,
+	cipher: Aes256Gcm,
+	counter: u64,
+	buffer: Vec<u8>,
+}
+
+impl<W: Write> TleWriter<W> {
+	/// A writer that timelock encrypts a fresh payload key for an identity and
+	/// writes it ahead of the payload
+	///
+	/// * `p_pub`: The public key of the beacon
+	/// * `id`: The identity (e.g. round) to encrypt for
+	/// * `inner`: Where to write the ciphertext
+	/// * `rng`: A CSPRNG
+	pub fn new<E, S, R>(
+		p_pub: E::PublicKeyGroup,
+		id: Identity,
+		mut inner: W,
+		rng: R,
+	) -> io::Result<Self>
+	where
+		E: EngineBLS,
+		S: BlockCipherProvider<32>,
+		R: Rng + CryptoRng,
+	{
+		let (payload_key, header) = lock_payload_key::<E, S, R>(p_pub, id, rng)?;
+		inner.write_all(&header)?;
+		Ok(Self::with_key(&payload_key, inner))
+	}
+
+	/// A writer that encrypts with a payload key managed by the caller
+	///
+	/// * `key`: The payload key
+	/// * `inner`: Where to write the encrypted chunks
+	pub fn with_key(key: &[u8; 32], inner: W) -> Self {
+		Self {
+			inner,
+			cipher: Aes256Gcm::new(key.into()),
+			counter: 0,
+			buffer: Vec::with_capacity(CHUNK_SIZE),
+		}
+	}
+
+	/// Encrypt the buffered chunk
+	fn seal(&mut self, last: bool) -> io::Result<()> {
+		let chunk = seal_chunk(&self.cipher, self.counter, &self.buffer, last);
+		self.inner.write_all(&chunk)?;
+		self.buffer.clear();
+		self.counter += 1;
+		Ok(())
+	}
+
+	/// Write the last chunk, returning the inner writer
+	pub fn finish(mut self) -> io::Result<W> {
+		self.seal(true)?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+}
+
+impl<W: Write> Write for TleWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if buf.is_empty() {
+			return Ok(0);
+		}
+		// a full chunk is only sealed once more data shows it is not the last
+		if self.buffer.len() == CHUNK_SIZE {
+			self.seal(false)?;
+		}
+		let n = buf.len().min(CHUNK_SIZE - self.buffer.len());
+		self.buffer.extend_from_slice(&buf[..n]);
+		Ok(n)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+/// Decrypts a chunked payload as it is read
+///
+/// Chunks are returned as soon as they are authenticated, so a failure part
+/// way through follows a prefix of the message. Authentication failures are
+/// reported as [`io::ErrorKind::InvalidData`].
+pub struct TldReader<R: Read> {
+	inner: R,
+	cipher: Aes256Gcm,
+	counter: u64,
+	/// Sealed bytes read ahead, up to a chunk and one byte
+	sealed: Vec<u8>,
+	/// The plaintext of the current chunk
+	plaintext: Vec<u8>,
+	position: usize,
+	done: bool,
+}
+
+impl<R: Read> TldReader<R> {
+	/// A reader that decrypts the payload key written by [`TleWriter::new`]
+	/// with the signature of its round
+	///
+	/// * `inner`: The ciphertext
+	/// * `signature`: The signature of the round the ciphertext is locked to
+	pub fn new<E, S>(mut inner: R, signature: E::SignatureGroup) -> io::Result<Self>
+	where
+		E: EngineBLS,
+		S: BlockCipherProvider<32>,
+	{
+		let header = TLECiphertext::<E>::deserialize_compressed(&mut inner)
+			.map_err(|_| invalid_data("the header is malformed"))?;
+		let payload_key = unlock_payload_key::<E, S>(header, signature)?;
+		Ok(Self::with_key(&payload_key, inner))
+	}
+
+	/// A reader that decrypts with a payload key managed by the caller
+	///
+	/// * `key`: The payload key
+	/// * `inner`: The encrypted chunks
+	pub fn with_key(key: &[u8; 32], inner: R) -> Self {
+		Self {
+			inner,
+			cipher: Aes256Gcm::new(key.into()),
+			counter: 0,
+			sealed: Vec::with_capacity(SEALED_CHUNK_SIZE + 1),
+			plaintext: Vec::new(),
+			position: 0,
+			done: false,
+		}
+	}
+
+	/// Decrypt the next chunk, looking one byte ahead to find the last
+	fn open(&mut self) -> io::Result<()> {
+		let mut filled = self.sealed.len();
+		self.sealed.resize(SEALED_CHUNK_SIZE + 1, 0);
+		while filled < self.sealed.len() {
+			match self.inner.read(&mut self.sealed[filled..]) {
+				Ok(0) => break,
+				Ok(n) => filled += n,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+				Err(e) => {
+					self.sealed.truncate(filled);
+					return Err(e);
+				},
+			}
+		}
+		self.sealed.truncate(filled);
+
+		let last = filled <= SEALED_CHUNK_SIZE;
+		let len = filled.min(SEALED_CHUNK_SIZE);
+		let plaintext = open_chunk(&self.cipher, self.counter, &self.sealed[..len], last)?;
+		self.sealed.drain(..len);
+		self.plaintext = plaintext;
+		self.position = 0;
+		self.counter += 1;
+		self.done = last;
+		Ok(())
+	}
+
+	/// The inner reader, positioned after the payload once it is fully read
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: Read> Read for TldReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		while self.position == self.plaintext.len() {
+			if self.done {
+				return Ok(0);
+			}
+			self.open()?;
+		}
+		let n = buf.len().min(self.plaintext.len() - self.position);
+		buf[..n].copy_from_slice(&self.plaintext[self.position..self.position + n]);
+		self.position += n;
+		Ok(n)
+	}
+}
+
+/// Decrypts a chunked payload written to it into an inner writer
+///
+/// Chunks are written out as soon as they are authenticated, so a failure
+/// part way through follows a prefix of the message. The last chunk is only
+/// known once writing ends, so [`TldWriter::finish`] must be called to
+/// authenticate it; the payload is complete only if it succeeds.
+pub struct TldWriter<W: Write> {
+	inner: W,
+	cipher: Aes256Gcm,
+	counter: u64,
+	/// Sealed bytes written ahead, up to a chunk and one byte
+	sealed: Vec<u8>,
+}
+
+impl<W: Write> TldWriter<W> {
+	/// A writer that decrypts the payload following a header written by
+	/// [`TleWriter::new`], with the signature of its round
+	///
+	/// * `header`: The header of the stream
+	/// * `signature`: The signature of the round the stream is locked to
+	/// * `inner`: Where to write the message
+	pub fn new<E, S>(
+		header: TLECiphertext<E>,
+		signature: E::SignatureGroup,
+		inner: W,
+	) -> io::Result<Self>
+	where
+		E: EngineBLS,
+		S: BlockCipherProvider<32>,
+	{
+		let payload_key = unlock_payload_key::<E, S>(header, signature)?;
+		Ok(Self::with_key(&payload_key, inner))
+	}
+
+	/// A writer that decrypts with a payload key managed by the caller
+	///
+	/// * `key`: The payload key
+	/// * `inner`: Where to write the message
+	pub fn with_key(key: &[u8; 32], inner: W) -> Self {
+		Self {
+			inner,
+			cipher: Aes256Gcm::new(key.into()),
+			counter: 0,
+			sealed: Vec::with_capacity(SEALED_CHUNK_SIZE + 1),
+		}
+	}
+
+	/// Decrypt the first buffered chunk
+	fn open(&mut self, last: bool) -> io::Result<()> {
+		let len = self.sealed.len().min(SEALED_CHUNK_SIZE);
+		let mut plaintext = open_chunk(&self.cipher, self.counter, &self.sealed[..len], last)?;
+		let written = self.inner.write_all(&plaintext);
+		plaintext.zeroize();
+		written?;
+		self.sealed.drain(..len);
+		self.counter += 1;
+		Ok(())
+	}
+
+	/// Authenticate and write the last chunk, returning the inner writer
+	pub fn finish(mut self) -> io::Result<W> {
+		self.open(true)?;
+		self.inner.flush()?;
+		Ok(self.inner)
+	}
+}
+
+impl<W: Write> Write for TldWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		// a full chunk is only opened once more data shows it is not the last
+		let n = buf.len().min(SEALED_CHUNK_SIZE + 1 - self.sealed.len());
+		self.sealed.extend_from_slice(&buf[..n]);
+		if self.sealed.len() > SEALED_CHUNK_SIZE {
+			self.open(false)?;
+		}
+		Ok(n)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider, engines::drand::TinyBLS377, tlock::BeaconConfig,
+	};
+	use ark_ec::PrimeGroup;
+	use ark_std::{rand::rngs::OsRng, vec, UniformRand};
+
+	fn seal(key: &[u8; 32], message: &[u8]) -> Vec<u8> {
+		let mut writer = TleWriter::with_key(key, Vec::new());
+		io::copy(&mut &message[..], &mut writer).unwrap();
+		writer.finish().unwrap()
+	}
+
+	fn open(key: &[u8; 32], ciphertext: &[u8]) -> io::Result<Vec<u8>> {
+		let mut plaintext = Vec::new();
+		TldReader::with_key(key, ciphertext).read_to_end(&mut plaintext)?;
+		Ok(plaintext)
+	}
+
+	#[test]
+	fn payloads_round_trip_across_chunk_boundaries() {
+		let key = [7u8; 32];
+		for len in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE + 1, 3 * CHUNK_SIZE] {
+			let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+			let ciphertext = seal(&key, &message);
+			assert_eq!(ciphertext.len(), len + len.div_ceil(CHUNK_SIZE).max(1) * TAG_SIZE);
+			assert_eq!(plaintext_len(ciphertext.len() as u64), Some(len as u64));
+			assert_eq!(open(&key, &ciphertext).unwrap(), message);
+		}
+	}
+
+	#[test]
+	fn payloads_written_in_pieces_are_decrypted() {
+		let key = [7u8; 32];
+		let message: Vec<u8> = (0..2 * CHUNK_SIZE + 5).map(|i| i as u8).collect();
+		let ciphertext = seal(&key, &message);
+		for piece in [1000, SEALED_CHUNK_SIZE, ciphertext.len()] {
+			let mut writer = TldWriter::with_key(&key, Vec::new());
+			for chunk in ciphertext.chunks(piece) {
+				writer.write_all(chunk).unwrap();
+			}
+			assert_eq!(writer.finish().unwrap(), message);
+		}
+
+		let mut writer = TldWriter::with_key(&key, Vec::new());
+		writer.write_all(&ciphertext[..SEALED_CHUNK_SIZE]).unwrap();
+		assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+		let mut writer = TldWriter::with_key(&key, Vec::new());
+		writer.write_all(&ciphertext[..ciphertext.len() - 1]).unwrap();
+		assert!(writer.finish().is_err());
+	}
+
+	#[test]
+	fn truncated_or_reordered_payloads_are_rejected() {
+		let key = [7u8; 32];
+		let ciphertext = seal(&key, &vec![1u8; 2 * CHUNK_SIZE]);
+		let decrypts = |ciphertext: &[u8]| open(&key, ciphertext).is_ok();
+
+		assert!(decrypts(&ciphertext));
+		assert!(!decrypts(&ciphertext[..SEALED_CHUNK_SIZE]));
+		let (first, second) = ciphertext.split_at(SEALED_CHUNK_SIZE);
+		assert!(!decrypts(&[second, first].concat()));
+		assert!(!decrypts(&[&ciphertext[..], &[0u8; TAG_SIZE]].concat()));
+		let error = open(&key, &[]).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(plaintext_len(0), None);
+		assert_eq!(plaintext_len((SEALED_CHUNK_SIZE + 1) as u64), None);
+	}
+
+	#[test]
+	fn streams_are_timelocked_to_a_round() {
+		let secret = <TinyBLS377 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS377 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS377>::identity(10);
+		let signature = id.extract::<TinyBLS377>(secret).0;
+
+		let message = vec![3u8; CHUNK_SIZE + 10];
+		let mut writer = TleWriter::new::<TinyBLS377, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			id,
+			Vec::new(),
+			OsRng,
+		)
+		.unwrap();
+		io::copy(&mut &message[..], &mut writer).unwrap();
+		let ciphertext = writer.finish().unwrap();
+
+		let mut reader =
+			TldReader::new::<TinyBLS377, AESGCMBlockCipherProvider>(&ciphertext[..], signature)
+				.unwrap();
+		let mut plaintext = Vec::new();
+		reader.read_to_end(&mut plaintext).unwrap();
+		assert_eq!(plaintext, message);
+
+		let other = BeaconConfig::<TinyBLS377>::identity(11).extract::<TinyBLS377>(secret).0;
+		let result =
+			TldReader::new::<TinyBLS377, AESGCMBlockCipherProvider>(&ciphertext[..], other);
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,181 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Strict deserialization of untrusted points.
+//!
+//! `deserialize_compressed` rejects points off the curve or outside the prime
+//! order subgroup as a generic deserialization error, and accepts the
+//! identity. The identity is never an honest beacon public key, signature or
+//! ciphertext header: it makes the pairings it enters trivial. The functions
+//! here reject it as well, and report every bad point (the identity, or a
+//! point of low order) as [`Error::InvalidPoint`], so callers can tell a bad
+//! point from a malformed encoding.
+
+use crate::{
+	engines::EngineBLS,
+	tlock::{view::TLECiphertextRef, Error, TLECiphertext},
+};
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+
+/// Check that a point is in the prime order subgroup and is not the identity
+///
+/// * `point`: The point
+pub fn validate<G: CurveGroup>(point: G) -> Result<G, Error> {
+	if point.is_zero() || point.check().is_err() {
+		return Err(Error::InvalidPoint);
+	}
+	Ok(point)
+}
+
+/// Deserialize a compressed point, then [`validate`] it
+fn point<G: CurveGroup>(bytes: &[u8]) -> Result<G, Error> {
+	let point = G::deserialize_with_mode(bytes, Compress::Yes, Validate::No)
+		.map_err(|_| Error::DeserializationError)?;
+	validate(point)
+}
+
+/// Deserialize a beacon public key
+///
+/// * `bytes`: The compressed public key
+pub fn public_key<E: EngineBLS>(bytes: &[u8]) -> Result<E::PublicKeyGroup, Error> {
+	point(bytes)
+}
+
+/// Deserialize a beacon signature
+///
+/// * `bytes`: The compressed signature
+pub fn signature<E: EngineBLS>(bytes: &[u8]) -> Result<E::SignatureGroup, Error> {
+	point(bytes)
+}
+
+/// Deserialize a ciphertext, rejecting trailing bytes
+///
+/// * `bytes`: The compressed ciphertext
+pub fn ciphertext<E: EngineBLS>(bytes: &[u8]) -> Result<TLECiphertext<E>, Error> {
+	let mut reader = bytes;
+	let ciphertext =
+		TLECiphertext::<E>::deserialize_with_mode(&mut reader, Compress::Yes, Validate::No)
+			.map_err(|_| Error::DeserializationError)?;
+	if !reader.is_empty() {
+		return Err(Error::DeserializationError);
+	}
+	validate(ciphertext.header.u)?;
+	Ok(ciphertext)
+}
+
+/// Parse a view of a ciphertext
+///
+/// * `bytes`: The compressed ciphertext
+pub fn ciphertext_ref<E: EngineBLS>(bytes: &[u8]) -> Result<TLECiphertextRef<'_, E>, Error> {
+	let view = TLECiphertextRef::<E>::parse_with_mode(bytes, Validate::No)?;
+	validate(view.header.u)?;
+	Ok(view)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tle, BeaconConfig},
+	};
+	use ark_bls12_381::{Fq, G1Affine};
+	use ark_ec::PrimeGroup;
+	use ark_ff::Zero;
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{
+		rand::{rngs::StdRng, SeedableRng},
+		vec::Vec,
+		UniformRand,
+	};
+
+	fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		value.serialize_compressed(&mut bytes).unwrap();
+		bytes
+	}
+
+	/// A point on G1 outside the prime order subgroup
+	fn low_order_point() -> G1Affine {
+		(1u64..)
+			.filter_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), false))
+			.find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+			.unwrap()
+	}
+
+	#[test]
+	fn strict_points_reject_the_identity_and_low_order_points() {
+		let mut rng = StdRng::seed_from_u64(1);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let sig = BeaconConfig::<TinyBLS381>::identity(1).extract::<TinyBLS381>(secret).0;
+
+		assert_eq!(public_key::<TinyBLS381>(&bytes(&p_pub)), Ok(p_pub));
+		assert_eq!(signature::<TinyBLS381>(&bytes(&sig)), Ok(sig));
+
+		let identity = bytes(&<TinyBLS381 as EngineBLS>::PublicKeyGroup::zero());
+		assert_eq!(public_key::<TinyBLS381>(&identity), Err(Error::InvalidPoint));
+		let identity = bytes(&<TinyBLS381 as EngineBLS>::SignatureGroup::zero());
+		assert_eq!(signature::<TinyBLS381>(&identity), Err(Error::InvalidPoint));
+		let low_order = bytes(&low_order_point());
+		assert_eq!(signature::<TinyBLS381>(&low_order), Err(Error::InvalidPoint));
+		assert!(<TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(&low_order[..])
+			.is_err());
+		assert_eq!(signature::<TinyBLS381>(&low_order[1..]), Err(Error::DeserializationError));
+	}
+
+	#[test]
+	fn strict_ciphertexts_reject_an_identity_header() {
+		let mut rng = StdRng::seed_from_u64(2);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1);
+		let mut locked =
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, [1; 32], b"strict", id, rng)
+				.unwrap();
+		let valid = bytes(&locked);
+		assert!(ciphertext_ref::<TinyBLS381>(&valid).is_ok());
+		assert_eq!(bytes(&ciphertext::<TinyBLS381>(&valid).unwrap()), valid);
+
+		locked.header.u = <TinyBLS381 as EngineBLS>::PublicKeyGroup::zero();
+		let invalid = bytes(&locked);
+		assert!(matches!(ciphertext::<TinyBLS381>(&invalid), Err(Error::InvalidPoint)));
+		assert!(matches!(ciphertext_ref::<TinyBLS381>(&invalid), Err(Error::InvalidPoint)));
+		assert!(matches!(
+			ciphertext_ref::<TinyBLS381>(&valid[..10]),
+			Err(Error::DeserializationError)
+		));
+	}
+
+	#[test]
+	fn strict_ciphertexts_reject_trailing_bytes() {
+		let mut rng = StdRng::seed_from_u64(3);
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut rng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1);
+		let locked =
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, [1; 32], b"strict", id, rng)
+				.unwrap();
+		let mut padded = bytes(&locked);
+		padded.push(0);
+
+		assert!(TLECiphertext::<TinyBLS381>::deserialize_compressed(&padded[..]).is_ok());
+		assert!(matches!(ciphertext::<TinyBLS381>(&padded), Err(Error::DeserializationError)));
+		assert!(matches!(ciphertext_ref::<TinyBLS381>(&padded), Err(Error::DeserializationError)));
+	}
+}
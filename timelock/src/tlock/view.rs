@@ -0,0 +1,214 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Borrowed views of serialized ciphertexts.
+//!
+//! Deserializing a [`TLECiphertext`] copies its body and cipher suite into new
+//! vectors. Services that only inspect or route ciphertexts (e.g. by the
+//! header, or the length of the body) can parse a [`TLECiphertextRef`]
+//! instead, which deserializes the header and borrows the rest from the
+//! serialized bytes.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::Ciphertext as IBECiphertext,
+	tlock::{
+		witness::{open_body, BeaconSignature, WitnessScheme},
+		Error, TLECiphertext,
+	},
+};
+use ark_ec::PrimeGroup;
+use ark_serialize::{
+	CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate,
+};
+use ark_std::vec::Vec;
+use zeroize::Zeroize;
+
+/// The size of a length prefix
+pub(crate) const LEN_SIZE: usize = 8;
+
+/// The size of the serialized IBE header of a ciphertext
+pub(crate) fn header_len<E: EngineBLS>() -> usize {
+	E::PublicKeyGroup::generator().compressed_size() + 64
+}
+
+/// Read `len` bytes at `*at`, advancing it
+pub(crate) fn take<'a>(input: &'a [u8], at: &mut usize, len: usize) -> Result<&'a [u8], Error> {
+	let end = at.checked_add(len).filter(|end| *end <= input.len());
+	let bytes = &input[*at..end.ok_or(Error::DeserializationError)?];
+	*at += len;
+	Ok(bytes)
+}
+
+/// Read a length prefix at `*at`, advancing it
+pub(crate) fn take_len(input: &[u8], at: &mut usize) -> Result<usize, Error> {
+	let bytes = take(input, at, LEN_SIZE)?;
+	let len = u64::from_le_bytes(bytes.try_into().expect("The prefix has 8 bytes."));
+	usize::try_from(len).map_err(|_| Error::DeserializationError)
+}
+
+/// The size of the chunks [`read_bytes`] reads
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// Deserialize a length prefixed byte vector
+///
+/// Unlike the implementation for `Vec<u8>`, which allocates the length read
+/// from the (untrusted) prefix upfront, the vector only grows with the bytes
+/// actually read.
+pub(crate) fn read_bytes<R: Read>(mut reader: R) -> Result<Vec<u8>, SerializationError> {
+	let len = u64::deserialize_compressed(&mut reader)?;
+	let mut remaining = usize::try_from(len).map_err(|_| SerializationError::NotEnoughSpace)?;
+	let mut bytes = Vec::with_capacity(remaining.min(READ_CHUNK_SIZE));
+	let mut chunk = [0u8; READ_CHUNK_SIZE];
+	while remaining > 0 {
+		let n = remaining.min(READ_CHUNK_SIZE);
+		reader.read_exact(&mut chunk[..n])?;
+		bytes.extend_from_slice(&chunk[..n]);
+		remaining -= n;
+	}
+	Ok(bytes)
+}
+
+/// A [`TLECiphertext`] borrowing its body and cipher suite from its
+/// serialization
+#[derive(Debug, Clone, PartialEq)]
+pub struct TLECiphertextRef<'a, E: EngineBLS> {
+	/// The header holds the encapsulated key
+	pub header: IBECiphertext<E>,
+	/// The body holds the message encrypted with a stream cipher
+	pub body: &'a [u8],
+	/// The cipher suite used (symmetric encryption scheme)
+	pub cipher_suite: &'a [u8],
+}
+
+impl<'a, E: EngineBLS> TLECiphertextRef<'a, E> {
+	/// Parse the compressed serialization of a [`TLECiphertext`]
+	///
+	/// The header is deserialized (and its point validated); the body and
+	/// cipher suite are not copied.
+	///
+	/// * `bytes`: The serialized ciphertext
+	pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+		Self::parse_with_mode(bytes, Validate::Yes)
+	}
+
+	/// Parse a view, validating the header point only if asked to
+	pub(crate) fn parse_with_mode(bytes: &'a [u8], validate: Validate) -> Result<Self, Error> {
+		let mut at = 0;
+		let header = take(bytes, &mut at, header_len::<E>())?;
+		let header = IBECiphertext::<E>::deserialize_with_mode(header, Compress::Yes, validate)
+			.map_err(|_| Error::DeserializationError)?;
+		let body_len = take_len(bytes, &mut at)?;
+		let body = take(bytes, &mut at, body_len)?;
+		let suite_len = take_len(bytes, &mut at)?;
+		let cipher_suite = take(bytes, &mut at, suite_len)?;
+		if at != bytes.len() {
+			return Err(Error::DeserializationError);
+		}
+		Ok(Self { header, body, cipher_suite })
+	}
+
+	/// Copy the view into an owned ciphertext
+	pub fn to_ciphertext(&self) -> TLECiphertext<E> {
+		TLECiphertext {
+			header: IBECiphertext { u: self.header.u, v: self.header.v, w: self.header.w },
+			body: self.body.to_vec(),
+			cipher_suite: self.cipher_suite.to_vec(),
+		}
+	}
+
+	/// Decrypt the ciphertext, as [`super::tld`] does
+	///
+	/// * `signature`: A BLS signature that allows decryption of the ciphertext
+	pub fn decrypt<S>(&self, signature: E::SignatureGroup) -> Result<Vec<u8>, Error>
+	where
+		S: BlockCipherProvider<32>,
+	{
+		let mut secret_key = BeaconSignature::<E>::decapsulate(&self.header, &signature)?;
+		let plaintext = open_body::<S>(self.body, secret_key);
+		secret_key.zeroize();
+		plaintext
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		block_ciphers::{AESGCMBlockCipherProvider, AESOutput},
+		engines::drand::TinyBLS381,
+		tlock::{tle, BeaconConfig},
+	};
+	use ark_std::{rand::rngs::OsRng, UniformRand};
+
+	#[test]
+	fn views_borrow_the_serialized_ciphertext() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let ciphertext = tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+			p_pub,
+			[5u8; 32],
+			b"routed, not copied",
+			id.clone(),
+			OsRng,
+		)
+		.unwrap();
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+
+		let view = TLECiphertextRef::<TinyBLS381>::parse(&bytes).unwrap();
+		assert_eq!(view.body, &ciphertext.body[..]);
+		assert_eq!(view.cipher_suite, b"AES_GCM_");
+		assert!(bytes.as_ptr_range().contains(&view.body.as_ptr()));
+		let mut copied = Vec::new();
+		view.to_ciphertext().serialize_compressed(&mut copied).unwrap();
+		assert_eq!(copied, bytes);
+
+		let signature = id.extract::<TinyBLS381>(secret).0;
+		let plaintext = view.decrypt::<AESGCMBlockCipherProvider>(signature);
+		assert_eq!(plaintext.as_deref(), Ok(&b"routed, not copied"[..]));
+
+		for len in [0, 10, bytes.len() - 1] {
+			let view = TLECiphertextRef::<TinyBLS381>::parse(&bytes[..len]);
+			assert!(matches!(view, Err(Error::DeserializationError)));
+		}
+		bytes.push(0);
+		let view = TLECiphertextRef::<TinyBLS381>::parse(&bytes);
+		assert!(matches!(view, Err(Error::DeserializationError)));
+	}
+
+	#[test]
+	fn oversized_length_prefixes_are_rejected() {
+		let secret = <TinyBLS381 as EngineBLS>::Scalar::rand(&mut OsRng);
+		let p_pub = <TinyBLS381 as EngineBLS>::PublicKeyGroup::generator() * secret;
+		let id = BeaconConfig::<TinyBLS381>::identity(1000);
+		let ciphertext =
+			tle::<TinyBLS381, AESGCMBlockCipherProvider, _>(p_pub, [5u8; 32], b"", id, OsRng)
+				.unwrap();
+		let mut bytes = Vec::new();
+		ciphertext.serialize_compressed(&mut bytes).unwrap();
+
+		let at = header_len::<TinyBLS381>();
+		for len in [u64::MAX, 1 << 40] {
+			bytes[at..at + LEN_SIZE].copy_from_slice(&len.to_le_bytes());
+			assert!(TLECiphertext::<TinyBLS381>::deserialize_compressed(&bytes[..]).is_err());
+			let body = &bytes[at..];
+			assert!(AESOutput::deserialize_compressed(body).is_err());
+		}
+	}
+}
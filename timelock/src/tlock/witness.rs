@@ -0,0 +1,230 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Witness encryption backends for timelock encryption.
+//!
+//! Timelock encryption is a KEM-DEM construction: a [`WitnessScheme`]
+//! encapsulates a symmetric key to a statement (e.g. "the beacon signed round
+//! r"), and the message is encrypted under that key with a
+//! [`BlockCipherProvider`]. Anyone holding a witness for the statement (e.g.
+//! the beacon signature) can decapsulate the key and decrypt the body.
+//!
+//! BLS signature based tlock is provided by [`BeaconSignature`], and
+//! [`crate::tlock::tle`]/[`crate::tlock::tld`] are thin wrappers around
+//! [`seal`]/[`open`] for that scheme.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::fullident::{Ciphertext as IBECiphertext, IBESecret, Identity, Input},
+	tlock::{Envelope, Error, OpaqueSecretKey},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, Rng},
+	vec::Vec,
+};
+use zeroize::Zeroize;
+
+/// A scheme that encapsulates a key to a statement, which can later be
+/// decapsulated by anyone holding a witness for that statement
+pub trait WitnessScheme {
+	/// The public parameters of the scheme (e.g. the beacon public key)
+	type PublicParams;
+	/// The statement a key is encapsulated to (e.g. an identity)
+	type Statement;
+	/// A witness for a statement (e.g. a signature on the identity)
+	type Witness;
+	/// The encapsulated key, stored in the header of an [`Envelope`]
+	type Header: CanonicalSerialize + CanonicalDeserialize;
+
+	/// Encapsulate a key to a statement
+	///
+	/// * `params`: The public parameters
+	/// * `statement`: The statement to encapsulate to
+	/// * `key`: The key to encapsulate
+	/// * `rng`: A CSPRNG
+	fn encapsulate<R: Rng + CryptoRng>(
+		params: &Self::PublicParams,
+		statement: &Self::Statement,
+		key: OpaqueSecretKey,
+		rng: &mut R,
+	) -> Result<Self::Header, Error>;
+
+	/// Recover an encapsulated key with a witness for its statement
+	///
+	/// * `header`: The encapsulated key
+	/// * `witness`: A witness for the statement the key was encapsulated to
+	fn decapsulate(
+		header: &Self::Header,
+		witness: &Self::Witness,
+	) -> Result<OpaqueSecretKey, Error>;
+}
+
+/// Encapsulation to an identity with BF-IBE, where the witness is a BLS
+/// signature on the identity output by a beacon
+pub struct BeaconSignature<E: EngineBLS>(PhantomData<E>);
+
+impl<E: EngineBLS> WitnessScheme for BeaconSignature<E> {
+	type PublicParams = E::PublicKeyGroup;
+	type Statement = Identity;
+	type Witness = E::SignatureGroup;
+	type Header = IBECiphertext<E>;
+
+	fn encapsulate<R: Rng + CryptoRng>(
+		params: &Self::PublicParams,
+		statement: &Self::Statement,
+		key: OpaqueSecretKey,
+		rng: &mut R,
+	) -> Result<Self::Header, Error> {
+		// IBE encryption 'to the future'
+		let input = Input::new(key).map_err(|_| Error::InvalidSecretKey)?;
+		Ok(statement.encrypt(&input, *params, rng))
+	}
+
+	fn decapsulate(
+		header: &Self::Header,
+		witness: &Self::Witness,
+	) -> Result<OpaqueSecretKey, Error> {
+		Ok(IBESecret(*witness).decrypt(header)?)
+	}
+}
+
+/// Encrypt a message under a key encapsulated to a statement
+///
+/// * `params`: The public parameters of the witness scheme
+/// * `statement`: The statement to encrypt to
+/// * `secret_key`: the ephemeral key used to encrypt the message
+/// * `message`: The message to encrypt
+/// * `rng`: A CSPRNG
+pub fn seal<W, S, R>(
+	params: &W::PublicParams,
+	statement: &W::Statement,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	mut rng: R,
+) -> Result<Envelope<W::Header>, Error>
+where
+	W: WitnessScheme,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	let header = W::encapsulate(params, statement, secret_key, &mut rng)?;
+	envelope::<W::Header, S, R>(header, secret_key, message, rng)
+}
+
+/// Encrypt a message under a key already encapsulated in `header`
+pub(crate) fn envelope<H, S, R>(
+	header: H,
+	secret_key: OpaqueSecretKey,
+	message: &[u8],
+	mut rng: R,
+) -> Result<Envelope<H>, Error>
+where
+	H: CanonicalSerialize + CanonicalDeserialize,
+	S: BlockCipherProvider<32>,
+	R: Rng + CryptoRng,
+{
+	// encrypt arbitrary-length messages with a block cipher
+	let body =
+		S::encrypt(message, secret_key, &mut rng).map_err(|_| Error::MessageEncryptionError)?;
+
+	let mut message_bytes = Vec::new();
+	body.serialize_compressed(&mut message_bytes)
+		.map_err(|_| Error::MessageEncryptionError)?;
+
+	Ok(Envelope { header, body: message_bytes, cipher_suite: S::CIPHER_SUITE.to_vec() })
+}
+
+/// Decrypt a message with a witness for the statement it was sealed to
+///
+/// * `ciphertext`: An envelope output by [`seal`]
+/// * `witness`: A witness for the statement
+pub fn open<W, S>(ciphertext: Envelope<W::Header>, witness: &W::Witness) -> Result<Vec<u8>, Error>
+where
+	W: WitnessScheme,
+	S: BlockCipherProvider<32>,
+{
+	let mut secret_bytes = W::decapsulate(&ciphertext.header, witness)?;
+	let plaintext = open_body::<S>(&ciphertext.body, secret_bytes);
+	secret_bytes.zeroize();
+	plaintext
+}
+
+/// Decrypt the body of an envelope with its decapsulated key
+pub(crate) fn open_body<S: BlockCipherProvider<32>>(
+	body: &[u8],
+	mut secret_bytes: OpaqueSecretKey,
+) -> Result<Vec<u8>, Error> {
+	// TODO: Enhanced SerializationError handling https://github.com/ideal-lab5/timelock/issues/11
+	let ct = S::Ciphertext::deserialize_compressed(&mut &body[..])
+		.map_err(|_| Error::DeserializationError);
+
+	let plaintext =
+		ct.and_then(|ct| S::decrypt(ct, secret_bytes).map_err(|_| Error::DecryptionError));
+	secret_bytes.zeroize();
+	plaintext
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{block_ciphers::AESGCMBlockCipherProvider, ibe::utils::sha256};
+	use ark_std::rand::rngs::OsRng;
+
+	/// A toy scheme where the witness is the key itself, to exercise the
+	/// envelope
+	struct Plain;
+
+	impl WitnessScheme for Plain {
+		type PublicParams = ();
+		type Statement = ();
+		type Witness = OpaqueSecretKey;
+		type Header = [u8; 32];
+
+		fn encapsulate<R: Rng + CryptoRng>(
+			_: &(),
+			_: &(),
+			key: OpaqueSecretKey,
+			_: &mut R,
+		) -> Result<[u8; 32], Error> {
+			Ok(sha256(&key).try_into().unwrap())
+		}
+
+		fn decapsulate(header: &[u8; 32], witness: &OpaqueSecretKey) -> Result<[u8; 32], Error> {
+			if sha256(witness)[..] != header[..] {
+				return Err(Error::InvalidSignature);
+			}
+			Ok(*witness)
+		}
+	}
+
+	#[test]
+	pub fn custom_witness_scheme_plugs_into_envelope() {
+		let ct = seal::<Plain, AESGCMBlockCipherProvider, OsRng>(&(), &(), [9; 32], b"hi", OsRng)
+			.unwrap();
+		let mut bytes = Vec::new();
+		ct.serialize_compressed(&mut bytes).unwrap();
+		let ct = Envelope::<[u8; 32]>::deserialize_compressed(&bytes[..]).unwrap();
+
+		assert_eq!(
+			open::<Plain, AESGCMBlockCipherProvider>(ct.clone(), &[8; 32]),
+			Err(Error::InvalidSignature)
+		);
+		assert_eq!(open::<Plain, AESGCMBlockCipherProvider>(ct, &[9; 32]).unwrap(), b"hi".to_vec());
+	}
+}
@@ -0,0 +1,375 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! An append-only container of labelled timelock ciphertexts.
+//!
+//! A vault starts with the magic bytes `TLV` and a version byte, followed by
+//! one record per entry. A record is the little-endian `u32` length of the
+//! compressed [`VaultEntry`], the entry itself, and a SHA-256 checksum over
+//! the previous record's checksum and the entry. Chaining the checksums
+//! makes any modified, reordered or truncated record detectable, while new
+//! entries are added by appending a record without rewriting the vault.
+//!
+//! Opening a vault checks every record and indexes the labels, rounds and
+//! chains of its entries; ciphertexts are only deserialized when an entry is
+//! extracted.
+
+use crate::{
+	block_ciphers::BlockCipherProvider,
+	engines::EngineBLS,
+	ibe::utils::sha256,
+	tlock::{tld, TLECiphertext},
+};
+use alloc::string::String;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{marker::PhantomData, ops::Range, vec::Vec};
+
+/// The magic bytes of a vault
+pub const MAGIC: &[u8; 3] = b"TLV";
+
+/// The current version of the vault format
+pub const VERSION: u8 = 1;
+
+/// The size of a record checksum
+const CHECKSUM_SIZE: usize = 32;
+
+/// The checksum preceding the first record
+const GENESIS_CHECKSUM: [u8; CHECKSUM_SIZE] = [0u8; CHECKSUM_SIZE];
+
+/// Errors that may occur while using a vault
+#[derive(Debug, PartialEq)]
+pub enum Error {
+	/// The input does not start with the magic bytes and a known version
+	InvalidFormat,
+	/// A record is malformed, or its checksum does not match
+	Corrupted {
+		/// The index of the first bad record
+		index: usize,
+	},
+	/// The vault already has an entry with the label
+	DuplicateLabel,
+	/// The vault has no entry with the label
+	UnknownLabel,
+	/// The entry could not be decrypted
+	Decryption(crate::tlock::Error),
+}
+
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Error::InvalidFormat => f.write_str("the input is not a vault"),
+			Error::Corrupted { index } => write!(f, "record {} of the vault is corrupted", index),
+			Error::DuplicateLabel => f.write_str("the vault already has an entry with the label"),
+			Error::UnknownLabel => f.write_str("the vault has no entry with the label"),
+			Error::Decryption(e) => write!(f, "the entry could not be decrypted: {}", e),
+		}
+	}
+}
+
+impl core::error::Error for Error {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		match self {
+			Error::Decryption(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+/// A labelled ciphertext and the round it is locked to
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, Clone, PartialEq)]
+pub struct VaultEntry<E: EngineBLS> {
+	/// The label of the entry, unique within its vault
+	pub label: String,
+	/// The round the ciphertext is locked to
+	pub round: u64,
+	/// The hash of the chain of the round
+	pub chain_hash: Vec<u8>,
+	/// The ciphertext
+	pub ciphertext: TLECiphertext<E>,
+}
+
+/// The fields of an entry that are indexed when a vault is opened
+///
+/// They are serialized first in a [`VaultEntry`], so they can be read
+/// without deserializing the ciphertext.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+	/// The label of the entry
+	pub label: String,
+	/// The round the ciphertext is locked to
+	pub round: u64,
+	/// The hash of the chain of the round
+	pub chain_hash: Vec<u8>,
+}
+
+/// An append-only container of labelled ciphertexts for the engine `E`
+pub struct Vault<E: EngineBLS> {
+	/// The encoded vault
+	bytes: Vec<u8>,
+	/// The indexed fields and entry bytes of each record, in order
+	index: Vec<(IndexEntry, Range<usize>)>,
+	/// The checksum of the last record
+	head: [u8; CHECKSUM_SIZE],
+	_engine: PhantomData<E>,
+}
+
+impl<E: EngineBLS> Default for Vault<E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<E: EngineBLS> Vault<E> {
+	/// An empty vault
+	pub fn new() -> Self {
+		let mut bytes = MAGIC.to_vec();
+		bytes.push(VERSION);
+		Self { bytes, index: Vec::new(), head: GENESIS_CHECKSUM, _engine: PhantomData }
+	}
+
+	/// Open an encoded vault, checking every record
+	///
+	/// * `bytes`: The encoded vault
+	pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+		match bytes.strip_prefix(MAGIC).and_then(|rest| rest.first()) {
+			Some(&VERSION) => {},
+			_ => return Err(Error::InvalidFormat),
+		}
+
+		let mut vault = Self { bytes, ..Self::new() };
+		let mut offset = MAGIC.len() + 1;
+		while offset < vault.bytes.len() {
+			let index = vault.index.len();
+			let corrupted = Error::Corrupted { index };
+			let len = vault
+				.bytes
+				.get(offset..offset + 4)
+				.map(|len| u32::from_le_bytes(len.try_into().expect("4 bytes.")) as usize)
+				.ok_or(Error::Corrupted { index })?;
+			let entry = offset + 4..offset + 4 + len;
+			let checksum = entry.end..entry.end + CHECKSUM_SIZE;
+			let (Some(entry_bytes), Some(stored)) =
+				(vault.bytes.get(entry.clone()), vault.bytes.get(checksum.clone()))
+			else {
+				return Err(corrupted);
+			};
+			let expected = chain(&vault.head, entry_bytes);
+			if stored != expected {
+				return Err(corrupted);
+			}
+			let fields = IndexEntry::deserialize_compressed(entry_bytes).map_err(|_| corrupted)?;
+			if vault.get(&fields.label).is_some() {
+				return Err(Error::Corrupted { index });
+			}
+			vault.head = expected;
+			vault.index.push((fields, entry));
+			offset = checksum.end;
+		}
+		Ok(vault)
+	}
+
+	/// The encoded vault
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Append an entry, returning the bytes appended to the encoded vault
+	///
+	/// Writing the returned bytes to the end of a stored vault is equivalent
+	/// to storing [`Vault::as_bytes`] again.
+	///
+	/// * `entry`: The entry, whose label must be new to the vault
+	pub fn append(&mut self, entry: &VaultEntry<E>) -> Result<&[u8], Error> {
+		if self.get(&entry.label).is_some() {
+			return Err(Error::DuplicateLabel);
+		}
+		let mut entry_bytes = Vec::new();
+		entry
+			.serialize_compressed(&mut entry_bytes)
+			.expect("The entry must be serializable.");
+		let len = u32::try_from(entry_bytes.len()).map_err(|_| Error::InvalidFormat)?;
+
+		let start = self.bytes.len();
+		self.head = chain(&self.head, &entry_bytes);
+		self.bytes.extend_from_slice(&len.to_le_bytes());
+		let range = self.bytes.len()..self.bytes.len() + entry_bytes.len();
+		self.bytes.extend_from_slice(&entry_bytes);
+		self.bytes.extend_from_slice(&self.head);
+		let fields = IndexEntry {
+			label: entry.label.clone(),
+			round: entry.round,
+			chain_hash: entry.chain_hash.clone(),
+		};
+		self.index.push((fields, range));
+		Ok(&self.bytes[start..])
+	}
+
+	/// The number of entries
+	pub fn len(&self) -> usize {
+		self.index.len()
+	}
+
+	/// Whether the vault has no entries
+	pub fn is_empty(&self) -> bool {
+		self.index.is_empty()
+	}
+
+	/// The indexed fields of the entries, in the order they were appended
+	pub fn iter(&self) -> impl Iterator<Item = &IndexEntry> {
+		self.index.iter().map(|(fields, _)| fields)
+	}
+
+	/// The indexed fields of the entry with a label
+	pub fn get(&self, label: &str) -> Option<&IndexEntry> {
+		self.iter().find(|fields| fields.label == label)
+	}
+
+	/// The indexed fields of the entries locked to a round of a chain
+	///
+	/// * `chain_hash`: The hash of the chain
+	/// * `round`: The round
+	pub fn locked_to<'a>(
+		&'a self,
+		chain_hash: &'a [u8],
+		round: u64,
+	) -> impl Iterator<Item = &'a IndexEntry> + 'a {
+		self.iter()
+			.filter(move |fields| fields.round == round && fields.chain_hash == chain_hash)
+	}
+
+	/// The entry with a label
+	///
+	/// * `label`: The label of the entry
+	pub fn entry(&self, label: &str) -> Result<VaultEntry<E>, Error> {
+		let position = self.index.iter().position(|(fields, _)| fields.label == label);
+		let (_, range) = &self.index[position.ok_or(Error::UnknownLabel)?];
+		VaultEntry::deserialize_compressed(&self.bytes[range.clone()])
+			.map_err(|_| Error::Corrupted { index: position.expect("Found above.") })
+	}
+
+	/// Decrypt the entry with a label
+	///
+	/// * `label`: The label of the entry
+	/// * `signature`: The signature of the entry's round
+	pub fn extract<S: BlockCipherProvider<32>>(
+		&self,
+		label: &str,
+		signature: E::SignatureGroup,
+	) -> Result<Vec<u8>, Error> {
+		let entry = self.entry(label)?;
+		tld::<E, S>(entry.ciphertext, signature).map_err(Error::Decryption)
+	}
+}
+
+/// The checksum of a record following a record with checksum `previous`
+fn chain(previous: &[u8; CHECKSUM_SIZE], entry: &[u8]) -> [u8; CHECKSUM_SIZE] {
+	sha256(&[&previous[..], entry].concat())
+		.try_into()
+		.expect("SHA-256 outputs 32 bytes.")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		beacons::QUICKNET,
+		block_ciphers::AESGCMBlockCipherProvider,
+		engines::drand::TinyBLS381,
+		tlock::{tle, BeaconConfig},
+	};
+	use ark_std::rand::rngs::OsRng;
+
+	fn entry(label: &str, round: u64, message: &[u8]) -> VaultEntry<TinyBLS381> {
+		let config = QUICKNET.config::<TinyBLS381>().unwrap();
+		let ciphertext = tle::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
+			config.p_pub,
+			[7u8; 32],
+			message,
+			BeaconConfig::<TinyBLS381>::identity(round),
+			OsRng,
+		)
+		.unwrap();
+		VaultEntry {
+			label: label.into(),
+			round,
+			chain_hash: QUICKNET.chain_hash_bytes(),
+			ciphertext,
+		}
+	}
+
+	#[test]
+	fn vaults_append_index_and_extract_entries() {
+		let mut vault = Vault::<TinyBLS381>::new();
+		let mut stored = vault.as_bytes().to_vec();
+		for (label, round) in [("first", 1000), ("second", 2000), ("third", 1000)] {
+			stored.extend_from_slice(vault.append(&entry(label, round, label.as_bytes())).unwrap());
+		}
+		assert_eq!(stored, vault.as_bytes());
+		assert_eq!(vault.append(&entry("second", 3, b"")), Err(Error::DuplicateLabel));
+
+		let vault = Vault::<TinyBLS381>::from_bytes(stored).unwrap();
+		assert_eq!(vault.len(), 3);
+		let labels: Vec<&str> = vault.iter().map(|fields| fields.label.as_str()).collect();
+		assert_eq!(labels, ["first", "second", "third"]);
+		let chain_hash = QUICKNET.chain_hash_bytes();
+		let unlocked: Vec<&str> =
+			vault.locked_to(&chain_hash, 1000).map(|fields| fields.label.as_str()).collect();
+		assert_eq!(unlocked, ["first", "third"]);
+		assert_eq!(vault.get("second").unwrap().round, 2000);
+
+		// the signature of quicknet's round 1000
+		let signature = <TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(
+			&array_bytes::hex2bytes("b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125e342b73a8dd2bacbe47e4b6b63ed5e39").unwrap()[..],
+		)
+		.unwrap();
+		let extract = |label| vault.extract::<AESGCMBlockCipherProvider>(label, signature);
+		assert_eq!(extract("third"), Ok(b"third".to_vec()));
+		assert!(matches!(extract("second"), Err(Error::Decryption(_))));
+		assert_eq!(extract("fourth"), Err(Error::UnknownLabel));
+	}
+
+	#[test]
+	fn vaults_detect_tampering() {
+		let mut vault = Vault::<TinyBLS381>::new();
+		vault.append(&entry("first", 1000, b"first")).unwrap();
+		vault.append(&entry("second", 2000, b"second")).unwrap();
+		let bytes = vault.as_bytes().to_vec();
+
+		assert!(matches!(
+			Vault::<TinyBLS381>::from_bytes(b"TLE\x01".to_vec()),
+			Err(Error::InvalidFormat)
+		));
+		assert!(Vault::<TinyBLS381>::from_bytes(Vault::<TinyBLS381>::new().as_bytes().to_vec())
+			.unwrap()
+			.is_empty());
+		let corrupted = |bytes: Vec<u8>| match Vault::<TinyBLS381>::from_bytes(bytes) {
+			Err(Error::Corrupted { index }) => Some(index),
+			_ => None,
+		};
+		assert_eq!(corrupted(bytes[..bytes.len() - 1].to_vec()), Some(1));
+		let mut flipped = bytes.clone();
+		flipped[20] ^= 1;
+		assert_eq!(corrupted(flipped), Some(0));
+
+		// a record moved to the front no longer chains from the genesis
+		let first_end = vault.index[0].1.end + CHECKSUM_SIZE;
+		let mut reordered = bytes[..4].to_vec();
+		reordered.extend_from_slice(&bytes[first_end..]);
+		reordered.extend_from_slice(&bytes[4..first_end]);
+		assert_eq!(corrupted(reordered), Some(0));
+		assert_eq!(corrupted([&bytes[..], &[0u8; 3]].concat()), Some(2));
+	}
+}
@@ -19,23 +19,64 @@ use timelock::{
 	block_ciphers::{AESGCMBlockCipherProvider, AESOutput, BlockCipherProvider},
 	engines::{drand::TinyBLS381, EngineBLS},
 	ibe::fullident::Identity,
-	tlock::{tld as timelock_decrypt, tle as timelock_encrypt, TLECiphertext},
+	tlock::{strict, tld as timelock_decrypt, tle as timelock_encrypt, TLECiphertext},
 };
 
-use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use crate::rng::{self, RngProvider};
+use js_sys::{Function, Uint8Array};
+use rand_chacha::ChaCha20Rng;
 use wasm_bindgen::prelude::*;
 
 type R = ChaCha20Rng;
 
-/// a helper function to deserialize arkworks elements from bytes
-fn convert_from_bytes<E: CanonicalDeserialize, const N: usize>(bytes: &[u8; N]) -> Option<E> {
-	E::deserialize_compressed(&bytes[..]).ok()
+fn get_rng() -> Result<ChaCha20Rng, JsError> {
+	rng::rng().map_err(|e| JsError::new(&e))
 }
 
-fn get_rng() -> Result<ChaCha20Rng, JsError> {
-	let mut seed = [0u8; 32];
-	getrandom::getrandom(&mut seed).map_err(|e| JsError::new(&format!("RNG failed: {:?}", e)))?;
-	Ok(ChaCha20Rng::from_seed(seed))
+/// Use a caller supplied CSPRNG for encryption, for runtimes without
+/// `crypto.getRandomValues`
+/// * `fill_js`: A function filling the `Uint8Array` it is passed with random
+///   bytes
+#[wasm_bindgen]
+pub fn set_rng(fill_js: Function) {
+	rng::set_provider(RngProvider::Custom(Box::new(move |bytes: &mut [u8]| {
+		let array = Uint8Array::new_with_length(bytes.len() as u32);
+		fill_js
+			.call1(&JsValue::NULL, &array)
+			.map_err(|e| format!("RNG failed: {:?}", e))?;
+		array.copy_to(bytes);
+		Ok(())
+	})));
+}
+
+/// Seed the RNG used for encryption, making ciphertexts reproducible
+///
+/// This is for generating test vectors only: never encrypt real messages with
+/// a fixed seed.
+/// * `seed_js`: A 32 byte seed
+#[wasm_bindgen]
+pub fn set_rng_seed(seed_js: JsValue) -> Result<(), JsError> {
+	let seed: [u8; 32] = serde_wasm_bindgen::from_value(seed_js)
+		.map_err(|_| JsError::new("could not decode seed, expected 32 bytes"))?;
+	rng::set_seed(seed);
+	Ok(())
+}
+
+/// Go back to the OS CSPRNG (the default) for encryption
+#[wasm_bindgen]
+pub fn use_os_rng() {
+	rng::set_provider(RngProvider::Os);
+}
+
+/// Run the known-answer tests of every engine and cipher, as a power-on self
+/// test
+///
+/// Returns the report: one `{ engine, cipher_suite, failure }` per check, where
+/// `failure` is null if the check passed.
+#[wasm_bindgen]
+pub fn self_test() -> Result<JsValue, JsError> {
+	serde_wasm_bindgen::to_value(&timelock::self_test())
+		.map_err(|_| JsError::new("could not encode the self test report"))
 }
 
 /// The encrypt wrapper used by the WASM blob to call tlock.rs encrypt function
@@ -69,8 +110,8 @@ pub fn do_tle<E: EngineBLS>(
 		.try_into()
 		.map_err(|_| JsError::new("could not convert public params"))?;
 
-	let pp = convert_from_bytes::<E::PublicKeyGroup, 96>(&pp_bytes.clone())
-		.ok_or(JsError::new("Could not convert secret key"))?;
+	let pp = strict::public_key::<E>(&pp_bytes)
+		.map_err(|e| JsError::new(&format!("could not deserialize p_pub: {}", e)))?;
 
 	let id_bytes: Vec<u8> = serde_wasm_bindgen::from_value(id_js.clone())
 		.map_err(|_| JsError::new("could not decode id"))?;
@@ -87,7 +128,7 @@ pub fn do_tle<E: EngineBLS>(
 		identity,
 		rng,
 	)
-	.map_err(|_| JsError::new("encryption failed"))?;
+	.map_err(|e| JsError::new(&format!("encryption failed: {}", e)))?;
 
 	ciphertext
 		.serialize_compressed(&mut ciphertext_bytes)
@@ -111,16 +152,16 @@ fn do_tld<E: EngineBLS>(ciphertext_js: JsValue, sig_vec_js: JsValue) -> Result<J
 	let sig_conversion: Vec<u8> = serde_wasm_bindgen::from_value(sig_vec_js.clone())
 		.map_err(|_| JsError::new("could not decode secret key"))?;
 	let sig_bytes = sig_conversion.as_slice();
-	let sig_point = <E as EngineBLS>::SignatureGroup::deserialize_compressed(sig_bytes)
-		.map_err(|_| JsError::new("could not deserialize sig_vec"))?;
+	let sig_point = strict::signature::<E>(sig_bytes)
+		.map_err(|e| JsError::new(&format!("could not deserialize sig_vec: {}", e)))?;
 	let ciphertext_vec: Vec<u8> = serde_wasm_bindgen::from_value(ciphertext_js.clone())
 		.map_err(|_| JsError::new("could not decode ciphertext"))?;
 	let ciphertext_bytes: &[u8] = ciphertext_vec.as_slice();
 
-	let ciphertext: TLECiphertext<E> = TLECiphertext::deserialize_compressed(ciphertext_bytes)
-		.map_err(|_| JsError::new("Could not deserialize ciphertext"))?;
+	let ciphertext: TLECiphertext<E> = strict::ciphertext::<E>(ciphertext_bytes)
+		.map_err(|e| JsError::new(&format!("Could not deserialize ciphertext: {}", e)))?;
 	let result: Vec<u8> = timelock_decrypt::<E, AESGCMBlockCipherProvider>(ciphertext, sig_point)
-		.map_err(|e| JsError::new(&format!("decryption has failed {:?}", e)))?;
+		.map_err(|e| JsError::new(&format!("decryption has failed: {}", e)))?;
 	serde_wasm_bindgen::to_value(&result)
 		.map_err(|_| JsError::new("plaintext conversion has failed"))
 }
@@ -146,14 +187,14 @@ pub fn do_decrypt<E: EngineBLS>(
 	let ciphertext_vec: Vec<u8> = serde_wasm_bindgen::from_value(ciphertext_js.clone())
 		.map_err(|_| JsError::new("could not decode ciphertext"))?;
 	let ciphertext_bytes: &[u8] = ciphertext_vec.as_slice();
-	let ciphertext: TLECiphertext<E> = TLECiphertext::deserialize_compressed(ciphertext_bytes)
-		.map_err(|_| JsError::new("Could not deserialize ciphertext"))?;
+	let ciphertext: TLECiphertext<E> = strict::ciphertext::<E>(ciphertext_bytes)
+		.map_err(|e| JsError::new(&format!("Could not deserialize ciphertext: {}", e)))?;
 
 	let aes_ciphertext: AESOutput =
 		AESOutput::deserialize_compressed(&mut &ciphertext.body[..]).unwrap();
 
 	let result: Vec<u8> = AESGCMBlockCipherProvider::decrypt(aes_ciphertext, secret_key)
-		.map_err(|_| JsError::new("Message decryption failed"))?;
+		.map_err(|e| JsError::new(&format!("Message decryption failed: {}", e)))?;
 
 	serde_wasm_bindgen::to_value(&result)
 		.map_err(|_| JsError::new("plaintext conversion has failed"))
@@ -174,6 +215,11 @@ mod test {
 		DecryptFailure { _error: JsError },
 	}
 
+	/// a helper function to deserialize arkworks elements from bytes
+	fn convert_from_bytes<E: CanonicalDeserialize, const N: usize>(bytes: &[u8; N]) -> Option<E> {
+		E::deserialize_compressed(&bytes[..]).ok()
+	}
+
 	/// This function is used purely for testing purposes.
 	/// It takes in a seed and generates a secret key and public params
 	fn generate_keys<E: EngineBLS>() -> ([u8; 96], [u8; 32]) {
@@ -15,6 +15,7 @@
  */
 
 pub mod js;
+pub mod rng;
 
 #[cfg(feature = "python")]
 pub mod py;
@@ -16,15 +16,19 @@
 //! This module provides wasm-bindings for the Timelock library that are
 //! compatible with Python
 
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+// the wrappers #[pyfunction] generates for functions returning PyResult
+// convert PyErr into itself, outside the span an allow on the function covers
+#![allow(clippy::useless_conversion)]
+
+use crate::rng::{self, RngProvider};
+use ark_serialize::CanonicalSerialize;
 use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
-use rand::rngs::OsRng;
 use sha2::Digest;
 use timelock::{
 	block_ciphers::AESGCMBlockCipherProvider,
-	engines::{drand::TinyBLS381, EngineBLS},
+	engines::drand::TinyBLS381,
 	ibe::fullident::Identity,
-	tlock::{tld as timelock_decrypt, tle as timelock_encrypt, TLECiphertext},
+	tlock::{strict, tld as timelock_decrypt, tle as timelock_encrypt, TLECiphertext},
 };
 
 /// The encrypt wrapper used by the Python bindings to call tlock.rs encrypt
@@ -44,23 +48,24 @@ fn tle(
 		.try_into()
 		.map_err(|_| PyErr::new::<PyValueError, _>("Could not convert secret key"))?;
 
-	let pp = <TinyBLS381 as EngineBLS>::PublicKeyGroup::deserialize_compressed(&p_pub_py[..])
-		.map_err(|_| {
-			PyErr::new::<PyValueError, _>(
-				"The public key bytes could not be deserialized to a valid public key.",
-			)
-		})?;
+	let pp = strict::public_key::<TinyBLS381>(&p_pub_py).map_err(|e| {
+		PyErr::new::<PyValueError, _>(format!(
+			"The public key bytes could not be deserialized to a valid public key: {}",
+			e
+		))
+	})?;
 	let id = {
 		let mut hasher = sha2::Sha256::new();
 		hasher.update(round_number.to_be_bytes());
 		hasher.finalize().to_vec()
 	};
-	let identity = Identity::new(b"", id);
+	let identity = Identity::new(b"", &id);
 
-	let ciphertext = timelock_encrypt::<TinyBLS381, AESGCMBlockCipherProvider, OsRng>(
-		pp, msk_bytes, &message, identity, OsRng,
+	let rng = rng::rng().map_err(PyErr::new::<PyValueError, _>)?;
+	let ciphertext = timelock_encrypt::<TinyBLS381, AESGCMBlockCipherProvider, _>(
+		pp, msk_bytes, &message, identity, rng,
 	)
-	.map_err(|_| PyErr::new::<PyValueError, _>("Encryption failed"))?;
+	.map_err(|e| PyErr::new::<PyValueError, _>(format!("Encryption failed: {}", e)))?;
 
 	let mut ciphertext_bytes: Vec<u8> = Vec::new();
 	ciphertext
@@ -76,25 +81,79 @@ fn tle(
 /// * 'sig_bytes': A signature (output of IBE Extract)
 #[pyfunction]
 fn tld(ciphertext_bytes: Vec<u8>, sig_bytes: Vec<u8>) -> PyResult<Vec<u8>> {
-	let sig_point =
-		<TinyBLS381 as EngineBLS>::SignatureGroup::deserialize_compressed(sig_bytes.as_slice())
-			.map_err(|_| PyErr::new::<PyValueError, _>("Could not deserialize signature"))?;
+	let sig_point = strict::signature::<TinyBLS381>(&sig_bytes).map_err(|e| {
+		PyErr::new::<PyValueError, _>(format!("Could not deserialize signature: {}", e))
+	})?;
 
-	let ciphertext: TLECiphertext<TinyBLS381> =
-		TLECiphertext::deserialize_compressed(ciphertext_bytes.as_slice())
-			.map_err(|_| PyErr::new::<PyValueError, _>("Could not deserialize ciphertext"))?;
+	let ciphertext: TLECiphertext<TinyBLS381> = strict::ciphertext::<TinyBLS381>(&ciphertext_bytes)
+		.map_err(|e| {
+			PyErr::new::<PyValueError, _>(format!("Could not deserialize ciphertext: {}", e))
+		})?;
 
-	let result =
-		timelock_decrypt::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, sig_point)
-			.map_err(|e| PyErr::new::<PyValueError, _>(format!("Decryption failed: {:?}", e)))?;
+	let result = timelock_decrypt::<TinyBLS381, AESGCMBlockCipherProvider>(ciphertext, sig_point)
+		.map_err(|e| PyErr::new::<PyValueError, _>(format!("Decryption failed: {}", e)))?;
 
 	Ok(result)
 }
 
+/// Use a caller supplied CSPRNG for encryption in the calling thread
+/// * 'fill': A callable taking a length and returning that many random bytes
+#[pyfunction]
+fn set_rng(fill: PyObject) {
+	rng::set_provider(RngProvider::Custom(Box::new(move |bytes: &mut [u8]| {
+		let random: Vec<u8> = Python::with_gil(|py| fill.call1(py, (bytes.len(),))?.extract(py))
+			.map_err(|e| format!("RNG failed: {}", e))?;
+		if random.len() != bytes.len() {
+			return Err(format!("RNG returned {} bytes, expected {}", random.len(), bytes.len()));
+		}
+		bytes.copy_from_slice(&random);
+		Ok(())
+	})));
+}
+
+/// Seed the RNG used for encryption in the calling thread, making ciphertexts
+/// reproducible
+///
+/// This is for generating test vectors only: never encrypt real messages with
+/// a fixed seed.
+/// * 'seed': A 32 byte seed
+#[pyfunction]
+fn set_rng_seed(seed: Vec<u8>) -> PyResult<()> {
+	let seed: [u8; 32] = seed
+		.try_into()
+		.map_err(|_| PyErr::new::<PyValueError, _>("The seed must be 32 bytes"))?;
+	rng::set_seed(seed);
+	Ok(())
+}
+
+/// Go back to the OS CSPRNG (the default) for encryption in the calling thread
+#[pyfunction]
+fn use_os_rng() {
+	rng::set_provider(RngProvider::Os);
+}
+
+/// Run the known-answer tests of every engine and cipher, as a power-on self
+/// test
+///
+/// Returns one (engine, cipher suite, failure) tuple per check, where failure
+/// is None if the check passed.
+#[pyfunction]
+fn self_test() -> Vec<(String, String, Option<String>)> {
+	timelock::self_test()
+		.checks
+		.into_iter()
+		.map(|check| (check.engine, check.cipher_suite, check.failure.map(|f| f.to_string())))
+		.collect()
+}
+
 #[pymodule]
 #[pyo3(name = "timelock_wasm_wrapper")]
 fn py(m: &Bound<'_, PyModule>) -> PyResult<()> {
 	m.add_function(wrap_pyfunction!(tle, m)?)?;
 	m.add_function(wrap_pyfunction!(tld, m)?)?;
+	m.add_function(wrap_pyfunction!(set_rng, m)?)?;
+	m.add_function(wrap_pyfunction!(set_rng_seed, m)?)?;
+	m.add_function(wrap_pyfunction!(use_os_rng, m)?)?;
+	m.add_function(wrap_pyfunction!(self_test, m)?)?;
 	Ok(())
 }
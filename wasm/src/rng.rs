@@ -0,0 +1,103 @@
+/*
+ * Copyright 2025 by Ideal Labs, LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! The randomness used by the bindings.
+//!
+//! Every encryption draws a fresh 32 byte seed from the current
+//! [`RngProvider`] and runs a ChaCha20 RNG from it. By default seeds come
+//! from the OS (`getrandom`); hosts where that is unavailable can supply
+//! their own CSPRNG, and test-vector generation can fix a seed so that the
+//! ciphertexts are reproducible.
+
+use rand_chacha::{
+	rand_core::{RngCore, SeedableRng},
+	ChaCha20Rng,
+};
+use std::cell::RefCell;
+
+/// A caller supplied CSPRNG, filling the buffer it is given
+pub type FillBytes = Box<dyn FnMut(&mut [u8]) -> Result<(), String>>;
+
+/// Where the bindings get their randomness
+pub enum RngProvider {
+	/// The OS CSPRNG
+	Os,
+	/// A caller supplied CSPRNG
+	Custom(FillBytes),
+	/// A ChaCha20 RNG from a fixed seed, for reproducible test vectors only
+	Seeded(Box<ChaCha20Rng>),
+}
+
+impl RngProvider {
+	/// Draw the seed of the RNG for one operation
+	fn seed(&mut self) -> Result<[u8; 32], String> {
+		let mut seed = [0u8; 32];
+		match self {
+			RngProvider::Os =>
+				getrandom::getrandom(&mut seed).map_err(|e| format!("RNG failed: {:?}", e))?,
+			RngProvider::Custom(fill) => fill(&mut seed)?,
+			RngProvider::Seeded(rng) => rng.fill_bytes(&mut seed),
+		}
+		Ok(seed)
+	}
+}
+
+thread_local! {
+	static PROVIDER: RefCell<RngProvider> = const { RefCell::new(RngProvider::Os) };
+}
+
+/// Replace the provider used by later operations
+///
+/// * `provider`: The new provider
+pub fn set_provider(provider: RngProvider) {
+	PROVIDER.with(|p| *p.borrow_mut() = provider);
+}
+
+/// Use a fixed seed, so that the following operations are deterministic
+///
+/// * `seed`: The seed
+pub fn set_seed(seed: [u8; 32]) {
+	set_provider(RngProvider::Seeded(Box::new(ChaCha20Rng::from_seed(seed))));
+}
+
+/// An RNG for one operation, seeded from the current provider
+pub fn rng() -> Result<ChaCha20Rng, String> {
+	PROVIDER.with(|p| p.borrow_mut().seed()).map(ChaCha20Rng::from_seed)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn providers_seed_the_rng() {
+		set_seed([7; 32]);
+		let first = rng().unwrap().next_u64();
+		set_seed([7; 32]);
+		assert_eq!(rng().unwrap().next_u64(), first);
+		assert_ne!(rng().unwrap().next_u64(), first);
+
+		set_provider(RngProvider::Custom(Box::new(|bytes| {
+			bytes.fill(1);
+			Ok(())
+		})));
+		assert_eq!(rng().unwrap().next_u64(), ChaCha20Rng::from_seed([1; 32]).next_u64());
+		set_provider(RngProvider::Custom(Box::new(|_| Err("no entropy".into()))));
+		assert_eq!(rng().err().as_deref(), Some("no entropy"));
+
+		set_provider(RngProvider::Os);
+		assert_ne!(rng().unwrap().next_u64(), rng().unwrap().next_u64());
+	}
+}
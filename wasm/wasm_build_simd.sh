@@ -0,0 +1,3 @@
+# builds the js package with wasm simd128 enabled, so XOR over large payloads
+# uses 128-bit vector instructions. Runtimes without simd128 cannot load it.
+RUSTFLAGS="-C target-feature=+simd128" wasm-pack build --target web --out-dir pkg/js-simd